@@ -0,0 +1,61 @@
+use smol::Timer;
+use std::time::Duration;
+
+fn main() {
+    smol::block_on(async {
+        #[cfg(feature = "channels-console")]
+        let _channels_guard = channels_console::ChannelsGuard::new();
+
+        let (txa, _rxa) = async_channel::unbounded::<i32>();
+        #[cfg(feature = "channels-console")]
+        let (txa, _rxa) = channels_console::instrument!((txa, _rxa), log = true);
+
+        let (txb, rxb) = async_channel::bounded::<i32>(10);
+        #[cfg(feature = "channels-console")]
+        let (txb, rxb) = channels_console::instrument!((txb, rxb));
+
+        let (txc, rxc) = async_channel::bounded::<String>(1);
+        #[cfg(feature = "channels-console")]
+        let (txc, rxc) = channels_console::instrument!((txc, rxc), label = "hello-there");
+
+        let sender_handle = smol::spawn(async move {
+            for i in 1..=3 {
+                println!("[Sender] Sending message: {}", i);
+                txa.send(i).await.expect("Failed to send");
+                Timer::after(Duration::from_millis(100)).await;
+            }
+
+            for i in 1..=3 {
+                println!("[Sender] Sending message: {}", i);
+                txb.send(i).await.expect("Failed to send");
+                Timer::after(Duration::from_millis(250)).await;
+            }
+
+            println!("[Sender] Done sending messages");
+        });
+
+        let bounded_receiver_handle = smol::spawn(async move {
+            match rxc.recv().await {
+                Ok(msg) => println!("[Bounded-1] Received: {}", msg),
+                Err(_) => println!("[Bounded-1] Sender dropped"),
+            }
+        });
+
+        println!("[Bounded-1] Sending message");
+        txc.send("Hello from bounded channel!".to_string())
+            .await
+            .expect("Failed to send");
+
+        sender_handle.await;
+        bounded_receiver_handle.await;
+
+        #[cfg(feature = "channels-console")]
+        drop(_channels_guard);
+
+        while let Ok(msg) = rxb.recv().await {
+            println!("[Receiver] Received message: {}", msg);
+        }
+
+        println!("\nExample completed!");
+    });
+}
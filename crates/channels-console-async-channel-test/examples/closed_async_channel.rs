@@ -0,0 +1,65 @@
+use smol::Timer;
+use std::time::Duration;
+
+fn main() {
+    smol::block_on(async {
+        #[cfg(feature = "channels-console")]
+        let _channels_guard = channels_console::ChannelsGuard::new();
+
+        let (txa, rxa) = async_channel::unbounded::<i32>();
+        #[cfg(feature = "channels-console")]
+        let (txa, rxa) = channels_console::instrument!((txa, rxa), label = "unbounded");
+
+        let (txb, rxb) = async_channel::bounded::<i32>(10);
+        #[cfg(feature = "channels-console")]
+        let (txb, rxb) = channels_console::instrument!((txb, rxb), label = "bounded");
+
+        println!("[Unbounded] Sending 3 messages...");
+        for i in 1..=3 {
+            txa.send(i).await.expect("Failed to send");
+        }
+
+        for _ in 0..3 {
+            if let Ok(msg) = rxa.recv().await {
+                println!("[Unbounded] Received: {}", msg);
+            }
+        }
+
+        println!("[Bounded] Sending 3 messages...");
+        for i in 1..=3 {
+            txb.send(i).await.expect("Failed to send");
+        }
+
+        for _ in 0..3 {
+            if let Ok(msg) = rxb.recv().await {
+                println!("[Bounded] Received: {}", msg);
+            }
+        }
+
+        println!("\nClosing channels from receiver side...");
+
+        drop(rxa);
+        println!("[Unbounded] Receiver closed");
+
+        drop(rxb);
+        println!("[Bounded] Receiver closed");
+
+        Timer::after(Duration::from_millis(100)).await;
+
+        println!("\nAttempting to send after closing receivers...");
+
+        match txa.send(999).await {
+            Ok(_) => panic!("[Unbounded] Send succeeded (unexpected)"),
+            Err(_) => println!("[Unbounded] Send failed - channel closed"),
+        }
+
+        match txb.send(999).await {
+            Ok(_) => panic!("[Bounded] Send succeeded (unexpected)"),
+            Err(_) => println!("[Bounded] Send failed - channel closed"),
+        }
+
+        Timer::after(Duration::from_millis(100)).await;
+
+        println!("\nExample completed!");
+    });
+}
@@ -2,6 +2,7 @@ use futures_util::stream::StreamExt;
 use smol::Timer;
 use std::time::Duration;
 
+#[cfg(feature = "channels-console")]
 struct Actor {
     name: String,
 }
@@ -9,6 +10,7 @@ struct Actor {
 #[allow(unused_mut)]
 fn main() {
     smol::block_on(async {
+        #[cfg(feature = "channels-console")]
         let actor1 = Actor {
             name: "Actor 1".to_string(),
         };
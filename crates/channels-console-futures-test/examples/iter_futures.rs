@@ -1,6 +1,7 @@
 use smol::Timer;
 use std::time::Duration;
 
+#[cfg(feature = "channels-console")]
 struct Actor {
     name: String,
 }
@@ -8,6 +9,7 @@ struct Actor {
 #[allow(unused_mut)]
 fn main() {
     smol::block_on(async {
+        #[cfg(feature = "channels-console")]
         let actor1 = Actor {
             name: "Actor 1".to_string(),
         };
@@ -28,7 +30,7 @@ fn main() {
 
             smol::spawn(async move {
                 tx.unbounded_send(i).expect("Failed to send");
-                let _ = rx.try_next();
+                let _ = rx.try_recv();
             })
             .detach();
         }
@@ -45,7 +47,7 @@ fn main() {
 
             smol::spawn(async move {
                 tx.try_send(i).expect("Failed to send");
-                let _ = rx.try_next();
+                let _ = rx.try_recv();
             })
             .detach();
         }
@@ -0,0 +1,40 @@
+use futures_util::stream::StreamExt;
+use smol::Timer;
+use std::time::Duration;
+
+#[allow(unused_mut)]
+fn main() {
+    smol::block_on(async {
+        let (tx, mut rx) = futures_channel::mpsc::channel::<i32>(2);
+        #[cfg(feature = "channels-console")]
+        let (tx, mut rx) = channels_console::instrument!((tx, rx), capacity = 2, label = "shared");
+
+        // Every sender gets a slot of its own on top of the buffer
+        let mut senders: Vec<_> = (0..4).map(|_| tx.clone()).collect();
+        senders.push(tx);
+
+        let mut sent = 0;
+        for _ in 0..5 {
+            for sender in &mut senders {
+                while sender.try_send(sent).is_ok() {
+                    sent += 1;
+                }
+            }
+            // Let the forwarders move messages along
+            Timer::after(Duration::from_millis(20)).await;
+        }
+        println!("[Shared] Sent: {}", sent);
+
+        #[cfg(feature = "channels-console")]
+        for stats in channels_console::snapshot() {
+            println!(
+                "{}: queued {} of {}",
+                stats.label, stats.queued, stats.channel_type
+            );
+        }
+
+        drop(senders);
+        let received = rx.count().await;
+        println!("[Shared] Received: {}", received);
+    })
+}
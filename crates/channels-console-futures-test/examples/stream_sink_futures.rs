@@ -0,0 +1,38 @@
+use futures_util::{SinkExt, StreamExt};
+use smol::Timer;
+use std::time::Duration;
+
+fn main() {
+    smol::block_on(async {
+        #[cfg(feature = "channels-console")]
+        let _channels_guard =
+            channels_console::ChannelsGuard::new().format(channels_console::Format::JsonPretty);
+
+        // Adapters exposing only `impl Sink` and `impl Stream`
+        let (tx, rx) = futures_channel::mpsc::unbounded::<u32>();
+        let sink =
+            tx.with(|n: u32| async move { Ok::<_, futures_channel::mpsc::SendError>(n * 10) });
+        let stream = rx.map(|n| n + 1);
+
+        #[cfg(feature = "channels-console")]
+        let (sink, stream) =
+            channels_console::instrument_stream!((sink, stream), label = "stream-pair", log = true);
+
+        let mut sink = Box::pin(sink);
+        let mut stream = Box::pin(stream);
+
+        for i in 1..=5 {
+            sink.send(i).await.expect("Failed to send");
+        }
+
+        for _ in 0..3 {
+            let msg = stream.next().await.expect("Stream ended");
+            println!("[Receiver] Received message: {}", msg);
+        }
+
+        // Let the stats collector catch up before the guard reports
+        Timer::after(Duration::from_millis(100)).await;
+
+        println!("\nExample completed!");
+    })
+}
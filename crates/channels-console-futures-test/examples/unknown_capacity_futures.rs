@@ -0,0 +1,29 @@
+use futures_util::sink::SinkExt;
+use futures_util::stream::StreamExt;
+
+#[allow(unused_mut)]
+fn main() {
+    smol::block_on(async {
+        #[cfg(feature = "channels-console")]
+        let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+            .format(channels_console::Format::JsonLines)
+            .flush_timeout(std::time::Duration::from_millis(500))
+            .build();
+
+        // Instrumented without `capacity = 4`, which can't be read from the channel
+        let (mut tx, mut rx) = futures_channel::mpsc::channel::<u32>(4);
+        #[cfg(feature = "channels-console")]
+        let (mut tx, mut rx) = channels_console::instrument!((tx, rx), label = "no-capacity");
+
+        // Filling the original capacity before receiving must not block
+        for i in 0..4 {
+            tx.send(i).await.expect("Failed to send");
+        }
+        for _ in 0..4 {
+            let msg = rx.next().await.expect("Failed to receive");
+            println!("[Receiver] Received: {}", msg);
+        }
+
+        println!("\nUnknown capacity example completed!");
+    });
+}
@@ -0,0 +1,54 @@
+fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .flush_timeout(std::time::Duration::from_secs(1))
+        .build();
+
+    let (txa, rxa) = kanal::bounded::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (txa, rxa) = channels_console::instrument!((txa, rxa), label = "jobs", log = true);
+
+    let (txb, rxb) = kanal::unbounded::<String>();
+    #[cfg(feature = "channels-console")]
+    let (txb, rxb) = channels_console::instrument!((txb, rxb), label = "events");
+
+    let (txc, rxc) = kanal::bounded_async::<i32>(5);
+    #[cfg(feature = "channels-console")]
+    let (txc, rxc) = channels_console::instrument!((txc, rxc), label = "async-jobs");
+
+    let (txd, rxd) = kanal::bounded::<i32>(2);
+    #[cfg(feature = "channels-console")]
+    let (txd, rxd) = channels_console::instrument!((txd, rxd), mode = tap, label = "tapped");
+
+    let producer = std::thread::spawn(move || {
+        for i in 1..=3 {
+            println!("[Sender] Sending message: {}", i);
+            txa.send(i).expect("Failed to send");
+        }
+        txb.send("started".to_string()).expect("Failed to send");
+    });
+
+    for msg in rxa {
+        println!("[Receiver] Received message: {}", msg);
+    }
+    producer.join().expect("Producer panicked");
+    println!("[Events] Received: {:?}", rxb.recv());
+
+    smol::block_on(async {
+        for i in 1..=2 {
+            txc.send(i).await.expect("Failed to send");
+        }
+        drop(txc);
+        while let Ok(msg) = rxc.recv().await {
+            println!("[Async] Received message: {}", msg);
+        }
+    });
+
+    txd.send(1).expect("Failed to send");
+    txd.send(2).expect("Failed to send");
+    // Rejected, the channel is full
+    assert_eq!(txd.try_send(3), Ok(false));
+    println!("[Tapped] Received: {:?}", rxd.recv());
+
+    println!("\nExample completed!");
+}
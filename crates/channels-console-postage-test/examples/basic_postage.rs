@@ -0,0 +1,42 @@
+use postage::prelude::{Sink, Stream};
+
+#[allow(unused_mut)]
+fn main() {
+    smol::block_on(async {
+        #[cfg(feature = "channels-console")]
+        let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+            .flush_timeout(std::time::Duration::from_secs(1))
+            .build();
+
+        let (mut txa, mut rxa) = postage::mpsc::channel::<i32>(10);
+        #[cfg(feature = "channels-console")]
+        let (mut txa, mut rxa) =
+            channels_console::instrument!((txa, rxa), capacity = 10, label = "jobs", log = true);
+
+        let (mut txb, mut rxb) = postage::oneshot::channel::<String>();
+        #[cfg(feature = "channels-console")]
+        let (mut txb, mut rxb) = channels_console::instrument!((txb, rxb), label = "reply");
+
+        let (txc, mut rxc) = postage::oneshot::channel::<String>();
+        #[cfg(feature = "channels-console")]
+        let (txc, mut rxc) = channels_console::instrument!((txc, rxc), label = "abandoned");
+
+        for i in 1..=3 {
+            println!("[Sender] Sending message: {}", i);
+            txa.send(i).await.expect("Failed to send");
+        }
+        for _ in 1..=3 {
+            let msg = rxa.recv().await.expect("Failed to receive");
+            println!("[Receiver] Received message: {}", msg);
+        }
+
+        txb.send("done".to_string()).await.expect("Failed to send");
+        println!("[Reply] Received: {:?}", rxb.recv().await);
+
+        // Dropped without sending
+        drop(txc);
+        println!("[Abandoned] Received: {:?}", rxc.recv().await);
+
+        println!("\nExample completed!");
+    });
+}
@@ -0,0 +1,66 @@
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+#[cfg(feature = "channels-console")]
+use std::thread;
+#[cfg(feature = "channels-console")]
+use std::time::Duration;
+
+/// Sends to a bounded channel without consuming until it's full and returns how many messages
+/// were accepted.
+fn fill(tx: &SyncSender<i32>) -> usize {
+    let mut accepted = 0;
+    loop {
+        match tx.try_send(accepted as i32) {
+            Ok(_) => accepted += 1,
+            Err(TrySendError::Full(_)) => return accepted,
+            Err(TrySendError::Disconnected(_)) => panic!("Channel closed"),
+        }
+    }
+}
+
+/// Fills the channel again while its forwarder keeps moving messages out of the outer proxy,
+/// until it holds all it can.
+#[cfg(feature = "channels-console")]
+fn top_up(tx: &SyncSender<i32>) -> usize {
+    let mut accepted = 0;
+    loop {
+        thread::sleep(Duration::from_millis(50));
+        match fill(tx) {
+            0 => return accepted,
+            more => accepted += more,
+        }
+    }
+}
+
+/// Prints how many messages a plain and an instrumented `sync_channel($capacity)` accept.
+macro_rules! check_capacity {
+    ($capacity:literal) => {{
+        let (plain_tx, _plain_rx) = mpsc::sync_channel::<i32>($capacity);
+        let plain_accepted = fill(&plain_tx);
+
+        let (tx, rx) = mpsc::sync_channel::<i32>($capacity);
+        #[cfg(feature = "channels-console")]
+        let (tx, rx) = channels_console::instrument!((tx, rx), capacity = $capacity);
+        #[allow(unused_mut)]
+        let mut accepted = fill(&tx);
+        #[cfg(feature = "channels-console")]
+        {
+            accepted += top_up(&tx);
+        }
+
+        println!(
+            "Capacity {}: uninstrumented accepted {}, instrumented accepted {}",
+            $capacity, plain_accepted, accepted
+        );
+
+        for _ in 0..accepted {
+            rx.recv().expect("Failed to receive");
+        }
+    }};
+}
+
+fn main() {
+    check_capacity!(1);
+    check_capacity!(5);
+
+    println!("\nBackpressure example completed!");
+}
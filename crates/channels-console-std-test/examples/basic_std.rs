@@ -1,11 +1,13 @@
 use std::thread;
 use std::time::Duration;
 
+#[cfg(feature = "channels-console")]
 struct Actor {
     name: String,
 }
 
 fn main() {
+    #[cfg(feature = "channels-console")]
     let actor1 = Actor {
         name: "Actor 1".to_string(),
     };
@@ -0,0 +1,40 @@
+use std::sync::{mpsc, Arc, Barrier};
+
+const THREADS: usize = 8;
+const MESSAGES: u32 = 50;
+
+#[allow(unused_variables)]
+fn main() {
+    // Every thread instruments its first channel at once, racing the collector's start
+    let barrier = Arc::new(Barrier::new(THREADS));
+    let threads: Vec<_> = (0..THREADS)
+        .map(|i| {
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                barrier.wait();
+                let (tx, rx) = mpsc::channel::<u32>();
+                #[cfg(feature = "channels-console")]
+                let (tx, rx) = channels_console::instrument!(
+                    (tx, rx),
+                    label = format!("worker-{i}"),
+                    log = true
+                );
+                for n in 0..MESSAGES {
+                    tx.send(n).expect("Failed to send");
+                }
+                for _ in 0..MESSAGES {
+                    rx.recv().expect("Failed to receive");
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().expect("Worker panicked");
+    }
+
+    #[cfg(feature = "channels-console")]
+    {
+        let stats = channels_console::snapshot();
+        println!("channels {}", stats.len());
+    }
+}
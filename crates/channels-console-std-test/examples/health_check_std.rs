@@ -0,0 +1,36 @@
+use std::sync::mpsc;
+
+fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(std::time::Duration::from_secs(1))
+        .exit_on_violation(|stats| stats.dropped_count == 0)
+        .build();
+
+    let (healthy_tx, healthy_rx) = mpsc::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (healthy_tx, healthy_rx) =
+        channels_console::instrument!((healthy_tx, healthy_rx), label = "healthy");
+    healthy_tx.send(1).expect("Failed to send");
+    healthy_rx.recv().expect("Failed to receive");
+
+    let (lossy_tx, lossy_rx) = mpsc::sync_channel::<u32>(5);
+    #[cfg(feature = "channels-console")]
+    let (lossy_tx, lossy_rx) =
+        channels_console::instrument!((lossy_tx, lossy_rx), label = "lossy", capacity = 5);
+    for n in 0..3 {
+        lossy_tx.send(n).expect("Failed to send");
+    }
+    lossy_rx.recv().expect("Failed to receive");
+    // The queued messages are lost with the receiver
+    drop(lossy_rx);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    #[cfg(feature = "channels-console")]
+    if let Err(unhealthy) = channels_console::check(|stats| stats.dropped_count == 0) {
+        for stats in unhealthy {
+            println!("unhealthy: {}", stats.label);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Messages sent, each after the channel sat idle for a while.
+const SAMPLES: usize = 20;
+
+/// Median time from sending a message to receiving it, when sent after a quiet period.
+fn idle_latency(tx: mpsc::SyncSender<Instant>, rx: mpsc::Receiver<Instant>) -> Duration {
+    let consumer = thread::spawn(move || {
+        let mut latencies: Vec<Duration> = rx.iter().map(|sent| sent.elapsed()).collect();
+        latencies.sort();
+        latencies[latencies.len() / 2]
+    });
+    for _ in 0..SAMPLES {
+        thread::sleep(Duration::from_millis(20));
+        tx.send(Instant::now()).expect("Failed to send");
+    }
+    drop(tx);
+    consumer.join().expect("Consumer panicked")
+}
+
+fn main() {
+    let (tx, rx) = mpsc::sync_channel::<Instant>(10);
+    let plain = idle_latency(tx, rx);
+
+    let (tx, rx) = mpsc::sync_channel::<Instant>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), capacity = 10);
+    let instrumented = idle_latency(tx, rx);
+
+    println!("Uninstrumented latency: {}us", plain.as_micros());
+    println!("Instrumented latency: {}us", instrumented.as_micros());
+}
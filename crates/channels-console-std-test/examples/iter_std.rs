@@ -2,11 +2,13 @@ use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
+#[cfg(feature = "channels-console")]
 struct Actor {
     name: String,
 }
 
 fn main() {
+    #[cfg(feature = "channels-console")]
     let actor1 = Actor {
         name: "Actor 1".to_string(),
     };
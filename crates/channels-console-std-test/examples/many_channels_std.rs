@@ -0,0 +1,54 @@
+use std::sync::mpsc;
+
+/// Threads of this process, `None` where `/proc` isn't available.
+fn thread_count() -> Option<usize> {
+    std::fs::read_dir("/proc/self/task")
+        .ok()
+        .map(|tasks| tasks.count())
+}
+
+fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        // Forwarders may report a message after it was received
+        .flush_timeout(std::time::Duration::from_secs(1))
+        .build();
+
+    // Starts the collector, the metrics server and a forwarder thread
+    let (tx, rx) = mpsc::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), label = "first");
+    tx.send(0).expect("Failed to send");
+    rx.recv().expect("Failed to receive");
+    let before = thread_count();
+
+    let mut channels = Vec::new();
+    for i in 0..250 {
+        let (tx, rx) = mpsc::channel::<u32>();
+        #[cfg(feature = "channels-console")]
+        let (tx, rx) = channels_console::instrument!((tx, rx));
+        tx.send(i).expect("Failed to send");
+        channels.push((tx, rx));
+    }
+    let mut sync_channels = Vec::new();
+    for i in 0..250 {
+        let (tx, rx) = mpsc::sync_channel::<u32>(1);
+        #[cfg(feature = "channels-console")]
+        let (tx, rx) = channels_console::instrument!((tx, rx), capacity = 1);
+        tx.send(i).expect("Failed to send");
+        sync_channels.push((tx, rx));
+    }
+
+    for (_, rx) in &channels {
+        rx.recv().expect("Failed to receive");
+    }
+    for (_, rx) in &sync_channels {
+        rx.recv().expect("Failed to receive");
+    }
+
+    match (before, thread_count()) {
+        (Some(before), Some(after)) => println!("extra threads: {}", after - before),
+        _ => println!("extra threads: unknown"),
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .build();
+
+    let (tx, rx) = std::sync::mpsc::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), mode = tap, label = "jobs");
+
+    // Nothing sent yet, the producer is stalled
+    for _ in 0..2 {
+        assert_eq!(
+            rx.recv_timeout(Duration::from_millis(10)),
+            Err(RecvTimeoutError::Timeout)
+        );
+    }
+
+    for i in 0..3 {
+        tx.send(i).expect("Failed to send");
+    }
+    for i in 0..3 {
+        assert_eq!(rx.recv_timeout(Duration::from_millis(10)), Ok(i));
+    }
+
+    drop(tx);
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+
+    // Tapped channels report inline, so a flush is enough for the guard to see every event
+    #[cfg(feature = "channels-console")]
+    channels_console::flush();
+}
@@ -0,0 +1,32 @@
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    let (tx, rx) = std::sync::mpsc::sync_channel::<i32>(0);
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), label = "rendezvous", capacity = 0);
+
+    let receiver_handle = thread::spawn(move || {
+        for _ in 1..=3 {
+            let msg = rx.recv().expect("Failed to receive");
+            println!("[Receiver] Received: {}", msg);
+        }
+        rx
+    });
+
+    for i in 1..=3 {
+        println!("[Sender] Sending: {}", i);
+        tx.send(i).expect("Failed to send");
+    }
+
+    let _rx = receiver_handle.join().unwrap();
+    drop(tx);
+
+    // Give the forwarders time to report the handoffs and the closed channel
+    thread::sleep(Duration::from_millis(100));
+
+    println!("\nRendezvous example completed!");
+}
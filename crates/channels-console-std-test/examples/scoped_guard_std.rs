@@ -0,0 +1,37 @@
+use std::sync::mpsc;
+
+fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(std::time::Duration::from_secs(1))
+        .build();
+
+    let (setup_tx, setup_rx) = mpsc::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (setup_tx, setup_rx) = channels_console::instrument!((setup_tx, setup_rx), label = "setup");
+    setup_tx.send(1).expect("Failed to send");
+    setup_rx.recv().expect("Failed to receive");
+
+    {
+        #[cfg(feature = "channels-console")]
+        let _phase_guard = channels_console::ChannelsGuardBuilder::new()
+            .format(channels_console::Format::JsonLines)
+            .flush_timeout(std::time::Duration::from_secs(1))
+            .scoped()
+            .build();
+
+        let (phase_tx, phase_rx) = mpsc::channel::<u32>();
+        #[cfg(feature = "channels-console")]
+        let (phase_tx, phase_rx) =
+            channels_console::instrument!((phase_tx, phase_rx), label = "phase");
+        for n in 0..3 {
+            phase_tx.send(n).expect("Failed to send");
+            phase_rx.recv().expect("Failed to receive");
+        }
+        // The setup channel is still used, but was instrumented before the phase
+        setup_tx.send(2).expect("Failed to send");
+        setup_rx.recv().expect("Failed to receive");
+    }
+    println!("phase done");
+}
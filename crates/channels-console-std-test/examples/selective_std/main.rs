@@ -0,0 +1,27 @@
+use std::sync::mpsc;
+
+mod net;
+
+fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(std::time::Duration::from_secs(1))
+        .build();
+
+    // Only instrumented when CHANNELS_CONSOLE_INSTRUMENT_PATHS matches this file
+    let (tx, rx) = mpsc::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), label = "main");
+    tx.send(1).expect("Failed to send");
+    rx.recv().expect("Failed to receive");
+
+    // Still wrapped outside the configured paths, but never counted or reported
+    let (tx, rx) = mpsc::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), mode = tap, label = "main-tap");
+    tx.send(1).expect("Failed to send");
+    rx.recv().expect("Failed to receive");
+
+    net::run();
+}
@@ -0,0 +1,11 @@
+use std::sync::mpsc;
+
+pub fn run() {
+    let (tx, rx) = mpsc::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), label = "net");
+    for n in 0..2 {
+        tx.send(n).expect("Failed to send");
+        rx.recv().expect("Failed to receive");
+    }
+}
@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn main() {
+    // The program's own handler, installed before the guard, shuts it down on Ctrl-C
+    let interrupted = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&interrupted))
+        .expect("Failed to register SIGINT handler");
+
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(Duration::from_secs(1))
+        .report_on_sigint()
+        .exit_on_violation(|stats| stats.received_count == stats.sent_count)
+        .build();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::instrument!((tx, rx), mode = tap, label = "interrupted");
+    for n in 0..3 {
+        tx.send(n).expect("Failed to send");
+    }
+    rx.recv().expect("Failed to receive");
+
+    // Runs until interrupted, then drops the guard
+    println!("ready");
+    while !interrupted.load(Ordering::SeqCst) {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
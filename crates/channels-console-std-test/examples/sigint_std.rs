@@ -0,0 +1,23 @@
+use std::sync::mpsc;
+use std::time::Duration;
+
+fn main() {
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(Duration::from_secs(1))
+        .report_on_sigint()
+        .build();
+
+    let (tx, rx) = mpsc::channel::<u32>();
+    let (tx, rx) = channels_console::instrument!((tx, rx), label = "interrupted");
+    for n in 0..3 {
+        tx.send(n).expect("Failed to send");
+    }
+    rx.recv().expect("Failed to receive");
+
+    // Runs until interrupted
+    println!("ready");
+    loop {
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::BufRead;
+use std::sync::mpsc;
+
+const SENDERS: u32 = 4;
+const MESSAGES_PER_SENDER: u32 = 25_000;
+
+#[allow(unused_mut)]
+fn main() {
+    let (tx, rx) = mpsc::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), mode = tap, label = "busy");
+
+    let senders: Vec<_> = (0..SENDERS)
+        .map(|_| {
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                for i in 0..MESSAGES_PER_SENDER {
+                    tx.send(i).expect("Failed to send");
+                }
+            })
+        })
+        .collect();
+    drop(tx);
+
+    let mut received = 0;
+    while rx.recv().is_ok() {
+        received += 1;
+    }
+    for sender in senders {
+        sender.join().expect("Sender panicked");
+    }
+    println!("received {}", received);
+
+    // Counts are batched, `snapshot` still sees every message
+    #[cfg(feature = "channels-console")]
+    {
+        let stats = channels_console::snapshot();
+        println!(
+            "busy sent {} received {}",
+            stats[0].sent_count, stats[0].received_count
+        );
+    }
+
+    // Fewer messages than a batch are reported by the collector shortly after
+    let (quiet_tx, quiet_rx) = mpsc::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (quiet_tx, quiet_rx) =
+        channels_console::instrument!((quiet_tx, quiet_rx), mode = tap, label = "quiet");
+    for i in 0..3 {
+        quiet_tx.send(i).expect("Failed to send");
+    }
+    quiet_rx.recv().expect("Failed to receive");
+
+    println!("ready");
+    // Keep the channels alive until stdin closes
+    for _ in std::io::stdin().lock().lines() {}
+}
@@ -0,0 +1,23 @@
+fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(std::time::Duration::from_millis(500))
+        .build();
+
+    // Instrumented without `capacity = 4`, which can't be read from the channel
+    let (tx, rx) = std::sync::mpsc::sync_channel::<u32>(4);
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), label = "no-capacity");
+
+    // Filling the original capacity before receiving must not block
+    for i in 0..4 {
+        tx.send(i).expect("Failed to send");
+    }
+    for _ in 0..4 {
+        let msg = rx.recv().expect("Failed to receive");
+        println!("[Receiver] Received: {}", msg);
+    }
+
+    println!("\nUnknown capacity example completed!");
+}
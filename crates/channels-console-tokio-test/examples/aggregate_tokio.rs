@@ -0,0 +1,29 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let mut workers = Vec::new();
+    for _ in 0..3 {
+        let (tx, rx) = tokio::sync::mpsc::channel::<usize>(10);
+        #[cfg(feature = "channels-console")]
+        let (tx, rx) = channels_console::instrument!((tx, rx), label = "worker");
+        workers.push((tx, rx));
+    }
+
+    let (control_tx, mut control_rx) = tokio::sync::mpsc::channel::<&str>(1);
+    #[cfg(feature = "channels-console")]
+    let (control_tx, mut control_rx) =
+        channels_console::instrument!((control_tx, control_rx), label = "control");
+
+    for (i, (tx, rx)) in workers.iter_mut().enumerate() {
+        for n in 0..=i {
+            tx.send(n).await.expect("Failed to send");
+            rx.recv().await.expect("Failed to receive");
+        }
+    }
+
+    control_tx.send("stop").await.expect("Failed to send");
+    control_rx.recv().await.expect("Failed to receive");
+
+    println!("Waiting for the metrics to be fetched...");
+    tokio::time::sleep(tokio::time::Duration::from_secs(6)).await;
+}
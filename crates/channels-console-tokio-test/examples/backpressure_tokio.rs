@@ -0,0 +1,74 @@
+use tokio::sync::mpsc::error::TrySendError;
+#[cfg(feature = "channels-console")]
+use tokio::time::{sleep, Duration};
+
+/// Capacities below 3 accept more messages once instrumented.
+const CAPACITIES: [usize; 3] = [1, 2, 5];
+
+/// Sends to a bounded channel without consuming until it's full and returns how many messages
+/// were accepted.
+fn fill(tx: &tokio::sync::mpsc::Sender<i32>) -> usize {
+    let mut accepted = 0;
+    loop {
+        match tx.try_send(accepted as i32) {
+            Ok(_) => accepted += 1,
+            Err(TrySendError::Full(_)) => return accepted,
+            Err(TrySendError::Closed(_)) => panic!("Channel closed"),
+        }
+    }
+}
+
+/// Fills the channel again while its forwarders keep moving messages out of the outer proxy,
+/// until it holds all it can.
+#[cfg(feature = "channels-console")]
+async fn top_up(tx: &tokio::sync::mpsc::Sender<i32>) -> usize {
+    let mut accepted = 0;
+    loop {
+        sleep(Duration::from_millis(50)).await;
+        match fill(tx) {
+            0 => return accepted,
+            more => accepted += more,
+        }
+    }
+}
+
+/// Prints how many messages a plain and an instrumented `channel(capacity)` accept.
+async fn check_capacity(capacity: usize) {
+    let (plain_tx, _plain_rx) = tokio::sync::mpsc::channel::<i32>(capacity);
+    let plain_accepted = fill(&plain_tx);
+
+    #[allow(unused_mut)]
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(capacity);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx));
+    #[allow(unused_mut)]
+    let mut accepted = fill(&tx);
+
+    // The forwarders may still be moving the first messages out of the outer proxy, so fill it
+    // up again until they hold one in the original channel and one in the inner proxy
+    #[cfg(feature = "channels-console")]
+    {
+        accepted += top_up(&tx).await;
+    }
+
+    println!(
+        "Capacity {}: uninstrumented accepted {}, instrumented accepted {}",
+        capacity, plain_accepted, accepted
+    );
+
+    for _ in 0..accepted {
+        rx.recv().await.expect("Failed to receive");
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    for capacity in CAPACITIES {
+        check_capacity(capacity).await;
+    }
+
+    println!("\nBackpressure example completed!");
+}
@@ -0,0 +1,34 @@
+#[allow(unused_mut)]
+fn spawn_worker() -> tokio::sync::mpsc::Sender<i32> {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) =
+        channels_console::instrument!((tx, rx), capture_backtrace = true, label = "worker");
+
+    tokio::spawn(async move { while rx.recv().await.is_some() {} });
+    tx
+}
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    let worker = spawn_worker();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "plain");
+
+    for i in 1..=5 {
+        worker.send(i).await.expect("Failed to send");
+        tx.send(i).await.expect("Failed to send");
+        rx.recv().await.expect("Failed to receive");
+    }
+
+    println!("Waiting for the backtrace to be fetched...");
+    tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+
+    println!("\nBacktrace example completed!");
+}
@@ -0,0 +1,20 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .flush_timeout(std::time::Duration::from_millis(500))
+        .build();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<u64>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "drained");
+
+    for i in 1..=5 {
+        tx.send(i).await.expect("Failed to send");
+    }
+    drop(tx);
+
+    // Every message is received, so nothing stays queued
+    while rx.recv().await.is_some() {}
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(Duration::from_secs(1))
+        .build();
+
+    // A slow consumer keeps this one full, tapped so that its queue depth is exact
+    let (busy_tx, mut busy_rx) = mpsc::channel::<u64>(8);
+    #[cfg(feature = "channels-console")]
+    let (busy_tx, mut busy_rx) =
+        channels_console::instrument!((busy_tx, busy_rx), mode = tap, label = "busy");
+
+    // Messages are received as soon as they're sent, so the queue is practically always empty
+    let (idle_tx, mut idle_rx) = mpsc::channel::<u64>(64);
+    #[cfg(feature = "channels-console")]
+    let (idle_tx, mut idle_rx) = channels_console::instrument!((idle_tx, idle_rx), label = "idle");
+
+    // Never more than two messages queued, so most of the capacity goes unused
+    let (bursty_tx, mut bursty_rx) = mpsc::channel::<u64>(64);
+    #[cfg(feature = "channels-console")]
+    let (bursty_tx, mut bursty_rx) =
+        channels_console::instrument!((bursty_tx, bursty_rx), mode = tap, label = "bursty");
+
+    // Too few samples for a recommendation
+    let (rare_tx, mut rare_rx) = mpsc::channel::<u64>(8);
+    #[cfg(feature = "channels-console")]
+    let (rare_tx, mut rare_rx) = channels_console::instrument!((rare_tx, rare_rx), label = "rare");
+
+    let producer = tokio::spawn(async move {
+        for n in 0..200 {
+            busy_tx.send(n).await.expect("Failed to send");
+        }
+    });
+    for _ in 0..200 {
+        busy_rx.recv().await.expect("Failed to receive");
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    producer.await.expect("Producer failed");
+
+    for n in 0..200 {
+        idle_tx.send(n).await.expect("Failed to send");
+        idle_rx.recv().await.expect("Failed to receive");
+    }
+
+    for n in 0..100 {
+        bursty_tx.send(n).await.expect("Failed to send");
+        bursty_tx.send(n).await.expect("Failed to send");
+        bursty_rx.recv().await.expect("Failed to receive");
+        bursty_rx.recv().await.expect("Failed to receive");
+    }
+
+    rare_tx.send(1).await.expect("Failed to send");
+    rare_rx.recv().await.expect("Failed to receive");
+}
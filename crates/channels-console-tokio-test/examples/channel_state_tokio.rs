@@ -0,0 +1,50 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<u32>(2);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), mode = tap, label = "jobs");
+
+    let (events_tx, _events_rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (events_tx, _events_rx) =
+        channels_console::instrument!((events_tx, _events_rx), label = "events");
+
+    events_tx.send(0).expect("Failed to send");
+
+    #[cfg(feature = "channels-console")]
+    let id = {
+        channels_console::flush();
+        let channels = channels_console::list_channels();
+        for (id, label, state) in &channels {
+            println!("channel #{id} {label} {state}");
+        }
+        channels
+            .iter()
+            .find(|(_, label, _)| label == "jobs")
+            .map(|(id, _, _)| *id)
+            .expect("Expected the jobs channel to be listed")
+    };
+
+    for i in 0..2 {
+        tx.send(i).await.expect("Failed to send");
+    }
+
+    // Shed load while the consumer can't keep up
+    #[cfg(feature = "channels-console")]
+    {
+        channels_console::flush();
+        if channels_console::channel_state(id) == Some(channels_console::ChannelState::Full) {
+            println!("jobs is full, shedding load");
+        }
+    }
+
+    rx.recv().await.expect("Failed to receive");
+
+    #[cfg(feature = "channels-console")]
+    {
+        channels_console::flush();
+        println!("jobs is {:?}", channels_console::channel_state(id));
+        println!("unknown is {:?}", channels_console::channel_state(u64::MAX));
+    }
+}
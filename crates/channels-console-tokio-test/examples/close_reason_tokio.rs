@@ -0,0 +1,28 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    // The producer finishes and drops its sender
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<u32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "producer-done");
+
+    tx.send(1).await.expect("Failed to send");
+    drop(tx);
+    while let Some(value) = rx.recv().await {
+        println!("Received {value}");
+    }
+
+    // The consumer gives up and drops its receiver
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), label = "consumer-gone");
+
+    drop(rx);
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    if tx.send(1).is_err() {
+        println!("Receiver is gone");
+    }
+
+    println!("Waiting for the metrics to be fetched...");
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+}
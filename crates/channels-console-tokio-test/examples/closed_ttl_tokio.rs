@@ -0,0 +1,31 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    let (tx_long, mut rx_long) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx_long, mut rx_long) =
+        channels_console::instrument!((tx_long, rx_long), label = "long-lived");
+
+    let (tx_short, mut rx_short) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx_short, mut rx_short) =
+        channels_console::instrument!((tx_short, rx_short), label = "short-lived");
+
+    tx_short.send(1).await.expect("Failed to send");
+    rx_short.recv().await.expect("Failed to receive");
+    drop(tx_short);
+    drop(rx_short);
+
+    // Outlive the closed channel retention period
+    tokio::time::sleep(tokio::time::Duration::from_millis(600)).await;
+
+    tx_long.send(1).await.expect("Failed to send");
+    rx_long.recv().await.expect("Failed to receive");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    println!("\nClosed TTL example completed!");
+}
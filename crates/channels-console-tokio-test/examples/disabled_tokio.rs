@@ -0,0 +1,23 @@
+#[tokio::main]
+async fn main() {
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    // No `cfg` needed, with the `disabled` feature these return the channels unchanged
+    let (tx, rx) = tokio::sync::mpsc::channel::<i32>(10);
+    let (tx, mut rx) =
+        channels_console::instrument!((tx, rx), mode = tap, label = "not-instrumented");
+
+    let (otx, orx) = tokio::sync::oneshot::channel::<i32>();
+    let (otx, orx) = channels_console::instrument!((otx, orx), log = true);
+
+    tx.send(1).await.expect("Failed to send");
+    rx.recv().await.expect("Failed to receive");
+    otx.send(2).expect("Failed to send oneshot");
+    orx.await.expect("Failed to receive oneshot");
+
+    println!(
+        "instrumented channels: {}, metrics server: {:?}",
+        channels_console::snapshot().len(),
+        channels_console::metrics_server_status()
+    );
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .build();
+
+    let (txa, mut rxa) = tokio::sync::mpsc::channel::<i32>(10);
+
+    #[cfg(feature = "channels-console")]
+    let (txa, mut rxa) = channels_console::instrument!((txa, rxa), label = "drained");
+
+    let (txb, mut rxb) = tokio::sync::mpsc::channel::<i32>(10);
+
+    #[cfg(feature = "channels-console")]
+    let (txb, mut rxb) = channels_console::instrument!((txb, rxb), label = "stranded");
+
+    for i in 1..=5 {
+        txa.send(i).await.expect("Failed to send");
+        txb.send(i).await.expect("Failed to send");
+    }
+    drop(txa);
+
+    // Drain the first channel until it closes
+    while let Some(msg) = rxa.recv().await {
+        println!("[Drained] Received: {}", msg);
+    }
+
+    // Give up on the second channel after a single message
+    if let Some(msg) = rxb.recv().await {
+        println!("[Stranded] Received: {}", msg);
+    }
+    drop(rxb);
+    println!("[Stranded] Receiver dropped");
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    drop(txb);
+}
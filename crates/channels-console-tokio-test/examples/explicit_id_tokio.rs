@@ -0,0 +1,40 @@
+#[allow(unused_mut, unused_variables)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(std::time::Duration::from_secs(1))
+        .build();
+
+    let mut senders = Vec::new();
+    for i in 0..3 {
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<usize>(10);
+        #[cfg(feature = "channels-console")]
+        let (tx, mut rx) = channels_console::instrument!((tx, rx), id = "ingest.worker.{i}");
+
+        tokio::spawn(async move { while rx.recv().await.is_some() {} });
+        senders.push(tx);
+    }
+
+    // Channels created by a factory share its source location
+    let prefix = "pool";
+    let make = |name: &str| {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<usize>();
+        #[cfg(feature = "channels-console")]
+        let (tx, rx) = channels_console::instrument!(
+            (tx, rx),
+            id = format!("{prefix}.{name}"),
+            label = name.to_uppercase()
+        );
+        (tx, rx)
+    };
+    let (pool_tx, _pool_rx) = make("conn");
+
+    for (i, tx) in senders.iter().enumerate() {
+        for n in 0..=i {
+            tx.send(n).await.expect("Failed to send");
+        }
+    }
+    pool_tx.send(1).expect("Failed to send");
+}
@@ -0,0 +1,33 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<u32>(200);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), mode = tap, label = "flushed");
+
+    for i in 1..=100 {
+        tx.send(i).await.expect("Failed to send");
+
+        // Without a flush, the collector may not have seen the send yet
+        #[cfg(feature = "channels-console")]
+        {
+            let stats = channels_console::snapshot();
+            assert_eq!(stats[0].sent_count, i as u64);
+        }
+    }
+
+    while let Ok(msg) = rx.try_recv() {
+        if msg == 100 {
+            break;
+        }
+    }
+
+    #[cfg(feature = "channels-console")]
+    {
+        let stats = channels_console::snapshot();
+        println!(
+            "sent {} received {}",
+            stats[0].sent_count, stats[0].received_count
+        );
+    }
+}
@@ -0,0 +1,42 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .build();
+
+    let (txa, mut rxa) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (txa, mut rxa) =
+        channels_console::instrument!((txa, rxa), group = "processing", label = "transform");
+
+    let (txb, mut rxb) = tokio::sync::mpsc::unbounded_channel::<i32>();
+    #[cfg(feature = "channels-console")]
+    let (txb, mut rxb) = channels_console::instrument!((txb, rxb), label = "metrics");
+
+    let (txc, mut rxc) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (txc, mut rxc) =
+        channels_console::instrument!((txc, rxc), group = "ingest", label = "raw-events");
+
+    let (txd, mut rxd) = tokio::sync::mpsc::unbounded_channel::<i32>();
+    #[cfg(feature = "channels-console")]
+    let (txd, mut rxd) = channels_console::instrument!((txd, rxd), group = "ingest", log = true);
+
+    for i in 1..=3 {
+        txa.send(i).await.expect("Failed to send");
+        txb.send(i).expect("Failed to send");
+        txc.send(i).await.expect("Failed to send");
+        txd.send(i).expect("Failed to send");
+    }
+
+    for _ in 1..=3 {
+        rxa.recv().await.expect("Failed to receive");
+        rxb.recv().await.expect("Failed to receive");
+        rxc.recv().await.expect("Failed to receive");
+        rxd.recv().await.expect("Failed to receive");
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+}
@@ -0,0 +1,33 @@
+#[allow(unused_mut, unused_variables)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .build();
+
+    let (backlog_tx, backlog_rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (backlog_tx, _backlog_rx) =
+        channels_console::instrument!((backlog_tx, backlog_rx), mode = tap, label = "backlog");
+
+    let (drained_tx, mut drained_rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (drained_tx, mut drained_rx) =
+        channels_console::instrument!((drained_tx, drained_rx), mode = tap, label = "drained");
+
+    // Proxies move messages into the unbounded channel handed out right away, so only tapped
+    // channels see its backlog. The consumer of the backlog is stalled, the other one keeps up.
+    for i in 0..20 {
+        backlog_tx.send(i).expect("Failed to send");
+        drained_tx.send(i).expect("Failed to send");
+        drained_rx.recv().await.expect("Failed to receive");
+    }
+
+    // Report while the channels are still open
+    #[cfg(feature = "channels-console")]
+    {
+        channels_console::flush();
+        drop(channels_guard);
+    }
+}
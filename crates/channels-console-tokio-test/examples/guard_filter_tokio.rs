@@ -0,0 +1,37 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .only_label_prefix("worker")
+        .only_state(channels_console::ChannelState::Active)
+        .build();
+
+    let (tx1, mut rx1) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx1, mut rx1) = channels_console::instrument!((tx1, rx1), label = "worker-1");
+
+    let (tx2, rx2) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx2, rx2) = channels_console::instrument!((tx2, rx2), label = "worker-2");
+
+    let (tx3, mut rx3) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx3, mut rx3) = channels_console::instrument!((tx3, rx3), label = "scheduler");
+
+    tx1.send(1).await.expect("Failed to send");
+    tx3.send(3).await.expect("Failed to send");
+    println!("[worker-1] Received: {:?}", rx1.recv().await);
+    println!("[scheduler] Received: {:?}", rx3.recv().await);
+
+    // Close the second worker channel, so it's filtered out by state
+    drop(rx2);
+    drop(tx2);
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    // Report while the remaining channels are still open
+    #[cfg(feature = "channels-console")]
+    drop(_channels_guard);
+}
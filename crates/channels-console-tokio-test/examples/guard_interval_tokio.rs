@@ -0,0 +1,24 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::Json)
+        .interval(std::time::Duration::from_millis(200))
+        .build();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "ticks");
+
+    // Keep sending for a few report intervals
+    for i in 0..10 {
+        tx.send(i).await.expect("Failed to send");
+        rx.recv().await.expect("Failed to receive");
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+
+    // The final report follows the periodic ones
+    #[cfg(feature = "channels-console")]
+    drop(_channels_guard);
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "channels-console")]
 struct Actor {
     name: String,
 }
@@ -5,6 +6,7 @@ struct Actor {
 #[allow(unused_mut)]
 #[tokio::main]
 async fn main() {
+    #[cfg(feature = "channels-console")]
     let actor1 = Actor {
         name: "Actor 1".to_string(),
     };
@@ -0,0 +1,27 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(std::time::Duration::from_secs(1))
+        .build();
+
+    let (txa, mut rxa) = tokio::sync::mpsc::unbounded_channel::<i32>();
+    #[cfg(feature = "channels-console")]
+    let (txa, mut rxa) = channels_console::instrument!((txa, rxa), label = "lines-unbounded");
+
+    let (txb, mut rxb) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (txb, mut rxb) = channels_console::instrument!((txb, rxb), label = "lines-bounded");
+
+    for i in 1..=3 {
+        txa.send(i).expect("Failed to send");
+        txb.send(i).await.expect("Failed to send");
+    }
+
+    for _ in 1..=3 {
+        rxa.recv().await.expect("Failed to receive");
+        rxb.recv().await.expect("Failed to receive");
+    }
+}
@@ -0,0 +1,31 @@
+use serde::Serialize;
+
+/// Logged as JSON, without implementing `Debug`
+#[derive(Serialize)]
+struct Order {
+    id: u64,
+    customer: String,
+    items: Vec<&'static str>,
+}
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<Order>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), log = json, label = "orders");
+
+    for id in 1..=2 {
+        let order = Order {
+            id,
+            customer: format!("customer-{id}"),
+            items: vec!["apple", "pear"],
+        };
+        tx.send(order).await.expect("Failed to send");
+        let order = rx.recv().await.expect("Failed to receive");
+        println!("Received order {} of {}", order.id, order.customer);
+    }
+
+    println!("Waiting for the logs to be fetched...");
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .build();
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<i32>();
+
+    // Tap mode observes the channel itself, so latency includes the whole time spent queued
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), mode = tap, label = "backlog");
+
+    // Queue up a burst, so each message waits longer than the one before it
+    for i in 1..=20 {
+        tx.send(i).expect("Failed to send");
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    for _ in 1..=20 {
+        let msg = rx.recv().await.expect("Failed to receive");
+        println!("[Backlog] Received: {}", msg);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+}
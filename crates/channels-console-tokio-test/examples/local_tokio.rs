@@ -0,0 +1,53 @@
+use std::rc::Rc;
+use tokio::sync::mpsc;
+use tokio::task::LocalSet;
+
+#[derive(Debug)]
+struct Event {
+    name: Rc<str>,
+}
+
+#[allow(unused_mut)]
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(std::time::Duration::from_secs(1))
+        .build();
+
+    // Rc isn't Send, so these channels can't be forwarded on the shared runtime
+    LocalSet::new()
+        .run_until(async {
+            let (events_tx, mut events_rx) = mpsc::channel::<Event>(10);
+            #[cfg(feature = "channels-console")]
+            let (events_tx, mut events_rx) = channels_console::instrument_local!(
+                (events_tx, events_rx),
+                label = "events",
+                log = true
+            );
+
+            let (names_tx, mut names_rx) = mpsc::unbounded_channel::<Rc<str>>();
+            #[cfg(feature = "channels-console")]
+            let (names_tx, mut names_rx) =
+                channels_console::instrument_local!((names_tx, names_rx), label = "names");
+
+            let producer = tokio::task::spawn_local(async move {
+                for name in ["start", "tick", "stop"] {
+                    events_tx
+                        .send(Event { name: name.into() })
+                        .await
+                        .expect("Failed to send");
+                }
+            });
+
+            while let Some(event) = events_rx.recv().await {
+                names_tx.send(event.name).expect("Failed to send");
+            }
+            producer.await.expect("Producer failed");
+
+            drop(names_tx);
+            while names_rx.recv().await.is_some() {}
+        })
+        .await;
+}
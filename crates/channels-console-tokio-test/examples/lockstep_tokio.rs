@@ -0,0 +1,43 @@
+use std::sync::{Arc, Mutex};
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let transitions = Arc::new(Mutex::new(Vec::<String>::new()));
+
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .flush_timeout(std::time::Duration::from_secs(1))
+        .build();
+
+    #[cfg(feature = "channels-console")]
+    {
+        let transitions = Arc::clone(&transitions);
+        channels_console::on_state_change(move |_id, label, old, new| {
+            transitions
+                .lock()
+                .unwrap()
+                .push(format!("{label}: {old} -> {new}"));
+        });
+    }
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<u64>(4);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), mode = tap, label = "lockstep");
+
+    // Never more than one message queued, across many batches of counts
+    for n in 0..1000 {
+        tx.send(n).await.expect("Failed to send");
+        rx.recv().await.expect("Failed to receive");
+    }
+    drop(tx);
+    assert!(rx.recv().await.is_none());
+
+    #[cfg(feature = "channels-console")]
+    channels_console::flush();
+
+    for transition in transitions.lock().unwrap().iter() {
+        println!("{transition}");
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static FORMATTED: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts how often it's formatted, to check that unsampled messages are skipped
+struct Job(u32);
+
+impl std::fmt::Debug for Job {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        FORMATTED.fetch_add(1, Ordering::Relaxed);
+        write!(f, "Job({})", self.0)
+    }
+}
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<Job>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) =
+        channels_console::instrument!((tx, rx), log = true, sample = 10, label = "sampled");
+
+    for i in 1..=50 {
+        tx.send(Job(i)).await.expect("Failed to send");
+        rx.recv().await.expect("Failed to receive");
+    }
+
+    println!("Waiting for the logs to be fetched...");
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+    #[cfg(feature = "channels-console")]
+    assert_eq!(FORMATTED.load(Ordering::Relaxed), 5);
+    println!("formatted {} of 50", FORMATTED.load(Ordering::Relaxed));
+}
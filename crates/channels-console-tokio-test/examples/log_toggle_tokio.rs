@@ -0,0 +1,36 @@
+use std::io::BufRead;
+
+/// Prints the current step and blocks until a line arrives on stdin,
+/// so a test can toggle logging between steps.
+fn step(name: &str) {
+    println!("step: {}", name);
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .expect("Failed to read stdin");
+}
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "toggled", log = true);
+
+    // Give the collector time to process events before each step
+    let settle = || tokio::time::sleep(tokio::time::Duration::from_millis(100));
+
+    for burst in ["first", "muted", "last"] {
+        for i in 1..=2 {
+            tx.send(format!("{}-{}", burst, i))
+                .await
+                .expect("Failed to send");
+            rx.recv().await.expect("Failed to receive");
+        }
+        settle().await;
+        step(burst);
+    }
+
+    println!("\nLog toggle example completed!");
+}
@@ -0,0 +1,19 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::Markdown)
+        .build();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "md|bounded");
+
+    for i in 1..=3 {
+        tx.send(i).await.expect("Failed to send");
+    }
+    rx.recv().await.expect("Failed to receive");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+}
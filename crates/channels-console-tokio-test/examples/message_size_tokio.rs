@@ -0,0 +1,44 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    let (signal_tx, mut signal_rx) = tokio::sync::mpsc::channel::<()>(10);
+    #[cfg(feature = "channels-console")]
+    let (signal_tx, mut signal_rx) =
+        channels_console::instrument!((signal_tx, signal_rx), label = "signals");
+
+    let (payload_tx, mut payload_rx) = tokio::sync::mpsc::channel::<String>(10);
+    #[cfg(feature = "channels-console")]
+    let (payload_tx, mut payload_rx) = channels_console::instrument!(
+        (payload_tx, payload_rx),
+        size_hint = 128,
+        label = "payloads"
+    );
+
+    let (buffer_tx, mut buffer_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(10);
+    #[cfg(feature = "channels-console")]
+    let (buffer_tx, mut buffer_rx) =
+        channels_console::instrument!((buffer_tx, buffer_rx), size = dynamic, label = "buffers");
+
+    for i in 1..=3 {
+        signal_tx.send(()).await.expect("Failed to send");
+        payload_tx
+            .send("x".repeat(100 + i))
+            .await
+            .expect("Failed to send");
+        buffer_tx
+            .send(Vec::with_capacity(1000 * i))
+            .await
+            .expect("Failed to send");
+    }
+
+    for _ in 1..=3 {
+        signal_rx.recv().await.expect("Failed to receive");
+        payload_rx.recv().await.expect("Failed to receive");
+        buffer_rx.recv().await.expect("Failed to receive");
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+}
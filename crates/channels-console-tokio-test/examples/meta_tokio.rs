@@ -0,0 +1,47 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let (charges_tx, mut charges_rx) = tokio::sync::mpsc::channel::<u64>(10);
+    #[cfg(feature = "channels-console")]
+    let (charges_tx, mut charges_rx) = channels_console::instrument!(
+        (charges_tx, charges_rx),
+        meta = [("team", "payments"), ("criticality", "high")],
+        label = "charges"
+    );
+
+    let (refunds_tx, mut refunds_rx) = tokio::sync::mpsc::channel::<u64>(10);
+    #[cfg(feature = "channels-console")]
+    let (refunds_tx, mut refunds_rx) = channels_console::instrument!(
+        (refunds_tx, refunds_rx),
+        meta = [("team", "payments"), ("criticality", "low")],
+        group = "billing",
+        label = "refunds"
+    );
+
+    let (emails_tx, mut emails_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    #[cfg(feature = "channels-console")]
+    let (emails_tx, mut emails_rx) = channels_console::instrument!(
+        (emails_tx, emails_rx),
+        meta = [("team", "notifications")],
+        label = "emails"
+    );
+
+    let (plain_tx, mut plain_rx) = tokio::sync::mpsc::channel::<()>(1);
+    #[cfg(feature = "channels-console")]
+    let (plain_tx, mut plain_rx) =
+        channels_console::instrument!((plain_tx, plain_rx), label = "plain");
+
+    charges_tx.send(100).await.expect("Failed to send");
+    charges_rx.recv().await.expect("Failed to receive");
+    refunds_tx.send(20).await.expect("Failed to send");
+    refunds_rx.recv().await.expect("Failed to receive");
+    emails_tx
+        .send("receipt".to_string())
+        .expect("Failed to send");
+    emails_rx.recv().await.expect("Failed to receive");
+    plain_tx.send(()).await.expect("Failed to send");
+    plain_rx.recv().await.expect("Failed to receive");
+
+    println!("Waiting for the metrics to be fetched...");
+    tokio::time::sleep(tokio::time::Duration::from_secs(6)).await;
+}
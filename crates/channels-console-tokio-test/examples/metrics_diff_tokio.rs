@@ -0,0 +1,39 @@
+use std::io::BufRead;
+
+/// Prints the current step and blocks until a line arrives on stdin,
+/// so a test can poll `/metrics/diff` between steps.
+fn step(name: &str) {
+    println!("step: {}", name);
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .expect("Failed to read stdin");
+}
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let (busy_tx, mut busy_rx) = tokio::sync::mpsc::channel::<u32>(10);
+    #[cfg(feature = "channels-console")]
+    let (busy_tx, mut busy_rx) = channels_console::instrument!((busy_tx, busy_rx), label = "busy");
+
+    let (idle_tx, _idle_rx) = tokio::sync::mpsc::channel::<u32>(10);
+    #[cfg(feature = "channels-console")]
+    let (idle_tx, _idle_rx) = channels_console::instrument!((idle_tx, _idle_rx), label = "idle");
+
+    // Give the collector time to process events before each step
+    let settle = || tokio::time::sleep(tokio::time::Duration::from_millis(100));
+
+    idle_tx.send(0).await.expect("Failed to send");
+    for burst in [3, 2, 0] {
+        for i in 0..burst {
+            busy_tx.send(i).await.expect("Failed to send");
+            busy_rx.recv().await.expect("Failed to receive");
+        }
+        settle().await;
+        step(&format!("sent {}", burst));
+    }
+
+    println!("\nMetrics diff example completed!");
+}
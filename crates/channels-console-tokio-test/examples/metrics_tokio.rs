@@ -0,0 +1,68 @@
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Stands in for a Prometheus recorder, keeping the latest value of each metric
+#[derive(Default)]
+struct MemoryRecorder {
+    values: Mutex<BTreeMap<String, Arc<AtomicU64>>>,
+}
+
+impl MemoryRecorder {
+    fn value(&self, key: &Key) -> Arc<AtomicU64> {
+        let labels: Vec<String> = key
+            .labels()
+            .map(|label| format!("{}=\"{}\"", label.key(), label.value()))
+            .collect();
+        let name = format!("{}{{{}}}", key.name(), labels.join(","));
+        self.values.lock().unwrap().entry(name).or_default().clone()
+    }
+}
+
+impl Recorder for MemoryRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.value(key))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.value(key))
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let recorder: &'static MemoryRecorder = Box::leak(Box::default());
+    metrics::set_global_recorder(recorder).expect("Failed to install recorder");
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<i32>(10);
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "metrics-channel");
+
+    for i in 1..=5 {
+        tx.send(i).await.expect("Failed to send");
+    }
+    drop(tx);
+    while let Some(msg) = rx.recv().await {
+        println!("[Receiver] Received: {}", msg);
+    }
+
+    channels_console::flush();
+
+    for (name, value) in recorder.values.lock().unwrap().iter() {
+        let value = value.load(Ordering::Relaxed);
+        if name.starts_with("channel_queued") {
+            // Gauges store the bits of an f64
+            println!("{} {}", name, f64::from_bits(value));
+        } else {
+            println!("{} {}", name, value);
+        }
+    }
+}
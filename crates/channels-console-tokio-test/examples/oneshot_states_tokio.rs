@@ -0,0 +1,87 @@
+use std::io::BufRead;
+
+/// Prints the current step and blocks until a line arrives on stdin,
+/// so a test can inspect the metrics endpoint between steps.
+fn step(name: &str) {
+    println!("step: {}", name);
+    let mut line = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .expect("Failed to read stdin");
+}
+
+#[tokio::main]
+async fn main() {
+    let (tx, rx) = tokio::sync::oneshot::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tx, rx) = channels_console::instrument!((tx, rx), label = "oneshot-delivered");
+
+    let (dropped_tx, dropped_rx) = tokio::sync::oneshot::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (dropped_tx, dropped_rx) =
+        channels_console::instrument!((dropped_tx, dropped_rx), label = "oneshot-dropped");
+
+    let (abandoned_tx, abandoned_rx) = tokio::sync::oneshot::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (abandoned_tx, abandoned_rx) =
+        channels_console::instrument!((abandoned_tx, abandoned_rx), label = "oneshot-abandoned");
+
+    // The same channels with an explicit `mode = tap`, which oneshots use by default
+    let (tapped_tx, tapped_rx) = tokio::sync::oneshot::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tapped_tx, tapped_rx) = channels_console::instrument!(
+        (tapped_tx, tapped_rx),
+        mode = tap,
+        label = "tapped-delivered"
+    );
+
+    let (tapped_dropped_tx, tapped_dropped_rx) = tokio::sync::oneshot::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tapped_dropped_tx, tapped_dropped_rx) = channels_console::instrument!(
+        (tapped_dropped_tx, tapped_dropped_rx),
+        mode = tap,
+        label = "tapped-dropped"
+    );
+
+    let (tapped_abandoned_tx, tapped_abandoned_rx) = tokio::sync::oneshot::channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (tapped_abandoned_tx, tapped_abandoned_rx) = channels_console::instrument!(
+        (tapped_abandoned_tx, tapped_abandoned_rx),
+        mode = tap,
+        label = "tapped-abandoned"
+    );
+
+    // Give the collector time to process events before each step
+    let settle = || tokio::time::sleep(tokio::time::Duration::from_millis(100));
+
+    settle().await;
+    step("created");
+
+    tx.send(42).expect("Failed to send oneshot");
+    tapped_tx.send(42).expect("Failed to send oneshot");
+    settle().await;
+    step("sent");
+
+    let value = rx.await.expect("Failed to receive oneshot");
+    println!("[Oneshot] Received: {}", value);
+    assert_eq!(tapped_rx.await.expect("Failed to receive oneshot"), value);
+    settle().await;
+    step("received");
+
+    drop(dropped_tx);
+    assert!(dropped_rx.await.is_err());
+    drop(tapped_dropped_tx);
+    assert!(tapped_dropped_rx.await.is_err());
+    settle().await;
+    step("dropped");
+
+    drop(abandoned_rx);
+    drop(tapped_abandoned_rx);
+    settle().await;
+    assert!(abandoned_tx.send(7).is_err());
+    assert!(tapped_abandoned_tx.send(7).is_err());
+    step("abandoned");
+
+    println!("\nOneshot states example completed!");
+}
@@ -0,0 +1,99 @@
+use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
+
+const PRODUCERS: u64 = 4;
+const MESSAGES: u64 = 2_500;
+
+/// Messages of each producer must arrive in the order it sent them
+fn check_order(received: &[(u64, u64)]) -> Result<(), String> {
+    let mut next = [0; PRODUCERS as usize];
+    for &(producer, seq) in received {
+        let expected = &mut next[producer as usize];
+        if seq != *expected {
+            return Err(format!(
+                "producer {producer} sent {expected} but {seq} arrived"
+            ));
+        }
+        *expected += 1;
+    }
+    Ok(())
+}
+
+fn report(name: &str, received: &[(u64, u64)]) {
+    match check_order(received) {
+        Ok(()) => println!(
+            "{name}: received {} of {} in order",
+            received.len(),
+            PRODUCERS * MESSAGES
+        ),
+        Err(e) => println!("{name}: out of order, {e}"),
+    }
+}
+
+async fn produce_bounded(tx: Sender<(u64, u64)>, producer: u64) {
+    for seq in 0..MESSAGES {
+        tx.send((producer, seq)).await.expect("Failed to send");
+        // Vary the interleaving of producers and forwarders
+        if seq % 97 == 0 {
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+async fn produce_unbounded(tx: UnboundedSender<(u64, u64)>, producer: u64) {
+    for seq in 0..MESSAGES {
+        tx.send((producer, seq)).expect("Failed to send");
+        if seq % 97 == 0 {
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+/// Receives until all senders are dropped, pausing now and then so the channel fills up
+async fn consume_bounded(mut rx: Receiver<(u64, u64)>) -> Vec<(u64, u64)> {
+    let mut received = Vec::new();
+    while let Some(msg) = rx.recv().await {
+        received.push(msg);
+        if received.len() % 500 == 0 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(5)).await;
+        }
+    }
+    received
+}
+
+async fn consume_unbounded(mut rx: UnboundedReceiver<(u64, u64)>) -> Vec<(u64, u64)> {
+    let mut received = Vec::new();
+    while let Some(msg) = rx.recv().await {
+        received.push(msg);
+        if received.len() % 500 == 0 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(5)).await;
+        }
+    }
+    received
+}
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<(u64, u64)>(8);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "ordered-bounded");
+
+    let consumer = tokio::spawn(consume_bounded(rx));
+    for producer in 0..PRODUCERS {
+        tokio::spawn(produce_bounded(tx.clone(), producer));
+    }
+    // The channel closes once the producers are done, with messages still in flight
+    drop(tx);
+    report("bounded", &consumer.await.expect("Consumer panicked"));
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<(u64, u64)>();
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "ordered-unbounded");
+
+    let consumer = tokio::spawn(consume_unbounded(rx));
+    for producer in 0..PRODUCERS {
+        tokio::spawn(produce_unbounded(tx.clone(), producer));
+    }
+    drop(tx);
+    report("unbounded", &consumer.await.expect("Consumer panicked"));
+}
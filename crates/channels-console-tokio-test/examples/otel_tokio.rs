@@ -0,0 +1,45 @@
+use opentelemetry::metrics::MeterProvider;
+use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData};
+use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+
+#[tokio::main]
+async fn main() {
+    // Stands in for an OTLP exporter
+    let exporter = InMemoryMetricExporter::default();
+    let provider = SdkMeterProvider::builder()
+        .with_reader(PeriodicReader::builder(exporter.clone()).build())
+        .build();
+    channels_console::register_otel_metrics(&provider.meter("otel-example"));
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<i32>(10);
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "otel-channel");
+
+    for i in 1..=5 {
+        tx.send(i).await.expect("Failed to send");
+    }
+    drop(tx);
+    while let Some(msg) = rx.recv().await {
+        println!("[Receiver] Received: {}", msg);
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    provider.force_flush().expect("Failed to flush metrics");
+
+    let resource_metrics = exporter
+        .get_finished_metrics()
+        .expect("Failed to read metrics");
+    let Some(last) = resource_metrics.last() else {
+        return;
+    };
+    for metric in last.scope_metrics().flat_map(|scope| scope.metrics()) {
+        let AggregatedMetrics::U64(data) = metric.data() else {
+            continue;
+        };
+        let values: Vec<u64> = match data {
+            MetricData::Sum(sum) => sum.data_points().map(|p| p.value()).collect(),
+            MetricData::Gauge(gauge) => gauge.data_points().map(|p| p.value()).collect(),
+            _ => continue,
+        };
+        println!("{} {:?}", metric.name(), values);
+    }
+}
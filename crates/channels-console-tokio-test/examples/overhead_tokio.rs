@@ -0,0 +1,107 @@
+//! Measures the cost of instrumenting a Tokio bounded channel.
+//!
+//! Run in release mode, optionally with the number of messages to send:
+//! `cargo run --release -p channels-console-tokio-test --example overhead_tokio --features channels-console -- 1000000`
+use std::time::{Duration, Instant};
+
+const CAPACITY: usize = 100;
+const DEFAULT_MESSAGES: u64 = 200_000;
+/// Round trips timed for the latency, each one waiting for the previous message to arrive
+const LATENCY_SAMPLES: usize = 10_000;
+
+struct Measurement {
+    /// Messages per second with a producer and consumer running concurrently
+    throughput: f64,
+    /// Median time from `send` until `recv` returned the message
+    p50_latency: Duration,
+    p99_latency: Duration,
+}
+
+/// Times sending `messages` through the channel, then the latency of single messages. The
+/// consumer acknowledges each latency sample on a plain channel, so the queue is empty when the
+/// next one is sent.
+macro_rules! measure {
+    ($tx:expr, $rx:expr, $messages:expr) => {{
+        let tx = $tx;
+        let mut rx = $rx;
+        let messages: u64 = $messages;
+        let (ack_tx, mut ack_rx) = tokio::sync::mpsc::channel::<Duration>(1);
+
+        let consumer = tokio::spawn(async move {
+            let mut start = None;
+            for _ in 0..messages {
+                start = Some(rx.recv().await.expect("Failed to receive").1);
+            }
+            // Throughput is timed until the consumer got the last message
+            let elapsed = start.map_or(Duration::ZERO, |start: Instant| start.elapsed());
+            ack_tx.send(elapsed).await.expect("Failed to ack");
+            for _ in 0..LATENCY_SAMPLES {
+                let sent_at: Instant = rx.recv().await.expect("Failed to receive").1;
+                ack_tx.send(sent_at.elapsed()).await.expect("Failed to ack");
+            }
+        });
+
+        let start = Instant::now();
+        for i in 0..messages {
+            tx.send((i, start)).await.expect("Failed to send");
+        }
+        let throughput_elapsed = ack_rx.recv().await.expect("Failed to receive ack");
+
+        let mut latencies = Vec::with_capacity(LATENCY_SAMPLES);
+        for i in 0..LATENCY_SAMPLES {
+            tx.send((i as u64, Instant::now()))
+                .await
+                .expect("Failed to send");
+            latencies.push(ack_rx.recv().await.expect("Failed to receive ack"));
+        }
+        consumer.await.expect("Consumer panicked");
+
+        latencies.sort();
+        Measurement {
+            throughput: messages as f64 / throughput_elapsed.as_secs_f64(),
+            p50_latency: latencies[latencies.len() / 2],
+            p99_latency: latencies[latencies.len() * 99 / 100],
+        }
+    }};
+}
+
+fn print_row(mode: &str, measurement: &Measurement, baseline: &Measurement) {
+    println!(
+        "{:<8} {:>10.0} msg/s  p50 {:>9.2?}  p99 {:>9.2?}  p50 overhead {:>9.2?}",
+        mode,
+        measurement.throughput,
+        measurement.p50_latency,
+        measurement.p99_latency,
+        measurement.p50_latency.saturating_sub(baseline.p50_latency),
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    let messages = std::env::args()
+        .nth(1)
+        .map(|arg| arg.parse().expect("Number of messages must be a number"))
+        .unwrap_or(DEFAULT_MESSAGES);
+
+    println!(
+        "Sending {} messages through a bounded[{}] channel, {} latency samples\n",
+        messages, CAPACITY, LATENCY_SAMPLES
+    );
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<(u64, Instant)>(CAPACITY);
+    let plain = measure!(tx, rx, messages);
+    print_row("plain", &plain, &plain);
+
+    #[cfg(feature = "channels-console")]
+    {
+        let (tx, rx) = tokio::sync::mpsc::channel::<(u64, Instant)>(CAPACITY);
+        let (tx, rx) = channels_console::instrument!((tx, rx), label = "proxied");
+        let proxied = measure!(tx, rx, messages);
+        print_row("proxied", &proxied, &plain);
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<(u64, Instant)>(CAPACITY);
+        let (tx, rx) = channels_console::instrument!((tx, rx), label = "tapped", mode = tap);
+        let tapped = measure!(tx, rx, messages);
+        print_row("tap", &tapped, &plain);
+    }
+}
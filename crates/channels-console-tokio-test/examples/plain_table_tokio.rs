@@ -0,0 +1,19 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::PlainTable)
+        .flush_timeout(std::time::Duration::from_millis(500))
+        .build();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "plain-bounded");
+
+    for i in 1..=3 {
+        tx.send(i).await.expect("Failed to send");
+    }
+    drop(tx);
+    while rx.recv().await.is_some() {}
+}
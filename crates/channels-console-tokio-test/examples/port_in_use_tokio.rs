@@ -0,0 +1,18 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let port = std::env::var("CHANNELS_CONSOLE_METRICS_PORT").unwrap_or_else(|_| "6770".into());
+    // Occupy the metrics port, like a second instrumented app would
+    let _listener =
+        std::net::TcpListener::bind(format!("127.0.0.1:{port}")).expect("Failed to bind port");
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "fallback");
+
+    tx.send(1).await.expect("Failed to send");
+    rx.recv().await.expect("Failed to receive");
+
+    #[cfg(feature = "channels-console")]
+    println!("{:?}", channels_console::metrics_server_status());
+}
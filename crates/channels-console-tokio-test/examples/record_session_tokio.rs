@@ -0,0 +1,35 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .expect("Usage: record_session_tokio <session file>");
+
+    // Record before instrumenting, so the replay knows every channel
+    #[cfg(feature = "channels-console")]
+    channels_console::record_to(&path).expect("Failed to create session file");
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<u32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), mode = tap, label = "recorded");
+
+    for i in 0..5 {
+        tx.send(i).await.expect("Failed to send");
+    }
+    for _ in 0..3 {
+        rx.recv().await.expect("Failed to receive");
+    }
+    drop(tx);
+
+    // Waits for the collector, which flushes the session file once it caught up
+    #[cfg(feature = "channels-console")]
+    {
+        let stats = channels_console::snapshot();
+        println!(
+            "sent {} received {}",
+            stats[0].sent_count, stats[0].received_count
+        );
+    }
+
+    println!("recorded to {}", path);
+}
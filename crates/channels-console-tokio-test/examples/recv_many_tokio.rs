@@ -0,0 +1,48 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<u32>(100);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), mode = tap, label = "batched");
+
+    let (utx, mut urx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+    #[cfg(feature = "channels-console")]
+    let (utx, mut urx) =
+        channels_console::instrument!((utx, urx), mode = tap, label = "batched-unbounded");
+
+    for i in 1..=10 {
+        tx.send(i).await.expect("Failed to send");
+        utx.send(i).expect("Failed to send");
+    }
+    drop(tx);
+    drop(utx);
+
+    let mut buffer = Vec::new();
+    let mut batches = Vec::new();
+    loop {
+        let count = rx.recv_many(&mut buffer, 4).await;
+        if count == 0 {
+            break;
+        }
+        batches.push(count);
+
+        #[cfg(feature = "channels-console")]
+        {
+            let stats = channels_console::snapshot();
+            let batched = stats.iter().find(|s| s.label == "batched").unwrap();
+            assert_eq!(batched.received_count, buffer.len() as u64);
+        }
+    }
+    println!("batches {:?}", batches);
+
+    buffer.clear();
+    while urx.recv_many(&mut buffer, 100).await > 0 {}
+
+    #[cfg(feature = "channels-console")]
+    for stat in channels_console::snapshot() {
+        println!(
+            "{} received {} state {}",
+            stat.label, stat.received_count, stat.state
+        );
+    }
+}
@@ -0,0 +1,29 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "reset-bounded");
+
+    let (otx, orx) = tokio::sync::oneshot::channel::<i32>();
+    #[cfg(feature = "channels-console")]
+    let (otx, orx) = channels_console::instrument!((otx, orx), label = "reset-oneshot");
+
+    for i in 1..=5 {
+        tx.send(i).await.expect("Failed to send");
+    }
+    for _ in 1..=5 {
+        rx.recv().await.expect("Failed to receive");
+    }
+
+    otx.send(42).expect("Failed to send oneshot");
+    orx.await.expect("Failed to receive oneshot");
+
+    println!("Waiting for a reset...");
+    tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+
+    println!("\nReset example completed!");
+}
@@ -0,0 +1,30 @@
+use tokio::time::{sleep, timeout, Duration};
+
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(1);
+
+    // Instrumented clone that never sends
+    let idle_tx = tx.clone();
+    #[cfg(feature = "channels-console")]
+    let idle_tx = channels_console::instrument_sender!(idle_tx, label = "idle-producer");
+
+    // Give the forwarder time to start before the uninstrumented clone uses the only slot
+    sleep(Duration::from_millis(50)).await;
+
+    for i in 1..=3 {
+        timeout(Duration::from_secs(1), tx.send(i))
+            .await
+            .expect("Uninstrumented sender blocked")
+            .expect("Failed to send");
+        let msg = rx.recv().await.expect("Failed to receive");
+        println!("[Receiver] Received: {}", msg);
+    }
+
+    drop(idle_tx);
+
+    println!("\nSender idle example completed!");
+}
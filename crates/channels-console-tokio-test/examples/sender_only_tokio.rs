@@ -0,0 +1,26 @@
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+
+    // Sender cloned before the channel could be instrumented
+    let late_tx = tx.clone();
+    #[cfg(feature = "channels-console")]
+    let late_tx = channels_console::instrument_sender!(late_tx, label = "late-producer");
+
+    for i in 1..=3 {
+        late_tx.send(i).await.expect("Failed to send");
+    }
+    tx.send(4).await.expect("Failed to send");
+
+    for _ in 1..=4 {
+        let msg = rx.recv().await.expect("Failed to receive");
+        println!("[Receiver] Received: {}", msg);
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    println!("\nSender only example completed!");
+}
@@ -0,0 +1,38 @@
+use std::sync::{Arc, Mutex};
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    let transitions = Arc::new(Mutex::new(Vec::<String>::new()));
+
+    #[cfg(feature = "channels-console")]
+    {
+        let transitions = Arc::clone(&transitions);
+        // Runs on the collector thread, so only record the transition
+        channels_console::on_state_change(move |_id, label, old, new| {
+            transitions
+                .lock()
+                .unwrap()
+                .push(format!("{label}: {old} -> {new}"));
+        });
+    }
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(2);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "jobs", mode = tap);
+
+    // Fill the channel, then make room again
+    tx.try_send(1).expect("Failed to send");
+    tx.try_send(2).expect("Failed to send");
+    rx.recv().await.expect("Failed to receive");
+
+    drop(tx);
+    while rx.recv().await.is_some() {}
+
+    #[cfg(feature = "channels-console")]
+    channels_console::flush();
+
+    for transition in transitions.lock().unwrap().iter() {
+        println!("{transition}");
+    }
+}
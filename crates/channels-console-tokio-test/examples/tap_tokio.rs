@@ -0,0 +1,43 @@
+use tokio::sync::mpsc::error::TrySendError;
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::JsonLines)
+        .build();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(2);
+
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), mode = tap, label = "tapped");
+
+    // The original capacity applies exactly, there's no proxy buffering extra messages
+    for i in 1..=3 {
+        match tx.try_send(i) {
+            Ok(()) => println!("[Tapped] Sent: {}", i),
+            Err(TrySendError::Full(i)) => println!("[Tapped] Full: {}", i),
+            Err(TrySendError::Closed(i)) => println!("[Tapped] Closed: {}", i),
+        }
+    }
+    if let Some(msg) = rx.recv().await {
+        println!("[Tapped] Received: {}", msg);
+    }
+
+    let (utx, mut urx) = tokio::sync::mpsc::unbounded_channel::<i32>();
+
+    #[cfg(feature = "channels-console")]
+    let (utx, mut urx) =
+        channels_console::instrument!((utx, urx), label = "tapped-unbounded", mode = tap);
+
+    for i in 1..=3 {
+        utx.send(i).expect("Failed to send");
+    }
+    drop(utx);
+    while let Some(msg) = urx.recv().await {
+        println!("[Unbounded] Received: {}", msg);
+    }
+
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
@@ -0,0 +1,27 @@
+use tracing_subscriber::EnvFilter;
+
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new("channels_console=trace"))
+        .with_ansi(false)
+        .init();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "traced-channel");
+
+    for i in 1..=3 {
+        tx.send(i).await.expect("Failed to send");
+    }
+    drop(tx);
+
+    while let Some(msg) = rx.recv().await {
+        println!("[Receiver] Received: {}", msg);
+    }
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    println!("\nTracing example completed!");
+}
@@ -0,0 +1,20 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuardBuilder::new()
+        .format(channels_console::Format::Markdown)
+        .byte_units(channels_console::ByteUnits::Si)
+        .build();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<[u8; 1000]>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "si-bounded");
+
+    for _ in 0..3 {
+        tx.send([0; 1000]).await.expect("Failed to send");
+    }
+    rx.recv().await.expect("Failed to receive");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+}
@@ -0,0 +1,20 @@
+#[allow(unused_mut)]
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "channels-console")]
+    let _channels_guard = channels_console::ChannelsGuard::new();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<i32>(10);
+    #[cfg(feature = "channels-console")]
+    let (tx, mut rx) = channels_console::instrument!((tx, rx), label = "ws-ticks");
+
+    tokio::spawn(async move { while rx.recv().await.is_some() {} });
+
+    println!("Streaming metrics over /ws...");
+    for i in 0..200 {
+        tx.send(i).await.expect("Failed to send");
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    }
+
+    println!("\nWebSocket example completed!");
+}
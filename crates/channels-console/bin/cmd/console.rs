@@ -1,5 +1,10 @@
 pub(crate) mod app;
+pub(crate) mod export;
+pub(crate) mod history;
 pub(crate) mod http;
+pub(crate) mod replay;
+pub(crate) mod snapshot;
+pub(crate) mod theme;
 pub(crate) mod views;
 pub(crate) mod widgets;
 
@@ -1,19 +1,46 @@
-use channels_console::{ChannelLogs, LogEntry, SerializableChannelStats};
-use clap::Parser;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use channels_console::{
+    aggregate_by_type, ByteUnits, ChannelLogs, Diagnostics, LogEntry, SerializableChannelStats,
+    StatsSummary, TypeStats,
+};
+use clap::{builder::BoolishValueParser, Args, ValueEnum};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    MouseButton, MouseEvent, MouseEventKind,
+};
 use eyre::Result;
 use ratatui::{
-    layout::{Constraint, Layout},
+    layout::{Constraint, Layout, Position, Rect},
     widgets::TableState,
     DefaultTerminal, Frame,
 };
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
-use std::{collections::HashMap, io};
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
 
-use super::http::{fetch_logs, fetch_metrics};
+use super::export::{append_to_file, copy_to_clipboard, format_log_entry, EXPORT_FILE};
+use super::history::{record_throughput, ThroughputHistory};
+use super::http::{
+    fetch_backtrace, fetch_diagnostics, fetch_logs, fetch_metrics, fetch_server_info, reset_stats,
+    InvalidResponse,
+};
+use super::replay::Replay;
+use super::snapshot::{print_metrics, print_snapshot};
+use super::theme::{no_color, Theme};
 use super::views::bottom_bar::render_bottom_bar;
-use super::views::main_view::render_main_view;
+use super::views::channels::{
+    arrange_closed, backlog_ranking, collapse_sources, display_rows, group_by_meta, Column,
+    DisplayRow, View,
+};
+use super::views::help::render_help_popup;
+use super::views::inspect::{field_lines, pretty_json, render_backtrace_popup};
+use super::views::logs::LogSort;
+use super::views::main_view::{render_main_view, PaneAreas};
 use super::views::top_bar::render_top_bar;
+use super::views::types::render_types_popup;
+use super::widgets::ascii::to_ascii;
 
 /// Represents which UI component has focus
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,17 +56,91 @@ pub(crate) struct CachedLogs {
     pub(crate) received_map: HashMap<u64, LogEntry>,
 }
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Args)]
 pub struct ConsoleArgs {
     /// Port for the metrics server
     #[arg(long, default_value = "6770")]
     pub metrics_port: u16,
+
+    /// Color theme: `dark`, `light`, `mono` or a path to a TOML theme file.
+    /// Setting `NO_COLOR` always selects `mono`.
+    #[arg(long, env = "CHANNELS_CONSOLE_THEME", default_value = "dark")]
+    pub theme: String,
+
+    /// Draw with ASCII characters only, for terminals without Unicode support
+    #[arg(long, env = "CHANNELS_CONSOLE_ASCII", value_parser = BoolishValueParser::new())]
+    pub ascii: bool,
+
+    /// Bearer token required by the metrics server, see `CHANNELS_CONSOLE_TOKEN`
+    #[arg(long, env = "CHANNELS_CONSOLE_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+
+    /// Print a single metrics snapshot and exit instead of starting the TUI
+    #[arg(long)]
+    pub once: bool,
+
+    /// Replay snapshots saved from `/metrics` instead of connecting to the metrics server.
+    /// The file holds one snapshot, or one per line, e.g. appended by `--once --format json`,
+    /// or a session recorded with `channels_console::record_to`.
+    #[arg(long, visible_alias = "replay", value_name = "PATH")]
+    pub from_file: Option<PathBuf>,
+
+    /// Output format used with `--once`
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: SnapshotFormat,
+
+    /// Comma-separated channel table columns to show, in order
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        env = "CHANNELS_CONSOLE_COLUMNS",
+        default_value = "channel,type,payload,state,sent,sent-bytes,received,dropped,errors,queue,mem"
+    )]
+    pub columns: Vec<Column>,
+
+    /// Column preset to show instead of `--columns`: `memory`, `throughput` or `full`
+    #[arg(long, value_enum, env = "CHANNELS_CONSOLE_VIEW")]
+    pub view: Option<View>,
+
+    /// Units used for byte sizes: `binary` (1024-based), `si` (1000-based) or `bits`
+    #[arg(
+        long,
+        value_enum,
+        env = "CHANNELS_CONSOLE_UNITS",
+        default_value = "binary"
+    )]
+    pub units: ByteUnits,
+
+    /// Show message types with their module paths, e.g. `alloc::string::String` instead of
+    /// `String`
+    #[arg(long, env = "CHANNELS_CONSOLE_FULL_TYPE_NAMES", value_parser = BoolishValueParser::new())]
+    pub full_type_names: bool,
+
+    /// Group channels by the value of this `meta` key instead of their `group`, e.g. `team`
+    #[arg(long, value_name = "KEY")]
+    pub group_by: Option<String>,
+}
+
+/// Output format of a metrics snapshot
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SnapshotFormat {
+    Table,
+    Json,
 }
 
 pub(crate) struct App {
     stats: Vec<SerializableChannelStats>,
+    /// Saved snapshots shown instead of live metrics, see `--from-file`
+    replay: Option<Replay>,
+    /// Totals of the listed channels, counting each one once even if aggregated
+    summary: StatsSummary,
     error: Option<String>,
+    /// The server responded to the failed fetch, but with a body that couldn't be parsed
+    invalid_response: bool,
     exit: bool,
+    /// Set when the terminal was resized, to repaint it from scratch on the next draw
+    resized: bool,
     last_refresh: Instant,
     last_successful_fetch: Option<Instant>,
     metrics_port: u16,
@@ -49,12 +150,62 @@ pub(crate) struct App {
     focus: Focus,
     show_logs: bool,
     logs: Option<CachedLogs>,
+    log_sort: LogSort,
     paused: bool,
     inspected_log: Option<LogEntry>,
+    /// Highlighted field of an inspected JSON message, see `log = json`
+    inspected_field: usize,
     agent: ureq::Agent,
+    token: Option<String>,
     current_elapsed_ns: u64,
+    /// Collector health, `None` if the server doesn't report it
+    diagnostics: Option<Diagnostics>,
+    /// Send and receive rates of each channel over the last refreshes
+    throughput: HashMap<u64, ThroughputHistory>,
+    pane_areas: PaneAreas,
+    show_help: bool,
+    /// Label and creation backtrace of the channel shown in the backtrace popup
+    backtrace: Option<(String, String)>,
+    theme: Theme,
+    /// Replace Unicode symbols with ASCII after rendering, see `--ascii`
+    ascii: bool,
+    /// Short-lived confirmation shown in the bottom bar
+    flash: Option<(String, Instant)>,
+    /// Row the last `g` press jumped to, so pressing it again moves on to the next-worst one
+    worst_jump: Option<(u64, bool)>,
+    /// Process start as reported by the server, in milliseconds since the Unix epoch
+    start_wall_clock: Option<u64>,
+    show_wall_clock: bool,
+    /// Whether closed channels are listed, below the open ones
+    show_closed: bool,
+    /// Whether channels are listed as single dense lines instead of the table
+    mini: bool,
+    /// Totals per message type of the listed channels, shown in a popup
+    types: Vec<TypeStats>,
+    show_types: bool,
+    /// Whether channels created at the same source are collapsed into a single row
+    aggregate: bool,
+    /// Aggregated sources whose individual channels are listed below their row
+    expanded_sources: HashSet<String>,
+    refresh_interval: Duration,
+    columns: Vec<Column>,
+    /// Column preset in use, `None` while showing `--columns`
+    view: Option<View>,
+    units: ByteUnits,
+    /// Show message types with their module paths, see `--full-type-names`
+    full_type_names: bool,
+    /// Metadata key to group channels by, see `--group-by`
+    group_by: Option<String>,
 }
 
+/// Refresh intervals to step through with `+`/`-`, in milliseconds
+const REFRESH_INTERVALS_MS: [u64; 8] = [50, 100, 200, 500, 1000, 2000, 5000, 10000];
+
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How long a confirmation stays in the bottom bar
+const FLASH_DURATION: Duration = Duration::from_secs(2);
+
 impl ConsoleArgs {
     pub fn run(&self) -> Result<()> {
         let config = ureq::Agent::config_builder()
@@ -64,10 +215,35 @@ impl ConsoleArgs {
 
         let agent: ureq::Agent = config.into();
 
+        let replay = self.from_file.as_deref().map(Replay::load).transpose()?;
+
+        if self.once {
+            if let Some(replay) = &replay {
+                return print_metrics(replay.last(), self.format, self.units);
+            }
+            return print_snapshot(
+                &agent,
+                self.metrics_port,
+                self.token.as_deref(),
+                self.format,
+                self.units,
+            );
+        }
+
+        let theme = if no_color() {
+            Theme::mono()
+        } else {
+            Theme::load(&self.theme)?
+        };
+
         let mut app = App {
             stats: Vec::new(),
+            replay,
+            summary: StatsSummary::default(),
             error: None,
+            invalid_response: false,
             exit: false,
+            resized: false,
             last_refresh: Instant::now(),
             last_successful_fetch: None,
             metrics_port: self.metrics_port,
@@ -77,14 +253,44 @@ impl ConsoleArgs {
             focus: Focus::Channels,
             show_logs: false,
             logs: None,
+            log_sort: LogSort::default(),
             paused: false,
             inspected_log: None,
+            inspected_field: 0,
             agent,
+            token: self.token.clone(),
             current_elapsed_ns: 0,
+            diagnostics: None,
+            throughput: HashMap::new(),
+            pane_areas: PaneAreas::default(),
+            show_help: false,
+            backtrace: None,
+            theme,
+            ascii: self.ascii,
+            flash: None,
+            worst_jump: None,
+            start_wall_clock: None,
+            show_wall_clock: false,
+            show_closed: true,
+            mini: false,
+            types: Vec::new(),
+            show_types: false,
+            aggregate: false,
+            expanded_sources: HashSet::new(),
+            refresh_interval: initial_refresh_interval(),
+            columns: self
+                .view
+                .map_or_else(|| self.columns.clone(), View::columns),
+            view: self.view,
+            units: self.units,
+            full_type_names: self.full_type_names,
+            group_by: self.group_by.clone(),
         };
 
         let mut terminal = ratatui::init();
+        let _ = crossterm::execute!(io::stdout(), EnableMouseCapture);
         let app_result = app.run(&mut terminal);
+        let _ = crossterm::execute!(io::stdout(), DisableMouseCapture);
         ratatui::restore();
         app_result.map_err(|e| eyre::eyre!("TUI error: {}", e))
     }
@@ -92,19 +298,19 @@ impl ConsoleArgs {
 
 impl App {
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-        let refresh_interval = std::env::var("CHANNELS_CONSOLE_TUI_REFRESH_MS")
-            .ok()
-            .and_then(|s| s.parse::<u64>().ok())
-            .map(Duration::from_millis)
-            .unwrap_or(Duration::from_millis(200));
-
         self.refresh_data();
 
         while !self.exit {
-            if !self.paused && self.last_refresh.elapsed() >= refresh_interval {
+            if !self.paused && self.last_refresh.elapsed() >= self.refresh_interval {
                 self.refresh_data();
             }
 
+            // Cells left over from the previous size would otherwise linger until overwritten
+            if std::mem::take(&mut self.resized) {
+                terminal.autoresize()?;
+                terminal.clear()?;
+            }
+
             let render_start = Instant::now();
             terminal.draw(|frame| self.draw(frame))?;
             self.last_render_duration = render_start.elapsed();
@@ -115,23 +321,55 @@ impl App {
     }
 
     fn refresh_data(&mut self) {
+        // An aggregate row shares its id with the first channel of its source
         let selected_channel_id = self
             .table_state
             .selected()
             .and_then(|idx| self.stats.get(idx))
-            .map(|stat| stat.id);
+            .map(|stat| (stat.id, stat.count.is_some()));
 
-        match fetch_metrics(&self.agent, self.metrics_port) {
+        let metrics = match &self.replay {
+            Some(replay) => Ok(replay.current().clone()),
+            None => fetch_metrics(&self.agent, self.metrics_port, self.token.as_deref()),
+        };
+
+        match metrics {
             Ok(metrics) => {
                 self.current_elapsed_ns = metrics.current_elapsed_ns;
+                if self.start_wall_clock.is_none() && self.replay.is_none() {
+                    self.start_wall_clock =
+                        fetch_server_info(&self.agent, self.metrics_port, self.token.as_deref())
+                            .ok()
+                            .map(|info| info.start_wall_clock);
+                }
                 self.stats = metrics.stats;
+                if let Some(key) = &self.group_by {
+                    group_by_meta(&mut self.stats, key);
+                }
+                arrange_closed(&mut self.stats, self.show_closed);
+                record_throughput(&mut self.throughput, &self.stats, self.current_elapsed_ns);
+                self.summary = StatsSummary::from_stats(&self.stats);
+                self.types = aggregate_by_type(&self.stats);
+                if self.aggregate {
+                    collapse_sources(&mut self.stats, &self.expanded_sources);
+                }
+                self.diagnostics = match self.replay {
+                    Some(_) => None,
+                    None => {
+                        fetch_diagnostics(&self.agent, self.metrics_port, self.token.as_deref())
+                            .ok()
+                    }
+                };
                 self.error = None;
                 self.last_successful_fetch = Some(Instant::now());
 
                 // Try to restore selection to the same channel ID
                 if let Some(channel_id) = selected_channel_id {
                     // Find the new index of the previously selected channel
-                    if let Some(new_idx) = self.stats.iter().position(|stat| stat.id == channel_id)
+                    if let Some(new_idx) = self
+                        .stats
+                        .iter()
+                        .position(|stat| (stat.id, stat.count.is_some()) == channel_id)
                     {
                         self.table_state.select(Some(new_idx));
                     } else {
@@ -152,6 +390,9 @@ impl App {
             }
             Err(e) => {
                 self.error = Some(format!("Failed to fetch metrics: {}", e));
+                self.invalid_response = e.is::<InvalidResponse>();
+                // The process may be restarted with a different start time
+                self.start_wall_clock = None;
             }
         }
         self.last_refresh = Instant::now();
@@ -159,21 +400,59 @@ impl App {
 
     fn draw(&mut self, frame: &mut Frame) {
         self.render_ui(frame);
+        if self.ascii {
+            to_ascii(frame.buffer_mut());
+        }
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key_event) = event::read()? {
-                if key_event.kind == KeyEventKind::Press {
+        // Don't let waiting for input delay refreshes at short intervals
+        if event::poll(self.refresh_interval.min(Duration::from_millis(100)))? {
+            match event::read()? {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                     self.handle_key_event(key_event);
                 }
+                Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
+                Event::Resize(_, _) => self.resized = true,
+                _ => {}
             }
         }
         Ok(())
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        // The help popup captures all keys except quitting
+        if self.show_help {
+            match key_event.code {
+                KeyCode::Char('q') | KeyCode::Char('Q') => self.exit(),
+                KeyCode::Char('?') | KeyCode::Esc => self.show_help = false,
+                _ => {}
+            }
+            return;
+        }
+
+        // So does the types popup
+        if self.show_types {
+            match key_event.code {
+                KeyCode::Char('q') | KeyCode::Char('Q') => self.exit(),
+                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Esc => self.show_types = false,
+                _ => {}
+            }
+            return;
+        }
+
+        // So does the backtrace popup
+        if self.backtrace.is_some() {
+            match key_event.code {
+                KeyCode::Char('q') | KeyCode::Char('Q') => self.exit(),
+                KeyCode::Char('b') | KeyCode::Char('B') | KeyCode::Esc => self.backtrace = None,
+                _ => {}
+            }
+            return;
+        }
+
         match key_event.code {
+            KeyCode::Char('?') => self.show_help = true,
             KeyCode::Char('q') | KeyCode::Char('Q') => self.exit(),
             KeyCode::Char('o') | KeyCode::Char('O') => match self.focus {
                 Focus::Inspect => self.close_inspect_and_refocus_channels(),
@@ -181,6 +460,22 @@ impl App {
                 Focus::Channels => self.toggle_logs(),
             },
             KeyCode::Char('p') | KeyCode::Char('P') => self.toggle_pause(),
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reset_stats(),
+            KeyCode::Char('t') | KeyCode::Char('T') => self.toggle_wall_clock(),
+            KeyCode::Char('s') | KeyCode::Char('S') => self.cycle_log_sort(),
+            KeyCode::Char('x') | KeyCode::Char('X') => self.toggle_closed(),
+            KeyCode::Char('a') | KeyCode::Char('A') => self.toggle_aggregate(),
+            KeyCode::Char('m') | KeyCode::Char('M') => self.mini = !self.mini,
+            KeyCode::Char('v') | KeyCode::Char('V') => self.cycle_view(),
+            KeyCode::Char('y') | KeyCode::Char('Y') => self.show_types = true,
+            KeyCode::Char('g') | KeyCode::Char('G') => self.select_worst_channel(),
+            KeyCode::Char('[') => self.step_replay(-1),
+            KeyCode::Char(']') => self.step_replay(1),
+            KeyCode::Char('e') | KeyCode::Char('E') if self.focus == Focus::Channels => {
+                self.toggle_expanded()
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => self.slow_down_refresh(),
+            KeyCode::Char('-') | KeyCode::Char('_') => self.speed_up_refresh(),
             KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('H') => {
                 if self.focus == Focus::Inspect {
                     self.close_inspect_only();
@@ -190,6 +485,17 @@ impl App {
             }
             KeyCode::Right | KeyCode::Char('l') => self.focus_logs(),
             KeyCode::Char('i') | KeyCode::Char('I') => self.toggle_inspect(),
+            KeyCode::Char('b') | KeyCode::Char('B') if self.focus == Focus::Channels => {
+                self.show_backtrace()
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') if self.focus == Focus::Inspect => {
+                self.copy_inspected_log()
+            }
+            KeyCode::Char('w') | KeyCode::Char('W') if self.focus == Focus::Inspect => {
+                self.export_inspected_log()
+            }
+            KeyCode::Tab if self.focus == Focus::Inspect => self.select_inspected_field(1),
+            KeyCode::BackTab if self.focus == Focus::Inspect => self.select_inspected_field(-1),
             KeyCode::Up | KeyCode::Char('k') => match self.focus {
                 Focus::Channels => self.select_previous_channel(),
                 Focus::Logs | Focus::Inspect => self.select_previous_log(),
@@ -198,23 +504,120 @@ impl App {
                 Focus::Channels => self.select_next_channel(),
                 Focus::Logs | Focus::Inspect => self.select_next_log(),
             },
+            KeyCode::PageUp => self.move_selection(-self.page_size()),
+            KeyCode::PageDown => self.move_selection(self.page_size()),
+            KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::End => self.move_selection(isize::MAX),
             _ => {}
         }
     }
 
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        if self.show_help || self.show_types || self.backtrace.is_some() {
+            return;
+        }
+
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        let in_channels = self
+            .pane_areas
+            .channels
+            .is_some_and(|area| area.contains(position));
+        let in_logs = self
+            .pane_areas
+            .logs
+            .is_some_and(|area| area.contains(position));
+
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if in_channels {
+                    self.close_inspect_only();
+                    let display_rows = display_rows(&self.stats);
+                    if let Some(DisplayRow::Channel(i)) = self
+                        .pane_areas
+                        .channels
+                        .and_then(|area| {
+                            row_at(
+                                area,
+                                self.table_state.offset(),
+                                position,
+                                display_rows.len(),
+                                !self.mini,
+                            )
+                        })
+                        .map(|row| &display_rows[row])
+                    {
+                        self.select_channel(*i);
+                    }
+                } else if in_logs {
+                    let log_count = self
+                        .logs
+                        .as_ref()
+                        .map_or(0, |cached_logs| cached_logs.logs.sent_logs.len());
+                    if let Some(i) = self.pane_areas.logs.and_then(|area| {
+                        row_at(
+                            area,
+                            self.logs_table_state.offset(),
+                            position,
+                            log_count,
+                            true,
+                        )
+                    }) {
+                        // Clicking the already selected row opens the inspect popup
+                        let clicked_selected = self.focus == Focus::Logs
+                            && self.logs_table_state.selected() == Some(i);
+                        if self.focus != Focus::Inspect {
+                            self.focus = Focus::Logs;
+                        }
+                        self.select_log(i);
+                        if clicked_selected {
+                            self.toggle_inspect();
+                        }
+                    }
+                }
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let up = mouse_event.kind == MouseEventKind::ScrollUp;
+                // Scrolling moves the selection in the pane under the cursor,
+                // or in the logs while the inspect popup is open
+                if self.focus == Focus::Inspect || in_logs {
+                    if self.focus == Focus::Channels {
+                        self.focus_logs();
+                    }
+                    if up {
+                        self.select_previous_log();
+                    } else {
+                        self.select_next_log();
+                    }
+                } else if in_channels {
+                    self.focus_channels();
+                    if up {
+                        self.select_previous_channel();
+                    } else {
+                        self.select_next_channel();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn select_channel(&mut self, i: usize) {
+        self.table_state.select(Some(i));
+
+        if self.paused && self.show_logs {
+            self.logs = None;
+        } else if self.show_logs {
+            self.refresh_logs();
+        }
+    }
+
     fn select_previous_channel(&mut self) {
         if !self.stats.is_empty() {
             let i = match self.table_state.selected() {
                 Some(i) => i.saturating_sub(1),
                 None => 0,
             };
-            self.table_state.select(Some(i));
-
-            if self.paused && self.show_logs {
-                self.logs = None;
-            } else if self.show_logs {
-                self.refresh_logs();
-            }
+            self.select_channel(i);
         }
     }
 
@@ -224,12 +627,83 @@ impl App {
                 Some(i) => (i + 1).min(self.stats.len() - 1),
                 None => 0,
             };
-            self.table_state.select(Some(i));
+            self.select_channel(i);
+        }
+    }
 
-            if self.paused && self.show_logs {
-                self.logs = None;
-            } else if self.show_logs {
-                self.refresh_logs();
+    /// Selects the channel with the largest backlog, or the next-worst one if the previous
+    /// press selected a channel that is still selected
+    fn select_worst_channel(&mut self) {
+        let ranking = backlog_ranking(&self.stats);
+        if ranking.is_empty() {
+            self.flash = Some(("No channel has queued messages".to_string(), Instant::now()));
+            return;
+        }
+
+        let row_key = |i: usize| (self.stats[i].id, self.stats[i].count.is_some());
+        let selected = self
+            .table_state
+            .selected()
+            .filter(|&i| i < self.stats.len());
+        let rank = match (self.worst_jump, selected) {
+            (Some(last), Some(selected)) if row_key(selected) == last => ranking
+                .iter()
+                .position(|&i| row_key(i) == last)
+                .map_or(0, |rank| (rank + 1) % ranking.len()),
+            _ => 0,
+        };
+
+        let i = ranking[rank];
+        self.worst_jump = Some(row_key(i));
+        self.focus_channels();
+        self.select_channel(i);
+        let stat = &self.stats[i];
+        self.flash = Some((
+            format!(
+                "Backlog {}/{}: {} with {} queued",
+                rank + 1,
+                ranking.len(),
+                stat.label,
+                stat.queued
+            ),
+            Instant::now(),
+        ));
+    }
+
+    /// Number of rows visible in the focused table
+    fn page_size(&self) -> isize {
+        let area = match self.focus {
+            Focus::Channels => self.pane_areas.channels,
+            Focus::Logs | Focus::Inspect => self.pane_areas.logs,
+        };
+        // Top and bottom border plus the header row, which the mini channels list doesn't have
+        let header = !(self.mini && self.focus == Focus::Channels);
+        let chrome = 2 + u16::from(header);
+        area.map_or(1, |area| area.height.saturating_sub(chrome).max(1) as isize)
+    }
+
+    /// Moves the selection of the focused table by `delta` rows, clamped to its first and last row
+    fn move_selection(&mut self, delta: isize) {
+        match self.focus {
+            Focus::Channels => {
+                if !self.stats.is_empty() {
+                    let current = self.table_state.selected().unwrap_or(0);
+                    let i = current
+                        .saturating_add_signed(delta)
+                        .min(self.stats.len() - 1);
+                    self.select_channel(i);
+                }
+            }
+            Focus::Logs | Focus::Inspect => {
+                let log_count = self
+                    .logs
+                    .as_ref()
+                    .map_or(0, |cached_logs| cached_logs.logs.sent_logs.len());
+                if log_count > 0 {
+                    let current = self.logs_table_state.selected().unwrap_or(0);
+                    let i = current.saturating_add_signed(delta).min(log_count - 1);
+                    self.select_log(i);
+                }
             }
         }
     }
@@ -242,7 +716,12 @@ impl App {
             .unwrap_or(false);
 
         if !self.stats.is_empty() && has_valid_selection {
-            if self.show_logs {
+            if !self.show_logs && self.is_aggregate_selected() {
+                self.flash = Some((
+                    "Expand the source with e to see the logs of its channels".to_string(),
+                    Instant::now(),
+                ));
+            } else if self.show_logs {
                 self.hide_logs();
             } else {
                 self.show_logs = true;
@@ -255,6 +734,13 @@ impl App {
         }
     }
 
+    fn is_aggregate_selected(&self) -> bool {
+        self.table_state
+            .selected()
+            .and_then(|i| self.stats.get(i))
+            .is_some_and(|stat| stat.count.is_some())
+    }
+
     fn hide_logs(&mut self) {
         self.show_logs = false;
         self.logs = None;
@@ -267,34 +753,247 @@ impl App {
             return;
         }
 
+        let selected_entry = self.selected_log_index();
         self.logs = None;
+        // Snapshots don't include logs
+        if self.is_aggregate_selected() || self.replay.is_some() {
+            return;
+        }
 
         if let Some(selected) = self.table_state.selected() {
             if !self.stats.is_empty() && selected < self.stats.len() {
                 let channel_id = self.stats[selected].id;
-                if let Ok(logs) = fetch_logs(&self.agent, self.metrics_port, channel_id) {
+                let logs = fetch_logs(
+                    &self.agent,
+                    self.metrics_port,
+                    self.token.as_deref(),
+                    channel_id,
+                );
+                if let Err(e) = &logs {
+                    self.flash = Some((format!("Failed to fetch logs: {}", e), Instant::now()));
+                }
+                if let Ok(logs) = logs {
                     let received_map: std::collections::HashMap<u64, LogEntry> = logs
                         .received_logs
                         .iter()
                         .map(|entry| (entry.index, entry.clone()))
                         .collect();
 
-                    self.logs = Some(CachedLogs { logs, received_map });
+                    let mut cached_logs = CachedLogs { logs, received_map };
+                    self.log_sort.apply(&mut cached_logs);
+                    self.logs = Some(cached_logs);
 
-                    // Ensure logs table selection is valid
-                    if let Some(ref cached_logs) = self.logs {
-                        let log_count = cached_logs.logs.sent_logs.len();
-                        if let Some(selected) = self.logs_table_state.selected() {
-                            if selected >= log_count && log_count > 0 {
-                                self.logs_table_state.select(Some(log_count - 1));
-                            }
-                        }
-                    }
+                    // Keep the selection on the same message while new ones arrive
+                    self.reselect_log(selected_entry);
                 }
             }
         }
     }
 
+    fn cycle_log_sort(&mut self) {
+        let selected_entry = self.selected_log_index();
+        self.log_sort = self.log_sort.next();
+        if let Some(cached_logs) = &mut self.logs {
+            self.log_sort.apply(cached_logs);
+        }
+        self.reselect_log(selected_entry);
+    }
+
+    /// Message index of the selected log entry
+    fn selected_log_index(&self) -> Option<u64> {
+        let selected = self.logs_table_state.selected()?;
+        let cached_logs = self.logs.as_ref()?;
+        cached_logs
+            .logs
+            .sent_logs
+            .get(selected)
+            .map(|entry| entry.index)
+    }
+
+    /// Selects the row of the given message, or keeps the selection within the table
+    /// once it was evicted from the logs
+    fn reselect_log(&mut self, entry_index: Option<u64>) {
+        let (Some(cached_logs), Some(selected)) = (&self.logs, self.logs_table_state.selected())
+        else {
+            return;
+        };
+        let sent_logs = &cached_logs.logs.sent_logs;
+        if sent_logs.is_empty() {
+            return;
+        }
+        let row = entry_index
+            .and_then(|index| sent_logs.iter().position(|entry| entry.index == index))
+            .unwrap_or_else(|| selected.min(sent_logs.len() - 1));
+        self.logs_table_state.select(Some(row));
+    }
+
+    fn reset_stats(&mut self) {
+        if self.replay.is_some() {
+            self.flash = Some((
+                "Replaying snapshots, there's nothing to reset".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+
+        match reset_stats(&self.agent, self.metrics_port, self.token.as_deref()) {
+            Ok(()) => {
+                // Previously logged entries are gone, so drop any log selection
+                self.inspected_log = None;
+                self.focus_channels();
+                self.refresh_data();
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to reset stats: {}", e));
+            }
+        }
+    }
+
+    fn show_backtrace(&mut self) {
+        let Some(stat) = self.table_state.selected().and_then(|i| self.stats.get(i)) else {
+            return;
+        };
+
+        if self.replay.is_some() {
+            self.flash = Some((
+                "Backtraces aren't included in snapshots".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+
+        if !stat.has_backtrace {
+            self.flash = Some((
+                "No backtrace captured, instrument with capture_backtrace = true".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+
+        match fetch_backtrace(
+            &self.agent,
+            self.metrics_port,
+            self.token.as_deref(),
+            stat.id,
+        ) {
+            Ok(backtrace) => self.backtrace = Some((stat.label.clone(), backtrace)),
+            Err(e) => {
+                self.flash = Some((format!("Failed to fetch backtrace: {}", e), Instant::now()));
+            }
+        }
+    }
+
+    /// Shows the snapshot `delta` steps away when replaying `--from-file`
+    fn step_replay(&mut self, delta: isize) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+        if replay.step(delta) {
+            // Rates between snapshots only make sense going forward
+            if delta < 0 {
+                self.throughput.clear();
+            }
+            self.refresh_data();
+        }
+    }
+
+    fn toggle_wall_clock(&mut self) {
+        if self.start_wall_clock.is_some() {
+            self.show_wall_clock = !self.show_wall_clock;
+        } else {
+            self.flash = Some((
+                "Wall-clock time not reported by the server".to_string(),
+                Instant::now(),
+            ));
+        }
+    }
+
+    fn cycle_view(&mut self) {
+        let view = self.view.map_or(View::Memory, View::next);
+        self.view = Some(view);
+        self.columns = view.columns();
+        self.flash = Some((
+            format!("Showing the {} view", view.as_str()),
+            Instant::now(),
+        ));
+    }
+
+    fn toggle_closed(&mut self) {
+        self.show_closed = !self.show_closed;
+        let message = if self.show_closed {
+            "Showing closed channels"
+        } else {
+            "Hiding closed channels"
+        };
+        self.flash = Some((message.to_string(), Instant::now()));
+        self.refresh_data();
+    }
+
+    fn toggle_aggregate(&mut self) {
+        self.aggregate = !self.aggregate;
+        let message = if self.aggregate {
+            "Aggregating channels by source"
+        } else {
+            "Showing every channel"
+        };
+        self.flash = Some((message.to_string(), Instant::now()));
+        self.refresh_data();
+    }
+
+    /// Expands or collapses the aggregate row of the selected channel's source
+    fn toggle_expanded(&mut self) {
+        let Some(stat) = self.table_state.selected().and_then(|i| self.stats.get(i)) else {
+            return;
+        };
+        if !self.aggregate {
+            return;
+        }
+
+        let source = stat.source.clone();
+        if !self.expanded_sources.remove(&source) {
+            if stat.count.is_none() {
+                return;
+            }
+            self.expanded_sources.insert(source.clone());
+        }
+        self.refresh_data();
+
+        // Keep the aggregate row selected, also when collapsing from one of its channels
+        if let Some(i) = self
+            .stats
+            .iter()
+            .position(|stat| stat.source == source && stat.count.is_some())
+        {
+            self.select_channel(i);
+        }
+    }
+
+    /// Process start time, when log timestamps should be shown as wall-clock times
+    fn displayed_wall_clock(&self) -> Option<u64> {
+        self.start_wall_clock.filter(|_| self.show_wall_clock)
+    }
+
+    /// Steps the refresh interval up to the next preset
+    fn slow_down_refresh(&mut self) {
+        let current = self.refresh_interval.as_millis() as u64;
+        let next = REFRESH_INTERVALS_MS
+            .iter()
+            .find(|ms| **ms > current)
+            .unwrap_or(&REFRESH_INTERVALS_MS[REFRESH_INTERVALS_MS.len() - 1]);
+        self.refresh_interval = Duration::from_millis(*next);
+    }
+
+    /// Steps the refresh interval down to the previous preset
+    fn speed_up_refresh(&mut self) {
+        let current = self.refresh_interval.as_millis() as u64;
+        let next = REFRESH_INTERVALS_MS
+            .iter()
+            .rev()
+            .find(|ms| **ms < current)
+            .unwrap_or(&REFRESH_INTERVALS_MS[0]);
+        self.refresh_interval = Duration::from_millis(*next);
+    }
+
     fn toggle_pause(&mut self) {
         self.paused = !self.paused;
     }
@@ -320,6 +1019,36 @@ impl App {
         }
     }
 
+    fn select_log(&mut self, i: usize) {
+        self.logs_table_state.select(Some(i));
+
+        // Update inspected log if inspect popup is open
+        if self.focus == Focus::Inspect {
+            if let Some(entry) = self
+                .logs
+                .as_ref()
+                .and_then(|cached_logs| cached_logs.logs.sent_logs.get(i))
+            {
+                self.inspected_log = Some(entry.clone());
+                self.inspected_field = 0;
+            }
+        }
+    }
+
+    /// Moves the highlight `delta` fields through an inspected JSON message
+    fn select_inspected_field(&mut self, delta: isize) {
+        let field_count = self
+            .inspected_log
+            .as_ref()
+            .and_then(|entry| entry.message.as_deref())
+            .and_then(pretty_json)
+            .map_or(0, |json| field_lines(&json).len());
+        self.inspected_field = self
+            .inspected_field
+            .saturating_add_signed(delta)
+            .min(field_count.saturating_sub(1));
+    }
+
     fn select_previous_log(&mut self) {
         if let Some(ref cached_logs) = self.logs {
             let log_count = cached_logs.logs.sent_logs.len();
@@ -328,14 +1057,7 @@ impl App {
                     Some(i) => i.saturating_sub(1),
                     None => 0,
                 };
-                self.logs_table_state.select(Some(i));
-
-                // Update inspected log if inspect popup is open
-                if self.focus == Focus::Inspect {
-                    if let Some(entry) = cached_logs.logs.sent_logs.get(i) {
-                        self.inspected_log = Some(entry.clone());
-                    }
-                }
+                self.select_log(i);
             }
         }
     }
@@ -348,14 +1070,7 @@ impl App {
                     Some(i) => (i + 1).min(log_count - 1),
                     None => 0,
                 };
-                self.logs_table_state.select(Some(i));
-
-                // Update inspected log if inspect popup is open
-                if self.focus == Focus::Inspect {
-                    if let Some(entry) = cached_logs.logs.sent_logs.get(i) {
-                        self.inspected_log = Some(entry.clone());
-                    }
-                }
+                self.select_log(i);
             }
         }
     }
@@ -371,6 +1086,7 @@ impl App {
                 if let Some(ref cached_logs) = self.logs {
                     if let Some(entry) = cached_logs.logs.sent_logs.get(selected) {
                         self.inspected_log = Some(entry.clone());
+                        self.inspected_field = 0;
                         self.focus = Focus::Inspect;
                     }
                 }
@@ -378,6 +1094,39 @@ impl App {
         }
     }
 
+    /// The inspected log entry formatted with its channel, ready to be exported
+    fn inspected_log_text(&self) -> Option<String> {
+        let entry = self.inspected_log.as_ref()?;
+        let label = self
+            .table_state
+            .selected()
+            .and_then(|i| self.stats.get(i))
+            .map_or("unknown", |stat| stat.label.as_str());
+        Some(format_log_entry(label, entry, self.start_wall_clock))
+    }
+
+    fn copy_inspected_log(&mut self) {
+        let Some(text) = self.inspected_log_text() else {
+            return;
+        };
+        let message = match copy_to_clipboard(&text) {
+            Ok(()) => "Copied to clipboard".to_string(),
+            Err(e) => format!("Failed to copy: {}", e),
+        };
+        self.flash = Some((message, Instant::now()));
+    }
+
+    fn export_inspected_log(&mut self) {
+        let Some(text) = self.inspected_log_text() else {
+            return;
+        };
+        let message = match append_to_file(Path::new(EXPORT_FILE), &text) {
+            Ok(()) => format!("Appended to {}", EXPORT_FILE),
+            Err(e) => format!("Failed to write {}: {}", EXPORT_FILE, e),
+        };
+        self.flash = Some((message, Instant::now()));
+    }
+
     fn close_inspect_and_refocus_channels(&mut self) {
         self.inspected_log = None;
         self.hide_logs();
@@ -413,28 +1162,103 @@ impl App {
             frame,
             chunks[0],
             self.paused,
+            self.replay.as_ref().map(Replay::progress),
             self.last_successful_fetch,
+            self.refresh_interval,
             self.error.is_some(),
             !self.stats.is_empty(),
+            &self.summary,
+            self.units,
+            &self.theme,
         );
 
         // Render main content area
-        render_main_view(
+        let start_wall_clock = self.displayed_wall_clock();
+        self.pane_areas = render_main_view(
             frame,
             chunks[1],
             &self.stats,
+            self.diagnostics,
+            &self.throughput,
             &self.error,
+            self.invalid_response,
             self.metrics_port,
             &mut self.table_state,
             &mut self.logs_table_state,
             self.focus,
             self.show_logs,
             &self.logs,
+            self.log_sort,
             self.paused,
             &self.inspected_log,
+            self.inspected_field,
             self.current_elapsed_ns,
+            start_wall_clock,
+            &self.columns,
+            self.mini,
+            self.units,
+            self.full_type_names,
+            &self.theme,
         );
 
-        render_bottom_bar(frame, chunks[2], self.focus, self.last_render_duration);
+        if self.show_types {
+            render_types_popup(&self.types, self.units, chunks[1], frame, &self.theme);
+        }
+
+        if self.show_help {
+            render_help_popup(chunks[1], frame, &self.theme);
+        }
+
+        if let Some((label, backtrace)) = &self.backtrace {
+            render_backtrace_popup(label, backtrace, chunks[1], frame);
+        }
+
+        let flash = self
+            .flash
+            .as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < FLASH_DURATION)
+            .map(|(message, _)| message.as_str());
+
+        render_bottom_bar(
+            frame,
+            chunks[2],
+            self.focus,
+            flash,
+            &self.theme,
+            self.last_render_duration,
+        );
     }
 }
+
+/// Refresh interval from `CHANNELS_CONSOLE_TUI_REFRESH_MS`, clamped to the adjustable range
+fn initial_refresh_interval() -> Duration {
+    std::env::var("CHANNELS_CONSOLE_TUI_REFRESH_MS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .map(|ms| {
+            Duration::from_millis(ms.clamp(
+                REFRESH_INTERVALS_MS[0],
+                REFRESH_INTERVALS_MS[REFRESH_INTERVALS_MS.len() - 1],
+            ))
+        })
+        .unwrap_or(DEFAULT_REFRESH_INTERVAL)
+}
+
+/// Maps a click position to a table row index, accounting for the border,
+/// the header row if there is one and the current scroll offset
+fn row_at(
+    area: Rect,
+    offset: usize,
+    position: Position,
+    row_count: usize,
+    header: bool,
+) -> Option<usize> {
+    let first_row_y = area.y + 1 + u16::from(header);
+    let last_row_y = area.bottom().saturating_sub(1);
+    if position.y < first_row_y || position.y >= last_row_y {
+        return None;
+    }
+
+    let i = offset + (position.y - first_row_y) as usize;
+    (i < row_count).then_some(i)
+}
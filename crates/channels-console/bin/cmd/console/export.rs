@@ -0,0 +1,49 @@
+use channels_console::LogEntry;
+use crossterm::clipboard::CopyToClipboard;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use super::widgets::formatters::{format_timestamp, format_wall_clock};
+
+/// File that inspected log entries are appended to, relative to the working directory
+pub(crate) const EXPORT_FILE: &str = "channels-console-export.log";
+
+/// Formats a log entry with its channel and metadata for exporting
+pub(crate) fn format_log_entry(
+    channel_label: &str,
+    entry: &LogEntry,
+    start_wall_clock: Option<u64>,
+) -> String {
+    let wall_clock = start_wall_clock
+        .map(|start| {
+            format!(
+                "wall clock: {}\n",
+                format_wall_clock(start, entry.timestamp)
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        "channel: {}\nindex: {}\ntimestamp: {}\n{}message: {}\n",
+        channel_label,
+        entry.index,
+        format_timestamp(entry.timestamp),
+        wall_clock,
+        entry
+            .message
+            .as_deref()
+            .unwrap_or("(missing \"log = true\")"),
+    )
+}
+
+/// Copies text to the system clipboard through the terminal (OSC 52).
+/// Works over SSH, as long as the terminal emulator supports it.
+pub(crate) fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    crossterm::execute!(io::stdout(), CopyToClipboard::to_clipboard_from(text))
+}
+
+/// Appends text to the export file, separating entries with a blank line
+pub(crate) fn append_to_file(path: &Path, text: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", text)
+}
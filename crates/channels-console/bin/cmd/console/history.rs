@@ -0,0 +1,60 @@
+use channels_console::SerializableChannelStats;
+use std::collections::{HashMap, VecDeque};
+
+/// Number of rate samples kept per channel
+const HISTORY_LEN: usize = 60;
+
+/// Send and receive rates of a channel at one refresh
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RateSample {
+    /// Server time of the refresh, in nanoseconds since the process started
+    pub(crate) elapsed_ns: u64,
+    pub(crate) sent_per_sec: f64,
+    pub(crate) received_per_sec: f64,
+}
+
+/// Rates of a channel over the last refreshes, accumulated by the console
+#[derive(Debug, Default)]
+pub(crate) struct ThroughputHistory {
+    /// Server time and counts at the previous refresh
+    last: Option<(u64, u64, u64)>,
+    pub(crate) samples: VecDeque<RateSample>,
+}
+
+impl ThroughputHistory {
+    fn record(&mut self, elapsed_ns: u64, sent: u64, received: u64) {
+        if let Some((last_ns, last_sent, last_received)) = self.last {
+            // Counts going backwards means the stats were reset
+            if sent < last_sent || received < last_received {
+                self.samples.clear();
+            } else if elapsed_ns > last_ns {
+                let secs = (elapsed_ns - last_ns) as f64 / 1_000_000_000.0;
+                if self.samples.len() == HISTORY_LEN {
+                    self.samples.pop_front();
+                }
+                self.samples.push_back(RateSample {
+                    elapsed_ns,
+                    sent_per_sec: (sent - last_sent) as f64 / secs,
+                    received_per_sec: (received - last_received) as f64 / secs,
+                });
+            }
+        }
+        self.last = Some((elapsed_ns, sent, received));
+    }
+}
+
+/// Records a rate sample for every channel, forgetting channels that are gone
+pub(crate) fn record_throughput(
+    histories: &mut HashMap<u64, ThroughputHistory>,
+    stats: &[SerializableChannelStats],
+    elapsed_ns: u64,
+) {
+    histories.retain(|id, _| stats.iter().any(|stat| stat.id == *id));
+    for stat in stats {
+        histories.entry(stat.id).or_default().record(
+            elapsed_ns,
+            stat.sent_count,
+            stat.received_count,
+        );
+    }
+}
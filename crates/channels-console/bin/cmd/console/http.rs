@@ -1,16 +1,167 @@
-use channels_console::{ChannelLogs, MetricsJson};
+use channels_console::{
+    ChannelLogs, Diagnostics, MetricsJson, ServerInfo, METRICS_SCHEMA_VERSION, MSGPACK_CONTENT_TYPE,
+};
 use eyre::Result;
+use serde::de::DeserializeOwned;
+use std::fmt;
 
-/// Fetches channel metrics from the HTTP server
-pub(crate) fn fetch_metrics(agent: &ureq::Agent, port: u16) -> Result<MetricsJson> {
+/// Longest part of an unparseable response body quoted in the error
+const BODY_SNIPPET_LEN: usize = 120;
+
+/// A response that arrived but couldn't be parsed, as opposed to an unreachable server
+#[derive(Debug)]
+pub(crate) struct InvalidResponse {
+    format: &'static str,
+    error: String,
+    len: usize,
+    /// Start of a textual body, to tell e.g. a truncated payload from a proxy's error page
+    snippet: Option<String>,
+}
+
+impl fmt::Display for InvalidResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid {} response ({}, {} bytes)",
+            self.format, self.error, self.len
+        )?;
+        match &self.snippet {
+            Some(snippet) => write!(f, ": {}", snippet),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for InvalidResponse {}
+
+/// The start of `body` quoted, `None` if it's empty
+fn body_snippet(body: &[u8]) -> Option<String> {
+    if body.is_empty() {
+        return None;
+    }
+    let snippet = String::from_utf8_lossy(&body[..body.len().min(BODY_SNIPPET_LEN)]);
+    let ellipsis = if body.len() > BODY_SNIPPET_LEN {
+        "…"
+    } else {
+        ""
+    };
+    Some(format!("{:?}{}", snippet, ellipsis))
+}
+
+/// Reads and parses a JSON response, quoting the body in the error if it's invalid
+fn read_json<T: DeserializeOwned>(response: &mut ureq::http::Response<ureq::Body>) -> Result<T> {
+    let body = response.body_mut().read_to_vec()?;
+    serde_json::from_slice(&body).map_err(|e| {
+        InvalidResponse {
+            format: "JSON",
+            error: e.to_string(),
+            len: body.len(),
+            snippet: body_snippet(&body),
+        }
+        .into()
+    })
+}
+
+/// Adds the bearer token, if any, to a request
+fn authorize<B>(request: ureq::RequestBuilder<B>, token: Option<&str>) -> ureq::RequestBuilder<B> {
+    match token {
+        Some(token) => request.header("Authorization", format!("Bearer {}", token)),
+        None => request,
+    }
+}
+
+/// Fetches channel metrics from the HTTP server.
+/// Asks for MessagePack, which is cheaper to parse, falling back to JSON for older servers.
+pub(crate) fn fetch_metrics(
+    agent: &ureq::Agent,
+    port: u16,
+    token: Option<&str>,
+) -> Result<MetricsJson> {
     let url = format!("http://127.0.0.1:{}/metrics", port);
-    let metrics: MetricsJson = agent.get(&url).call()?.body_mut().read_json()?;
+    let mut response = authorize(agent.get(&url), token)
+        .header(
+            "Accept",
+            format!("{}, application/json", MSGPACK_CONTENT_TYPE),
+        )
+        .call()?;
+    let content_type = response.headers().get("Content-Type");
+    let metrics: MetricsJson = if content_type.is_some_and(|value| value == MSGPACK_CONTENT_TYPE) {
+        let body = response.body_mut().read_to_vec()?;
+        // Binary, so not quoted
+        rmp_serde::from_slice(&body).map_err(|e| InvalidResponse {
+            format: "MessagePack",
+            error: e.to_string(),
+            len: body.len(),
+            snippet: None,
+        })?
+    } else {
+        read_json(&mut response)?
+    };
+    if metrics.version > METRICS_SCHEMA_VERSION {
+        eyre::bail!(
+            "Server reports metrics schema v{}, this console supports up to v{}: upgrade channels-console",
+            metrics.version,
+            METRICS_SCHEMA_VERSION
+        );
+    }
     Ok(metrics)
 }
 
+/// Fetches information about the instrumented process from the HTTP server
+pub(crate) fn fetch_server_info(
+    agent: &ureq::Agent,
+    port: u16,
+    token: Option<&str>,
+) -> Result<ServerInfo> {
+    let url = format!("http://127.0.0.1:{}/info", port);
+    let info: ServerInfo = read_json(&mut authorize(agent.get(&url), token).call()?)?;
+    Ok(info)
+}
+
+/// Fetches the health of the stats collector from the HTTP server
+pub(crate) fn fetch_diagnostics(
+    agent: &ureq::Agent,
+    port: u16,
+    token: Option<&str>,
+) -> Result<Diagnostics> {
+    let url = format!("http://127.0.0.1:{}/diagnostics", port);
+    let diagnostics: Diagnostics = read_json(&mut authorize(agent.get(&url), token).call()?)?;
+    Ok(diagnostics)
+}
+
 /// Fetches logs for a specific channel from the HTTP server
-pub(crate) fn fetch_logs(agent: &ureq::Agent, port: u16, channel_id: u64) -> Result<ChannelLogs> {
+pub(crate) fn fetch_logs(
+    agent: &ureq::Agent,
+    port: u16,
+    token: Option<&str>,
+    channel_id: u64,
+) -> Result<ChannelLogs> {
     let url = format!("http://127.0.0.1:{}/logs/{}", port, channel_id);
-    let logs: ChannelLogs = agent.get(&url).call()?.body_mut().read_json()?;
+    let logs: ChannelLogs = read_json(&mut authorize(agent.get(&url), token).call()?)?;
     Ok(logs)
 }
+
+/// Fetches the creation backtrace of a channel from the HTTP server
+pub(crate) fn fetch_backtrace(
+    agent: &ureq::Agent,
+    port: u16,
+    token: Option<&str>,
+    channel_id: u64,
+) -> Result<String> {
+    let url = format!(
+        "http://127.0.0.1:{}/channels/{}/backtrace",
+        port, channel_id
+    );
+    let backtrace = authorize(agent.get(&url), token)
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+    Ok(backtrace)
+}
+
+/// Asks the server to drop closed channels and reset counts of live ones
+pub(crate) fn reset_stats(agent: &ureq::Agent, port: u16, token: Option<&str>) -> Result<()> {
+    let url = format!("http://127.0.0.1:{}/reset", port);
+    authorize(agent.post(&url), token).send_empty()?;
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use channels_console::{
+    MetricsJson, RecordedEvent, SerializableChannelStats, StatsSummary, METRICS_SCHEMA_VERSION,
+};
+use eyre::{Result, WrapErr};
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Recorded time covered by each snapshot of a replayed session
+const SESSION_SNAPSHOT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A saved snapshot, either a whole `/metrics` payload or just its channels
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Snapshot {
+    Metrics(MetricsJson),
+    Channels(Vec<SerializableChannelStats>),
+}
+
+impl From<Snapshot> for MetricsJson {
+    fn from(snapshot: Snapshot) -> Self {
+        match snapshot {
+            Snapshot::Metrics(metrics) => metrics,
+            Snapshot::Channels(stats) => MetricsJson {
+                version: METRICS_SCHEMA_VERSION,
+                current_elapsed_ns: 0,
+                summary: StatsSummary::from_stats(&stats),
+                stats,
+            },
+        }
+    }
+}
+
+/// Snapshots loaded with `--from-file`, shown one at a time instead of live metrics
+pub(crate) struct Replay {
+    snapshots: Vec<MetricsJson>,
+    position: usize,
+}
+
+impl Replay {
+    /// Reads a file holding a single snapshot, or one snapshot per line, e.g. collected with
+    /// `channels-console --once --format json` in a loop, or a session recorded with
+    /// `channels_console::record_to`
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+
+        let is_session = content
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .is_some_and(|line| serde_json::from_str::<RecordedEvent>(line).is_ok());
+
+        let snapshots: Vec<MetricsJson> = if is_session {
+            let events = content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| {
+                    serde_json::from_str::<RecordedEvent>(line).wrap_err_with(|| {
+                        format!("{}:{}: not a recorded event", path.display(), i + 1)
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            channels_console::replay_session(events, SESSION_SNAPSHOT_INTERVAL)
+        } else {
+            Self::parse_snapshots(path, &content)?
+        };
+
+        if snapshots.is_empty() {
+            eyre::bail!("{} contains no snapshots", path.display());
+        }
+        if let Some(metrics) = snapshots
+            .iter()
+            .find(|metrics| metrics.version > METRICS_SCHEMA_VERSION)
+        {
+            eyre::bail!(
+                "{} holds metrics schema v{}, this console supports up to v{}: upgrade channels-console",
+                path.display(),
+                metrics.version,
+                METRICS_SCHEMA_VERSION
+            );
+        }
+
+        Ok(Self {
+            snapshots,
+            position: 0,
+        })
+    }
+
+    fn parse_snapshots(path: &Path, content: &str) -> Result<Vec<MetricsJson>> {
+        match serde_json::from_str::<Snapshot>(content) {
+            Ok(snapshot) => Ok(vec![snapshot.into()]),
+            Err(_) => content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| {
+                    serde_json::from_str::<Snapshot>(line)
+                        .map(MetricsJson::from)
+                        .wrap_err_with(|| {
+                            format!("{}:{}: not a metrics snapshot", path.display(), i + 1)
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn current(&self) -> &MetricsJson {
+        &self.snapshots[self.position]
+    }
+
+    pub(crate) fn last(&self) -> &MetricsJson {
+        &self.snapshots[self.snapshots.len() - 1]
+    }
+
+    /// Moves `delta` snapshots forward or back, returning whether the position changed
+    pub(crate) fn step(&mut self, delta: isize) -> bool {
+        let position = self
+            .position
+            .saturating_add_signed(delta)
+            .min(self.snapshots.len() - 1);
+        let moved = position != self.position;
+        self.position = position;
+        moved
+    }
+
+    /// 1-based position of the current snapshot and the number of snapshots
+    pub(crate) fn progress(&self) -> (usize, usize) {
+        (self.position + 1, self.snapshots.len())
+    }
+}
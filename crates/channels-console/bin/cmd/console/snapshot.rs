@@ -0,0 +1,92 @@
+use channels_console::{ByteUnits, MetricsJson, StatsSummary};
+use eyre::Result;
+use prettytable::{Cell, Row, Table};
+
+use super::app::SnapshotFormat;
+use super::http::fetch_metrics;
+
+/// Fetches metrics once and prints them to stdout
+pub(crate) fn print_snapshot(
+    agent: &ureq::Agent,
+    port: u16,
+    token: Option<&str>,
+    format: SnapshotFormat,
+    units: ByteUnits,
+) -> Result<()> {
+    let metrics = fetch_metrics(agent, port, token)?;
+    print_metrics(&metrics, format, units)
+}
+
+/// Prints a metrics snapshot to stdout
+pub(crate) fn print_metrics(
+    metrics: &MetricsJson,
+    format: SnapshotFormat,
+    units: ByteUnits,
+) -> Result<()> {
+    match format {
+        SnapshotFormat::Json => println!("{}", serde_json::to_string(metrics)?),
+        SnapshotFormat::Table => print_table(metrics, units),
+    }
+
+    Ok(())
+}
+
+fn print_table(metrics: &MetricsJson, units: ByteUnits) {
+    if metrics.stats.is_empty() {
+        println!("No instrumented channels found.");
+        return;
+    }
+
+    let mut table = Table::new();
+
+    table.add_row(Row::new(vec![
+        Cell::new("Channel"),
+        Cell::new("Type"),
+        Cell::new("State"),
+        Cell::new("Sent"),
+        Cell::new("Sent B"),
+        Cell::new("Received"),
+        Cell::new("Dropped"),
+        Cell::new("Queued"),
+        Cell::new("Mem"),
+    ]));
+
+    for stat in &metrics.stats {
+        let (received, queued, mem) = if stat.sender_only {
+            ("N/A".to_string(), "N/A".to_string(), "N/A".to_string())
+        } else {
+            (
+                stat.received_count.to_string(),
+                stat.queued.to_string(),
+                units.format_message(stat.queued_bytes, stat.type_size),
+            )
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(&stat.label),
+            Cell::new(&stat.channel_type_label()),
+            Cell::new(stat.state.as_str()),
+            Cell::new(&stat.sent_count.to_string()),
+            Cell::new(&units.format_message(stat.total_bytes_sent, stat.type_size)),
+            Cell::new(&received),
+            Cell::new(&stat.dropped_count.to_string()),
+            Cell::new(&queued),
+            Cell::new(&mem),
+        ]));
+    }
+
+    // Computed locally, servers of older versions don't report a summary
+    let summary = StatsSummary::from_stats(&metrics.stats);
+    table.add_row(Row::new(vec![
+        Cell::new(&format!("Total ({} channels)", summary.channels)),
+        Cell::new(""),
+        Cell::new(""),
+        Cell::new(&summary.sent_count.to_string()),
+        Cell::new(&units.format(summary.total_bytes_sent)),
+        Cell::new(&summary.received_count.to_string()),
+        Cell::new(&summary.dropped_count.to_string()),
+        Cell::new(&summary.queued.to_string()),
+        Cell::new(&units.format(summary.queued_bytes)),
+    ]));
+
+    table.printstd();
+}
@@ -0,0 +1,194 @@
+use eyre::{eyre, Result};
+use ratatui::style::Color;
+use std::str::FromStr;
+
+/// Colors used across the console views
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Theme {
+    /// Table header text
+    pub(crate) header: Color,
+    /// Keybinding hints
+    pub(crate) key: Color,
+    /// Unfocused panes and placeholder text
+    pub(crate) dimmed: Color,
+    /// Background of the selected row
+    pub(crate) selected_bg: Color,
+    pub(crate) active: Color,
+    pub(crate) closed: Color,
+    pub(crate) full: Color,
+    pub(crate) notified: Color,
+    /// Oneshots whose sender was dropped without sending
+    pub(crate) cancelled: Color,
+    /// Queue below 50% of capacity
+    pub(crate) usage_low: Color,
+    /// Queue at 50% of capacity or more
+    pub(crate) usage_medium: Color,
+    /// Queue at capacity
+    pub(crate) usage_high: Color,
+    /// Live status indicator
+    pub(crate) ok: Color,
+    /// Paused and stale status indicators, hints
+    pub(crate) warning: Color,
+    /// Error messages
+    pub(crate) error: Color,
+}
+
+/// Whether colors are disabled with a non-empty `NO_COLOR`, see <https://no-color.org>
+pub(crate) fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Default theme, tuned for dark terminal backgrounds
+    pub(crate) fn dark() -> Self {
+        Self {
+            header: Color::Yellow,
+            key: Color::Blue,
+            dimmed: Color::DarkGray,
+            selected_bg: Color::DarkGray,
+            active: Color::Green,
+            closed: Color::Yellow,
+            full: Color::Red,
+            notified: Color::Blue,
+            cancelled: Color::LightRed,
+            usage_low: Color::Green,
+            usage_medium: Color::Yellow,
+            usage_high: Color::Red,
+            ok: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+        }
+    }
+
+    /// Darker shades that stay readable on light terminal backgrounds
+    pub(crate) fn light() -> Self {
+        // Dark orange, plain yellow is unreadable on white
+        let amber = Color::Indexed(130);
+        Self {
+            header: Color::Blue,
+            key: Color::Blue,
+            dimmed: Color::Gray,
+            selected_bg: Color::Gray,
+            active: Color::Green,
+            closed: amber,
+            full: Color::Red,
+            notified: Color::Magenta,
+            cancelled: Color::Indexed(88),
+            usage_low: Color::Green,
+            usage_medium: amber,
+            usage_high: Color::Red,
+            ok: Color::Green,
+            warning: amber,
+            error: Color::Red,
+        }
+    }
+
+    /// No colors, only the terminal's default foreground and background
+    pub(crate) fn mono() -> Self {
+        Self {
+            header: Color::Reset,
+            key: Color::Reset,
+            dimmed: Color::Reset,
+            selected_bg: Color::Reset,
+            active: Color::Reset,
+            closed: Color::Reset,
+            full: Color::Reset,
+            notified: Color::Reset,
+            cancelled: Color::Reset,
+            usage_low: Color::Reset,
+            usage_medium: Color::Reset,
+            usage_high: Color::Reset,
+            ok: Color::Reset,
+            warning: Color::Reset,
+            error: Color::Reset,
+        }
+    }
+
+    /// Resolves a built-in theme name (`dark`, `light`, `mono`) or a path to a TOML theme file
+    pub(crate) fn load(name_or_path: &str) -> Result<Self> {
+        if let Some(theme) = Self::builtin(name_or_path) {
+            return Ok(theme);
+        }
+
+        let contents = std::fs::read_to_string(name_or_path).map_err(|e| {
+            eyre!(
+                "Unknown theme {}: expected dark, light, mono or a path to a TOML theme file ({})",
+                name_or_path,
+                e
+            )
+        })?;
+        Self::from_toml(&contents).map_err(|e| eyre!("Invalid theme file {}: {}", name_or_path, e))
+    }
+
+    fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "mono" => Some(Self::mono()),
+            _ => None,
+        }
+    }
+
+    /// Parses a TOML theme. An optional `base` key selects the built-in theme
+    /// to start from, all other keys override single colors, e.g.:
+    ///
+    /// ```toml
+    /// base = "light"
+    /// header = "magenta"
+    /// full = "#d70000"
+    /// ```
+    fn from_toml(contents: &str) -> Result<Self> {
+        let table: toml::Table = contents.parse()?;
+
+        let mut theme = match table.get("base") {
+            Some(base) => {
+                let base = base
+                    .as_str()
+                    .ok_or_else(|| eyre!("`base` must be a string"))?;
+                Self::builtin(base).ok_or_else(|| eyre!("Unknown base theme: {}", base))?
+            }
+            None => Self::default(),
+        };
+
+        for (key, value) in table.iter().filter(|(key, _)| *key != "base") {
+            let value = value
+                .as_str()
+                .ok_or_else(|| eyre!("`{}` must be a color string", key))?;
+            let color = Color::from_str(value)
+                .map_err(|_| eyre!("Invalid color for `{}`: {}", key, value))?;
+            *theme
+                .color_mut(key)
+                .ok_or_else(|| eyre!("Unknown theme key: {}", key))? = color;
+        }
+
+        Ok(theme)
+    }
+
+    fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+        let color = match key {
+            "header" => &mut self.header,
+            "key" => &mut self.key,
+            "dimmed" => &mut self.dimmed,
+            "selected_bg" => &mut self.selected_bg,
+            "active" => &mut self.active,
+            "closed" => &mut self.closed,
+            "full" => &mut self.full,
+            "notified" => &mut self.notified,
+            "cancelled" => &mut self.cancelled,
+            "usage_low" => &mut self.usage_low,
+            "usage_medium" => &mut self.usage_medium,
+            "usage_high" => &mut self.usage_high,
+            "ok" => &mut self.ok,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            _ => return None,
+        };
+        Some(color)
+    }
+}
@@ -1,6 +1,10 @@
 pub(crate) mod bottom_bar;
 pub(crate) mod channels;
+pub(crate) mod help;
 pub(crate) mod inspect;
 pub(crate) mod logs;
 pub(crate) mod main_view;
+pub(crate) mod mini;
+pub(crate) mod throughput;
 pub(crate) mod top_bar;
+pub(crate) mod types;
@@ -9,48 +9,67 @@ use ratatui::{
 use std::time::Duration;
 
 use crate::cmd::console::app::Focus;
+use crate::cmd::console::theme::Theme;
 
 /// Renders the bottom controls bar showing context-aware keybindings
 pub fn render_bottom_bar(
     frame: &mut Frame,
     area: Rect,
     focus: Focus,
+    flash: Option<&str>,
+    theme: &Theme,
     _last_render_duration: Duration,
 ) {
     let controls_line = match focus {
         Focus::Channels => Line::from(vec![
             " Quit ".into(),
-            "<q> ".blue().bold(),
+            "<q> ".fg(theme.key).bold(),
+            " | Help ".into(),
+            "<?> ".fg(theme.key).bold(),
             " | Navigate ".into(),
-            "<←↑↓→/hjkl> ".blue().bold(),
+            "<←↑↓→/hjkl> ".fg(theme.key).bold(),
             " | Toggle Logs ".into(),
-            "<o> ".blue().bold(),
+            "<o> ".fg(theme.key).bold(),
             " | Pause ".into(),
-            "<p> ".blue().bold(),
+            "<p> ".fg(theme.key).bold(),
+            " | Reset ".into(),
+            "<r> ".fg(theme.key).bold(),
         ]),
         Focus::Logs => Line::from(vec![
             " Quit ".into(),
-            "<q> ".blue().bold(),
+            "<q> ".fg(theme.key).bold(),
+            " | Help ".into(),
+            "<?> ".fg(theme.key).bold(),
             " | Navigate ".into(),
-            "<←↑↓→/hjkl> ".blue().bold(),
+            "<←↑↓→/hjkl> ".fg(theme.key).bold(),
             " | Toggle Logs ".into(),
-            "<o> ".blue().bold(),
+            "<o> ".fg(theme.key).bold(),
             " | Pause ".into(),
-            "<p> ".blue().bold(),
+            "<p> ".fg(theme.key).bold(),
             " | Inspect ".into(),
-            "<i> ".blue().bold(),
+            "<i> ".fg(theme.key).bold(),
+            " | Sort ".into(),
+            "<s> ".fg(theme.key).bold(),
+            " | Time ".into(),
+            "<t> ".fg(theme.key).bold(),
         ]),
         Focus::Inspect => Line::from(vec![
             " Quit ".into(),
-            "<q> ".blue().bold(),
+            "<q> ".fg(theme.key).bold(),
+            " | Help ".into(),
+            "<?> ".fg(theme.key).bold(),
             " | Navigate ".into(),
-            "<←↑↓→/hjkl> ".blue().bold(),
+            "<←↑↓→/hjkl> ".fg(theme.key).bold(),
             " | Toggle Logs ".into(),
-            "<o> ".blue().bold(),
+            "<o> ".fg(theme.key).bold(),
             " | Pause ".into(),
-            "<p> ".blue().bold(),
+            "<p> ".fg(theme.key).bold(),
+            " | Copy ".into(),
+            "<c> ".fg(theme.key).bold(),
+            " | Save ".into(),
+            "<w> ".fg(theme.key).bold(),
             " | Close ".into(),
-            "<i/o/h> ".blue().bold(),
+            "<i/o/h> ".fg(theme.key).bold(),
         ]),
     };
 
@@ -72,6 +91,13 @@ pub fn render_bottom_bar(
         .title(" Controls ")
         .border_set(border::PLAIN);
 
+    let block = match flash {
+        Some(message) => {
+            block.title(Line::from(format!(" {} ", message).fg(theme.ok)).right_aligned())
+        }
+        None => block,
+    };
+
     let paragraph = Paragraph::new(controls_line).block(block).left_aligned();
 
     frame.render_widget(paragraph, area);
@@ -1,19 +1,259 @@
 use crate::cmd::console::app::Focus;
-use crate::cmd::console::widgets::formatters::{queue_status, truncate_left};
-use channels_console::{format_bytes, ChannelState, ChannelType, SerializableChannelStats};
+use crate::cmd::console::theme::Theme;
+use crate::cmd::console::widgets::formatters::{queue_status, short_type_name, truncate_left};
+use crate::cmd::console::widgets::scrollbar::render_table_scrollbar;
+use channels_console::{
+    aggregate_by_source, ByteUnits, ChannelState, ChannelType, Diagnostics,
+    SerializableChannelStats,
+};
+use clap::ValueEnum;
 use ratatui::{
     layout::{Constraint, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     symbols::border,
     text::Text,
     widgets::{Block, Cell, HighlightSpacing, Row, Table, TableState},
     Frame,
 };
+use std::collections::HashSet;
+
+/// Collector queue length from which its row is highlighted, the instrumentation itself is lagging
+const COLLECTOR_LAG_WARNING: u64 = 1000;
+
+/// A column of the channels table, selectable with `--columns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    Channel,
+    Type,
+    Payload,
+    State,
+    Sent,
+    SentBytes,
+    Received,
+    Dropped,
+    Queue,
+    Mem,
+    Timeouts,
+    Errors,
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Channel => "Channel",
+            Column::Type => "Type",
+            Column::Payload => "Payload",
+            Column::State => "State",
+            Column::Sent => "Sent",
+            Column::SentBytes => "Sent B",
+            Column::Received => "Received",
+            Column::Dropped => "Dropped",
+            Column::Queue => "Queue",
+            Column::Mem => "Mem",
+            Column::Timeouts => "Timeouts",
+            Column::Errors => "Errors",
+        }
+    }
+
+    /// Share of the table width, relative to the other visible columns
+    fn width(self) -> u16 {
+        match self {
+            Column::Channel => 22,
+            Column::State => 14,
+            Column::Payload => 16,
+            Column::Type | Column::Queue => 12,
+            Column::SentBytes | Column::Received => 10,
+            Column::Sent | Column::Dropped | Column::Mem | Column::Timeouts | Column::Errors => 8,
+        }
+    }
+}
+
+/// A named set of channel table columns, selectable with `--view` and cycled with `v`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum View {
+    /// Queue depth and memory, to tell whether a channel is piling up messages
+    Memory,
+    /// Message counts, to tell whether data is flowing
+    Throughput,
+    /// All columns shown by default
+    Full,
+}
+
+impl View {
+    pub(crate) fn columns(self) -> Vec<Column> {
+        match self {
+            View::Memory => vec![
+                Column::Channel,
+                Column::Type,
+                Column::State,
+                Column::Queue,
+                Column::Mem,
+                Column::SentBytes,
+            ],
+            View::Throughput => vec![
+                Column::Channel,
+                Column::State,
+                Column::Sent,
+                Column::Received,
+                Column::Dropped,
+                Column::Errors,
+            ],
+            View::Full => vec![
+                Column::Channel,
+                Column::Type,
+                Column::Payload,
+                Column::State,
+                Column::Sent,
+                Column::SentBytes,
+                Column::Received,
+                Column::Dropped,
+                Column::Errors,
+                Column::Queue,
+                Column::Mem,
+            ],
+        }
+    }
+
+    pub(crate) fn next(self) -> Self {
+        match self {
+            View::Memory => View::Throughput,
+            View::Throughput => View::Full,
+            View::Full => View::Memory,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            View::Memory => "memory",
+            View::Throughput => "throughput",
+            View::Full => "full",
+        }
+    }
+}
+
+/// A row of the channels table
+pub(crate) enum DisplayRow<'a> {
+    /// Header introducing the channels of a group
+    Group(&'a str),
+    /// Index into the channel stats
+    Channel(usize),
+    /// Event queue of the stats collector, shown after all channels
+    Collector(Diagnostics),
+}
+
+/// Lays out the channels table rows, inserting a header before each group.
+/// Channels are expected to be sorted by group. Without any groups no headers are added.
+pub(crate) fn display_rows(stats: &[SerializableChannelStats]) -> Vec<DisplayRow<'_>> {
+    let has_groups = stats.iter().any(|stat| stat.group.is_some());
+    let mut rows = Vec::with_capacity(stats.len());
+    let mut current_group = None;
+
+    for (i, stat) in stats.iter().enumerate() {
+        if has_groups && (i == 0 || stat.group.as_deref() != current_group) {
+            current_group = stat.group.as_deref();
+            rows.push(DisplayRow::Group(current_group.unwrap_or("(ungrouped)")));
+        }
+        rows.push(DisplayRow::Channel(i));
+    }
+
+    rows
+}
+
+/// Groups channels by the value of a metadata key instead of their `group`, see `--group-by`.
+/// Their order within each group is kept, and channels without the key come last, ungrouped.
+pub(crate) fn group_by_meta(stats: &mut [SerializableChannelStats], key: &str) {
+    for stat in stats.iter_mut() {
+        stat.group = stat.meta.get(key).cloned();
+    }
+    stats.sort_by(|a, b| (a.group.is_none(), &a.group).cmp(&(b.group.is_none(), &b.group)));
+}
+
+/// Moves closed channels below the open ones of their group, or drops them if hidden.
+/// Channels are expected to be sorted by group, which is preserved.
+pub(crate) fn arrange_closed(stats: &mut Vec<SerializableChannelStats>, show_closed: bool) {
+    if !show_closed {
+        stats.retain(|stat| stat.state != ChannelState::Closed);
+        return;
+    }
+
+    let mut group_run = 0;
+    let mut runs = Vec::with_capacity(stats.len());
+    for (i, stat) in stats.iter().enumerate() {
+        if i > 0 && stat.group != stats[i - 1].group {
+            group_run += 1;
+        }
+        runs.push(group_run);
+    }
+
+    let mut keyed: Vec<_> = runs.into_iter().zip(stats.drain(..)).collect();
+    keyed.sort_by_key(|(run, stat)| (*run, stat.state == ChannelState::Closed));
+    stats.extend(keyed.into_iter().map(|(_, stat)| stat));
+}
+
+/// Collapses the channels of each source into a single row with their totals,
+/// listing the individual channels right below it if the source is expanded.
+pub(crate) fn collapse_sources(
+    stats: &mut Vec<SerializableChannelStats>,
+    expanded: &HashSet<String>,
+) {
+    let channels = std::mem::take(stats);
+    for row in aggregate_by_source(&channels) {
+        let source = row.source.clone();
+        let show_channels = row.count.is_some() && expanded.contains(&source);
+        stats.push(row);
+        if show_channels {
+            stats.extend(
+                channels
+                    .iter()
+                    .filter(|stat| stat.source == source)
+                    .cloned(),
+            );
+        }
+    }
+}
+
+/// Indices of the rows with queued messages, worst first: full and growing channels lead,
+/// then by the number of messages queued and how full a bounded channel is.
+pub(crate) fn backlog_ranking(stats: &[SerializableChannelStats]) -> Vec<usize> {
+    let fill = |stat: &SerializableChannelStats| match stat.channel_type {
+        ChannelType::Bounded(cap) if cap > 0 && !stat.unknown_capacity => {
+            stat.queued as f64 / cap as f64
+        }
+        _ => 0.0,
+    };
+
+    let mut ranking: Vec<usize> = (0..stats.len())
+        .filter(|&i| {
+            let stat = &stats[i];
+            stat.queued > 0 && !stat.sender_only && stat.channel_type != ChannelType::Oneshot
+        })
+        .collect();
+    ranking.sort_by(|&a, &b| {
+        let key = |stat: &SerializableChannelStats| {
+            let needs_attention = matches!(stat.state, ChannelState::Full | ChannelState::Growing);
+            (needs_attention, stat.queued)
+        };
+        key(&stats[b])
+            .cmp(&key(&stats[a]))
+            .then(fill(&stats[b]).total_cmp(&fill(&stats[a])))
+    });
+    ranking
+}
+
+/// Whether the channel at `i` is listed below the expanded aggregate row of its source
+fn is_expanded_channel(stats: &[SerializableChannelStats], i: usize) -> bool {
+    stats[..i]
+        .iter()
+        .rev()
+        .take_while(|stat| stat.source == stats[i].source)
+        .any(|stat| stat.count.is_some())
+}
 
 /// Renders the channels table with channel statistics
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn render_channels_panel(
     stats: &[SerializableChannelStats],
+    diagnostics: Option<Diagnostics>,
     area: Rect,
     frame: &mut Frame,
     table_state: &mut TableState,
@@ -21,99 +261,96 @@ pub(crate) fn render_channels_panel(
     focus: Focus,
     channel_position: usize,
     total_channels: usize,
+    columns: &[Column],
+    units: ByteUnits,
+    full_type_names: bool,
+    theme: &Theme,
 ) {
+    let total_width: u16 = columns.iter().map(|column| column.width()).sum();
     let available_width = area.width.saturating_sub(10);
-    let channel_width = ((available_width as f32 * 0.22) as usize).max(36);
+    let channel_share = Column::Channel.width() as f32 / total_width.max(1) as f32;
+    let channel_width = ((available_width as f32 * channel_share) as usize).max(36);
 
     let header_style = Style::default()
-        .fg(Color::Yellow)
+        .fg(theme.header)
         .add_modifier(Modifier::BOLD);
 
-    let header = Row::new(vec![
-        Cell::from("Channel"),
-        Cell::from("Type"),
-        Cell::from("State"),
-        Cell::from("Sent"),
-        Cell::from("Received"),
-        Cell::from("Queue"),
-        Cell::from("Mem"),
-    ])
-    .style(header_style)
-    .height(1);
-
-    let rows: Vec<Row> = stats
+    let header = Row::new(columns.iter().map(|column| Cell::from(column.header())))
+        .style(header_style)
+        .height(1);
+
+    let mut display_rows = display_rows(stats);
+    if let Some(diagnostics) = diagnostics {
+        display_rows.push(DisplayRow::Collector(diagnostics));
+    }
+    let dimmed = show_logs && !matches!(focus, Focus::Channels);
+
+    let rows: Vec<Row> = display_rows
         .iter()
-        .map(|stat| {
-            let (state_text, state_style) = match stat.state {
-                ChannelState::Active => (stat.state.to_string(), Style::default().fg(Color::Green)),
-                ChannelState::Closed => {
-                    (stat.state.to_string(), Style::default().fg(Color::Yellow))
-                }
-                ChannelState::Full => {
-                    (format!("⚠ {}", stat.state), Style::default().fg(Color::Red))
+        .map(|display_row| {
+            let (stat, i) = match display_row {
+                DisplayRow::Group(group) => {
+                    let style = if dimmed {
+                        Style::default().fg(theme.dimmed)
+                    } else {
+                        Style::default().fg(theme.key)
+                    };
+                    return Row::new(vec![Cell::from(format!("▾ {group}"))])
+                        .style(style.add_modifier(Modifier::BOLD));
                 }
-                ChannelState::Notified => {
-                    (stat.state.to_string(), Style::default().fg(Color::Blue))
+                DisplayRow::Channel(i) => (&stats[*i], i),
+                DisplayRow::Collector(diagnostics) => {
+                    let color = if diagnostics.event_queue_len >= COLLECTOR_LAG_WARNING {
+                        theme.warning
+                    } else {
+                        theme.dimmed
+                    };
+                    return Row::new(vec![Cell::from(format!(
+                        "⚙ collector: {} events queued (peak {})",
+                        diagnostics.event_queue_len, diagnostics.peak_event_queue_len
+                    ))])
+                    .style(Style::default().fg(color));
                 }
             };
 
-            let mem_cell = match stat.channel_type {
-                ChannelType::Unbounded => Cell::from("N/A"),
-                _ => Cell::from(format_bytes(stat.queued_bytes)),
-            };
+            let label = channel_label(stats, *i);
+            let row = Row::new(columns.iter().map(|column| {
+                channel_cell(
+                    *column,
+                    stat,
+                    &label,
+                    channel_width,
+                    units,
+                    full_type_names,
+                    theme,
+                )
+            }));
 
-            let row = Row::new(vec![
-                Cell::from(truncate_left(&stat.label, channel_width)),
-                Cell::from(stat.channel_type.to_string()),
-                Cell::from(state_text).style(state_style),
-                Cell::from(stat.sent_count.to_string()),
-                Cell::from(stat.received_count.to_string()),
-                queue_status(stat.queued, &stat.channel_type, 8),
-                mem_cell,
-            ]);
-
-            // Dim the row if logs are shown and channels table is not focused
-            if show_logs && !matches!(focus, Focus::Channels) {
-                row.style(Style::default().fg(Color::DarkGray))
+            // Closed channels only keep their final counts around
+            if stat.state == ChannelState::Closed {
+                row.style(
+                    Style::default()
+                        .fg(theme.dimmed)
+                        .add_modifier(Modifier::CROSSED_OUT),
+                )
+            } else if dimmed {
+                // Dim the row if logs are shown and channels table is not focused
+                row.style(Style::default().fg(theme.dimmed))
             } else {
                 row
             }
         })
         .collect();
 
-    let widths = [
-        Constraint::Percentage(30), // Channel
-        Constraint::Percentage(14), // Type
-        Constraint::Percentage(10), // State
-        Constraint::Percentage(9),  // Sent
-        Constraint::Percentage(11), // Received
-        Constraint::Percentage(16), // Queue
-        Constraint::Percentage(10), // Mem
-    ];
+    let widths = columns
+        .iter()
+        .map(|column| Constraint::Fill(column.width()));
 
     let selected_row_style = Style::default()
         .add_modifier(Modifier::REVERSED)
-        .bg(Color::DarkGray);
+        .bg(theme.selected_bg);
 
-    let table_block = if show_logs {
-        let border_set = if focus == Focus::Channels {
-            border::THICK
-        } else {
-            border::PLAIN
-        };
-        Block::bordered()
-            .title(format!(" [{}/{}] ", channel_position, total_channels))
-            .border_set(border_set)
-            .style(if focus == Focus::Channels {
-                Style::default()
-            } else {
-                Style::default().fg(Color::DarkGray)
-            })
-    } else {
-        Block::bordered()
-            .title(format!(" [{}/{}] ", channel_position, total_channels))
-            .border_set(border::THICK)
-    };
+    let table_block = channels_block(show_logs, focus, channel_position, total_channels, theme);
 
     let table = Table::new(rows, widths)
         .header(header)
@@ -123,5 +360,133 @@ pub(crate) fn render_channels_panel(
         .highlight_symbol(Text::from(">"))
         .highlight_spacing(HighlightSpacing::Always);
 
-    frame.render_stateful_widget(table, area, table_state);
+    let mut display_state = display_state(&display_rows, table_state);
+    frame.render_stateful_widget(table, area, &mut display_state);
+    *table_state.offset_mut() = display_state.offset();
+
+    render_table_scrollbar(
+        frame,
+        area,
+        display_rows.len(),
+        display_state.offset(),
+        theme,
+    );
+}
+
+/// Border of the channels panel, titled with the position of the selected channel
+pub(crate) fn channels_block(
+    show_logs: bool,
+    focus: Focus,
+    channel_position: usize,
+    total_channels: usize,
+    theme: &Theme,
+) -> Block<'static> {
+    let block = Block::bordered().title(format!(" [{}/{}] ", channel_position, total_channels));
+    if !show_logs {
+        return block.border_set(border::THICK);
+    }
+
+    if focus == Focus::Channels {
+        block.border_set(border::THICK)
+    } else {
+        block
+            .border_set(border::PLAIN)
+            .style(Style::default().fg(theme.dimmed))
+    }
+}
+
+/// Maps the selected channel to its row. The selection refers to channels,
+/// while the table also contains group headers.
+pub(crate) fn display_state(display_rows: &[DisplayRow], table_state: &TableState) -> TableState {
+    TableState::default()
+        .with_offset(table_state.offset())
+        .with_selected(table_state.selected().and_then(|selected| {
+            display_rows
+                .iter()
+                .position(|row| matches!(row, DisplayRow::Channel(i) if *i == selected))
+        }))
+}
+
+/// Label of the channel at `i`, marking aggregate rows with their channel count
+/// and indenting the channels listed below an expanded one
+pub(crate) fn channel_label(stats: &[SerializableChannelStats], i: usize) -> String {
+    let stat = &stats[i];
+    match stat.count {
+        Some(count) => {
+            let expanded = stats
+                .get(i + 1)
+                .is_some_and(|next| next.source == stat.source);
+            let marker = if expanded { "▾" } else { "▸" };
+            format!("{marker} {} ×{count}", stat.label)
+        }
+        None if is_expanded_channel(stats, i) => format!("  {}", stat.label),
+        None => stat.label.clone(),
+    }
+}
+
+/// Text and style of a channel state, marking the ones that need attention and
+/// telling which end closed a channel, e.g. "closed (rx dropped)"
+pub(crate) fn state_label(stat: &SerializableChannelStats, theme: &Theme) -> (String, Style) {
+    let state = stat.state;
+    match state {
+        ChannelState::Active => (state.to_string(), Style::default().fg(theme.active)),
+        ChannelState::Closed => {
+            let text = match stat.close_reason {
+                Some(reason) => format!("{state} ({reason})"),
+                None => state.to_string(),
+            };
+            (text, Style::default().fg(theme.closed))
+        }
+        ChannelState::Full => (format!("⚠ {}", state), Style::default().fg(theme.full)),
+        ChannelState::Notified => (state.to_string(), Style::default().fg(theme.notified)),
+        ChannelState::Cancelled => (format!("✗ {}", state), Style::default().fg(theme.cancelled)),
+        ChannelState::Growing => (format!("⚠ {}", state), Style::default().fg(theme.warning)),
+    }
+}
+
+fn channel_cell(
+    column: Column,
+    stat: &SerializableChannelStats,
+    label: &str,
+    channel_width: usize,
+    units: ByteUnits,
+    full_type_names: bool,
+    theme: &Theme,
+) -> Cell<'static> {
+    match column {
+        Column::Channel => Cell::from(truncate_left(label, channel_width)),
+        Column::Type => Cell::from(stat.channel_type_label()),
+        Column::Payload if full_type_names => Cell::from(stat.type_name.clone()),
+        Column::Payload => Cell::from(short_type_name(&stat.type_name)),
+        Column::State => {
+            let (state_text, state_style) = state_label(stat, theme);
+            Cell::from(state_text).style(state_style)
+        }
+        Column::Sent => Cell::from(stat.sent_count.to_string()),
+        Column::SentBytes => {
+            Cell::from(units.format_message(stat.total_bytes_sent, stat.type_size))
+        }
+        Column::Received if stat.sender_only => Cell::from("N/A"),
+        Column::Received => Cell::from(stat.received_count.to_string()),
+        Column::Dropped if stat.dropped_count > 0 => {
+            Cell::from(stat.dropped_count.to_string()).style(Style::default().fg(theme.full))
+        }
+        Column::Dropped => Cell::from("0"),
+        Column::Timeouts if stat.timeout_count > 0 => {
+            Cell::from(stat.timeout_count.to_string()).style(Style::default().fg(theme.warning))
+        }
+        Column::Timeouts => Cell::from("0"),
+        Column::Errors if stat.error_count() > 0 => {
+            Cell::from(stat.error_count().to_string()).style(Style::default().fg(theme.full))
+        }
+        Column::Errors => Cell::from("0"),
+        Column::Queue if stat.sender_only => Cell::from("N/A"),
+        Column::Queue if stat.unknown_capacity => Cell::from(format!("[{}/?]", stat.queued)),
+        Column::Queue => queue_status(stat.queued, &stat.channel_type, 8, theme),
+        Column::Mem => match stat.channel_type {
+            _ if stat.sender_only => Cell::from("N/A"),
+            ChannelType::Unbounded => Cell::from("N/A"),
+            _ => Cell::from(units.format_message(stat.queued_bytes, stat.type_size)),
+        },
+    }
 }
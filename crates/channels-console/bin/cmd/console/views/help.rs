@@ -0,0 +1,83 @@
+use crate::cmd::console::theme::Theme;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    symbols::border,
+    widgets::{Block, Cell, Clear, Row, Table},
+    Frame,
+};
+
+const KEYBINDINGS: &[(&str, &str)] = &[
+    ("q", "Quit"),
+    ("↑ / k", "Select previous channel or log"),
+    ("↓ / j", "Select next channel or log"),
+    ("PgUp / PgDn", "Move the selection by a page"),
+    ("Home / End", "Select the first or last row"),
+    ("← / h", "Focus channels, close inspect popup"),
+    ("→ / l", "Focus logs, opening them if hidden"),
+    ("o", "Toggle logs for the selected channel"),
+    ("i", "Inspect the selected log message"),
+    (
+        "Tab / S-Tab",
+        "Next or previous field of an inspected JSON message",
+    ),
+    ("b", "Show where the selected channel was created"),
+    ("c", "Copy the inspected message to the clipboard"),
+    (
+        "w",
+        "Append the inspected message to channels-console-export.log",
+    ),
+    ("p", "Pause or resume refreshing"),
+    ("t", "Toggle wall-clock log timestamps"),
+    ("s", "Sort logs by index or latency, either direction"),
+    ("x", "Show or hide closed channels"),
+    ("m", "Toggle compact one-line-per-channel mode"),
+    ("v", "Cycle memory, throughput and full column views"),
+    ("[ / ]", "Previous or next snapshot, with --from-file"),
+    ("a", "Aggregate channels created at the same source"),
+    ("e", "Expand or collapse the selected aggregate row"),
+    ("y", "Show totals per message type"),
+    (
+        "g",
+        "Jump to the most backed-up channel, again for the next",
+    ),
+    ("+ / -", "Refresh less or more often"),
+    ("r", "Reset stats and drop closed channels"),
+    ("?", "Toggle this help"),
+    ("Esc", "Close this help"),
+    ("Mouse", "Click to select, scroll to move the selection"),
+];
+
+/// Renders a centered popup listing all keybindings
+pub(crate) fn render_help_popup(area: Rect, frame: &mut Frame, theme: &Theme) {
+    let popup_width = 60.min(area.width);
+    let popup_height = (KEYBINDINGS.len() as u16 + 2).min(area.height);
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect {
+        x: area.x + x,
+        y: area.y + y,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let key_style = Style::default().fg(theme.key).add_modifier(Modifier::BOLD);
+
+    let rows: Vec<Row> = KEYBINDINGS
+        .iter()
+        .map(|(key, action)| Row::new(vec![Cell::from(*key).style(key_style), Cell::from(*action)]))
+        .collect();
+
+    let block = Block::bordered()
+        .title(" Help - press ? or Esc to close ")
+        .border_set(border::DOUBLE);
+
+    let table = Table::new(rows, [Constraint::Length(8), Constraint::Min(0)])
+        .block(block)
+        .column_spacing(2);
+
+    frame.render_widget(table, popup_area);
+}
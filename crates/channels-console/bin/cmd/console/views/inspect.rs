@@ -1,48 +1,272 @@
-use crate::cmd::console::widgets::formatters::format_timestamp;
+use crate::cmd::console::theme::Theme;
+use crate::cmd::console::widgets::formatters::format_log_time;
 use channels_console::LogEntry;
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style, Stylize},
     symbols::border,
-    text::Line,
-    widgets::{Block, Clear},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
-/// Renders a centered popup displaying the full log message
-pub(crate) fn render_inspect_popup(entry: &LogEntry, area: Rect, frame: &mut Frame) {
-    // Center the popup at 80% of screen size
-    let popup_width = (area.width as f32 * 0.8) as u16;
-    let popup_height = (area.height as f32 * 0.8) as u16;
-    let x = (area.width.saturating_sub(popup_width)) / 2;
-    let y = (area.height.saturating_sub(popup_height)) / 2;
-
-    let popup_area = Rect {
-        x: area.x + x,
-        y: area.y + y,
-        width: popup_width,
-        height: popup_height,
-    };
-
+/// Renders a centered popup displaying the full log message. Messages logged with
+/// `log = json` are pretty-printed, with the `field`th field highlighted. If the matching
+/// `received` entry carries the message as well, both are shown side by side with the lines
+/// that differ highlighted.
+pub(crate) fn render_inspect_popup(
+    entry: &LogEntry,
+    received: Option<&LogEntry>,
+    field: usize,
+    start_wall_clock: Option<u64>,
+    area: Rect,
+    frame: &mut Frame,
+    theme: &Theme,
+) {
     let message = entry
         .message
         .as_deref()
         .unwrap_or("(missing \"log = true\")");
 
-    frame.render_widget(Clear, popup_area);
-
-    let block = Block::bordered()
-        .title(format!(
+    let title = match received {
+        Some(received) => format!(
+            " Message (Index: {}) - sent {}, received {} ",
+            entry.index,
+            format_log_time(start_wall_clock, entry.timestamp),
+            format_log_time(start_wall_clock, received.timestamp)
+        ),
+        None => format!(
             " Message (Index: {}) - {} ",
             entry.index,
-            format_timestamp(entry.timestamp)
-        ))
-        .border_set(border::DOUBLE);
+            format_log_time(start_wall_clock, entry.timestamp)
+        ),
+    };
+    if let Some(received_message) = received.and_then(|received| received.message.as_deref()) {
+        render_diff_popup(&title, message, received_message, area, frame, theme);
+        return;
+    }
+    match pretty_json(message) {
+        Some(json) => render_json_popup(&title, &json, field, area, frame, theme),
+        None => render_text_popup(&title, message, area, frame),
+    }
+}
+
+/// Pretty-printed message if it holds a JSON object or array, as logged with `log = json`
+pub(crate) fn pretty_json(message: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(message).ok()?;
+    if !value.is_object() && !value.is_array() {
+        return None;
+    }
+    serde_json::to_string_pretty(&value).ok()
+}
+
+/// Indexes of the lines of pretty-printed JSON that hold an object field, `"key": value`
+pub(crate) fn field_lines(json: &str) -> Vec<usize> {
+    json.lines()
+        .enumerate()
+        .filter(|(_, line)| field_key_len(line).is_some())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Length of the indented `"key": ` prefix of a pretty-printed JSON line
+fn field_key_len(line: &str) -> Option<usize> {
+    let key = line.trim_start();
+    if !key.starts_with('"') {
+        return None;
+    }
+    // Keys are escaped, so the first unescaped quote closes them
+    let mut escaped = false;
+    let end = key[1..].char_indices().find_map(|(i, c)| match c {
+        '\\' if !escaped => {
+            escaped = true;
+            None
+        }
+        '"' if !escaped => Some(i + 2),
+        _ => {
+            escaped = false;
+            None
+        }
+    })?;
+    key[end..]
+        .starts_with(": ")
+        .then_some(line.len() - key.len() + end + 2)
+}
+
+/// Renders pretty-printed JSON with highlighted keys, scrolled to keep the selected field visible
+fn render_json_popup(
+    title: &str,
+    json: &str,
+    field: usize,
+    area: Rect,
+    frame: &mut Frame,
+    theme: &Theme,
+) {
+    let popup_area = popup_area(area);
+    frame.render_widget(Clear, popup_area);
+
+    let fields = field_lines(json);
+    let selected = fields
+        .get(field.min(fields.len().saturating_sub(1)))
+        .copied();
+    let title = match selected {
+        Some(_) => format!("{}- <Tab> {}/{} fields ", title, field + 1, fields.len()),
+        None => title.to_string(),
+    };
+    let block = Block::bordered().title(title).border_set(border::DOUBLE);
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let lines: Vec<Line> = json
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = match field_key_len(line) {
+                Some(key_len) => Line::from(vec![
+                    Span::styled(&line[..key_len], Style::default().fg(theme.key)),
+                    Span::raw(&line[key_len..]),
+                ]),
+                None => Line::from(line),
+            };
+            if Some(i) == selected {
+                line.add_modifier(Modifier::REVERSED)
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    let scroll = selected
+        .unwrap_or(0)
+        .saturating_sub(inner_area.height as usize / 2)
+        .min(lines.len().saturating_sub(inner_area.height as usize));
+
+    frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), inner_area);
+}
+
+/// Renders the sent and received message side by side, highlighting the lines only one of
+/// them has
+fn render_diff_popup(
+    title: &str,
+    sent: &str,
+    received: &str,
+    area: Rect,
+    frame: &mut Frame,
+    theme: &Theme,
+) {
+    let popup_area = popup_area(area);
+    frame.render_widget(Clear, popup_area);
+
+    let sent = pretty_json(sent).unwrap_or_else(|| sent.to_string());
+    let received = pretty_json(received).unwrap_or_else(|| received.to_string());
+    let sent_lines: Vec<&str> = sent.lines().collect();
+    let received_lines: Vec<&str> = received.lines().collect();
+    let (sent_changed, received_changed) = changed_lines(&sent_lines, &received_lines);
+
+    let title = if sent_changed.iter().chain(&received_changed).any(|&c| c) {
+        format!("{}- differs ", title)
+    } else {
+        format!("{}- identical ", title)
+    };
+    let block = Block::bordered().title(title).border_set(border::DOUBLE);
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let [sent_area, received_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .spacing(1)
+            .areas(inner_area);
+    let changed_style = Style::default()
+        .fg(theme.warning)
+        .add_modifier(Modifier::BOLD);
+    let pane = |name: &'static str, lines: &[&str], changed: &[bool]| {
+        let lines: Vec<Line> = lines
+            .iter()
+            .zip(changed)
+            .map(|(line, &changed)| {
+                if changed {
+                    Line::styled(line.to_string(), changed_style)
+                } else {
+                    Line::from(line.to_string())
+                }
+            })
+            .collect();
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::new().borders(Borders::TOP).title(name))
+    };
+    frame.render_widget(pane(" Sent ", &sent_lines, &sent_changed), sent_area);
+    frame.render_widget(
+        pane(" Received ", &received_lines, &received_changed),
+        received_area,
+    );
+}
+
+/// Largest number of line pairs compared to find the common lines of two messages, beyond which
+/// lines are compared by position instead.
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+/// Marks the lines of `a` and `b` that aren't part of their longest common subsequence.
+fn changed_lines(a: &[&str], b: &[&str]) -> (Vec<bool>, Vec<bool>) {
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        let changed = |lines: &[&str], other: &[&str]| {
+            lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| other.get(i) != Some(line))
+                .collect()
+        };
+        return (changed(a, b), changed(b, a));
+    }
+
+    // common[i][j] is the length of the longest common subsequence of a[i..] and b[j..]
+    let mut common = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            common[i][j] = if a[i] == b[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut a_changed = vec![true; a.len()];
+    let mut b_changed = vec![true; b.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            a_changed[i] = false;
+            b_changed[j] = false;
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (a_changed, b_changed)
+}
+
+/// Renders a centered popup displaying where a channel was created
+pub(crate) fn render_backtrace_popup(label: &str, backtrace: &str, area: Rect, frame: &mut Frame) {
+    let title = format!(" Backtrace of {} - press b or Esc to close ", label);
+    render_text_popup(&title, backtrace, area, frame);
+}
+
+/// Renders `text` in a bordered popup, wrapping lines longer than its width
+fn render_text_popup(title: &str, text: &str, area: Rect, frame: &mut Frame) {
+    let popup_area = popup_area(area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::bordered().title(title).border_set(border::DOUBLE);
 
     let inner_area = block.inner(popup_area);
 
     frame.render_widget(block, popup_area);
 
-    let text_lines: Vec<Line> = message
+    let text_lines: Vec<Line> = text
         .lines()
         .flat_map(|line| {
             let max_width = inner_area.width.saturating_sub(2) as usize;
@@ -70,3 +294,18 @@ pub(crate) fn render_inspect_popup(entry: &LogEntry, area: Rect, frame: &mut Fra
 
     frame.render_widget(paragraph, inner_area);
 }
+
+/// Centers a popup at 80% of the area's size
+fn popup_area(area: Rect) -> Rect {
+    let popup_width = (area.width as f32 * 0.8) as u16;
+    let popup_height = (area.height as f32 * 0.8) as u16;
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+
+    Rect {
+        x: area.x + x,
+        y: area.y + y,
+        width: popup_width,
+        height: popup_height,
+    }
+}
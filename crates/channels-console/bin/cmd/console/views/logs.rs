@@ -1,13 +1,83 @@
 use crate::cmd::console::app::CachedLogs;
-use crate::cmd::console::widgets::formatters::{format_delay, format_time_ago, truncate_message};
+use crate::cmd::console::theme::Theme;
+use crate::cmd::console::widgets::formatters::{
+    format_created_at, format_delay, format_log_time, format_time_ago, truncate_message,
+};
+use crate::cmd::console::widgets::scrollbar::render_table_scrollbar;
+use channels_console::{ChannelType, LogEntry, SerializableChannelStats};
 use ratatui::{
     layout::Rect,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     symbols::border,
-    text::Text,
+    text::{Line, Text},
     widgets::{Block, HighlightSpacing, Row, Table, TableState},
     Frame,
 };
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Order of the logs table, cycled with `s`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum LogSort {
+    /// Most recent first
+    #[default]
+    IndexDesc,
+    IndexAsc,
+    /// Slowest first, with messages still in flight on top
+    LatencyDesc,
+    LatencyAsc,
+}
+
+impl LogSort {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            LogSort::IndexDesc => LogSort::IndexAsc,
+            LogSort::IndexAsc => LogSort::LatencyDesc,
+            LogSort::LatencyDesc => LogSort::LatencyAsc,
+            LogSort::LatencyAsc => LogSort::IndexDesc,
+        }
+    }
+
+    /// Sorts the sent entries, which the logs table and its selection index into
+    pub(crate) fn apply(self, cached_logs: &mut CachedLogs) {
+        let received_map = &cached_logs.received_map;
+        let last_received_index = cached_logs.logs.last_received_index;
+        let latency = |entry: &LogEntry| latency_sort_key(entry, received_map, last_received_index);
+        let sent_logs = &mut cached_logs.logs.sent_logs;
+        match self {
+            LogSort::IndexDesc => sent_logs.sort_by_key(|entry| Reverse(entry.index)),
+            LogSort::IndexAsc => sent_logs.sort_by_key(|entry| entry.index),
+            LogSort::LatencyDesc => {
+                sent_logs.sort_by_key(|entry| Reverse((latency(entry), entry.index)))
+            }
+            LogSort::LatencyAsc => sent_logs.sort_by_key(|entry| (latency(entry), entry.index)),
+        }
+    }
+
+    /// Header of the sortable columns, with an arrow on the sorted one
+    fn headers(self) -> (&'static str, &'static str) {
+        match self {
+            LogSort::IndexDesc => ("Index ▼", "Latency"),
+            LogSort::IndexAsc => ("Index ▲", "Latency"),
+            LogSort::LatencyDesc => ("Index", "Latency ▼"),
+            LogSort::LatencyAsc => ("Index", "Latency ▲"),
+        }
+    }
+}
+
+/// Time from send to receive, messages in flight sort as the slowest,
+/// followed by received messages whose receive wasn't logged
+fn latency_sort_key(
+    entry: &LogEntry,
+    received_map: &HashMap<u64, LogEntry>,
+    last_received_index: u64,
+) -> u64 {
+    match received_map.get(&entry.index) {
+        Some(received) => received.timestamp.saturating_sub(entry.timestamp),
+        None if entry.index <= last_received_index => u64::MAX - 1,
+        None => u64::MAX,
+    }
+}
 
 /// Renders a placeholder when no logs are available
 pub(crate) fn render_logs_placeholder(
@@ -15,6 +85,7 @@ pub(crate) fn render_logs_placeholder(
     message: &str,
     area: Rect,
     frame: &mut Frame,
+    theme: &Theme,
 ) {
     let block = Block::bordered()
         .title(format!(" {} ", channel_label))
@@ -30,11 +101,12 @@ pub(crate) fn render_logs_placeholder(
     if x < inner_area.x + inner_area.width && y < inner_area.y + inner_area.height {
         frame
             .buffer_mut()
-            .set_string(x, y, message, Style::default().fg(Color::DarkGray));
+            .set_string(x, y, message, Style::default().fg(theme.dimmed));
     }
 }
 
 /// Renders the logs panel with sent and received log entries
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn render_logs_panel(
     cached_logs: &CachedLogs,
     channel_label: &str,
@@ -43,6 +115,10 @@ pub(crate) fn render_logs_panel(
     table_state: &mut TableState,
     is_focused: bool,
     current_elapsed_ns: u64,
+    start_wall_clock: Option<u64>,
+    channel: Option<&SerializableChannelStats>,
+    sort: LogSort,
+    theme: &Theme,
 ) {
     let border_set = if is_focused {
         border::THICK
@@ -50,30 +126,79 @@ pub(crate) fn render_logs_panel(
         border::PLAIN
     };
 
-    let block = Block::bordered()
+    let mut block = Block::bordered()
         .title(format!(" {} ", channel_label))
-        .border_set(border_set)
-        .style(if is_focused {
-            Style::default()
-        } else {
-            Style::default().fg(Color::DarkGray)
-        });
+        .border_set(border_set);
+    if let Some(channel) = channel.filter(|c| c.error_count() > 0) {
+        block = block.title(
+            Line::from(format!(
+                " errors: {} dropped, {} rejected, {} timed out ",
+                channel.dropped_count, channel.try_send_full_count, channel.timeout_count
+            ))
+            .style(Style::default().fg(theme.full))
+            .right_aligned(),
+        );
+    }
+    if let Some(created) = channel.and_then(|c| format_created_at(&c.created_at)) {
+        block = block.title_bottom(Line::from(format!(" created {} ", created)));
+    }
+    if let Some(channel) = channel {
+        if let (ChannelType::Bounded(capacity), Some(p99), Some(recommended)) = (
+            channel.channel_type,
+            channel.queued_p99,
+            channel.recommended_capacity,
+        ) {
+            block = block.title_bottom(
+                Line::from(format!(
+                    " p99 queued {}, cap {}, consider {} ",
+                    p99, capacity, recommended
+                ))
+                .centered(),
+            );
+        }
+    }
+    if let Some(latency) = channel.and_then(|c| c.latency.as_ref()) {
+        block = block.title_bottom(
+            Line::from(format!(
+                " p50 {}  p95 {}  p99 {}  max {} ",
+                format_delay(latency.p50_ns),
+                format_delay(latency.p95_ns),
+                format_delay(latency.p99_ns),
+                format_delay(latency.max_ns),
+            ))
+            .right_aligned(),
+        );
+    }
+    let block = block.style(if is_focused {
+        Style::default()
+    } else {
+        Style::default().fg(theme.dimmed)
+    });
 
     let inner_area = block.inner(area);
     frame.render_widget(block, area);
 
     let received_map = &cached_logs.received_map;
+    let last_received_index = cached_logs.logs.last_received_index;
 
     let available_width = inner_area.width.saturating_sub(2);
-    let msg_width = (available_width.saturating_sub(30) as usize).max(20);
+    let msg_width = (available_width.saturating_sub(56) as usize).max(20);
 
     let header_style = Style::default()
-        .fg(Color::Yellow)
+        .fg(theme.header)
         .add_modifier(Modifier::BOLD);
 
-    let header = Row::new(vec!["Index", "Message", "Delay", "Ago"])
-        .style(header_style)
-        .height(1);
+    let (index_header, latency_header) = sort.headers();
+    let header = Row::new(vec![
+        index_header,
+        "Sent",
+        "Received",
+        "Message",
+        latency_header,
+        "Ago",
+    ])
+    .style(header_style)
+    .height(1);
 
     let rows: Vec<Row> = cached_logs
         .logs
@@ -85,26 +210,36 @@ pub(crate) fn render_logs_panel(
             let msg = entry.message.as_deref().unwrap_or("");
             let truncated_msg = truncate_message(msg, msg_width);
 
-            let delay_str = if let Some(received_entry) = received_map.get(&entry.index) {
-                if received_entry.timestamp >= entry.timestamp {
-                    let delay_ns = received_entry.timestamp - entry.timestamp;
-                    format_delay(delay_ns)
-                } else {
-                    "⚠".to_string()
+            let (received_str, delay_str) = match received_map.get(&entry.index) {
+                Some(received_entry) => {
+                    let delay_str = if received_entry.timestamp >= entry.timestamp {
+                        format_delay(received_entry.timestamp - entry.timestamp)
+                    } else {
+                        "⚠".to_string()
+                    };
+                    (
+                        format_log_time(start_wall_clock, received_entry.timestamp),
+                        delay_str,
+                    )
                 }
-            } else {
-                "queued".to_string()
+                // Received, but the entry was evicted or logging was off at the time
+                None if entry.index <= last_received_index => {
+                    ("received".to_string(), String::new())
+                }
+                None => ("in flight".to_string(), String::new()),
             };
 
             let row = Row::new(vec![
                 entry.index.to_string(),
+                format_log_time(start_wall_clock, entry.timestamp),
+                received_str,
                 truncated_msg,
                 delay_str,
                 time_ago,
             ]);
 
             if !is_focused {
-                row.style(Style::default().fg(Color::DarkGray))
+                row.style(Style::default().fg(theme.dimmed))
             } else {
                 row
             }
@@ -112,15 +247,17 @@ pub(crate) fn render_logs_panel(
         .collect();
 
     let widths = [
-        ratatui::layout::Constraint::Length(6),  // Index
+        ratatui::layout::Constraint::Length(9),  // Index
+        ratatui::layout::Constraint::Length(12), // Sent
+        ratatui::layout::Constraint::Length(12), // Received
         ratatui::layout::Constraint::Min(20),    // Message
-        ratatui::layout::Constraint::Length(12), // Delay
+        ratatui::layout::Constraint::Length(10), // Latency
         ratatui::layout::Constraint::Length(13), // Ago
     ];
 
     let selected_row_style = Style::default()
         .add_modifier(Modifier::REVERSED)
-        .bg(Color::DarkGray);
+        .bg(theme.selected_bg);
 
     let table = Table::new(rows, widths)
         .header(header)
@@ -129,4 +266,12 @@ pub(crate) fn render_logs_panel(
         .highlight_spacing(HighlightSpacing::Always);
 
     frame.render_stateful_widget(table, inner_area, table_state);
+
+    render_table_scrollbar(
+        frame,
+        area,
+        cached_logs.logs.sent_logs.len(),
+        table_state.offset(),
+        theme,
+    );
 }
@@ -1,18 +1,33 @@
-use channels_console::SerializableChannelStats;
+use channels_console::{ByteUnits, Diagnostics, SerializableChannelStats};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::Stylize,
     symbols::border,
     text::Line,
-    widgets::{Block, Paragraph, TableState},
+    widgets::{Block, Paragraph, TableState, Wrap},
     Frame,
 };
+use std::collections::HashMap;
 
 use crate::cmd::console::app::{CachedLogs, Focus};
+use crate::cmd::console::history::ThroughputHistory;
+use crate::cmd::console::theme::Theme;
 
-use super::channels::render_channels_panel;
+use super::channels::{render_channels_panel, Column};
 use super::inspect::render_inspect_popup;
-use super::logs::{render_logs_panel, render_logs_placeholder};
+use super::logs::{render_logs_panel, render_logs_placeholder, LogSort};
+use super::mini::render_mini_panel;
+use super::throughput::render_throughput_chart;
+
+/// Height of the throughput chart above the logs
+const THROUGHPUT_CHART_HEIGHT: u16 = 12;
+
+/// Screen areas of the rendered panes, used to map mouse clicks to rows
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PaneAreas {
+    pub(crate) channels: Option<Rect>,
+    pub(crate) logs: Option<Rect>,
+}
 
 /// Renders the main content area including channels table, logs panel, and error states
 #[allow(clippy::too_many_arguments)]
@@ -20,36 +35,77 @@ pub fn render_main_view(
     frame: &mut Frame,
     area: Rect,
     stats: &[SerializableChannelStats],
+    diagnostics: Option<Diagnostics>,
+    throughput: &HashMap<u64, ThroughputHistory>,
     error: &Option<String>,
+    invalid_response: bool,
     metrics_port: u16,
     table_state: &mut TableState,
     logs_table_state: &mut TableState,
     focus: Focus,
     show_logs: bool,
     logs: &Option<CachedLogs>,
+    log_sort: LogSort,
     paused: bool,
     inspected_log: &Option<channels_console::LogEntry>,
+    inspected_field: usize,
     current_elapsed_ns: u64,
-) {
+    start_wall_clock: Option<u64>,
+    columns: &[Column],
+    mini: bool,
+    units: ByteUnits,
+    full_type_names: bool,
+    theme: &Theme,
+) -> PaneAreas {
     if let Some(ref error_msg) = error {
         if stats.is_empty() {
-            let error_text = vec![
+            let mut error_text = vec![
                 Line::from(""),
-                Line::from("Error").red().bold().centered(),
+                Line::from("Error").fg(theme.error).bold().centered(),
                 Line::from(""),
-                Line::from(error_msg.as_str()).red().centered(),
+                Line::from(error_msg.as_str()).fg(theme.error).centered(),
                 Line::from(""),
-                Line::from(format!(
-                    "Make sure the metrics server is running on http://127.0.0.1:{}",
-                    metrics_port
-                ))
-                .yellow()
-                .centered(),
             ];
+            if invalid_response {
+                error_text.push(
+                    Line::from(format!(
+                        "Something on http://127.0.0.1:{} responded, but not with valid metrics",
+                        metrics_port
+                    ))
+                    .fg(theme.warning)
+                    .centered(),
+                );
+                error_text.push(
+                    Line::from("A proxy in between may have truncated or replaced the response")
+                        .fg(theme.dimmed)
+                        .centered(),
+                );
+            } else {
+                error_text.push(
+                    Line::from(format!(
+                        "Make sure the metrics server is running on http://127.0.0.1:{}",
+                        metrics_port
+                    ))
+                    .fg(theme.warning)
+                    .centered(),
+                );
+                error_text.push(
+                    Line::from(
+                        "If that port was taken, the app prints the one it fell back to on startup",
+                    )
+                    .fg(theme.dimmed)
+                    .centered(),
+                );
+            }
 
             let block = Block::bordered().border_set(border::THICK);
-            frame.render_widget(Paragraph::new(error_text).block(block), area);
-            return;
+            frame.render_widget(
+                Paragraph::new(error_text)
+                    .wrap(Wrap { trim: false })
+                    .block(block),
+                area,
+            );
+            return PaneAreas::default();
         }
     }
 
@@ -57,7 +113,7 @@ pub fn render_main_view(
         let empty_text = vec![
             Line::from(""),
             Line::from("No channel statistics found")
-                .yellow()
+                .fg(theme.warning)
                 .centered(),
             Line::from(""),
             Line::from("Make sure channels are instrumented and the server is running").centered(),
@@ -65,7 +121,7 @@ pub fn render_main_view(
 
         let block = Block::bordered().border_set(border::THICK);
         frame.render_widget(Paragraph::new(empty_text).block(block), area);
-        return;
+        return PaneAreas::default();
     }
 
     // Split the area if logs are being shown
@@ -83,22 +139,65 @@ pub fn render_main_view(
     let channel_position = selected_index + 1; // 1-indexed
     let total_channels = stats.len();
 
-    render_channels_panel(
-        stats,
-        table_area,
-        frame,
-        table_state,
-        show_logs,
-        focus,
-        channel_position,
-        total_channels,
-    );
+    if mini {
+        render_mini_panel(
+            stats,
+            diagnostics,
+            table_area,
+            frame,
+            table_state,
+            show_logs,
+            focus,
+            channel_position,
+            total_channels,
+            theme,
+        );
+    } else {
+        render_channels_panel(
+            stats,
+            diagnostics,
+            table_area,
+            frame,
+            table_state,
+            show_logs,
+            focus,
+            channel_position,
+            total_channels,
+            columns,
+            units,
+            full_type_names,
+            theme,
+        );
+    }
 
     // Render logs panel if visible
-    if let Some(logs_area) = logs_area {
-        let channel_label = table_state
-            .selected()
-            .and_then(|i| stats.get(i))
+    let mut logs_pane = logs_area;
+    if let Some(side_area) = logs_area {
+        let selected_stat = table_state.selected().and_then(|i| stats.get(i));
+
+        // Chart the selected channel's rates above its logs when there is room for both
+        let logs_area = if side_area.height >= THROUGHPUT_CHART_HEIGHT * 2 {
+            let [chart_area, logs_area] = Layout::vertical([
+                Constraint::Length(THROUGHPUT_CHART_HEIGHT),
+                Constraint::Fill(1),
+            ])
+            .areas(side_area);
+            render_throughput_chart(
+                // Aggregate rows share their id with the first channel of their source
+                selected_stat
+                    .filter(|stat| stat.count.is_none())
+                    .and_then(|stat| throughput.get(&stat.id)),
+                selected_stat.is_some_and(|stat| stat.sender_only),
+                chart_area,
+                frame,
+                theme,
+            );
+            logs_area
+        } else {
+            side_area
+        };
+        logs_pane = Some(logs_area);
+        let channel_label = selected_stat
             .map(|stat| {
                 if stat.label.is_empty() {
                     stat.id.to_string()
@@ -114,11 +213,14 @@ pub fn render_main_view(
                 .sent_logs
                 .iter()
                 .any(|entry| entry.message.is_none());
-            let display_label = if has_missing_log {
+            let mut display_label = if has_missing_log {
                 format!("{} (missing \"log = true\")", channel_label)
             } else {
                 channel_label
             };
+            if let Some(sample) = selected_stat.and_then(|stat| stat.log_sample) {
+                display_label.push_str(&format!(" (1/{} sampled)", sample));
+            }
             render_logs_panel(
                 cached_logs,
                 &display_label,
@@ -127,6 +229,10 @@ pub fn render_main_view(
                 logs_table_state,
                 focus == Focus::Logs,
                 current_elapsed_ns,
+                start_wall_clock,
+                selected_stat,
+                log_sort,
+                theme,
             );
         } else {
             let message = if paused {
@@ -136,13 +242,29 @@ pub fn render_main_view(
             } else {
                 "(no data)"
             };
-            render_logs_placeholder(&channel_label, message, logs_area, frame);
+            render_logs_placeholder(&channel_label, message, logs_area, frame, theme);
         }
     }
 
     if focus == Focus::Inspect {
         if let Some(ref inspected_log) = inspected_log {
-            render_inspect_popup(inspected_log, area, frame);
+            let received = logs
+                .as_ref()
+                .and_then(|cached_logs| cached_logs.received_map.get(&inspected_log.index));
+            render_inspect_popup(
+                inspected_log,
+                received,
+                inspected_field,
+                start_wall_clock,
+                area,
+                frame,
+                theme,
+            );
         }
     }
+
+    PaneAreas {
+        channels: Some(table_area),
+        logs: logs_pane,
+    }
 }
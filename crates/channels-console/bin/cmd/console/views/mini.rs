@@ -0,0 +1,174 @@
+use crate::cmd::console::app::Focus;
+use crate::cmd::console::theme::Theme;
+use crate::cmd::console::widgets::formatters::{truncate_left, usage_color};
+use crate::cmd::console::widgets::scrollbar::render_list_scrollbar;
+use channels_console::{ChannelState, ChannelType, Diagnostics, SerializableChannelStats};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Cell, HighlightSpacing, Row, Table, TableState},
+    Frame,
+};
+
+use super::channels::{
+    channel_label, channels_block, display_rows, display_state, state_label, DisplayRow,
+};
+
+/// Width of the bar showing how full a bounded channel is
+const BAR_WIDTH: usize = 8;
+
+/// Share of the line width the label may take up
+const MAX_LABEL_SHARE: usize = 3;
+
+/// Renders the channels as dense single lines, `label [type] state sent/recv q=N bar`,
+/// to fit many of them in a small terminal
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_mini_panel(
+    stats: &[SerializableChannelStats],
+    diagnostics: Option<Diagnostics>,
+    area: Rect,
+    frame: &mut Frame,
+    table_state: &mut TableState,
+    show_logs: bool,
+    focus: Focus,
+    channel_position: usize,
+    total_channels: usize,
+    theme: &Theme,
+) {
+    let mut display_rows = display_rows(stats);
+    if let Some(diagnostics) = diagnostics {
+        display_rows.push(DisplayRow::Collector(diagnostics));
+    }
+    let dimmed = show_logs && !matches!(focus, Focus::Channels);
+
+    let labels: Vec<String> = (0..stats.len()).map(|i| channel_label(stats, i)).collect();
+    let label_width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(area.width as usize / MAX_LABEL_SHARE);
+
+    let rows: Vec<Row> = display_rows
+        .iter()
+        .map(|display_row| match display_row {
+            DisplayRow::Group(group) => {
+                let color = if dimmed { theme.dimmed } else { theme.key };
+                Row::new(vec![Cell::from(format!("▾ {group}"))])
+                    .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+            }
+            DisplayRow::Channel(i) => {
+                let stat = &stats[*i];
+                let line = mini_line(stat, &labels[*i], label_width, theme);
+                let row = Row::new(vec![Cell::from(line)]);
+                if stat.state == ChannelState::Closed {
+                    row.style(
+                        Style::default()
+                            .fg(theme.dimmed)
+                            .add_modifier(Modifier::CROSSED_OUT),
+                    )
+                } else if dimmed {
+                    row.style(Style::default().fg(theme.dimmed))
+                } else {
+                    row
+                }
+            }
+            DisplayRow::Collector(diagnostics) => Row::new(vec![Cell::from(format!(
+                "⚙ collector q={} peak={}",
+                diagnostics.event_queue_len, diagnostics.peak_event_queue_len
+            ))])
+            .style(Style::default().fg(theme.dimmed)),
+        })
+        .collect();
+
+    let selected_row_style = Style::default()
+        .add_modifier(Modifier::REVERSED)
+        .bg(theme.selected_bg);
+
+    let table = Table::new(rows, [Constraint::Fill(1)])
+        .block(channels_block(
+            show_logs,
+            focus,
+            channel_position,
+            total_channels,
+            theme,
+        ))
+        .row_highlight_style(selected_row_style)
+        .highlight_symbol(Text::from(">"))
+        .highlight_spacing(HighlightSpacing::Always);
+
+    let mut display_state = display_state(&display_rows, table_state);
+    frame.render_stateful_widget(table, area, &mut display_state);
+    *table_state.offset_mut() = display_state.offset();
+
+    render_list_scrollbar(
+        frame,
+        area,
+        display_rows.len(),
+        display_state.offset(),
+        theme,
+    );
+}
+
+fn mini_line(
+    stat: &SerializableChannelStats,
+    label: &str,
+    label_width: usize,
+    theme: &Theme,
+) -> Line<'static> {
+    let (state_text, state_style) = state_label(stat, theme);
+    let received = if stat.sender_only {
+        "-".to_string()
+    } else {
+        stat.received_count.to_string()
+    };
+
+    let mut spans = vec![
+        Span::raw(format!(
+            "{:<label_width$}",
+            truncate_left(label, label_width)
+        )),
+        Span::styled(
+            format!(" [{}] ", stat.channel_type_label()),
+            Style::default().fg(theme.dimmed),
+        ),
+        Span::styled(state_text, state_style),
+        Span::raw(format!(" {}/{}", stat.sent_count, received)),
+    ];
+
+    if !stat.sender_only {
+        spans.push(Span::raw(format!(" q={}", stat.queued)));
+        // There's nothing to fill a bar of unknown size with
+        let bar_capacity = match stat.channel_type {
+            ChannelType::Bounded(capacity) if !stat.unknown_capacity => Some(capacity),
+            _ => None,
+        };
+        if let Some(capacity) = bar_capacity {
+            spans.push(Span::raw(" "));
+            spans.push(queue_bar(stat.queued, capacity, theme));
+        }
+    }
+
+    Line::from(spans)
+}
+
+/// Fill bar of a bounded queue, e.g. `███░░░░░`
+fn queue_bar(queued: u64, capacity: usize, theme: &Theme) -> Span<'static> {
+    let filled = if capacity == 0 {
+        if queued > 0 {
+            BAR_WIDTH
+        } else {
+            0
+        }
+    } else {
+        (queued as usize * BAR_WIDTH)
+            .div_ceil(capacity)
+            .min(BAR_WIDTH)
+    };
+
+    Span::styled(
+        format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled)),
+        Style::default().fg(usage_color(queued, capacity, theme)),
+    )
+}
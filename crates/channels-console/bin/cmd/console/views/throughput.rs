@@ -0,0 +1,94 @@
+use crate::cmd::console::history::ThroughputHistory;
+use crate::cmd::console::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    symbols::{self, border},
+    text::Line,
+    widgets::{Axis, Block, Chart, Dataset, GraphType, Paragraph},
+    Frame,
+};
+
+/// Renders the send and receive rates of the selected channel over the last refreshes
+pub(crate) fn render_throughput_chart(
+    history: Option<&ThroughputHistory>,
+    sender_only: bool,
+    area: Rect,
+    frame: &mut Frame,
+    theme: &Theme,
+) {
+    let block = Block::bordered()
+        .title(" Throughput ")
+        .border_set(border::PLAIN);
+
+    let Some(samples) = history
+        .map(|history| &history.samples)
+        .filter(|samples| samples.len() >= 2)
+    else {
+        let text = Line::from("(collecting samples)")
+            .fg(theme.dimmed)
+            .centered();
+        frame.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    };
+
+    let latest_ns = samples.back().map_or(0, |sample| sample.elapsed_ns);
+    let seconds_ago = |elapsed_ns: u64| -((latest_ns - elapsed_ns) as f64 / 1_000_000_000.0);
+
+    let sent: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|sample| (seconds_ago(sample.elapsed_ns), sample.sent_per_sec))
+        .collect();
+    let received: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|sample| (seconds_ago(sample.elapsed_ns), sample.received_per_sec))
+        .collect();
+
+    let min_x = sent.first().map_or(0.0, |(x, _)| *x);
+    let max_y = sent
+        .iter()
+        .chain(&received)
+        .map(|(_, y)| *y)
+        .fold(0.0, f64::max);
+    // Leave headroom above the peak, and keep an idle channel's axis readable
+    let max_y = if max_y > 0.0 { max_y * 1.1 } else { 1.0 };
+
+    let mut datasets = vec![Dataset::default()
+        .name("sent/s")
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(theme.key))
+        .data(&sent)];
+    // Receives of send-only channels aren't observed
+    if !sender_only {
+        datasets.push(
+            Dataset::default()
+                .name("recv/s")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.active))
+                .data(&received),
+        );
+    }
+
+    let x_axis = Axis::default()
+        .title("time")
+        .style(Style::default().fg(theme.dimmed))
+        .bounds([min_x, 0.0])
+        .labels([format!("{:.0}s", min_x), "now".to_string()]);
+    let y_axis = Axis::default()
+        .title("msg/s")
+        .style(Style::default().fg(theme.dimmed))
+        .bounds([0.0, max_y])
+        .labels([
+            "0".to_string(),
+            format!("{:.0}", max_y / 2.0),
+            format!("{:.0}", max_y),
+        ]);
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(x_axis)
+        .y_axis(y_axis);
+    frame.render_widget(chart, area);
+}
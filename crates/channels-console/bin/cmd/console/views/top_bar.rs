@@ -1,24 +1,42 @@
+use crate::cmd::console::theme::Theme;
+use channels_console::{ByteUnits, StatsSummary};
 use ratatui::{
     layout::Rect,
     style::Stylize,
     symbols::border,
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, Paragraph},
     Frame,
 };
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-/// Renders the top status bar showing connection status and refresh timer
+/// Renders the top status bar showing connection status, totals and refresh timer
+#[allow(clippy::too_many_arguments)]
 pub fn render_top_bar(
     frame: &mut Frame,
     area: Rect,
     is_paused: bool,
+    replay: Option<(usize, usize)>,
     last_successful_fetch: Option<Instant>,
+    refresh_interval: Duration,
     has_error: bool,
     has_data: bool,
+    summary: &StatsSummary,
+    units: ByteUnits,
+    theme: &Theme,
 ) {
-    let status_text = if is_paused {
-        Line::from(vec!["⏸ ".yellow(), "PAUSED".yellow().bold()])
+    let mut status_text = if let Some((position, count)) = replay {
+        Line::from(vec![
+            "⏵ ".fg(theme.key),
+            "Replay ".fg(theme.key).bold(),
+            format!("snapshot {}/{} ", position, count).into(),
+            "<[/]> ".fg(theme.key).bold(),
+        ])
+    } else if is_paused {
+        Line::from(vec![
+            "⏸ ".fg(theme.warning),
+            "PAUSED".fg(theme.warning).bold(),
+        ])
     } else if let Some(last_fetch) = last_successful_fetch {
         let elapsed = Instant::now().duration_since(last_fetch);
         let seconds = elapsed.as_secs();
@@ -27,14 +45,14 @@ pub fn render_top_bar(
 
         if is_stale {
             Line::from(vec![
-                "⚠ ".yellow(),
+                "⚠ ".fg(theme.warning),
                 "Stale ".into(),
-                format!("(refreshed {}s ago)", seconds).yellow(),
+                format!("(refreshed {}s ago)", seconds).fg(theme.warning),
             ])
         } else {
             Line::from(vec![
-                "✓ ".green(),
-                "Live ".green().bold(),
+                "✓ ".fg(theme.ok),
+                "Live ".fg(theme.ok).bold(),
                 format!("(refreshed {}s ago)", seconds).into(),
             ])
         }
@@ -42,11 +60,58 @@ pub fn render_top_bar(
         Line::from(vec!["⋯ ".into(), "Connecting...".into()])
     };
 
+    if has_data {
+        status_text
+            .spans
+            .extend(summary_spans(summary, units, theme));
+    }
+
     let block = Block::bordered()
         .title(" Status ")
+        .title(
+            Line::from(vec![
+                " Refresh every ".into(),
+                format_interval(refresh_interval).bold(),
+                " <+/-> ".fg(theme.key).bold(),
+            ])
+            .right_aligned(),
+        )
         .border_set(border::PLAIN);
 
     let paragraph = Paragraph::new(status_text).block(block).left_aligned();
 
     frame.render_widget(paragraph, area);
 }
+
+/// Totals across all channels, e.g. "│ 3 channels  1543 sent  1540 received  12.0 KB queued"
+fn summary_spans<'a>(summary: &StatsSummary, units: ByteUnits, theme: &Theme) -> Vec<Span<'a>> {
+    let mut spans = vec![
+        "  │ ".fg(theme.dimmed),
+        summary.channels.to_string().bold(),
+        " channels  ".into(),
+        summary.sent_count.to_string().bold(),
+        " sent  ".into(),
+        summary.received_count.to_string().bold(),
+        " received  ".into(),
+        units.format(summary.queued_bytes).bold(),
+        " queued".into(),
+    ];
+    if summary.dropped_count > 0 {
+        spans.push("  ".into());
+        spans.push(summary.dropped_count.to_string().fg(theme.full).bold());
+        spans.push(" dropped".fg(theme.full));
+    }
+    spans
+}
+
+/// Formats a refresh interval as "200ms" or "2s"
+fn format_interval(interval: Duration) -> String {
+    let ms = interval.as_millis();
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms.is_multiple_of(1000) {
+        format!("{}s", ms / 1000)
+    } else {
+        format!("{:.1}s", interval.as_secs_f64())
+    }
+}
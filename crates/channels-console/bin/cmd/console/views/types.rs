@@ -0,0 +1,75 @@
+use crate::cmd::console::theme::Theme;
+use crate::cmd::console::widgets::formatters::truncate_left;
+use channels_console::{ByteUnits, TypeStats};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    symbols::border,
+    widgets::{Block, Cell, Clear, Row, Table},
+    Frame,
+};
+
+const HEADERS: [&str; 7] = [
+    "Type", "Channels", "Sent", "Sent B", "Received", "Queued", "Mem",
+];
+
+/// Renders a centered popup with the totals of each message type
+pub(crate) fn render_types_popup(
+    types: &[TypeStats],
+    units: ByteUnits,
+    area: Rect,
+    frame: &mut Frame,
+    theme: &Theme,
+) {
+    let popup_width = (area.width * 9 / 10).max(60).min(area.width);
+    let popup_height = (types.len() as u16 + 3).min(area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+        y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let header_style = Style::default()
+        .fg(theme.header)
+        .add_modifier(Modifier::BOLD);
+    let header = Row::new(HEADERS.map(Cell::from)).style(header_style);
+
+    // Six numeric columns of 10, spacing and borders, the type name gets the rest
+    let type_width = (popup_width as usize)
+        .saturating_sub(6 * 10 + 6 + 2)
+        .max(10);
+    let rows: Vec<Row> = types
+        .iter()
+        .map(|row| {
+            Row::new(vec![
+                Cell::from(truncate_left(&row.type_name, type_width)),
+                Cell::from(row.channels.to_string()),
+                Cell::from(row.sent_count.to_string()),
+                Cell::from(units.format_message(row.total_bytes_sent, row.type_size)),
+                Cell::from(row.received_count.to_string()),
+                Cell::from(row.queued.to_string()),
+                Cell::from(units.format_message(row.queued_bytes, row.type_size)),
+            ])
+        })
+        .collect();
+
+    let block = Block::bordered()
+        .title(" Message types - press y or Esc to close ")
+        .border_set(border::DOUBLE);
+
+    let widths = [
+        Constraint::Fill(1),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(rows, widths).header(header).block(block);
+
+    frame.render_widget(table, popup_area);
+}
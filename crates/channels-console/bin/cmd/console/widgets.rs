@@ -1 +1,3 @@
+pub(crate) mod ascii;
 pub(crate) mod formatters;
+pub(crate) mod scrollbar;
@@ -0,0 +1,39 @@
+use ratatui::buffer::Buffer;
+
+/// Replaces every non-ASCII symbol in a rendered frame with a close ASCII stand-in,
+/// e.g. box-drawing borders with `+-|` and queue bars with `#`/`-`
+pub(crate) fn to_ascii(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        if cell.symbol().is_ascii() {
+            continue;
+        }
+        let symbol = cell.symbol().chars().next().map_or(' ', ascii_fallback);
+        cell.set_char(symbol);
+    }
+}
+
+fn ascii_fallback(c: char) -> char {
+    match c {
+        // Borders, from the plain, rounded, double and thick sets plus dashed lines
+        '─' | '━' | '═' | '┄' | '┅' | '┈' | '┉' | '╌' | '╍' => '-',
+        '│' | '┃' | '║' | '┆' | '┇' | '┊' | '┋' | '╎' | '╏' => '|',
+        '\u{2500}'..='\u{257F}' => '+',
+        // Queue bars and scrollbar thumbs
+        '░' => '-',
+        '\u{2580}'..='\u{259F}' => '#',
+        // Chart dots
+        '\u{2800}' => ' ',
+        '\u{2801}'..='\u{28FF}' | '•' | '⚙' => '*',
+        '▸' | '▶' | '⏵' | '→' => '>',
+        '◀' | '←' => '<',
+        '▾' | '▼' | '↓' => 'v',
+        '▴' | '▲' | '↑' => '^',
+        '⚠' => '!',
+        '✗' | '×' => 'x',
+        '✓' => '+',
+        '⏸' => '=',
+        '⋯' | '…' => '.',
+        'μ' => 'u',
+        _ => '?',
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cmd::console::theme::Theme;
 use channels_console::ChannelType;
 use ratatui::{
     style::{Color, Style},
@@ -5,19 +6,37 @@ use ratatui::{
 };
 
 pub(crate) fn truncate_left(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    let len = s.chars().count();
+    if len <= max_len {
         s.to_string()
     } else {
         let truncated_len = max_len.saturating_sub(3);
-        let start_idx = s.len().saturating_sub(truncated_len);
-        format!("...{}", &s[start_idx..])
+        let tail: String = s.chars().skip(len - truncated_len).collect();
+        format!("...{}", tail)
     }
 }
 
+/// Type name with the module path of every type in it trimmed, e.g. `Vec<Event>` for
+/// `alloc::vec::Vec<my_crate::Event>`
+pub(crate) fn short_type_name(type_name: &str) -> String {
+    let mut short = String::with_capacity(type_name.len());
+    let mut path_start = 0;
+    for c in type_name.chars() {
+        short.push(c);
+        if short.ends_with("::") {
+            short.truncate(path_start);
+        } else if !c.is_alphanumeric() && c != '_' && c != ':' {
+            path_start = short.len();
+        }
+    }
+    short
+}
+
 pub(crate) fn queue_status(
     queued: u64,
     channel_type: &ChannelType,
     _width: usize,
+    theme: &Theme,
 ) -> Cell<'static> {
     let capacity = match channel_type {
         ChannelType::Bounded(cap) => Some(*cap),
@@ -26,25 +45,35 @@ pub(crate) fn queue_status(
     };
 
     match capacity {
-        Some(cap) if cap > 0 => {
-            let percentage = (queued as f64 / cap as f64 * 100.0).min(100.0);
-
+        // Rendezvous channel, messages are handed over directly
+        Some(cap) => {
             let text = format!("[{}/{}]", queued, cap);
-
-            let color = if percentage >= 100.0 {
-                Color::Red
-            } else if percentage >= 50.0 {
-                Color::Yellow
-            } else {
-                Color::Green
-            };
-
-            Cell::from(text).style(Style::default().fg(color))
+            Cell::from(text).style(Style::default().fg(usage_color(queued, cap, theme)))
         }
         _ => Cell::from("N/A"),
     }
 }
 
+/// Color of a queue by how full it is. Any message waiting in a rendezvous channel counts as full.
+pub(crate) fn usage_color(queued: u64, capacity: usize, theme: &Theme) -> Color {
+    if capacity == 0 {
+        return if queued > 0 {
+            theme.usage_high
+        } else {
+            theme.usage_low
+        };
+    }
+
+    let percentage = (queued as f64 / capacity as f64 * 100.0).min(100.0);
+    if percentage >= 100.0 {
+        theme.usage_high
+    } else if percentage >= 50.0 {
+        theme.usage_medium
+    } else {
+        theme.usage_low
+    }
+}
+
 pub(crate) fn format_delay(delay_ns: u64) -> String {
     if delay_ns < 1_000 {
         format!("{}ns", delay_ns)
@@ -66,13 +95,43 @@ pub(crate) fn truncate_message(msg: &str, max_len: usize) -> String {
     }
 }
 
-/// Formats a timestamp in nanoseconds as MM:SS.mmm
+/// Formats a timestamp in nanoseconds since process start as "+1.234s"
 pub(crate) fn format_timestamp(timestamp_ns: u64) -> String {
-    let total_secs = timestamp_ns / 1_000_000_000;
+    let secs = timestamp_ns / 1_000_000_000;
     let millis = (timestamp_ns % 1_000_000_000) / 1_000_000;
-    let minutes = (total_secs % 3600) / 60;
-    let seconds = total_secs % 60;
-    format!("{:02}:{:02}.{:03}", minutes, seconds, millis)
+    format!("+{}.{:03}s", secs, millis)
+}
+
+/// Formats a timestamp in nanoseconds since process start as local wall-clock time, e.g. "14:03:21.532"
+pub(crate) fn format_wall_clock(start_wall_clock_ms: u64, timestamp_ns: u64) -> String {
+    let millis = start_wall_clock_ms + timestamp_ns / 1_000_000;
+    match chrono::DateTime::from_timestamp_millis(millis as i64) {
+        Some(time) => time
+            .with_timezone(&chrono::Local)
+            .format("%H:%M:%S%.3f")
+            .to_string(),
+        None => format_timestamp(timestamp_ns),
+    }
+}
+
+/// Formats a channel's RFC 3339 creation time as local time, e.g. "2025-10-15 14:03:21"
+pub(crate) fn format_created_at(created_at: &str) -> Option<String> {
+    chrono::DateTime::parse_from_rfc3339(created_at)
+        .ok()
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+}
+
+/// Formats a log timestamp as wall-clock time when the process start time is known,
+/// relative to process start otherwise
+pub(crate) fn format_log_time(start_wall_clock_ms: Option<u64>, timestamp_ns: u64) -> String {
+    match start_wall_clock_ms {
+        Some(start) => format_wall_clock(start, timestamp_ns),
+        None => format_timestamp(timestamp_ns),
+    }
 }
 
 /// Formats a time difference in nanoseconds as "now", "1s ago", "1m ago", "1h ago", etc.
@@ -0,0 +1,65 @@
+use crate::cmd::console::theme::Theme;
+use ratatui::{
+    layout::{Margin, Rect},
+    style::Style,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+
+/// Renders a scrollbar on the right border of a bordered table with a header row,
+/// when its rows don't fit in the area
+pub(crate) fn render_table_scrollbar(
+    frame: &mut Frame,
+    area: Rect,
+    row_count: usize,
+    offset: usize,
+    theme: &Theme,
+) {
+    render_scrollbar(frame, area, row_count, offset, 1, theme);
+}
+
+/// Renders a scrollbar on the right border of a bordered table without a header row
+pub(crate) fn render_list_scrollbar(
+    frame: &mut Frame,
+    area: Rect,
+    row_count: usize,
+    offset: usize,
+    theme: &Theme,
+) {
+    render_scrollbar(frame, area, row_count, offset, 0, theme);
+}
+
+fn render_scrollbar(
+    frame: &mut Frame,
+    area: Rect,
+    row_count: usize,
+    offset: usize,
+    header_rows: u16,
+    theme: &Theme,
+) {
+    // Top and bottom border plus the header rows
+    let visible_rows = area.height.saturating_sub(2 + header_rows) as usize;
+    if row_count <= visible_rows {
+        return;
+    }
+
+    let max_offset = row_count - visible_rows;
+    let mut state = ScrollbarState::new(max_offset + 1)
+        .position(offset.min(max_offset))
+        .viewport_content_length(visible_rows);
+
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .thumb_style(Style::default().fg(theme.key))
+        .track_style(Style::default().fg(theme.dimmed));
+
+    frame.render_stateful_widget(
+        scrollbar,
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut state,
+    );
+}
@@ -13,15 +13,16 @@ pub enum TCSubcommand {
 #[command(
     version,
     about,
-    long_about = "channels-console - real-time monitoring and metrics for your Tokio channels"
+    long_about = "channels-console - real-time monitoring and metrics for your Tokio channels",
+    args_conflicts_with_subcommands = true
 )]
 pub struct TCArgs {
     #[command(subcommand)]
     pub cmd: Option<TCSubcommand>,
 
-    /// Port for the metrics server (used when no subcommand is provided)
-    #[arg(long, default_value = "6770", global = true)]
-    pub metrics_port: u16,
+    // Same options as the `console` subcommand, used when no subcommand is provided
+    #[command(flatten)]
+    pub console: ConsoleArgs,
 }
 
 fn main() -> Result<()> {
@@ -32,10 +33,7 @@ fn main() -> Result<()> {
             args.run()?;
         }
         None => {
-            let args = ConsoleArgs {
-                metrics_port: root_args.metrics_port,
-            };
-            args.run()?;
+            root_args.console.run()?;
         }
     }
 
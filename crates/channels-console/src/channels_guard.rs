@@ -1,8 +1,18 @@
-use std::time::Instant;
+use std::sync::atomic::Ordering;
+use std::sync::{mpsc, Arc};
+#[cfg(all(feature = "sigint", unix))]
+use std::sync::{Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 use prettytable::{Cell, Row, Table};
 
-use crate::{format_bytes, get_metrics_json, get_sorted_channel_stats, resolve_label, Format};
+use crate::{
+    flush_until_idle, get_serializable_stats, metrics_json, ByteUnits, ChannelState, Format,
+    SerializableChannelStats, StatsSummary, CHANNEL_ID_COUNTER,
+};
+
+type StatsFilter = Arc<dyn Fn(&SerializableChannelStats) -> bool + Send + Sync>;
 
 /// Builder for creating a ChannelsGuard with custom configuration.
 ///
@@ -18,6 +28,13 @@ use crate::{format_bytes, get_metrics_json, get_sorted_channel_stats, resolve_la
 /// ```
 pub struct ChannelsGuardBuilder {
     format: Format,
+    filters: Vec<StatsFilter>,
+    byte_units: ByteUnits,
+    flush_timeout: Option<Duration>,
+    interval: Option<Duration>,
+    checks: Vec<StatsFilter>,
+    #[cfg(all(feature = "sigint", unix))]
+    report_on_sigint: bool,
 }
 
 impl ChannelsGuardBuilder {
@@ -25,6 +42,13 @@ impl ChannelsGuardBuilder {
     pub fn new() -> Self {
         Self {
             format: Format::default(),
+            filters: Vec::new(),
+            byte_units: ByteUnits::default(),
+            flush_timeout: None,
+            interval: None,
+            checks: Vec::new(),
+            #[cfg(all(feature = "sigint", unix))]
+            report_on_sigint: false,
         }
     }
 
@@ -44,12 +68,218 @@ impl ChannelsGuardBuilder {
         self
     }
 
+    /// Set the units of the byte columns in the table, plain table and Markdown formats.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::{ByteUnits, ChannelsGuardBuilder};
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .byte_units(ByteUnits::Si)
+    ///     .build();
+    /// ```
+    pub fn byte_units(mut self, byte_units: ByteUnits) -> Self {
+        self.byte_units = byte_units;
+        self
+    }
+
+    /// Only report channels matching the predicate.
+    /// Can be called multiple times, a channel is reported only if it matches all filters.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::ChannelsGuardBuilder;
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .filter(|stats| stats.sent_count > 0)
+    ///     .build();
+    /// ```
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&SerializableChannelStats) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Only report channels whose label starts with `prefix`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::ChannelsGuardBuilder;
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .only_label_prefix("worker")
+    ///     .build();
+    /// ```
+    pub fn only_label_prefix(self, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        self.filter(move |stats| stats.label.starts_with(&prefix))
+    }
+
+    /// Only report channels in the given state.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::{ChannelState, ChannelsGuardBuilder};
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .only_state(ChannelState::Full)
+    ///     .build();
+    /// ```
+    pub fn only_state(self, state: ChannelState) -> Self {
+        self.filter(move |stats| stats.state == state)
+    }
+
+    /// Only report channels instrumented after this call, e.g. for a report per test or phase
+    /// while another guard in `main` covers the whole program.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::ChannelsGuardBuilder;
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .scoped()
+    ///     .build();
+    /// ```
+    pub fn scoped(self) -> Self {
+        let first_id = CHANNEL_ID_COUNTER.load(Ordering::Relaxed);
+        self.filter(move |stats| stats.id >= first_id)
+    }
+
+    /// Before reporting, wait up to `timeout` for the collector to catch up with messages
+    /// that forwarders are still moving, instead of reporting the counts as they are.
+    ///
+    /// Forwarders need to keep running meanwhile, e.g. on the worker threads of a
+    /// multi-threaded runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::ChannelsGuardBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .flush_timeout(Duration::from_millis(500))
+    ///     .build();
+    /// ```
+    pub fn flush_timeout(mut self, timeout: Duration) -> Self {
+        self.flush_timeout = Some(timeout);
+        self
+    }
+
+    /// Also print the statistics every `interval` while the guard is alive, from a background
+    /// thread, for long-running programs that rarely reach the report on drop.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::ChannelsGuardBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .interval(Duration::from_secs(60))
+    ///     .build();
+    /// ```
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// After the report, exit the process with status 1 if any reported channel fails the
+    /// `healthy` predicate, listing those channels on stderr. Turns channel health into a gate,
+    /// e.g. for CI runs. Can be called multiple times, a channel must pass all checks. Use
+    /// `channels_console::check` instead to handle violations yourself, e.g. in a test.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::{ChannelState, ChannelsGuardBuilder};
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .exit_on_violation(|stats| stats.state != ChannelState::Full)
+    ///     .exit_on_violation(|stats| stats.dropped_count == 0)
+    ///     .build();
+    /// ```
+    pub fn exit_on_violation<F>(mut self, healthy: F) -> Self
+    where
+        F: Fn(&SerializableChannelStats) -> bool + Send + Sync + 'static,
+    {
+        self.checks.push(Arc::new(healthy));
+        self
+    }
+
+    /// Also print the statistics when the process receives SIGINT, e.g. on Ctrl-C, which
+    /// would otherwise end it without dropping the guard. Requires the `sigint` feature and
+    /// is only available on Unix.
+    ///
+    /// Handlers installed before the guard, e.g. by `tokio::signal::ctrl_c`, keep running and
+    /// are left to shut the process down. Without one, the process is terminated by the signal
+    /// right after the report, as it would have been without the guard. Either way, the report
+    /// isn't printed again when the guard drops, though `exit_on_violation` checks still run.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use channels_console::ChannelsGuardBuilder;
+    ///
+    /// let _guard = ChannelsGuardBuilder::new()
+    ///     .report_on_sigint()
+    ///     .build();
+    /// ```
+    #[cfg(all(feature = "sigint", unix))]
+    pub fn report_on_sigint(mut self) -> Self {
+        self.report_on_sigint = true;
+        self
+    }
+
     /// Build and return the ChannelsGuard.
     /// Statistics will be printed when the guard is dropped.
     pub fn build(self) -> ChannelsGuard {
+        let start_time = Instant::now();
+        let periodic = self
+            .interval
+            .filter(|_| !cfg!(feature = "disabled"))
+            .map(|interval| {
+                PeriodicReport::spawn(
+                    interval,
+                    start_time,
+                    self.format,
+                    self.filters.clone(),
+                    self.byte_units,
+                )
+            });
+
+        #[cfg(all(feature = "sigint", unix))]
+        let sigint = if self.report_on_sigint && !cfg!(feature = "disabled") {
+            SigintReport::spawn(
+                start_time,
+                self.format,
+                self.filters.clone(),
+                self.byte_units,
+                self.flush_timeout,
+            )
+            .inspect_err(|e| eprintln!("channels-console: failed to handle SIGINT: {}", e))
+            .ok()
+        } else {
+            None
+        };
+
         ChannelsGuard {
-            start_time: Instant::now(),
+            start_time,
             format: self.format,
+            filters: self.filters,
+            byte_units: self.byte_units,
+            flush_timeout: self.flush_timeout,
+            checks: self.checks,
+            periodic,
+            #[cfg(all(feature = "sigint", unix))]
+            sigint,
         }
     }
 }
@@ -77,6 +307,13 @@ impl Default for ChannelsGuardBuilder {
 pub struct ChannelsGuard {
     start_time: Instant,
     format: Format,
+    filters: Vec<StatsFilter>,
+    byte_units: ByteUnits,
+    flush_timeout: Option<Duration>,
+    checks: Vec<StatsFilter>,
+    periodic: Option<PeriodicReport>,
+    #[cfg(all(feature = "sigint", unix))]
+    sigint: Option<SigintReport>,
 }
 
 impl ChannelsGuard {
@@ -88,6 +325,13 @@ impl ChannelsGuard {
         Self {
             start_time: Instant::now(),
             format: Format::default(),
+            filters: Vec::new(),
+            byte_units: ByteUnits::default(),
+            flush_timeout: None,
+            checks: Vec::new(),
+            periodic: None,
+            #[cfg(all(feature = "sigint", unix))]
+            sigint: None,
         }
     }
 
@@ -115,65 +359,292 @@ impl Default for ChannelsGuard {
 
 impl Drop for ChannelsGuard {
     fn drop(&mut self) {
-        let elapsed = self.start_time.elapsed();
-        let stats = get_sorted_channel_stats();
-
-        if stats.is_empty() {
-            println!("\nNo instrumented channels found.");
+        // Nothing was instrumented, so there's nothing to report
+        if cfg!(feature = "disabled") {
             return;
         }
 
-        match self.format {
-            Format::Table => {
-                let mut table = Table::new();
-
-                table.add_row(Row::new(vec![
-                    Cell::new("Channel"),
-                    Cell::new("Type"),
-                    Cell::new("State"),
-                    Cell::new("Sent"),
-                    Cell::new("Received"),
-                    Cell::new("Queued"),
-                    Cell::new("Mem"),
-                ]));
-
-                for channel_stats in stats {
-                    let label = resolve_label(
-                        channel_stats.source,
-                        channel_stats.label.as_deref(),
-                        channel_stats.iter,
-                    );
-                    table.add_row(Row::new(vec![
-                        Cell::new(&label),
-                        Cell::new(&channel_stats.channel_type.to_string()),
-                        Cell::new(channel_stats.state.as_str()),
-                        Cell::new(&channel_stats.sent_count.to_string()),
-                        Cell::new(&channel_stats.received_count.to_string()),
-                        Cell::new(&channel_stats.queued().to_string()),
-                        Cell::new(&format_bytes(channel_stats.queued_bytes())),
-                    ]));
+        if let Some(periodic) = self.periodic.take() {
+            periodic.stop();
+        }
+
+        // SIGINT may have printed the report already, but the checks still have to run
+        #[cfg(all(feature = "sigint", unix))]
+        let report = self.sigint.as_ref().is_none_or(SigintReport::claim);
+        #[cfg(not(all(feature = "sigint", unix)))]
+        let report = true;
+
+        let elapsed = self.start_time.elapsed();
+        if let Some(timeout) = self.flush_timeout {
+            flush_until_idle(timeout);
+        }
+        if report {
+            print_report(self.format, &self.filters, self.byte_units, elapsed);
+        }
+        exit_on_violation(&self.filters, &self.checks);
+    }
+}
+
+/// Exits with status 1 if a channel matching `filters` fails one of the `checks`, see
+/// `ChannelsGuardBuilder::exit_on_violation`.
+fn exit_on_violation(filters: &[StatsFilter], checks: &[StatsFilter]) {
+    use std::io::Write;
+
+    if checks.is_empty() {
+        return;
+    }
+    let unhealthy: Vec<SerializableChannelStats> = get_serializable_stats()
+        .into_iter()
+        .filter(|stats| filters.iter().all(|filter| filter(stats)))
+        .filter(|stats| !checks.iter().all(|check| check(stats)))
+        .collect();
+    if unhealthy.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "channels-console: {} channel(s) failed the health checks:",
+        unhealthy.len()
+    );
+    for stats in &unhealthy {
+        eprintln!(
+            "  {} ({}): {}, {} dropped",
+            stats.label, stats.source, stats.state, stats.dropped_count
+        );
+    }
+    let _ = std::io::stdout().flush();
+    std::process::exit(1);
+}
+
+/// Background thread printing the statistics at a fixed interval, see
+/// `ChannelsGuardBuilder::interval`.
+struct PeriodicReport {
+    stop: mpsc::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl PeriodicReport {
+    fn spawn(
+        interval: Duration,
+        start_time: Instant,
+        format: Format,
+        filters: Vec<StatsFilter>,
+        byte_units: ByteUnits,
+    ) -> Self {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = thread::Builder::new()
+            .name("channels-console-report".into())
+            .spawn(move || {
+                while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    print_report(format, &filters, byte_units, start_time.elapsed());
+                }
+            })
+            .expect("Failed to spawn report thread");
+
+        Self { stop, handle }
+    }
+
+    /// Stops the thread, waiting for a report it's printing to finish.
+    fn stop(self) {
+        drop(self.stop);
+        let _ = self.handle.join();
+    }
+}
+
+/// Thread printing the statistics when the process receives SIGINT, see
+/// `ChannelsGuardBuilder::report_on_sigint`.
+#[cfg(all(feature = "sigint", unix))]
+struct SigintReport {
+    /// Set by whichever prints the report first, the thread or the dropped guard. The thread
+    /// holds it while printing, so a guard dropped meanwhile waits for the report to finish.
+    reported: Arc<Mutex<bool>>,
+}
+
+#[cfg(all(feature = "sigint", unix))]
+impl SigintReport {
+    fn spawn(
+        start_time: Instant,
+        format: Format,
+        filters: Vec<StatsFilter>,
+        byte_units: ByteUnits,
+        flush_timeout: Option<Duration>,
+    ) -> std::io::Result<Self> {
+        use signal_hook::consts::SIGINT;
+        use std::io::Write;
+
+        // signal-hook chains the handlers installed before, but not the default action
+        let terminate = sigint_is_default();
+        let mut signals = signal_hook::iterator::Signals::new([SIGINT])?;
+        let reported = Arc::new(Mutex::new(false));
+        let thread_reported = Arc::clone(&reported);
+        // Keeps running after the guard drops, to still terminate the process on SIGINT
+        thread::Builder::new()
+            .name("channels-console-sigint".into())
+            .spawn(move || {
+                for _ in signals.forever() {
+                    {
+                        let mut reported = thread_reported
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner);
+                        if !std::mem::replace(&mut *reported, true) {
+                            if let Some(timeout) = flush_timeout {
+                                flush_until_idle(timeout);
+                            }
+                            print_report(format, &filters, byte_units, start_time.elapsed());
+                        }
+                    }
+                    if terminate {
+                        let _ = std::io::stdout().flush();
+                        let _ = signal_hook::low_level::emulate_default_handler(SIGINT);
+                    }
                 }
+            })?;
+
+        Ok(Self { reported })
+    }
+
+    /// Whether the guard still has to print its report, i.e. SIGINT didn't print it already.
+    fn claim(&self) -> bool {
+        let mut reported = self.reported.lock().unwrap_or_else(PoisonError::into_inner);
+        !std::mem::replace(&mut *reported, true)
+    }
+}
+
+/// Whether SIGINT still has its default action of terminating the process.
+#[cfg(all(feature = "sigint", unix))]
+fn sigint_is_default() -> bool {
+    // SAFETY: `sigaction` is a plain C struct, for which all zeroes is a valid value
+    let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+    // SAFETY: a null new action only reads the current one into `action`
+    let result = unsafe { libc::sigaction(libc::SIGINT, std::ptr::null(), &mut action) };
+    result == 0 && action.sa_sigaction == libc::SIG_DFL
+}
+
+/// Prints the statistics of the channels matching all `filters`.
+fn print_report(format: Format, filters: &[StatsFilter], byte_units: ByteUnits, elapsed: Duration) {
+    let stats: Vec<SerializableChannelStats> = get_serializable_stats()
+        .into_iter()
+        .filter(|stats| filters.iter().all(|filter| filter(stats)))
+        .collect();
+
+    if stats.is_empty() {
+        if filters.is_empty() {
+            println!("\nNo instrumented channels found.");
+        } else {
+            println!("\nNo instrumented channels matched the filters.");
+        }
+        return;
+    }
 
-                println!(
-                    "\n=== Channel Statistics (runtime: {:.2}s) ===",
-                    elapsed.as_secs_f64()
-                );
-                table.printstd();
+    match format {
+        Format::Table => {
+            let mut table = Table::new();
+            for row in table_rows(&stats, byte_units) {
+                table.add_row(Row::new(row.iter().map(|cell| Cell::new(cell)).collect()));
             }
-            Format::Json => {
-                let metrics = get_metrics_json();
-                match serde_json::to_string(&metrics) {
-                    Ok(json) => println!("{}", json),
-                    Err(e) => eprintln!("Failed to serialize statistics to JSON: {}", e),
+
+            println!(
+                "\n=== Channel Statistics (runtime: {:.2}s) ===",
+                elapsed.as_secs_f64()
+            );
+            table.printstd();
+        }
+        Format::Markdown => {
+            println!(
+                "\n### Channel Statistics (runtime: {:.2}s)\n",
+                elapsed.as_secs_f64()
+            );
+            for (i, row) in table_rows(&stats, byte_units).iter().enumerate() {
+                println!("{}", piped_row(row));
+                if i == 0 {
+                    println!("|{}", " --- |".repeat(row.len()));
                 }
             }
-            Format::JsonPretty => {
-                let metrics = get_metrics_json();
-                match serde_json::to_string_pretty(&metrics) {
+        }
+        Format::PlainTable => {
+            println!(
+                "\n=== Channel Statistics (runtime: {:.2}s) ===",
+                elapsed.as_secs_f64()
+            );
+            for row in table_rows(&stats, byte_units) {
+                println!("{}", piped_row(&row));
+            }
+        }
+        Format::Json => {
+            let metrics = metrics_json(stats);
+            match serde_json::to_string(&metrics) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("Failed to serialize statistics to JSON: {}", e),
+            }
+        }
+        Format::JsonPretty => {
+            let metrics = metrics_json(stats);
+            match serde_json::to_string_pretty(&metrics) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("Failed to serialize statistics to pretty JSON: {}", e),
+            }
+        }
+        Format::JsonLines => {
+            for channel_stats in &stats {
+                match serde_json::to_string(channel_stats) {
                     Ok(json) => println!("{}", json),
-                    Err(e) => eprintln!("Failed to serialize statistics to pretty JSON: {}", e),
+                    Err(e) => eprintln!("Failed to serialize statistics to JSON: {}", e),
                 }
             }
         }
     }
 }
+
+/// Formats a row as `| a | b |`, escaping pipes within cells.
+fn piped_row(row: &[String]) -> String {
+    let cells: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+/// Header, one row per channel and a totals row, shared by the table, plain table and
+/// Markdown formats.
+fn table_rows(stats: &[SerializableChannelStats], units: ByteUnits) -> Vec<Vec<String>> {
+    let mut rows = vec![[
+        "Channel", "Type", "State", "Sent", "Sent B", "Received", "Dropped", "Queued", "Mem",
+    ]
+    .map(String::from)
+    .to_vec()];
+
+    for channel_stats in stats {
+        let (received, queued, mem) = if channel_stats.sender_only {
+            ("N/A".to_string(), "N/A".to_string(), "N/A".to_string())
+        } else {
+            (
+                channel_stats.received_count.to_string(),
+                channel_stats.queued.to_string(),
+                units.format_message(channel_stats.queued_bytes, channel_stats.type_size),
+            )
+        };
+        rows.push(vec![
+            channel_stats.label.clone(),
+            channel_stats.channel_type_label(),
+            channel_stats.state.as_str().to_string(),
+            channel_stats.sent_count.to_string(),
+            units.format_message(channel_stats.total_bytes_sent, channel_stats.type_size),
+            received,
+            channel_stats.dropped_count.to_string(),
+            queued,
+            mem,
+        ]);
+    }
+
+    let summary = StatsSummary::from_stats(stats);
+    rows.push(vec![
+        format!("Total ({} channels)", summary.channels),
+        String::new(),
+        String::new(),
+        summary.sent_count.to_string(),
+        units.format(summary.total_bytes_sent),
+        summary.received_count.to_string(),
+        summary.dropped_count.to_string(),
+        summary.queued.to_string(),
+        units.format(summary.queued_bytes),
+    ]);
+
+    rows
+}
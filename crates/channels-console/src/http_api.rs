@@ -1,37 +1,208 @@
-use crate::{get_channel_logs, get_metrics_json};
+use crate::{
+    aggregate_by_source, aggregate_by_type, get_channel_backtrace, get_channel_index,
+    get_channel_logs, get_diagnostics, get_metrics_diff, get_metrics_json, get_prometheus_metrics,
+    get_serializable_stats, get_server_info, reset_stats, set_channel_logging, LegacyMetricsJson,
+    StatsSummary, CBOR_CONTENT_TYPE, MSGPACK_CONTENT_TYPE,
+};
 use serde::Serialize;
 use std::fmt::Display;
-use tiny_http::{Header, Request, Response, Server};
+use std::io::{self, Read, Write};
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Method, ReadWrite, Request, Response, Server};
 
-pub(crate) fn start_metrics_server(addr: &str) {
-    let server = match Server::http(addr) {
-        Ok(s) => s,
-        Err(e) => {
-            panic!("Failed to bind metrics server to {}: {}. Customize the port using the CHANNELS_CONSOLE_METRICS_PORT environment variable.", addr, e);
-        }
-    };
+/// Interval between `/ws` snapshots until the client sends `refresh <ms>`
+const WS_DEFAULT_REFRESH: Duration = Duration::from_millis(1000);
+/// Lower bound for the `refresh <ms>` control message
+const WS_MIN_REFRESH: Duration = Duration::from_millis(50);
+/// Largest client frame accepted on `/ws`, control messages are tiny
+const WS_MAX_FRAME_LEN: u64 = 4096;
+const WS_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// Ports tried after the configured one when it's already in use.
+const PORT_FALLBACK_ATTEMPTS: u16 = 10;
 
-    println!("Channel metrics server listening on http://{}", addr);
+/// Binds the metrics server to `host:port`, moving on to the next ports while they're in use.
+/// Returns the server and the address it's bound to.
+pub(crate) fn bind_metrics_server(host: &str, port: u16) -> Result<(Server, String), String> {
+    let mut port = port;
+    let mut attempts_left = PORT_FALLBACK_ATTEMPTS;
+    loop {
+        let addr = format!("{}:{}", host, port);
+        let e = match Server::http(&addr) {
+            Ok(server) => return Ok((server, addr)),
+            Err(e) => e,
+        };
+        let in_use = e
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::AddrInUse);
+        match port.checked_add(1) {
+            Some(next_port) if in_use && attempts_left > 0 => {
+                eprintln!(
+                    "channels-console: {} is already in use, trying port {}",
+                    addr, next_port
+                );
+                port = next_port;
+                attempts_left -= 1;
+            }
+            _ => return Err(format!("Failed to bind metrics server to {}: {}", addr, e)),
+        }
+    }
+}
 
+/// Serves metrics until the process exits. When a token is set, every request must carry it as
+/// a bearer token.
+pub(crate) fn serve_metrics(server: Server, token: Option<&str>) {
     for request in server.incoming_requests() {
+        if let Some(token) = token {
+            if !is_authorized(&request, token) && !is_ws_authorized(&request, token) {
+                respond_unauthorized(request);
+                continue;
+            }
+        }
         handle_request(request);
     }
 }
 
+/// Checks the `Authorization: Bearer <token>` header of a request
+fn is_authorized(request: &Request, token: &str) -> bool {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .and_then(|header| header.value.as_str().strip_prefix("Bearer "))
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), token.as_bytes()))
+}
+
+/// Browsers can't set headers on WebSocket connections, so `/ws` also takes `?token=<token>`
+fn is_ws_authorized(request: &Request, token: &str) -> bool {
+    request.url().split('?').next() == Some("/ws")
+        && query_param(request.url(), "token")
+            .is_some_and(|provided| constant_time_eq(provided.as_bytes(), token.as_bytes()))
+}
+
+fn query_param<'a>(url: &'a str, name: &str) -> Option<&'a str> {
+    url.split_once('?').and_then(|(_, query)| {
+        query.split('&').find_map(|param| {
+            param
+                .strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('='))
+        })
+    })
+}
+
+/// `meta.<key>=<value>` query parameters, all of which a channel's metadata has to match
+fn meta_filters(url: &str) -> Vec<(&str, &str)> {
+    url.split_once('?').map_or_else(Vec::new, |(_, query)| {
+        query
+            .split('&')
+            .filter_map(|param| param.strip_prefix("meta.")?.split_once('='))
+            .collect()
+    })
+}
+
+/// Compares two byte strings without leaking the position of the first mismatch through timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 fn handle_request(request: Request) {
     let path = request.url().split('?').next().unwrap_or("/");
 
     match path {
         "/metrics" => {
-            let metrics = get_metrics_json();
+            let mut metrics = get_metrics_json();
+            let filters = meta_filters(request.url());
+            if !filters.is_empty() {
+                metrics.stats.retain(|stat| {
+                    filters
+                        .iter()
+                        .all(|(key, value)| stat.meta.get(*key).is_some_and(|v| v == value))
+                });
+                metrics.summary = StatsSummary::from_stats(&metrics.stats);
+            }
+            match query_param(request.url(), "aggregate") {
+                None => {}
+                Some("source") => metrics.stats = aggregate_by_source(&metrics.stats),
+                Some(_) => {
+                    respond_error(request, 400, "Invalid aggregate: must be source");
+                    return;
+                }
+            }
+            respond_negotiated(request, &metrics);
+        }
+        "/metrics/diff" => match query_param(request.url(), "cursor").map(str::parse::<u64>) {
+            None => respond_negotiated(request, &get_metrics_diff(None)),
+            Some(Ok(cursor)) => respond_negotiated(request, &get_metrics_diff(Some(cursor))),
+            Some(Err(_)) => respond_error(request, 400, "Invalid cursor: must be a valid number"),
+        },
+        "/metrics/v0" => {
+            let metrics = LegacyMetricsJson::from(get_metrics_json());
             respond_json(request, &metrics);
         }
+        "/metrics/prometheus" => {
+            let mut response = Response::from_string(get_prometheus_metrics());
+            response.add_header(
+                Header::from_bytes(
+                    b"Content-Type".as_slice(),
+                    b"text/plain; version=0.0.4".as_slice(),
+                )
+                .unwrap(),
+            );
+            let _ = request.respond(response);
+        }
+        "/info" => {
+            respond_json(request, &get_server_info());
+        }
+        "/diagnostics" => {
+            respond_json(request, &get_diagnostics());
+        }
+        "/channels" => {
+            respond_json(request, &get_channel_index());
+        }
+        "/types" => {
+            respond_json(request, &aggregate_by_type(&get_serializable_stats()));
+        }
+        "/ws" => handle_websocket(request),
+        "/reset" => {
+            if request.method() == &Method::Post {
+                reset_stats();
+                let _ = request.respond(Response::empty(204));
+            } else {
+                respond_error(request, 405, "Method not allowed: use POST");
+            }
+        }
         _ => {
-            if let Some(id_str) = path.strip_prefix("/logs/") {
+            if let Some(id_str) = path
+                .strip_prefix("/channels/")
+                .and_then(|rest| rest.strip_suffix("/log"))
+            {
+                let id_str = id_str.to_string();
+                handle_set_logging(request, &id_str);
+            } else if let Some(id_str) = path
+                .strip_prefix("/channels/")
+                .and_then(|rest| rest.strip_suffix("/backtrace"))
+            {
+                let id_str = id_str.to_string();
+                handle_backtrace(request, &id_str);
+            } else if let Some(id_str) = path.strip_prefix("/logs/") {
+                let oldest_first = match query_param(request.url(), "order") {
+                    None | Some("desc") => false,
+                    Some("asc") => true,
+                    Some(_) => {
+                        respond_error(request, 400, "Invalid order: must be asc or desc");
+                        return;
+                    }
+                };
                 match id_str.parse::<u64>() {
                     Ok(channel_id) => {
                         let channel_id_str = channel_id.to_string();
-                        match get_channel_logs(&channel_id_str) {
+                        match get_channel_logs(&channel_id_str, oldest_first) {
                             Some(logs) => respond_json(request, &logs),
                             None => respond_error(request, 404, "Channel not found"),
                         }
@@ -47,6 +218,197 @@ fn handle_request(request: Request) {
     }
 }
 
+/// Handles `POST /channels/:id/log?enabled=true|false`
+fn handle_set_logging(request: Request, id_str: &str) {
+    if request.method() != &Method::Post {
+        respond_error(request, 405, "Method not allowed: use POST");
+        return;
+    }
+
+    let Ok(channel_id) = id_str.parse::<u64>() else {
+        respond_error(request, 400, "Invalid channel ID: must be a valid number");
+        return;
+    };
+
+    let enabled =
+        query_param(request.url(), "enabled").and_then(|value| value.parse::<bool>().ok());
+    let Some(enabled) = enabled else {
+        respond_error(request, 400, "Missing query parameter: enabled=true|false");
+        return;
+    };
+
+    if set_channel_logging(channel_id, enabled) {
+        let _ = request.respond(Response::empty(204));
+    } else {
+        respond_error(request, 404, "Channel not found");
+    }
+}
+
+/// Handles `GET /channels/:id/backtrace`
+fn handle_backtrace(request: Request, id_str: &str) {
+    let Ok(channel_id) = id_str.parse::<u64>() else {
+        respond_error(request, 400, "Invalid channel ID: must be a valid number");
+        return;
+    };
+
+    match get_channel_backtrace(channel_id) {
+        Some(Some(backtrace)) => {
+            let mut response = Response::from_string(backtrace.as_ref());
+            response.add_header(
+                Header::from_bytes(
+                    b"Content-Type".as_slice(),
+                    b"text/plain; charset=utf-8".as_slice(),
+                )
+                .unwrap(),
+            );
+            let _ = request.respond(response);
+        }
+        Some(None) => respond_error(
+            request,
+            404,
+            "No backtrace captured: instrument the channel with capture_backtrace = true",
+        ),
+        None => respond_error(request, 404, "Channel not found"),
+    }
+}
+
+/// Handles `GET /ws` by upgrading the connection and streaming metrics snapshots from its own thread
+fn handle_websocket(request: Request) {
+    let key = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Sec-WebSocket-Key"))
+        .map(|header| websocket_accept(header.value.as_str()));
+    let Some(accept) = key else {
+        respond_error(request, 400, "Expected a WebSocket upgrade request");
+        return;
+    };
+
+    let mut response = Response::empty(101);
+    response.add_header(
+        Header::from_bytes(b"Sec-WebSocket-Accept".as_slice(), accept.as_bytes()).unwrap(),
+    );
+    let stream = request.upgrade("websocket", response);
+
+    let _ = thread::Builder::new()
+        .name("channels-console-ws".into())
+        .spawn(move || {
+            let _ = serve_websocket(stream);
+        });
+}
+
+/// Sends a snapshot, then pings the client and handles its control messages until the pong arrives.
+/// The upgraded stream can't be split or given a read timeout, so the ping round trip is what
+/// lets a single thread both push snapshots and listen for `pause`, `resume`, `reset` and `refresh <ms>`.
+fn serve_websocket(mut stream: Box<dyn ReadWrite + Send>) -> io::Result<()> {
+    let mut paused = false;
+    let mut refresh = WS_DEFAULT_REFRESH;
+
+    loop {
+        if !paused {
+            let body = serde_json::to_vec(&get_metrics_json())?;
+            write_frame(&mut stream, OPCODE_TEXT, &body)?;
+        }
+        write_frame(&mut stream, OPCODE_PING, &[])?;
+
+        loop {
+            let (opcode, payload) = read_frame(&mut stream)?;
+            match opcode {
+                OPCODE_PONG => break,
+                OPCODE_PING => write_frame(&mut stream, OPCODE_PONG, &payload)?,
+                OPCODE_CLOSE => {
+                    let _ = write_frame(&mut stream, OPCODE_CLOSE, &[]);
+                    return Ok(());
+                }
+                OPCODE_TEXT => match String::from_utf8_lossy(&payload).trim() {
+                    "pause" => paused = true,
+                    "resume" => paused = false,
+                    "reset" => reset_stats(),
+                    command => {
+                        if let Some(ms) = command
+                            .strip_prefix("refresh ")
+                            .and_then(|ms| ms.trim().parse::<u64>().ok())
+                        {
+                            refresh = Duration::from_millis(ms).max(WS_MIN_REFRESH);
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        thread::sleep(refresh);
+    }
+}
+
+fn websocket_accept(key: &str) -> String {
+    use base64::Engine;
+    let digest = sha1_smol::Sha1::from(format!("{}{}", key.trim(), WS_GUID))
+        .digest()
+        .bytes();
+    base64::engine::general_purpose::STANDARD.encode(digest)
+}
+
+/// Reads a single client frame and unmasks its payload. Fragmented messages aren't supported.
+fn read_frame(stream: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header)?;
+    let opcode = header[0] & 0x0F;
+    let masked = header[1] & 0x80 != 0;
+
+    let len = match header[1] & 0x7F {
+        126 => {
+            let mut len = [0u8; 2];
+            stream.read_exact(&mut len)?;
+            u16::from_be_bytes(len) as u64
+        }
+        127 => {
+            let mut len = [0u8; 8];
+            stream.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        len => len as u64,
+    };
+    if len > WS_MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "WebSocket frame too large",
+        ));
+    }
+
+    let mut mask = [0u8; 4];
+    if masked {
+        stream.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    stream.read_exact(&mut payload)?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+
+    Ok((opcode, payload))
+}
+
+/// Writes a single unmasked frame, as servers must
+fn write_frame(stream: &mut impl Write, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame)?;
+    stream.flush()
+}
+
 fn respond_json<T: Serialize>(request: Request, value: &T) {
     match serde_json::to_vec(value) {
         Ok(body) => {
@@ -61,10 +423,83 @@ fn respond_json<T: Serialize>(request: Request, value: &T) {
     }
 }
 
+/// Encodings of metrics payloads a client can ask for with the `Accept` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+/// The first supported media type listed in `Accept`, ignoring quality values. JSON by default.
+fn accepted_encoding(request: &Request) -> Encoding {
+    let Some(accept) = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Accept"))
+    else {
+        return Encoding::Json;
+    };
+
+    accept
+        .value
+        .as_str()
+        .split(',')
+        .map(|media_type| media_type.split(';').next().unwrap_or_default().trim())
+        .find_map(|media_type| match media_type {
+            MSGPACK_CONTENT_TYPE | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(Encoding::MessagePack)
+            }
+            CBOR_CONTENT_TYPE => Some(Encoding::Cbor),
+            "application/json" => Some(Encoding::Json),
+            _ => None,
+        })
+        .unwrap_or(Encoding::Json)
+}
+
+/// Responds in the encoding the client asked for, see `accepted_encoding`
+fn respond_negotiated<T: Serialize>(request: Request, value: &T) {
+    let (body, content_type) = match accepted_encoding(&request) {
+        Encoding::Json => return respond_json(request, value),
+        Encoding::MessagePack => (
+            rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            MSGPACK_CONTENT_TYPE,
+        ),
+        Encoding::Cbor => {
+            let mut body = Vec::new();
+            (
+                ciborium::into_writer(value, &mut body)
+                    .map(|()| body)
+                    .map_err(|e| e.to_string()),
+                CBOR_CONTENT_TYPE,
+            )
+        }
+    };
+
+    match body {
+        Ok(body) => {
+            let mut response = Response::from_data(body);
+            response.add_header(
+                Header::from_bytes(b"Content-Type".as_slice(), content_type.as_bytes()).unwrap(),
+            );
+            let _ = request.respond(response);
+        }
+        Err(e) => respond_internal_error(request, e),
+    }
+}
+
 fn respond_error(request: Request, code: u16, msg: &str) {
     let _ = request.respond(Response::from_string(msg).with_status_code(code));
 }
 
+fn respond_unauthorized(request: Request) {
+    let mut response = Response::from_string("Unauthorized").with_status_code(401);
+    response.add_header(
+        Header::from_bytes(b"WWW-Authenticate".as_slice(), b"Bearer".as_slice()).unwrap(),
+    );
+    let _ = request.respond(response);
+}
+
 fn respond_internal_error(request: Request, e: impl Display) {
     eprintln!("Internal server error: {}", e);
     let _ = request.respond(
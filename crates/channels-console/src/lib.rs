@@ -1,16 +1,38 @@
-use crossbeam_channel::{unbounded, Sender as CbSender};
+use crossbeam_channel::{unbounded, RecvTimeoutError, Sender as CbSender};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
-use std::sync::atomic::AtomicU64;
-use std::sync::{Arc, OnceLock, RwLock};
-use std::time::Instant;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock, PoisonError, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub mod channels_guard;
 pub use channels_guard::{ChannelsGuard, ChannelsGuardBuilder};
 
-use crate::http_api::start_metrics_server;
+use crate::http_api::{bind_metrics_server, serve_metrics};
 mod http_api;
+#[cfg(feature = "metrics")]
+mod metrics_facade;
+#[cfg(feature = "otel")]
+mod otel;
+#[cfg(feature = "otel")]
+pub use otel::register_otel_metrics;
+mod prometheus;
+mod session;
+pub use session::{record_to, replay_session, RecordedEvent, SessionEvent};
 mod wrappers;
+pub use wrappers::stream::{InstrumentedSink, InstrumentedStream};
+#[cfg(feature = "kanal")]
+pub use wrappers::tap::{
+    TappedKanalAsyncReceiver, TappedKanalAsyncSender, TappedKanalReceiver, TappedKanalSender,
+};
+#[cfg(feature = "tokio")]
+pub use wrappers::tap::{
+    TappedOneshotReceiver, TappedOneshotSender, TappedReceiver, TappedSender,
+    TappedUnboundedReceiver, TappedUnboundedSender,
+};
+pub use wrappers::tap::{TappedStdReceiver, TappedStdSender};
 
 /// A single log entry for a message sent or received.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,7 +45,8 @@ pub struct LogEntry {
 impl LogEntry {
     pub(crate) fn new(index: u64, timestamp: Instant, message: Option<String>) -> Self {
         let start_time = START_TIME.get().copied().unwrap_or(timestamp);
-        let timestamp_nanos = timestamp.duration_since(start_time).as_nanos() as u64;
+        // Instants taken on other threads around the start may still compare as earlier
+        let timestamp_nanos = timestamp.saturating_duration_since(start_time).as_nanos() as u64;
         Self {
             index,
             timestamp: timestamp_nanos,
@@ -91,9 +114,26 @@ pub enum Format {
     Table,
     Json,
     JsonPretty,
+    /// One JSON object per channel per line (NDJSON).
+    JsonLines,
+    /// GitHub-flavored Markdown table, e.g. for pasting into issues.
+    Markdown,
+    /// The table as `| `-separated cells without padding or colors, so its lines don't depend on
+    /// label lengths or the terminal, e.g. for asserting on output in CI.
+    PlainTable,
 }
 
 /// State of a instrumented channel.
+///
+/// Oneshot channels go through `Active -> Notified -> Closed`:
+/// - `Active` until a value is sent.
+/// - `Notified` once the value was sent, and still after the receiver awaited it.
+/// - `Cancelled` if the sender is dropped without sending, e.g. an abandoned response.
+/// - `Closed` if the receiver is dropped before the value is delivered. `Closed` and
+///   `Cancelled` are final, a late `Notified` never overrides them.
+///
+/// Unbounded channels are never `Full`, instead they're `Growing` while more messages are
+/// queued than `CHANNELS_CONSOLE_GROWING_THRESHOLD`, if it's set.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ChannelState {
     #[default]
@@ -101,6 +141,8 @@ pub enum ChannelState {
     Closed,
     Full,
     Notified,
+    Cancelled,
+    Growing,
 }
 
 impl std::fmt::Display for ChannelState {
@@ -116,6 +158,8 @@ impl ChannelState {
             ChannelState::Closed => "closed",
             ChannelState::Full => "full",
             ChannelState::Notified => "notified",
+            ChannelState::Cancelled => "cancelled",
+            ChannelState::Growing => "growing",
         }
     }
 }
@@ -140,47 +184,679 @@ impl<'de> Deserialize<'de> for ChannelState {
             "closed" => Ok(ChannelState::Closed),
             "full" => Ok(ChannelState::Full),
             "notified" => Ok(ChannelState::Notified),
+            "cancelled" => Ok(ChannelState::Cancelled),
+            "growing" => Ok(ChannelState::Growing),
             _ => Err(serde::de::Error::custom("invalid channel state")),
         }
     }
 }
 
+/// Which end of a channel caused it to close.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseReason {
+    /// All senders were dropped.
+    SenderDropped,
+    /// The receiver was dropped.
+    ReceiverDropped,
+    /// A forwarder couldn't hand a message over to the inner channel.
+    ForwardFailed,
+}
+
+impl std::fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl CloseReason {
+    /// Short description, e.g. "rx dropped".
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloseReason::SenderDropped => "tx dropped",
+            CloseReason::ReceiverDropped => "rx dropped",
+            CloseReason::ForwardFailed => "forward failed",
+        }
+    }
+}
+
+/// Upper bounds of the queue utilization histogram buckets, as a fraction of capacity.
+pub(crate) const UTILIZATION_BUCKETS: [f64; 6] = [0.1, 0.25, 0.5, 0.75, 0.9, 1.0];
+
+/// Distribution of `queued / capacity` samples for a bounded channel.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UtilizationHistogram {
+    /// Per-bucket (non-cumulative) sample counts, matching `UTILIZATION_BUCKETS`.
+    pub(crate) buckets: [u64; UTILIZATION_BUCKETS.len()],
+    pub(crate) sum: f64,
+    pub(crate) count: u64,
+}
+
+impl UtilizationHistogram {
+    fn observe(&mut self, utilization: f64) {
+        if let Some(idx) = UTILIZATION_BUCKETS.iter().position(|le| utilization <= *le) {
+            self.buckets[idx] += 1;
+        }
+        self.sum += utilization;
+        self.count += 1;
+    }
+}
+
+/// Number of recent queue depths kept per bounded channel to recommend a capacity.
+const QUEUE_DEPTH_RESERVOIR_SIZE: usize = 1024;
+/// Queue depth samples needed before a capacity is recommended.
+const MIN_CAPACITY_SAMPLES: usize = 100;
+
+/// Recent queue depths of a bounded channel, sampled along with its utilization.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct QueueDepths {
+    /// Most recent depths, oldest first.
+    recent: VecDeque<u64>,
+    /// The same depths in ascending order, kept up to date as they're sampled so reading the
+    /// percentile on every `/metrics` request doesn't sort them.
+    sorted: Vec<u64>,
+}
+
+impl QueueDepths {
+    fn observe(&mut self, queued: u64) {
+        if self.recent.len() >= QUEUE_DEPTH_RESERVOIR_SIZE {
+            if let Some(oldest) = self.recent.pop_front() {
+                let idx = self.sorted.partition_point(|&depth| depth < oldest);
+                self.sorted.remove(idx);
+            }
+        }
+        self.recent.push_back(queued);
+        let idx = self.sorted.partition_point(|&depth| depth <= queued);
+        self.sorted.insert(idx, queued);
+    }
+
+    /// 99th percentile of the recent depths, `None` until enough were sampled.
+    fn p99(&self) -> Option<u64> {
+        if self.sorted.len() < MIN_CAPACITY_SAMPLES {
+            return None;
+        }
+        Some(self.sorted[((self.sorted.len() - 1) as f64 * 0.99).round() as usize])
+    }
+}
+
+/// Capacity leaving 25% of headroom above the 99th percentile queue depth, rounded up to a
+/// multiple of 8 once above 8. `None` for a queue that's empty at the 99th percentile, which
+/// gives nothing to size the capacity by.
+fn recommended_capacity(queued_p99: u64) -> Option<usize> {
+    if queued_p99 == 0 {
+        return None;
+    }
+    let capacity = (queued_p99 as usize * 5).div_ceil(4);
+    if capacity > 8 {
+        Some(capacity.next_multiple_of(8))
+    } else {
+        Some(capacity)
+    }
+}
+
+/// Number of recent latencies kept per channel to compute percentiles.
+const LATENCY_RESERVOIR_SIZE: usize = 1024;
+/// Send timestamps kept per channel to match with receives, later messages aren't sampled.
+const MAX_PENDING_SENDS: usize = 100_000;
+
+/// Delay between a message's send and receive events, matched in FIFO order.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LatencyTracker {
+    /// Send timestamps of queued messages with the number of messages sent at each, oldest first.
+    pending: VecDeque<(Instant, u64)>,
+    /// Queued messages sent after `pending` filled up, received without a sample.
+    untracked: u64,
+    /// Receives reported before their send, whose sends are skipped once they arrive.
+    early_receives: u64,
+    /// Most recent latencies in nanoseconds, oldest first.
+    recent: VecDeque<u64>,
+    total_ns: u64,
+    count: u64,
+    max_ns: u64,
+}
+
+impl LatencyTracker {
+    fn on_sent(&mut self, timestamp: Instant, count: u64) {
+        let skipped = count.min(self.early_receives);
+        self.early_receives -= skipped;
+        let count = count - skipped;
+        if count == 0 {
+            return;
+        }
+
+        if self.untracked > 0 || self.pending.len() >= MAX_PENDING_SENDS {
+            self.untracked += count;
+        } else {
+            self.pending.push_back((timestamp, count));
+        }
+    }
+
+    /// Matches `count` receives with the oldest sends, sampling once per batch of sends.
+    fn on_received(&mut self, timestamp: Instant, mut count: u64) {
+        while count > 0 {
+            let Some((sent_at, sent)) = self.pending.front_mut() else {
+                break;
+            };
+            let sent_at = *sent_at;
+            let matched = count.min(*sent);
+            *sent -= matched;
+            if *sent == 0 {
+                self.pending.pop_front();
+            }
+            count -= matched;
+            self.record(timestamp.saturating_duration_since(sent_at).as_nanos() as u64);
+        }
+
+        let untracked = count.min(self.untracked);
+        self.untracked -= untracked;
+        self.early_receives += count - untracked;
+    }
+
+    fn record(&mut self, latency_ns: u64) {
+        if self.recent.len() >= LATENCY_RESERVOIR_SIZE {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(latency_ns);
+        self.total_ns = self.total_ns.saturating_add(latency_ns);
+        self.count += 1;
+        self.max_ns = self.max_ns.max(latency_ns);
+    }
+
+    /// Drops collected samples, queued messages are still matched once received.
+    fn clear_samples(&mut self) {
+        self.recent.clear();
+        self.total_ns = 0;
+        self.count = 0;
+        self.max_ns = 0;
+    }
+
+    fn summary(&self) -> Option<LatencyStats> {
+        if self.recent.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<u64> = self.recent.iter().copied().collect();
+        sorted.sort_unstable();
+        let percentile = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+
+        Some(LatencyStats {
+            avg_ns: self.total_ns / self.count,
+            max_ns: self.max_ns,
+            p50_ns: percentile(0.5),
+            p95_ns: percentile(0.95),
+            p99_ns: percentile(0.99),
+        })
+    }
+}
+
+/// Sizes of individual messages, for channels instrumented with `size = dynamic`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PayloadBytes {
+    /// Sizes of messages sent but not yet received, in FIFO order.
+    pending: VecDeque<u64>,
+    pending_bytes: u64,
+    total_bytes_sent: u64,
+}
+
+impl PayloadBytes {
+    fn on_sent(&mut self, size: u64, track_pending: bool) {
+        self.total_bytes_sent = self.total_bytes_sent.saturating_add(size);
+        if track_pending {
+            self.pending.push_back(size);
+            self.pending_bytes += size;
+        }
+    }
+
+    fn on_received(&mut self) {
+        if let Some(size) = self.pending.pop_front() {
+            self.pending_bytes -= size;
+        }
+    }
+
+    /// Bytes of the newest `queued` pending messages, older ones are held by the forwarders.
+    fn queued_bytes(&self, queued: u64) -> u64 {
+        let in_flight = self.pending.len().saturating_sub(queued as usize);
+        self.pending_bytes - self.pending.iter().take(in_flight).sum::<u64>()
+    }
+}
+
 /// Statistics for a single instrumented channel.
 #[derive(Debug, Clone)]
 pub(crate) struct ChannelStats {
     pub(crate) id: u64,
-    pub(crate) source: &'static str,
+    pub(crate) source: Arc<str>,
     pub(crate) label: Option<String>,
+    /// Logical subsystem the channel belongs to, used to group channels together.
+    pub(crate) group: Option<String>,
+    /// Arbitrary key/value tags set by the `meta` parameter.
+    pub(crate) meta: BTreeMap<String, String>,
     pub(crate) channel_type: ChannelType,
+    /// Bounded, but instrumented without `capacity =` on a channel that doesn't expose it.
+    pub(crate) unknown_capacity: bool,
+    /// Holds a slot per sender on top of its capacity, like futures channels, so the queue is
+    /// capped at the capacity rather than shown above it.
+    pub(crate) queue_capped: bool,
     pub(crate) state: ChannelState,
     pub(crate) sent_count: u64,
     pub(crate) received_count: u64,
+    /// Messages lost because forwarding them failed, e.g. after the receiver was dropped.
+    pub(crate) dropped_count: u64,
+    /// `try_send` calls rejected because the channel was full, tap mode only.
+    pub(crate) try_send_full_count: u64,
+    /// `recv_timeout` calls that timed out, tap mode only.
+    pub(crate) timeout_count: u64,
     pub(crate) type_name: &'static str,
     pub(crate) type_size: usize,
     pub(crate) sent_logs: VecDeque<LogEntry>,
     pub(crate) received_logs: VecDeque<LogEntry>,
     pub(crate) iter: u32,
+    /// Only the sender is instrumented, so receives are not observed.
+    pub(crate) sender_only: bool,
+    /// Messages pass through forwarders, which hold one of them in flight.
+    pub(crate) proxied: bool,
+    /// Queue utilization sampled on every send and receive, bounded channels only.
+    pub(crate) utilization: UtilizationHistogram,
+    /// Queue depths sampled with `utilization`, to recommend a capacity.
+    pub(crate) queue_depths: QueueDepths,
+    pub(crate) latency: LatencyTracker,
+    /// Actual message sizes, `None` unless instrumented with `size = dynamic`.
+    pub(crate) payload_bytes: Option<PayloadBytes>,
+    /// Wall-clock time at which the channel was instrumented.
+    pub(crate) created_at: SystemTime,
+    /// Call stack that instrumented the channel, if captured.
+    pub(crate) backtrace: Option<Arc<str>>,
+    /// When the channel transitioned to `Closed`, used for retention.
+    pub(crate) closed_at: Option<Instant>,
+    /// Which end closed the channel, set on the first close.
+    pub(crate) close_reason: Option<CloseReason>,
+    /// Whether sent and received messages are logged, shared with the channel's wrapper.
+    pub(crate) logging: Arc<LogGate>,
+    /// Value of `CHANGE_VERSION` when an event last touched the channel, see `/metrics/diff`.
+    pub(crate) changed_version: u64,
+    #[cfg(feature = "tracing")]
+    pub(crate) span: tracing::Span,
 }
 
 impl ChannelStats {
     pub fn queued(&self) -> u64 {
-        self.sent_count
-            .saturating_sub(self.received_count)
-            .saturating_sub(1)
+        if self.sender_only {
+            return 0;
+        }
+        let mut queued = self.sent_count.saturating_sub(self.received_count);
+        if self.proxied {
+            queued = queued.saturating_sub(1);
+        }
+        match self.channel_type {
+            ChannelType::Bounded(cap) if self.queue_capped && !self.unknown_capacity => {
+                queued.min(cap as u64)
+            }
+            _ => queued,
+        }
     }
 
+    /// Bytes currently held in the channel.
     pub fn queued_bytes(&self) -> u64 {
-        self.queued() * self.type_size as u64
+        match &self.payload_bytes {
+            Some(payload_bytes) => payload_bytes.queued_bytes(self.queued()),
+            None => self.queued() * self.type_size as u64,
+        }
+    }
+
+    /// Display label, either the custom one or derived from the source location.
+    pub(crate) fn resolved_label(&self) -> String {
+        resolve_label(&self.source, self.label.as_deref(), self.iter)
+    }
+
+    /// Cumulative bytes of all messages ever sent through the channel.
+    pub fn total_bytes_sent(&self) -> u64 {
+        match &self.payload_bytes {
+            Some(payload_bytes) => payload_bytes.total_bytes_sent,
+            None => self.sent_count * self.type_size as u64,
+        }
+    }
+
+    /// Start counting from scratch. Messages still in flight are kept as sent,
+    /// so the queue depth stays accurate once they are received.
+    fn reset(&mut self) {
+        self.sent_count = self.sent_count.saturating_sub(self.received_count);
+        self.received_count = 0;
+        self.dropped_count = 0;
+        self.try_send_full_count = 0;
+        self.timeout_count = 0;
+        if let Some(payload_bytes) = &mut self.payload_bytes {
+            payload_bytes.total_bytes_sent = payload_bytes.pending_bytes;
+        }
+        self.sent_logs.clear();
+        self.received_logs.clear();
+        self.utilization = UtilizationHistogram::default();
+        self.queue_depths = QueueDepths::default();
+        self.latency.clear_samples();
     }
 }
 
+/// Version of the `/metrics` payload, bumped whenever a field of it is renamed or removed.
+pub const METRICS_SCHEMA_VERSION: u32 = 1;
+
+/// Media type of MessagePack `/metrics` payloads, served to clients that send it in `Accept`.
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Media type of CBOR `/metrics` payloads, served to clients that send it in `Accept`.
+pub const CBOR_CONTENT_TYPE: &str = "application/cbor";
+
 /// Wrapper for metrics JSON response containing stats and current time
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsJson {
+    /// Schema version, see `METRICS_SCHEMA_VERSION`. Servers predating it report 0.
+    #[serde(default)]
+    pub version: u32,
     /// Current elapsed time since program start in nanoseconds
     pub current_elapsed_ns: u64,
     /// Channel statistics
+    #[serde(rename = "channels", alias = "stats")]
     pub stats: Vec<SerializableChannelStats>,
+    /// Totals across all channels
+    #[serde(default)]
+    pub summary: StatsSummary,
+}
+
+/// Response of `/metrics/diff`, listing only the channels that changed since a cursor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsDiffJson {
+    /// Schema version, see `METRICS_SCHEMA_VERSION`.
+    pub version: u32,
+    /// Current elapsed time since program start in nanoseconds
+    pub current_elapsed_ns: u64,
+    /// Pass as `?cursor=` on the next request to get the changes since this one.
+    pub cursor: u64,
+    /// No cursor was given or it's too old, so every channel is listed and deltas count from zero.
+    pub full: bool,
+    /// Channels whose counts or state changed since the cursor
+    pub channels: Vec<ChannelDelta>,
+    /// Ids of channels removed since the cursor, e.g. by a reset or closed channel retention
+    pub removed: Vec<u64>,
+}
+
+/// A channel in `/metrics/diff`, with its counts and how much they grew since the cursor.
+///
+/// If counts were reset in between, the deltas count from the reset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelDelta {
+    pub id: u64,
+    pub label: String,
+    pub state: ChannelState,
+    pub sent_count: u64,
+    pub received_count: u64,
+    pub sent_delta: u64,
+    pub received_delta: u64,
+    pub queued: u64,
+}
+
+/// Counts of every channel at a `/metrics/diff` cursor, keyed by channel id.
+struct DiffCursor {
+    version: u64,
+    counts: HashMap<u64, (u64, u64)>,
+}
+
+/// Totals across a set of channels.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatsSummary {
+    pub channels: usize,
+    pub sent_count: u64,
+    pub received_count: u64,
+    pub dropped_count: u64,
+    pub queued: u64,
+    pub queued_bytes: u64,
+    pub total_bytes_sent: u64,
+}
+
+impl StatsSummary {
+    pub fn from_stats(stats: &[SerializableChannelStats]) -> Self {
+        stats.iter().fold(
+            Self {
+                channels: stats.len(),
+                ..Self::default()
+            },
+            |mut summary, s| {
+                summary.sent_count += s.sent_count;
+                summary.received_count += s.received_count;
+                summary.dropped_count += s.dropped_count;
+                summary.queued += s.queued;
+                summary.queued_bytes += s.queued_bytes;
+                summary.total_bytes_sent += s.total_bytes_sent;
+                summary
+            },
+        )
+    }
+}
+
+/// Collapses channels created at the same source into a single row with their summed counts.
+///
+/// The row takes the position, id, label and type of the first channel of its source, and its
+/// `count` is the number of channels collapsed into it. It's closed only once all of them are,
+/// and shows the latency of the slowest one and the capacity recommended for the fullest one.
+/// Sources with a single channel are kept as they are.
+///
+/// # Examples
+///
+/// ```
+/// let rows = channels_console::aggregate_by_source(&channels_console::snapshot());
+/// for row in rows.iter().filter(|row| row.count.is_some()) {
+///     println!("{}: {} channels, {} sent", row.source, row.count.unwrap(), row.sent_count);
+/// }
+/// ```
+pub fn aggregate_by_source(stats: &[SerializableChannelStats]) -> Vec<SerializableChannelStats> {
+    let mut rows: Vec<SerializableChannelStats> = Vec::with_capacity(stats.len());
+    let mut row_of_source: HashMap<&str, usize> = HashMap::new();
+
+    for stat in stats {
+        let Some(&i) = row_of_source.get(stat.source.as_str()) else {
+            row_of_source.insert(&stat.source, rows.len());
+            rows.push(stat.clone());
+            continue;
+        };
+
+        let row = &mut rows[i];
+        row.count = Some(row.count.unwrap_or(1) + 1);
+        row.sent_count += stat.sent_count;
+        row.received_count += stat.received_count;
+        row.dropped_count += stat.dropped_count;
+        row.try_send_full_count += stat.try_send_full_count;
+        row.timeout_count += stat.timeout_count;
+        row.queued += stat.queued;
+        row.queued_bytes += stat.queued_bytes;
+        row.total_bytes_sent += stat.total_bytes_sent;
+        row.sender_only &= stat.sender_only;
+        row.unknown_capacity |= stat.unknown_capacity;
+        row.logging |= stat.logging;
+        let needs_attention = matches!(stat.state, ChannelState::Full | ChannelState::Growing);
+        if row.state == ChannelState::Closed || (needs_attention && row.state != ChannelState::Full)
+        {
+            row.state = stat.state;
+        }
+        // Only a fully closed group whose channels all closed the same way keeps a reason
+        if row.state != ChannelState::Closed || row.close_reason != stat.close_reason {
+            row.close_reason = None;
+        }
+        if stat.latency.map(|latency| latency.p99_ns) > row.latency.map(|latency| latency.p99_ns) {
+            row.latency = stat.latency;
+        }
+        if stat.queued_p99 > row.queued_p99 {
+            row.queued_p99 = stat.queued_p99;
+            row.recommended_capacity = stat.recommended_capacity;
+        }
+    }
+
+    rows
+}
+
+/// Totals of all channels carrying the same message type, see `aggregate_by_type`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeStats {
+    pub type_name: String,
+    pub type_size: usize,
+    /// Number of channels carrying this type
+    pub channels: usize,
+    pub sent_count: u64,
+    pub received_count: u64,
+    pub dropped_count: u64,
+    pub queued: u64,
+    pub queued_bytes: u64,
+    pub total_bytes_sent: u64,
+}
+
+/// Sums up the channels of each message type, e.g. to see how much traffic a type generates
+/// across the whole program. Types are ordered by bytes sent, most first.
+///
+/// Rows collapsed by `aggregate_by_source` count as all of their channels.
+///
+/// # Examples
+///
+/// ```
+/// for row in channels_console::aggregate_by_type(&channels_console::snapshot()) {
+///     println!("{}: {} channels, {} sent", row.type_name, row.channels, row.sent_count);
+/// }
+/// ```
+pub fn aggregate_by_type(stats: &[SerializableChannelStats]) -> Vec<TypeStats> {
+    let mut rows: Vec<TypeStats> = Vec::new();
+    let mut row_of_type: HashMap<&str, usize> = HashMap::new();
+
+    for stat in stats {
+        let i = *row_of_type.entry(&stat.type_name).or_insert_with(|| {
+            rows.push(TypeStats {
+                type_name: stat.type_name.clone(),
+                type_size: stat.type_size,
+                ..TypeStats::default()
+            });
+            rows.len() - 1
+        });
+
+        let row = &mut rows[i];
+        row.channels += stat.count.unwrap_or(1);
+        row.sent_count += stat.sent_count;
+        row.received_count += stat.received_count;
+        row.dropped_count += stat.dropped_count;
+        row.queued += stat.queued;
+        row.queued_bytes += stat.queued_bytes;
+        row.total_bytes_sent += stat.total_bytes_sent;
+    }
+
+    rows.sort_by(|a, b| {
+        b.total_bytes_sent
+            .cmp(&a.total_bytes_sent)
+            .then(b.sent_count.cmp(&a.sent_count))
+            .then_with(|| a.type_name.cmp(&b.type_name))
+    });
+    rows
+}
+
+/// State of the metrics HTTP server, see `metrics_server_status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetricsServerStatus {
+    /// Listening on this address, which has a later port than configured if that one was taken.
+    Listening(String),
+    /// No port could be bound, with the error of the last attempt.
+    Failed(String),
+}
+
+static METRICS_SERVER_STATUS: OnceLock<MetricsServerStatus> = OnceLock::new();
+
+/// Where the metrics HTTP server is listening, or why it couldn't start.
+///
+/// The server starts along with the first instrumented channel, so this returns `None` before.
+///
+/// # Examples
+///
+/// ```
+/// use channels_console::MetricsServerStatus;
+///
+/// if let Some(MetricsServerStatus::Failed(e)) = channels_console::metrics_server_status() {
+///     eprintln!("No metrics for the TUI: {e}");
+/// }
+/// ```
+pub fn metrics_server_status() -> Option<MetricsServerStatus> {
+    METRICS_SERVER_STATUS.get().cloned()
+}
+
+type StateChangeCallback = Box<dyn Fn(u64, &str, ChannelState, ChannelState) + Send + Sync>;
+
+static STATE_CHANGE_CALLBACKS: RwLock<Vec<StateChangeCallback>> = RwLock::new(Vec::new());
+
+/// Registers a callback run whenever a channel changes state, e.g. to raise an alert once a
+/// channel is `Full` or `Closed`. It's called with the channel's id, label and its old and new
+/// state. Callbacks stay registered for the rest of the process and run in the order they were
+/// registered.
+///
+/// Callbacks run on the stats collector thread while it applies events, so they must be fast and
+/// never block: every channel's stats lag behind until they return. They must not call back into
+/// this crate, e.g. `snapshot` or `flush`, which wait for the collector and would deadlock.
+/// Hand the work over to another thread instead.
+///
+/// # Examples
+///
+/// ```
+/// use channels_console::ChannelState;
+///
+/// channels_console::on_state_change(|id, label, old, new| {
+///     if new == ChannelState::Full {
+///         eprintln!("channel {label} (#{id}) is full, was {old}");
+///     }
+/// });
+/// ```
+pub fn on_state_change<F>(callback: F)
+where
+    F: Fn(u64, &str, ChannelState, ChannelState) + Send + Sync + 'static,
+{
+    STATE_CHANGE_CALLBACKS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Box::new(callback));
+}
+
+fn run_state_change_callbacks(channel_stats: &ChannelStats, old: ChannelState, new: ChannelState) {
+    let callbacks = STATE_CHANGE_CALLBACKS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner);
+    if callbacks.is_empty() {
+        return;
+    }
+    let label = channel_stats.resolved_label();
+    for callback in callbacks.iter() {
+        callback(channel_stats.id, &label, old, new);
+    }
+}
+
+/// Information about the instrumented process, served at `/info`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerInfo {
+    /// Wall-clock time at which log timestamps start, in milliseconds since the Unix epoch
+    pub start_wall_clock: u64,
+}
+
+/// Id and label of a channel, listed at `/channels` for pickers that don't need full stats
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelIndexEntry {
+    pub id: u64,
+    pub label: String,
+}
+
+/// Health of the stats collector itself, served at `/diagnostics`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostics {
+    /// Events waiting to be applied by the collector thread
+    pub event_queue_len: u64,
+    /// Longest the event queue has been since the process started
+    pub peak_event_queue_len: u64,
+}
+
+/// Delay between sending and receiving messages, in nanoseconds.
+/// Average and max cover every message since the last reset, percentiles the most recent 1024.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub avg_ns: u64,
+    pub max_ns: u64,
+    pub p50_ns: u64,
+    pub p95_ns: u64,
+    pub p99_ns: u64,
 }
 
 /// Serializable version of channel statistics for JSON responses.
@@ -190,35 +866,115 @@ pub struct SerializableChannelStats {
     pub source: String,
     pub label: String,
     pub has_custom_label: bool,
+    pub group: Option<String>,
+    /// Key/value tags set with the `meta` parameter, e.g. the owning team
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, String>,
     pub channel_type: ChannelType,
+    /// The channel is bounded but its capacity is unknown, so the one in `channel_type` is
+    /// meaningless. Happens for std and futures channels instrumented without `capacity =`.
+    #[serde(default)]
+    pub unknown_capacity: bool,
     pub state: ChannelState,
+    /// Which end closed the channel, `None` while it's open
+    #[serde(default)]
+    pub close_reason: Option<CloseReason>,
     pub sent_count: u64,
     pub received_count: u64,
+    pub dropped_count: u64,
+    /// `try_send` calls rejected because the channel was full, only counted in tap mode
+    #[serde(default)]
+    pub try_send_full_count: u64,
+    /// `recv_timeout` calls that timed out instead of receiving, only counted in tap mode
+    #[serde(default)]
+    pub timeout_count: u64,
     pub queued: u64,
     pub type_name: String,
     pub type_size: usize,
     pub queued_bytes: u64,
+    /// Cumulative bytes of all messages sent, unlike `queued_bytes` which only counts queued ones
+    #[serde(default)]
+    pub total_bytes_sent: u64,
     pub iter: u32,
+    pub sender_only: bool,
+    pub logging: bool,
+    /// `None` until a message has been received
+    #[serde(default)]
+    pub latency: Option<LatencyStats>,
+    /// 99th percentile of the recent queue depths of a bounded channel, `None` until at least
+    /// 100 sends and receives were sampled
+    #[serde(default)]
+    pub queued_p99: Option<u64>,
+    /// Capacity leaving 25% of headroom above `queued_p99`, `None` along with it or while it's 0
+    #[serde(default)]
+    pub recommended_capacity: Option<usize>,
+    /// Wall-clock time at which the channel was instrumented, in RFC 3339 format
+    #[serde(default)]
+    pub created_at: String,
+    /// Whether a creation backtrace was captured, served at `/channels/:id/backtrace`
+    #[serde(default)]
+    pub has_backtrace: bool,
+    /// Only 1 in this many messages is logged, `None` if every message is
+    #[serde(default)]
+    pub log_sample: Option<u64>,
+    /// Number of channels collapsed into this row by `aggregate_by_source`, `None` for a single channel
+    #[serde(default)]
+    pub count: Option<usize>,
+}
+
+impl SerializableChannelStats {
+    /// The channel type as shown in tables, e.g. `bounded[10]`, or `bounded[?]` if its capacity
+    /// is unknown.
+    pub fn channel_type_label(&self) -> String {
+        match self.channel_type {
+            ChannelType::Bounded(_) if self.unknown_capacity => "bounded[?]".to_string(),
+            channel_type => channel_type.to_string(),
+        }
+    }
+
+    /// Failed operations on the channel: messages dropped without being received, `try_send`
+    /// calls rejected because it was full, and `recv_timeout` calls that timed out.
+    pub fn error_count(&self) -> u64 {
+        self.dropped_count + self.try_send_full_count + self.timeout_count
+    }
 }
 
 impl From<&ChannelStats> for SerializableChannelStats {
     fn from(stats: &ChannelStats) -> Self {
-        let label = resolve_label(stats.source, stats.label.as_deref(), stats.iter);
+        let label = stats.resolved_label();
+        let queued_p99 = stats.queue_depths.p99();
 
         Self {
             id: stats.id,
             source: stats.source.to_string(),
             label,
             has_custom_label: stats.label.is_some(),
+            group: stats.group.clone(),
+            meta: stats.meta.clone(),
             channel_type: stats.channel_type,
+            unknown_capacity: stats.unknown_capacity,
             state: stats.state,
+            close_reason: stats.close_reason,
             sent_count: stats.sent_count,
             received_count: stats.received_count,
+            dropped_count: stats.dropped_count,
+            try_send_full_count: stats.try_send_full_count,
+            timeout_count: stats.timeout_count,
             queued: stats.queued(),
             type_name: stats.type_name.to_string(),
             type_size: stats.type_size,
             queued_bytes: stats.queued_bytes(),
+            total_bytes_sent: stats.total_bytes_sent(),
             iter: stats.iter,
+            sender_only: stats.sender_only,
+            logging: stats.logging.enabled(),
+            latency: stats.latency.summary(),
+            queued_p99,
+            recommended_capacity: queued_p99.and_then(recommended_capacity),
+            created_at: format_rfc3339(stats.created_at),
+            has_backtrace: stats.backtrace.is_some(),
+            log_sample: (stats.logging.sample > 1).then_some(stats.logging.sample),
+            count: None,
         }
     }
 }
@@ -226,7 +982,7 @@ impl From<&ChannelStats> for SerializableChannelStats {
 impl ChannelStats {
     fn new(
         id: u64,
-        source: &'static str,
+        source: Arc<str>,
         label: Option<String>,
         channel_type: ChannelType,
         type_name: &'static str,
@@ -237,491 +993,2518 @@ impl ChannelStats {
             id,
             source,
             label,
+            group: None,
+            meta: BTreeMap::new(),
             channel_type,
+            unknown_capacity: false,
+            queue_capped: false,
             state: ChannelState::default(),
             sent_count: 0,
             received_count: 0,
+            dropped_count: 0,
+            try_send_full_count: 0,
+            timeout_count: 0,
             type_name,
             type_size,
             sent_logs: VecDeque::new(),
             received_logs: VecDeque::new(),
             iter,
+            sender_only: false,
+            proxied: true,
+            utilization: UtilizationHistogram::default(),
+            queue_depths: QueueDepths::default(),
+            latency: LatencyTracker::default(),
+            payload_bytes: None,
+            created_at: SystemTime::now(),
+            backtrace: None,
+            closed_at: None,
+            close_reason: None,
+            logging: log_gate(id),
+            changed_version: 0,
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::none(),
         }
     }
 
+    /// Whether the queue is observed and the state still follows it.
+    fn tracks_queue(&self) -> bool {
+        !matches!(
+            self.state,
+            ChannelState::Closed | ChannelState::Notified | ChannelState::Cancelled
+        ) && !self.sender_only
+    }
+
     fn update_state(&mut self) {
-        if self.state == ChannelState::Closed || self.state == ChannelState::Notified {
+        if !self.tracks_queue() {
             return;
         }
 
         let queued = self.queued();
+        self.sample_queue(queued, 1);
+        self.set_state(self.state_for(queued));
+    }
+
+    /// Applies the most messages a tapped channel held at once since its counts were last
+    /// reported, sampled once per message sent meanwhile. Moves through the state the channel
+    /// reached, so `on_state_change` callbacks see it before the counts settle.
+    fn observe_peak(&mut self, peak: u64, sent: u64) {
+        if !self.tracks_queue() {
+            return;
+        }
+
+        self.sample_queue(peak, sent);
+        let state = self.state_for(peak);
+        if state != ChannelState::Active {
+            self.set_state(state);
+        }
+    }
+
+    fn sample_queue(&mut self, queued: u64, samples: u64) {
+        if let ChannelType::Bounded(cap) = self.channel_type {
+            if cap > 0 && !self.unknown_capacity {
+                for _ in 0..samples {
+                    self.utilization.observe(queued as f64 / cap as f64);
+                    self.queue_depths.observe(queued);
+                }
+            }
+        }
+    }
+
+    /// State of the channel while `queued` messages are waiting.
+    fn state_for(&self, queued: u64) -> ChannelState {
         let is_full = match self.channel_type {
+            ChannelType::Bounded(_) if self.unknown_capacity => false,
+            // Rendezvous channel, only full while a send is waiting for a receiver
+            ChannelType::Bounded(0) => queued > 0,
             ChannelType::Bounded(cap) => queued >= cap as u64,
             ChannelType::Oneshot => queued >= 1,
             ChannelType::Unbounded => false,
         };
+        let is_growing = self.channel_type == ChannelType::Unbounded
+            && GROWING_THRESHOLD.is_some_and(|threshold| queued > threshold);
 
         if is_full {
-            self.state = ChannelState::Full;
+            ChannelState::Full
+        } else if is_growing {
+            ChannelState::Growing
         } else {
-            self.state = ChannelState::Active;
+            ChannelState::Active
+        }
+    }
+
+    /// Moves the channel to `state`, running the `on_state_change` callbacks if it differs.
+    fn set_state(&mut self, state: ChannelState) {
+        let old = std::mem::replace(&mut self.state, state);
+        if old != state {
+            run_state_change_callbacks(self, old, state);
+        }
+    }
+
+    /// Counts `count` sent messages, of which only a single one carries a log or size. The
+    /// state is left to the caller to update, once receives reported alongside are counted.
+    fn on_sent(&mut self, count: u64, log: Option<String>, size: Option<u64>, timestamp: Instant) {
+        let first_index = self.sent_count + 1;
+        self.sent_count += count;
+        if !self.sender_only {
+            self.latency.on_sent(timestamp, count);
+        }
+        if let Some(size) = size {
+            let track_pending = !self.sender_only;
+            self.payload_bytes
+                .get_or_insert_with(PayloadBytes::default)
+                .on_sent(size, track_pending);
+        }
+
+        #[cfg(feature = "tracing")]
+        self.span.in_scope(|| {
+            tracing::trace!(
+                channel = %self.resolved_label(),
+                index = self.sent_count,
+                count,
+                "sent"
+            )
+        });
+
+        #[cfg(feature = "metrics")]
+        metrics_facade::record_sent(self, count);
+
+        let mut log = log;
+        let limit = get_log_limit();
+        for index in first_index..=self.sent_count {
+            if !self.logging.logs_index(index) {
+                continue;
+            }
+            if self.sent_logs.len() >= limit {
+                self.sent_logs.pop_front();
+            }
+            self.sent_logs
+                .push_back(LogEntry::new(index, timestamp, log.take()));
+        }
+    }
+
+    fn on_received(&mut self, count: u64, timestamp: Instant) {
+        let first_index = self.received_count + 1;
+        self.received_count += count;
+        self.latency.on_received(timestamp, count);
+        if let Some(payload_bytes) = &mut self.payload_bytes {
+            for _ in 0..count {
+                payload_bytes.on_received();
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        self.span.in_scope(|| {
+            tracing::trace!(
+                channel = %self.resolved_label(),
+                index = self.received_count,
+                count,
+                "received"
+            )
+        });
+
+        #[cfg(feature = "metrics")]
+        metrics_facade::record_received(self, count);
+
+        let limit = get_log_limit();
+        for index in first_index..=self.received_count {
+            if !self.logging.logs_index(index) {
+                continue;
+            }
+            if self.received_logs.len() >= limit {
+                self.received_logs.pop_front();
+            }
+            self.received_logs
+                .push_back(LogEntry::new(index, timestamp, None));
         }
     }
 }
 
+/// Formats a wall-clock time in UTC with millisecond precision, e.g. `2025-10-15T12:03:21.532Z`.
+pub(crate) fn format_rfc3339(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since the Unix epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
 /// Events sent to the background statistics collection thread.
 #[derive(Debug)]
 pub(crate) enum StatsEvent {
     Created {
         id: u64,
-        source: &'static str,
+        source: Arc<str>,
         display_label: Option<String>,
+        group: Option<String>,
+        meta: BTreeMap<String, String>,
         channel_type: ChannelType,
         type_name: &'static str,
         type_size: usize,
+        iter: u32,
+        sender_only: bool,
+        proxied: bool,
+        created_at: SystemTime,
+        backtrace: Option<String>,
     },
     MessageSent {
         id: u64,
         log: Option<String>,
+        /// Size of the message in bytes, for channels instrumented with `size = dynamic`.
+        size: Option<u64>,
+        timestamp: Instant,
+    },
+    /// Several messages were sent since the last report, e.g. by a tapped channel batching its
+    /// counts.
+    MessageSentBatch {
+        id: u64,
+        count: u64,
+        /// When the first of the messages was sent
         timestamp: Instant,
+        /// Most messages queued at once since the last report, if the sender knows.
+        peak_queued: Option<u64>,
     },
     MessageReceived {
         id: u64,
         timestamp: Instant,
     },
-    Closed {
+    /// Several messages were received at once, e.g. by `recv_many`.
+    #[allow(dead_code)]
+    MessageReceivedBatch {
+        id: u64,
+        count: u64,
+        timestamp: Instant,
+    },
+    /// A message was lost because forwarding it failed.
+    MessageDropped {
         id: u64,
     },
+    /// A `try_send` was rejected because the channel was full.
     #[allow(dead_code)]
-    Notified {
+    SendRejected {
         id: u64,
     },
-}
-
-type StatsState = (
-    CbSender<StatsEvent>,
+    /// A `recv_timeout` timed out before a message arrived.
+    RecvTimeout {
+        id: u64,
+    },
+    Closed {
+        id: u64,
+        reason: CloseReason,
+    },
+    #[allow(dead_code)]
+    Notified {
+        id: u64,
+    },
+    /// A oneshot sender was dropped without sending.
+    #[allow(dead_code)]
+    Cancelled {
+        id: u64,
+    },
+    /// A bounded channel was instrumented without its capacity, which can't be read from it.
+    CapacityUnknown {
+        id: u64,
+    },
+    /// A bounded channel holds a slot per sender on top of its capacity, which can't be counted.
+    QueueCapped {
+        id: u64,
+    },
+    /// A bounded channel now holds `capacity` messages, e.g. because it was recreated with a
+    /// different size behind the same handle.
+    #[allow(dead_code)]
+    CapacityChanged {
+        id: u64,
+        capacity: usize,
+    },
+    /// A tapped channel counted a full batch of messages, which the collector drains right away.
+    BatchFilled {
+        id: u64,
+    },
+    /// Drop closed, notified and cancelled channels and reset counts of live ones.
+    Reset,
+    /// Signals `done` once all earlier events have been applied.
+    Flush {
+        done: CbSender<()>,
+    },
+}
+
+impl StatsEvent {
+    /// Channel the event belongs to, `None` for events about all of them.
+    fn channel_id(&self) -> Option<u64> {
+        match self {
+            Self::Created { id, .. }
+            | Self::MessageSent { id, .. }
+            | Self::MessageSentBatch { id, .. }
+            | Self::MessageReceived { id, .. }
+            | Self::MessageReceivedBatch { id, .. }
+            | Self::MessageDropped { id }
+            | Self::SendRejected { id }
+            | Self::RecvTimeout { id }
+            | Self::Closed { id, .. }
+            | Self::Notified { id }
+            | Self::Cancelled { id }
+            | Self::CapacityUnknown { id }
+            | Self::QueueCapped { id }
+            | Self::CapacityChanged { id, .. }
+            | Self::BatchFilled { id } => Some(*id),
+            Self::Reset | Self::Flush { .. } => None,
+        }
+    }
+
+    /// Whether the event only counts messages, which needs no batched counts applied before it.
+    fn is_message_count(&self) -> bool {
+        matches!(
+            self,
+            Self::MessageSent { .. }
+                | Self::MessageSentBatch { .. }
+                | Self::MessageReceived { .. }
+                | Self::MessageReceivedBatch { .. }
+        )
+    }
+}
+
+type StatsState = (
+    CbSender<StatsEvent>,
     Arc<RwLock<HashMap<u64, ChannelStats>>>,
 );
 
 /// Global state for statistics collection.
 static STATS_STATE: OnceLock<StatsState> = OnceLock::new();
 
-static START_TIME: OnceLock<Instant> = OnceLock::new();
+/// Longest event queue seen by the collector, see `Diagnostics`.
+static PEAK_EVENT_QUEUE_LEN: AtomicU64 = AtomicU64::new(0);
+
+/// Set by `init_stats_state` before any channel is instrumented, so every event is timestamped
+/// after it.
+pub(crate) static START_TIME: OnceLock<Instant> = OnceLock::new();
+
+/// Wall-clock time matching `START_TIME`, so monotonic offsets can be shown as real times.
+static START_WALL_CLOCK: OnceLock<SystemTime> = OnceLock::new();
+
+/// Bumped by the collector for every event that changes a channel, used as `/metrics/diff` cursor.
+static CHANGE_VERSION: AtomicU64 = AtomicU64::new(0);
+
+/// Counts of every channel at the most recently handed out `/metrics/diff` cursors.
+static DIFF_CURSORS: LazyLock<Mutex<VecDeque<DiffCursor>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// Number of cursors `/metrics/diff` can compute deltas from, older ones get a full response.
+const MAX_DIFF_CURSORS: usize = 16;
 
 /// Global counter for assigning unique IDs to channels.
 pub(crate) static CHANNEL_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 
-const DEFAULT_LOG_LIMIT: usize = 50;
+/// Number of channels created so far at each source location.
+static SOURCE_ITERS: LazyLock<Mutex<HashMap<Arc<str>, u32>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Assigns the next iteration number for a source location, in channel creation order.
+pub(crate) fn next_iter(source: &Arc<str>) -> u32 {
+    let mut iters = SOURCE_ITERS.lock().unwrap_or_else(PoisonError::into_inner);
+    let iter = iters.entry(Arc::clone(source)).or_insert(0);
+    let current = *iter;
+    *iter += 1;
+    current
+}
 
-fn get_log_limit() -> usize {
-    std::env::var("CHANNELS_CONSOLE_LOG_LIMIT")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(DEFAULT_LOG_LIMIT)
+/// Forgets the iteration numbers of sources no channel in `stats` has anymore, so ids given
+/// to channels created in a loop don't pile up once their channels are purged.
+fn forget_sources(stats: &HashMap<u64, ChannelStats>, removed: HashSet<Arc<str>>) {
+    if removed.is_empty() {
+        return;
+    }
+    let in_use: HashSet<&str> = stats.values().map(|cs| &*cs.source).collect();
+    let mut iters = SOURCE_ITERS.lock().unwrap_or_else(PoisonError::into_inner);
+    for source in removed {
+        if !in_use.contains(&*source) {
+            iters.remove(&source);
+        }
+    }
 }
 
-/// Initialize the statistics collection system (called on first instrumented channel).
-/// Returns a reference to the global state.
-fn init_stats_state() -> &'static StatsState {
-    STATS_STATE.get_or_init(|| {
-        START_TIME.get_or_init(Instant::now);
+/// Options of the `instrument!` macro that are not passed through the `Instrument` traits.
+#[derive(Debug, Clone, Default)]
+struct InstrumentOptions {
+    group: Option<String>,
+    meta: BTreeMap<String, String>,
+    /// Replaces the source location as the channel's `source`, set by the `id` parameter.
+    source: Option<Arc<str>>,
+    capture_backtrace: bool,
+    size_hint: Option<usize>,
+    /// `fn(&T) -> u64` measuring each message, set by `size = dynamic`.
+    payload_sizer: Option<Arc<dyn Any + Send + Sync>>,
+    /// `fn(&T) -> Option<String>` formatting each message for the log, set by `log = json`.
+    log_formatter: Option<Arc<dyn Any + Send + Sync>>,
+    /// Only every `log_sample`th message is logged, 1 to log all of them.
+    log_sample: u64,
+}
 
-        let (tx, rx) = unbounded::<StatsEvent>();
-        let stats_map = Arc::new(RwLock::new(HashMap::<u64, ChannelStats>::new()));
-        let stats_map_clone = Arc::clone(&stats_map);
+thread_local! {
+    /// Options for channels instrumented on this thread, set by the macro parameters.
+    static CURRENT_OPTIONS: RefCell<InstrumentOptions> = const {
+        RefCell::new(InstrumentOptions {
+            group: None,
+            meta: BTreeMap::new(),
+            source: None,
+            capture_backtrace: false,
+            size_hint: None,
+            payload_sizer: None,
+            log_formatter: None,
+            log_sample: 1,
+        })
+    };
+}
 
-        std::thread::Builder::new()
-            .name("channel-stats-collector".into())
-            .spawn(move || {
-                while let Ok(event) = rx.recv() {
-                    let mut stats = stats_map_clone.write().unwrap();
-                    match event {
-                        StatsEvent::Created {
-                            id,
-                            source,
-                            display_label,
-                            channel_type,
-                            type_name,
-                            type_size,
-                        } => {
-                            // Count existing channels with the same source location
-                            let iter =
-                                stats.values().filter(|cs| cs.source == source).count() as u32;
-
-                            stats.insert(
-                                id,
-                                ChannelStats::new(
-                                    id,
-                                    source,
-                                    display_label,
-                                    channel_type,
-                                    type_name,
-                                    type_size,
-                                    iter,
-                                ),
-                            );
-                        }
-                        StatsEvent::MessageSent { id, log, timestamp } => {
-                            if let Some(channel_stats) = stats.get_mut(&id) {
-                                channel_stats.sent_count += 1;
-                                channel_stats.update_state();
-
-                                let limit = get_log_limit();
-                                if channel_stats.sent_logs.len() >= limit {
-                                    channel_stats.sent_logs.pop_front();
-                                }
-                                channel_stats.sent_logs.push_back(LogEntry::new(
-                                    channel_stats.sent_count,
-                                    timestamp,
-                                    log,
-                                ));
-                            }
-                        }
-                        StatsEvent::MessageReceived { id, timestamp } => {
-                            if let Some(channel_stats) = stats.get_mut(&id) {
-                                channel_stats.received_count += 1;
-                                channel_stats.update_state();
-
-                                let limit = get_log_limit();
-                                if channel_stats.received_logs.len() >= limit {
-                                    channel_stats.received_logs.pop_front();
-                                }
-                                channel_stats.received_logs.push_back(LogEntry::new(
-                                    channel_stats.received_count,
-                                    timestamp,
-                                    None,
-                                ));
-                            }
-                        }
-                        StatsEvent::Closed { id } => {
-                            if let Some(channel_stats) = stats.get_mut(&id) {
-                                channel_stats.state = ChannelState::Closed;
-                            }
-                        }
-                        StatsEvent::Notified { id } => {
-                            if let Some(channel_stats) = stats.get_mut(&id) {
-                                channel_stats.state = ChannelState::Notified;
-                            }
-                        }
-                    }
-                }
-            })
-            .expect("Failed to spawn channel-stats-collector thread");
+/// Group of the channel currently being instrumented on this thread.
+pub(crate) fn current_group() -> Option<String> {
+    CURRENT_OPTIONS.with(|options| options.borrow().group.clone())
+}
 
-        // Spawn the metrics HTTP server in the background
-        // Check environment variable for custom port, default to 6770
-        let port = std::env::var("CHANNELS_CONSOLE_METRICS_PORT")
-            .ok()
-            .and_then(|p| p.parse::<u16>().ok())
-            .unwrap_or(6770);
-        let addr = format!("127.0.0.1:{}", port);
+/// Metadata of the channel currently being instrumented on this thread.
+pub(crate) fn current_meta() -> BTreeMap<String, String> {
+    CURRENT_OPTIONS.with(|options| options.borrow().meta.clone())
+}
 
-        std::thread::spawn(move || {
-            start_metrics_server(&addr);
-        });
+/// Source of the channel currently being instrumented on this thread, the `id` parameter if
+/// given, the source location of the macro call otherwise.
+pub(crate) fn channel_source(location: &'static str) -> Arc<str> {
+    CURRENT_OPTIONS
+        .with(|options| options.borrow().source.clone())
+        .unwrap_or_else(|| Arc::from(location))
+}
 
-        (tx, stats_map)
-    })
+/// Whether every channel's creation backtrace is captured, see `CHANNELS_CONSOLE_CAPTURE_BACKTRACE`.
+static CAPTURE_ALL_BACKTRACES: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var("CHANNELS_CONSOLE_CAPTURE_BACKTRACE")
+        .is_ok_and(|value| matches!(value.as_str(), "1" | "true"))
+});
+
+/// Call stack instrumenting the current channel, if it was instrumented with
+/// `capture_backtrace = true` or `CHANNELS_CONSOLE_CAPTURE_BACKTRACE` is set.
+pub(crate) fn creation_backtrace() -> Option<String> {
+    let capture = *CAPTURE_ALL_BACKTRACES
+        || CURRENT_OPTIONS.with(|options| options.borrow().capture_backtrace);
+    capture.then(|| std::backtrace::Backtrace::force_capture().to_string())
 }
 
-fn resolve_label(id: &'static str, provided: Option<&str>, iter: u32) -> String {
-    let base_label = if let Some(l) = provided {
-        l.to_string()
-    } else if let Some(pos) = id.rfind(':') {
-        let (path, line_part) = id.split_at(pos);
-        let line = &line_part[1..];
-        format!("{}:{}", extract_filename(path), line)
-    } else {
-        extract_filename(id)
-    };
+/// Size reported per message of the channel currently being instrumented on this thread,
+/// the `size_hint` parameter if given, `size_of::<T>()` otherwise.
+pub(crate) fn message_size<T>() -> usize {
+    CURRENT_OPTIONS
+        .with(|options| options.borrow().size_hint)
+        .unwrap_or(std::mem::size_of::<T>())
+}
 
-    if iter > 0 {
-        format!("{}-{}", base_label, iter + 1)
-    } else {
-        base_label
-    }
+/// Measures each message of the channel currently being instrumented on this thread, if it was
+/// instrumented with `size = dynamic`.
+pub(crate) fn payload_sizer<T: 'static>() -> Option<fn(&T) -> u64> {
+    CURRENT_OPTIONS.with(|options| {
+        options
+            .borrow()
+            .payload_sizer
+            .as_ref()
+            .and_then(|sizer| sizer.downcast_ref::<fn(&T) -> u64>())
+            .copied()
+    })
 }
 
-fn extract_filename(path: &str) -> String {
-    let components: Vec<&str> = path.split('/').collect();
-    if components.len() >= 2 {
-        format!(
-            "{}/{}",
-            components[components.len() - 2],
-            components[components.len() - 1]
-        )
-    } else {
-        path.to_string()
-    }
+fn payload_size<T: ChannelPayloadSize>(msg: &T) -> u64 {
+    (std::mem::size_of::<T>() + msg.heap_bytes()) as u64
 }
 
-/// Format bytes into human-readable units (B, KB, MB, GB, TB).
-pub fn format_bytes(bytes: u64) -> String {
-    if bytes == 0 {
-        return "0 B".to_string();
-    }
+/// Formats each message of the channel currently being instrumented on this thread for the log,
+/// as JSON if it was instrumented with `log = json`, not at all otherwise.
+pub(crate) fn log_formatter<T: 'static>() -> fn(&T) -> Option<String> {
+    CURRENT_OPTIONS
+        .with(|options| {
+            options
+                .borrow()
+                .log_formatter
+                .as_ref()
+                .and_then(|formatter| formatter.downcast_ref::<fn(&T) -> Option<String>>())
+                .copied()
+        })
+        .unwrap_or(|_| None)
+}
 
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-    let mut size = bytes as f64;
-    let mut unit_idx = 0;
+fn json_message<T: Serialize>(msg: &T) -> Option<String> {
+    Some(serde_json::to_string(msg).unwrap_or_else(|e| format!("<failed to serialize: {}>", e)))
+}
 
-    while size >= 1024.0 && unit_idx < UNITS.len() - 1 {
-        size /= 1024.0;
-        unit_idx += 1;
+/// Whether `file` matches one of the comma-separated path `prefixes`, or any file if there are
+/// none. Evaluated at compile time, with the `CHANNELS_CONSOLE_INSTRUMENT_PATHS` of the crate
+/// expanding `instrument!`.
+///
+/// This function is not intended for direct use.
+#[doc(hidden)]
+pub const fn source_enabled(file: &str, prefixes: Option<&str>) -> bool {
+    let Some(prefixes) = prefixes else {
+        return true;
+    };
+    let file = file.as_bytes();
+    let prefixes = prefixes.as_bytes();
+
+    let mut start = 0;
+    while start <= prefixes.len() {
+        let mut end = start;
+        while end < prefixes.len() && prefixes[end] != b',' {
+            end += 1;
+        }
+        let (mut first, mut last) = (start, end);
+        while first < last && prefixes[first] == b' ' {
+            first += 1;
+        }
+        while last > first && prefixes[last - 1] == b' ' {
+            last -= 1;
+        }
+
+        let len = last - first;
+        if len > 0 && len <= file.len() {
+            let mut i = 0;
+            while i < len && same_path_byte(file[i], prefixes[first + i]) {
+                i += 1;
+            }
+            if i == len {
+                return true;
+            }
+        }
+        start = end + 1;
     }
+    false
+}
 
-    if unit_idx == 0 {
-        format!("{} {}", bytes, UNITS[unit_idx])
+/// Compares path bytes, treating `/` and `\` as the same separator.
+const fn same_path_byte(a: u8, b: u8) -> bool {
+    a == b || (matches!(a, b'/' | b'\\') && matches!(b, b'/' | b'\\'))
+}
+
+/// Instruments `channel` if `enabled`, returning it unchanged otherwise.
+///
+/// This function is not intended for direct use. It's called by `instrument!` and
+/// `instrument_sender!`.
+#[doc(hidden)]
+#[inline(always)]
+pub fn instrument_if<C, O: FromUninstrumented<C>>(
+    enabled: bool,
+    channel: C,
+    instrument: impl FnOnce(C) -> O,
+) -> O {
+    if enabled {
+        instrument(channel)
     } else {
-        format!("{:.1} {}", size, UNITS[unit_idx])
+        O::from_uninstrumented(channel)
     }
 }
 
-/// Trait for instrumenting channels.
+/// Conversion of a channel left uninstrumented by `CHANNELS_CONSOLE_INSTRUMENT_PATHS` into the
+/// type `instrument!` returns for it. That's the channel itself, except for channels wrapped in
+/// other types, like Tokio oneshots.
 ///
-/// This trait is not intended for direct use. Use the `instrument!` macro instead.
+/// This trait is not intended for direct use.
 #[doc(hidden)]
-pub trait Instrument {
-    type Output;
-    fn instrument(
-        self,
-        source: &'static str,
-        label: Option<String>,
-        capacity: Option<usize>,
-    ) -> Self::Output;
+pub trait FromUninstrumented<C> {
+    fn from_uninstrumented(channel: C) -> Self;
 }
 
-/// Trait for instrumenting channels with message logging.
+impl<C> FromUninstrumented<C> for C {
+    #[inline(always)]
+    fn from_uninstrumented(channel: C) -> Self {
+        channel
+    }
+}
+
+/// Whether channels instrumented in the calling file are enabled by
+/// `CHANNELS_CONSOLE_INSTRUMENT_PATHS`, see `source_enabled`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __source_enabled {
+    () => {{
+        const ENABLED: bool =
+            $crate::source_enabled(file!(), option_env!("CHANNELS_CONSOLE_INSTRUMENT_PATHS"));
+        ENABLED
+    }};
+}
+
+/// Applies macro parameters to channels instrumented while it is alive.
 ///
-/// This trait is not intended for direct use. Use the `instrument!` macro with `log = true` instead.
+/// This type is not intended for direct use. Use the `id`, `group`, `meta`, `capture_backtrace`,
+/// `size_hint`, `sample`, `size` and `log = json` parameters of `instrument!` instead.
 #[doc(hidden)]
-pub trait InstrumentLog {
-    type Output;
-    fn instrument_log(
-        self,
-        source: &'static str,
-        label: Option<String>,
-        capacity: Option<usize>,
-    ) -> Self::Output;
+pub struct InstrumentScope {
+    previous: InstrumentOptions,
 }
 
-cfg_if::cfg_if! {
-    if #[cfg(any(feature = "tokio", feature = "futures"))] {
-        use std::sync::LazyLock;
-        pub static RT: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
-            tokio::runtime::Builder::new_multi_thread()
-                .enable_time()
-                .build()
-                .unwrap()
+impl InstrumentScope {
+    pub fn group(group: String) -> Self {
+        Self::enter(|options| options.group = Some(group))
+    }
+
+    pub fn meta<K: ToString, V: ToString>(meta: impl IntoIterator<Item = (K, V)>) -> Self {
+        let meta = meta
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()));
+        Self::enter(|options| options.meta.extend(meta))
+    }
+
+    pub fn source_id(id: String) -> Self {
+        Self::enter(|options| options.source = Some(Arc::from(id)))
+    }
+
+    pub fn capture_backtrace() -> Self {
+        Self::enter(|options| options.capture_backtrace = true)
+    }
+
+    pub fn size_hint(size_hint: usize) -> Self {
+        Self::enter(|options| options.size_hint = Some(size_hint))
+    }
+
+    pub fn log_sample(sample: u64) -> Self {
+        Self::enter(|options| options.log_sample = sample.max(1))
+    }
+
+    /// Measures each message of `channel` with `ChannelPayloadSize`.
+    pub fn dynamic_size<C>(_channel: &C) -> Self
+    where
+        C: Instrument,
+        C::Payload: ChannelPayloadSize + 'static,
+    {
+        let sizer: fn(&C::Payload) -> u64 = payload_size::<C::Payload>;
+        Self::enter(|options| options.payload_sizer = Some(Arc::new(sizer)))
+    }
+
+    /// Logs each message of `channel` serialized as JSON.
+    pub fn json_log<C>(_channel: &C) -> Self
+    where
+        C: Instrument,
+        C::Payload: Serialize + 'static,
+    {
+        let formatter: fn(&C::Payload) -> Option<String> = json_message::<C::Payload>;
+        Self::enter(|options| options.log_formatter = Some(Arc::new(formatter)))
+    }
+
+    fn enter(update: impl FnOnce(&mut InstrumentOptions)) -> Self {
+        let previous = CURRENT_OPTIONS.with(|current| {
+            let mut current = current.borrow_mut();
+            let previous = current.clone();
+            update(&mut current);
+            previous
         });
+        Self { previous }
     }
 }
 
-/// Instrument a channel creation to wrap it with debugging proxies.
-/// Currently only supports bounded, unbounded and oneshot channels.
-///
-/// # Examples
-///
-/// ```
-/// use tokio::sync::mpsc;
-/// use channels_console::instrument;
-///
-/// #[tokio::main]
-/// async fn main() {
-///
-///    // Create channels normally
-///    let (tx, rx) = mpsc::channel::<String>(100);
-///
-///    // Instrument them only when the feature is enabled
-///    #[cfg(feature = "channels-console")]
-///    let (tx, rx) = channels_console::instrument!((tx, rx));
-///
-///    // The channel works exactly the same way
-///    tx.send("Hello".to_string()).await.unwrap();
-/// }
-/// ```
-///
-/// By default, channels are labeled with their file location and line number (e.g., `src/worker.rs:25`). You can provide custom labels for easier identification:
-///
-/// ```rust,no_run
-/// use tokio::sync::mpsc;
-/// use channels_console::instrument;
-/// let (tx, rx) = mpsc::channel::<String>(10);
-/// #[cfg(feature = "channels-console")]
-/// let (tx, rx) = channels_console::instrument!((tx, rx), label = "task-queue");
-/// ```
-///
-/// ## Capacity Parameter
-///
-/// **For `std::sync::mpsc` and `futures::channel::mpsc` bounded channels**, you **must** specify the `capacity` parameter
-/// because their APIs don't expose the capacity after creation:
-///
-/// ```rust,no_run
-/// use std::sync::mpsc;
-/// use channels_console::instrument;
-///
-/// // std::sync::mpsc::sync_channel - MUST specify capacity
-/// let (tx, rx) = mpsc::sync_channel::<String>(10);
-/// let (tx, rx) = instrument!((tx, rx), capacity = 10);
-///
-/// // With label
-/// let (tx, rx) = mpsc::sync_channel::<String>(10);
-/// let (tx, rx) = instrument!((tx, rx), label = "my-channel", capacity = 10);
-/// ```
-///
-/// Tokio channels don't require this because their capacity is accessible from the channel handles.
-///
-/// ## Message Logging
-///
-/// By default, instrumentation only tracks message timestamps. To capture the actual content of messages for debugging,
-/// enable logging with the `log = true` parameter (the message type must implement `std::fmt::Debug`):
-///
-/// ```rust,no_run
-/// use tokio::sync::mpsc;
-/// use channels_console::instrument;
-///
-/// // Enable message logging (requires Debug trait on the message type)
-/// let (tx, rx) = mpsc::channel::<String>(10);
-/// #[cfg(feature = "channels-console")]
-/// let (tx, rx) = channels_console::instrument!((tx, rx), log = true);
-///
+impl Drop for InstrumentScope {
+    fn drop(&mut self) {
+        let previous = std::mem::take(&mut self.previous);
+        CURRENT_OPTIONS.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Logging switch and sampling of a channel, shared between the wrappers and the collector.
+#[derive(Debug)]
+pub(crate) struct LogGate {
+    enabled: AtomicBool,
+    /// Only every `sample`th message is logged, set by the `sample` parameter.
+    sample: u64,
+    /// Messages seen by the forwarder, to pick the sampled ones.
+    seen: AtomicU64,
+}
+
+impl LogGate {
+    fn enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Whether the next sent message should be formatted for the log.
+    pub(crate) fn should_log(&self) -> bool {
+        if self.sample == 1 {
+            return self.enabled();
+        }
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed);
+        self.enabled() && seen.is_multiple_of(self.sample)
+    }
+
+    /// Whether the sent or received message with this 1-based index is kept in the logs.
+    fn logs_index(&self, index: u64) -> bool {
+        self.enabled() && index.saturating_sub(1).is_multiple_of(self.sample)
+    }
+}
+
+/// Per-channel log gates, shared between the wrappers and the collector.
+static LOG_GATES: LazyLock<Mutex<HashMap<u64, Arc<LogGate>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Log gate of a channel, registered enabled on first use with the sampling of the channel
+/// currently being instrumented on this thread.
+pub(crate) fn log_gate(id: u64) -> Arc<LogGate> {
+    let mut gates = LOG_GATES.lock().unwrap_or_else(PoisonError::into_inner);
+    Arc::clone(gates.entry(id).or_insert_with(|| {
+        Arc::new(LogGate {
+            enabled: AtomicBool::new(true),
+            sample: CURRENT_OPTIONS.with(|options| options.borrow().log_sample),
+            seen: AtomicU64::new(0),
+        })
+    }))
+}
+
+fn forget_log_gate(id: u64) {
+    LOG_GATES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&id);
+}
+
+static UNKNOWN_CAPACITY_WARNING: std::sync::Once = std::sync::Once::new();
+
+/// Flags a bounded channel instrumented without `capacity =`, warning about it once per process
+pub(crate) fn report_unknown_capacity(stats_tx: &CbSender<StatsEvent>, id: u64, source: &str) {
+    UNKNOWN_CAPACITY_WARNING.call_once(|| {
+        eprintln!(
+            "channels-console: the capacity of the bounded channel at {source} is unknown, pass `capacity = N` to instrument! to see how full it is. Channels without a capacity are shown as bounded[?]."
+        );
+    });
+    let _ = stats_tx.send(StatsEvent::CapacityUnknown { id });
+}
+
+/// Reports every message still held by a closed proxy as dropped. `try_recv` returns whether it
+/// removed a message.
+pub(crate) fn report_stranded(
+    stats_tx: &CbSender<StatsEvent>,
+    id: u64,
+    mut try_recv: impl FnMut() -> bool,
+) {
+    while try_recv() {
+        let _ = stats_tx.send(StatsEvent::MessageDropped { id });
+    }
+}
+
+/// Capacity of the outer proxy of a bounded channel whose forwarders hold up to `held` messages
+/// on their way to the consumer, so the channel is full at the original `capacity`. Rendezvous
+/// channels stay rendezvous, while channels of at most `held` keep a single slot in the proxy
+/// and so accept `held + 1` messages.
+pub(crate) fn proxy_capacity(capacity: usize, held: usize) -> usize {
+    if capacity == 0 {
+        return 0;
+    }
+    capacity.saturating_sub(held).max(1)
+}
+
+const DEFAULT_LOG_LIMIT: usize = 50;
+
+fn get_log_limit() -> usize {
+    std::env::var("CHANNELS_CONSOLE_LOG_LIMIT")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_LOG_LIMIT)
+}
+
+/// Queued messages above which an unbounded channel is reported as `Growing`, see
+/// `CHANNELS_CONSOLE_GROWING_THRESHOLD`. `None` (the default, or `0`) never reports it.
+static GROWING_THRESHOLD: LazyLock<Option<u64>> = LazyLock::new(|| {
+    std::env::var("CHANNELS_CONSOLE_GROWING_THRESHOLD")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|threshold| *threshold > 0)
+});
+
+/// How often closed channels are checked for eviction, at most.
+const MAX_PURGE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long closed channels are kept, `None` (the default, or `0`) keeps them forever.
+fn get_closed_ttl() -> Option<Duration> {
+    std::env::var("CHANNELS_CONSOLE_CLOSED_TTL_MS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis)
+}
+
+/// Adds sent or received messages to the counts of their channel, without updating its state.
+fn count_messages(stats: &mut HashMap<u64, ChannelStats>, event: StatsEvent) {
+    if let Some(id) = event.channel_id() {
+        mark_changed(stats, id);
+    }
+
+    match event {
+        StatsEvent::MessageSent {
+            id,
+            log,
+            size,
+            timestamp,
+        } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                channel_stats.on_sent(1, log, size, timestamp);
+            }
+        }
+        StatsEvent::MessageSentBatch {
+            id,
+            count,
+            timestamp,
+            peak_queued,
+        } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                channel_stats.on_sent(count, None, None, timestamp);
+                if let Some(peak) = peak_queued {
+                    channel_stats.observe_peak(peak, count);
+                }
+            }
+        }
+        StatsEvent::MessageReceived { id, timestamp } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                channel_stats.on_received(1, timestamp);
+            }
+        }
+        StatsEvent::MessageReceivedBatch {
+            id,
+            count,
+            timestamp,
+        } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                channel_stats.on_received(count, timestamp);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Applies counts batched by tapped channels, updating the state of each channel once all of its
+/// counts are in, so messages whose receives were still pending don't count as queued meanwhile.
+fn apply_batched_counts(
+    stats: &mut HashMap<u64, ChannelStats>,
+    mut events: Vec<StatsEvent>,
+    mut record: impl FnMut(&StatsEvent),
+) {
+    // Sends first, so receives are matched with the send timestamps of their messages
+    events.sort_by_key(|event| matches!(event, StatsEvent::MessageReceivedBatch { .. }));
+    let ids: HashSet<u64> = events.iter().filter_map(StatsEvent::channel_id).collect();
+    for event in events {
+        record(&event);
+        count_messages(stats, event);
+    }
+    for id in ids {
+        if let Some(channel_stats) = stats.get_mut(&id) {
+            channel_stats.update_state();
+        }
+    }
+}
+
+/// Applies an event to the statistics of all channels, on the collector thread or when
+/// replaying a recorded session.
+pub(crate) fn apply_event(stats: &mut HashMap<u64, ChannelStats>, event: StatsEvent) {
+    if event.is_message_count() {
+        let id = event.channel_id();
+        count_messages(stats, event);
+        if let Some(channel_stats) = id.and_then(|id| stats.get_mut(&id)) {
+            channel_stats.update_state();
+        }
+        return;
+    }
+
+    // New channels are marked once inserted
+    if let Some(id) = event.channel_id() {
+        mark_changed(stats, id);
+    }
+
+    match event {
+        StatsEvent::Created {
+            id,
+            source,
+            display_label,
+            group,
+            meta,
+            channel_type,
+            type_name,
+            type_size,
+            iter,
+            sender_only,
+            proxied,
+            created_at,
+            backtrace,
+        } => {
+            let mut channel_stats = ChannelStats::new(
+                id,
+                source,
+                display_label,
+                channel_type,
+                type_name,
+                type_size,
+                iter,
+            );
+            channel_stats.sender_only = sender_only;
+            channel_stats.proxied = proxied;
+            channel_stats.created_at = created_at;
+            channel_stats.backtrace = backtrace.map(Arc::from);
+            channel_stats.group = group;
+            channel_stats.meta = meta;
+
+            #[cfg(feature = "tracing")]
+            {
+                channel_stats.span = tracing::info_span!(
+                    "channel",
+                    id,
+                    label = %channel_stats.resolved_label(),
+                    channel_type = %channel_type,
+                );
+                channel_stats.span.in_scope(|| tracing::debug!("created"));
+            }
+
+            stats.insert(id, channel_stats);
+            mark_changed(stats, id);
+        }
+        StatsEvent::MessageSent { .. }
+        | StatsEvent::MessageSentBatch { .. }
+        | StatsEvent::MessageReceived { .. }
+        | StatsEvent::MessageReceivedBatch { .. } => {
+            unreachable!("message counts are applied by count_messages")
+        }
+        StatsEvent::MessageDropped { id } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                channel_stats.dropped_count += 1;
+
+                #[cfg(feature = "metrics")]
+                metrics_facade::record_dropped(channel_stats);
+
+                #[cfg(feature = "tracing")]
+                channel_stats.span.in_scope(|| {
+                    tracing::debug!(
+                        channel = %channel_stats.resolved_label(),
+                        dropped = channel_stats.dropped_count,
+                        "dropped"
+                    )
+                });
+            }
+        }
+        StatsEvent::SendRejected { id } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                channel_stats.try_send_full_count += 1;
+            }
+        }
+        StatsEvent::RecvTimeout { id } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                channel_stats.timeout_count += 1;
+            }
+        }
+        StatsEvent::Closed { id, reason } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                // The receive proxy reports the close after a cancel
+                if channel_stats.state == ChannelState::Cancelled {
+                    return;
+                }
+
+                #[cfg(feature = "tracing")]
+                if channel_stats.state != ChannelState::Closed {
+                    channel_stats.span.in_scope(|| {
+                        tracing::info!(
+                            channel = %channel_stats.resolved_label(),
+                            sent = channel_stats.sent_count,
+                            received = channel_stats.received_count,
+                            "closed"
+                        )
+                    });
+                }
+
+                if channel_stats.state != ChannelState::Closed {
+                    channel_stats.closed_at = Some(Instant::now());
+                    channel_stats.close_reason = Some(reason);
+                }
+                channel_stats.set_state(ChannelState::Closed);
+            }
+        }
+        StatsEvent::CapacityUnknown { id } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                channel_stats.unknown_capacity = true;
+                channel_stats.update_state();
+            }
+        }
+        StatsEvent::QueueCapped { id } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                channel_stats.queue_capped = true;
+            }
+        }
+        StatsEvent::CapacityChanged { id, capacity } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                // Unbounded and oneshot channels can't be resized
+                if !matches!(channel_stats.channel_type, ChannelType::Bounded(_)) {
+                    return;
+                }
+                channel_stats.channel_type = ChannelType::Bounded(capacity);
+                channel_stats.unknown_capacity = false;
+                channel_stats.update_state();
+
+                #[cfg(feature = "tracing")]
+                channel_stats.span.in_scope(|| {
+                    tracing::debug!(
+                        channel = %channel_stats.resolved_label(),
+                        capacity,
+                        "capacity changed"
+                    )
+                });
+            }
+        }
+        StatsEvent::Notified { id } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                // Send and receive proxies report independently, the
+                // receiver may already have been dropped
+                if channel_stats.state == ChannelState::Closed
+                    || channel_stats.state == ChannelState::Cancelled
+                {
+                    return;
+                }
+                channel_stats.set_state(ChannelState::Notified);
+
+                #[cfg(feature = "tracing")]
+                channel_stats.span.in_scope(|| {
+                    tracing::debug!(
+                        channel = %channel_stats.resolved_label(),
+                        "notified"
+                    )
+                });
+            }
+        }
+        StatsEvent::Cancelled { id } => {
+            if let Some(channel_stats) = stats.get_mut(&id) {
+                #[cfg(feature = "tracing")]
+                channel_stats.span.in_scope(|| {
+                    tracing::info!(
+                        channel = %channel_stats.resolved_label(),
+                        "cancelled"
+                    )
+                });
+
+                channel_stats.closed_at.get_or_insert_with(Instant::now);
+                channel_stats.set_state(ChannelState::Cancelled);
+            }
+        }
+        // Its counts are drained before the event is applied
+        StatsEvent::BatchFilled { .. } => {}
+        StatsEvent::Flush { done } => {
+            let _ = done.send(());
+        }
+        StatsEvent::Reset => {
+            let mut removed = HashSet::new();
+            stats.retain(|id, cs| {
+                let keep = cs.state != ChannelState::Closed
+                    && cs.state != ChannelState::Notified
+                    && cs.state != ChannelState::Cancelled;
+                if !keep {
+                    forget_log_gate(*id);
+                    removed.insert(Arc::clone(&cs.source));
+                }
+                keep
+            });
+            forget_sources(stats, removed);
+            let version = CHANGE_VERSION.fetch_add(1, Ordering::Relaxed) + 1;
+            for channel_stats in stats.values_mut() {
+                channel_stats.reset();
+                channel_stats.changed_version = version;
+            }
+        }
+    }
+}
+
+/// Stamps a channel with the next `CHANGE_VERSION`, so `/metrics/diff` reports it.
+fn mark_changed(stats: &mut HashMap<u64, ChannelStats>, id: u64) {
+    if let Some(channel_stats) = stats.get_mut(&id) {
+        channel_stats.changed_version = CHANGE_VERSION.fetch_add(1, Ordering::Relaxed) + 1;
+    }
+}
+
+fn purge_closed_channels(stats: &mut HashMap<u64, ChannelStats>, ttl: Duration) {
+    let mut removed = HashSet::new();
+    stats.retain(|id, cs| {
+        let keep = cs
+            .closed_at
+            .is_none_or(|closed_at| closed_at.elapsed() < ttl);
+        if !keep {
+            forget_log_gate(*id);
+            removed.insert(Arc::clone(&cs.source));
+        }
+        keep
+    });
+    forget_sources(stats, removed);
+}
+
+/// Logs a warning when the collector thread exits, whether it returned or panicked.
+struct CollectorExitGuard;
+
+impl Drop for CollectorExitGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!("channels-console: stats collector panicked, channel statistics are no longer updated");
+        } else {
+            eprintln!("channels-console: stats collector stopped, channel statistics are no longer updated");
+        }
+    }
+}
+
+/// Initialize the statistics collection system (called on first instrumented channel).
+/// Returns a reference to the global state.
+fn init_stats_state() -> &'static StatsState {
+    STATS_STATE.get_or_init(|| {
+        START_TIME.get_or_init(Instant::now);
+        START_WALL_CLOCK.get_or_init(SystemTime::now);
+
+        let (tx, rx) = unbounded::<StatsEvent>();
+        let stats_map = Arc::new(RwLock::new(HashMap::<u64, ChannelStats>::new()));
+        let stats_map_clone = Arc::clone(&stats_map);
+
+        std::thread::Builder::new()
+            .name("channel-stats-collector".into())
+            .spawn(move || {
+                let _exit_guard = CollectorExitGuard;
+                let closed_ttl = get_closed_ttl();
+                let purge_interval = closed_ttl.map(|ttl| ttl.min(MAX_PURGE_INTERVAL));
+                let mut last_purge = Instant::now();
+                let mut last_pending_flush = Instant::now();
+
+                loop {
+                    // Wake up periodically to evict expired channels and report the counts of
+                    // tapped channels even when idle
+                    let pending_flush_interval = wrappers::tap::has_tapped_channels()
+                        .then_some(wrappers::tap::PENDING_FLUSH_INTERVAL);
+                    let wake_interval = purge_interval
+                        .into_iter()
+                        .chain(pending_flush_interval)
+                        .min();
+                    let event = match wake_interval {
+                        Some(interval) => match rx.recv_timeout(interval) {
+                            Ok(event) => Some(event),
+                            Err(RecvTimeoutError::Timeout) => None,
+                            Err(RecvTimeoutError::Disconnected) => break,
+                        },
+                        None => match rx.recv() {
+                            Ok(event) => Some(event),
+                            Err(_) => break,
+                        },
+                    };
+
+                    // A panic while holding the lock must not stop collection for good
+                    let mut stats = stats_map_clone
+                        .write()
+                        .unwrap_or_else(PoisonError::into_inner);
+
+                    if let (Some(ttl), Some(interval)) = (closed_ttl, purge_interval) {
+                        if last_purge.elapsed() >= interval {
+                            purge_closed_channels(&mut stats, ttl);
+                            last_purge = Instant::now();
+                        }
+                    }
+
+                    let apply = |stats: &mut HashMap<u64, ChannelStats>, event: StatsEvent| {
+                        session::record(&event, rx.is_empty());
+                        apply_event(stats, event);
+                    };
+
+                    let record = |event: &StatsEvent| session::record(event, rx.is_empty());
+
+                    if let Some(interval) = pending_flush_interval {
+                        if last_pending_flush.elapsed() >= interval {
+                            let events = wrappers::tap::drain_pending(None, &stats);
+                            apply_batched_counts(&mut stats, events, record);
+                            last_pending_flush = Instant::now();
+                        }
+                    }
+
+                    let Some(event) = event else {
+                        continue;
+                    };
+                    PEAK_EVENT_QUEUE_LEN.fetch_max(rx.len() as u64 + 1, Ordering::Relaxed);
+
+                    // Counts batched by tapped channels go first, e.g. the messages received
+                    // before a close, or all of them before a flush is acknowledged
+                    if pending_flush_interval.is_some() && !event.is_message_count() {
+                        let events = wrappers::tap::drain_pending(event.channel_id(), &stats);
+                        apply_batched_counts(&mut stats, events, record);
+                    }
+                    apply(&mut stats, event);
+                }
+            })
+            .expect("Failed to spawn channel-stats-collector thread");
+
+        // Spawn the metrics HTTP server in the background
+        // Check environment variables for custom host and port, default to 127.0.0.1:6770
+        let host = std::env::var("CHANNELS_CONSOLE_METRICS_HOST")
+            .ok()
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| "127.0.0.1".to_string());
+        let port = std::env::var("CHANNELS_CONSOLE_METRICS_PORT")
+            .ok()
+            .and_then(|p| p.parse::<u16>().ok())
+            .unwrap_or(6770);
+        let token = std::env::var("CHANNELS_CONSOLE_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty());
+
+        let status = match bind_metrics_server(&host, port) {
+            Ok((server, addr)) => {
+                println!("Channel metrics server listening on http://{}", addr);
+                std::thread::spawn(move || {
+                    serve_metrics(server, token.as_deref());
+                });
+                MetricsServerStatus::Listening(addr)
+            }
+            Err(e) => {
+                eprintln!(
+                    "channels-console: {}. Customize the port using the CHANNELS_CONSOLE_METRICS_PORT environment variable.",
+                    e
+                );
+                MetricsServerStatus::Failed(e)
+            }
+        };
+        let _ = METRICS_SERVER_STATUS.set(status);
+
+        (tx, stats_map)
+    })
+}
+
+fn resolve_label(id: &str, provided: Option<&str>, iter: u32) -> String {
+    let base_label = if let Some(l) = provided {
+        l.to_string()
+    } else if let Some(pos) = id.rfind(':') {
+        let (path, line_part) = id.split_at(pos);
+        let line = &line_part[1..];
+        format!("{}:{}", extract_filename(path), line)
+    } else {
+        extract_filename(id)
+    };
+
+    if iter > 0 {
+        format!("{}-{}", base_label, iter + 1)
+    } else {
+        base_label
+    }
+}
+
+fn extract_filename(path: &str) -> String {
+    let components: Vec<&str> = path.split('/').collect();
+    if components.len() >= 2 {
+        format!(
+            "{}/{}",
+            components[components.len() - 2],
+            components[components.len() - 1]
+        )
+    } else {
+        path.to_string()
+    }
+}
+
+/// Format bytes of a channel's messages, or "—" if the message type is zero-sized,
+/// since byte counts of such channels are always 0 no matter how many messages flow.
+pub fn format_message_bytes(bytes: u64, type_size: usize) -> String {
+    ByteUnits::Binary.format_message(bytes, type_size)
+}
+
+/// Format bytes into human-readable units (B, KB, MB, GB, TB).
+pub fn format_bytes(bytes: u64) -> String {
+    format_scaled(bytes, 1024.0, &["B", "KB", "MB", "GB", "TB"])
+}
+
+/// Format bytes into decimal (SI) units (B, kB, MB, GB, TB), 1000-based.
+pub fn format_bytes_si(bytes: u64) -> String {
+    format_scaled(bytes, 1000.0, &["B", "kB", "MB", "GB", "TB"])
+}
+
+/// Format bytes as bits in decimal units (b, kb, Mb, Gb, Tb), as network tooling reports them.
+pub fn format_bits(bytes: u64) -> String {
+    format_scaled(
+        bytes.saturating_mul(8),
+        1000.0,
+        &["b", "kb", "Mb", "Gb", "Tb"],
+    )
+}
+
+fn format_scaled(value: u64, base: f64, units: &[&str]) -> String {
+    let mut size = value as f64;
+    let mut unit_idx = 0;
+
+    while size >= base && unit_idx < units.len() - 1 {
+        size /= base;
+        unit_idx += 1;
+    }
+
+    if unit_idx == 0 {
+        format!("{} {}", value, units[unit_idx])
+    } else {
+        format!("{:.1} {}", size, units[unit_idx])
+    }
+}
+
+/// Unit system used to format byte counts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "tui", derive(clap::ValueEnum))]
+pub enum ByteUnits {
+    /// 1024-based units, see `format_bytes`
+    #[default]
+    Binary,
+    /// 1000-based units, see `format_bytes_si`
+    Si,
+    /// 1000-based bit units, see `format_bits`
+    Bits,
+}
+
+impl ByteUnits {
+    pub fn format(self, bytes: u64) -> String {
+        match self {
+            ByteUnits::Binary => format_bytes(bytes),
+            ByteUnits::Si => format_bytes_si(bytes),
+            ByteUnits::Bits => format_bits(bytes),
+        }
+    }
+
+    /// Same as `format_message_bytes`, in these units.
+    pub fn format_message(self, bytes: u64, type_size: usize) -> String {
+        if type_size == 0 {
+            return "—".to_string();
+        }
+        self.format(bytes)
+    }
+}
+
+/// Heap memory owned by a message, for channels instrumented with `size = dynamic`.
+///
+/// The stack size of the message, `size_of::<T>()`, is added on top, so implementations only
+/// report what the message points to.
+///
+/// # Examples
+///
+/// ```
+/// use channels_console::ChannelPayloadSize;
+///
+/// struct Frame {
+///     header: [u8; 16],
+///     body: Vec<u8>,
+/// }
+///
+/// impl ChannelPayloadSize for Frame {
+///     fn heap_bytes(&self) -> usize {
+///         self.body.heap_bytes()
+///     }
+/// }
+/// ```
+pub trait ChannelPayloadSize {
+    /// Bytes allocated on the heap by this message.
+    fn heap_bytes(&self) -> usize;
+}
+
+impl<T> ChannelPayloadSize for Vec<T> {
+    fn heap_bytes(&self) -> usize {
+        self.capacity() * std::mem::size_of::<T>()
+    }
+}
+
+impl ChannelPayloadSize for String {
+    fn heap_bytes(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T> ChannelPayloadSize for Box<[T]> {
+    fn heap_bytes(&self) -> usize {
+        std::mem::size_of_val::<[T]>(self)
+    }
+}
+
+impl ChannelPayloadSize for Box<str> {
+    fn heap_bytes(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T: ChannelPayloadSize> ChannelPayloadSize for Option<T> {
+    fn heap_bytes(&self) -> usize {
+        self.as_ref().map_or(0, T::heap_bytes)
+    }
+}
+
+/// Trait for instrumenting channels.
+///
+/// This trait is not intended for direct use. Use the `instrument!` macro instead.
+#[doc(hidden)]
+pub trait Instrument {
+    type Output;
+    /// Type of the messages sent through the channel.
+    type Payload;
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output;
+}
+
+/// Trait for instrumenting channels with message logging.
+///
+/// This trait is not intended for direct use. Use the `instrument!` macro with `log = true` instead.
+#[doc(hidden)]
+pub trait InstrumentLog {
+    type Output;
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output;
+}
+
+/// Trait for instrumenting a standalone sender.
+///
+/// This trait is not intended for direct use. Use the `instrument_sender!` macro instead.
+#[doc(hidden)]
+pub trait InstrumentSender {
+    type Output;
+    fn instrument_sender(self, source: &'static str, label: Option<String>) -> Self::Output;
+}
+
+/// Trait for instrumenting a standalone sender with message logging.
+///
+/// This trait is not intended for direct use. Use the `instrument_sender!` macro with `log = true` instead.
+#[doc(hidden)]
+pub trait InstrumentSenderLog {
+    type Output;
+    fn instrument_sender_log(self, source: &'static str, label: Option<String>) -> Self::Output;
+}
+
+/// Trait for instrumenting a generic sink and stream pair. Without `enabled`, the pair is
+/// wrapped all the same but never reported.
+///
+/// This trait is not intended for direct use. Use the `instrument_stream!` macro instead.
+#[doc(hidden)]
+pub trait InstrumentStream {
+    type Output;
+    fn instrument_stream(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output;
+}
+
+/// Trait for instrumenting a generic sink and stream pair with message logging.
+///
+/// This trait is not intended for direct use. Use the `instrument_stream!` macro with `log = true` instead.
+#[doc(hidden)]
+pub trait InstrumentStreamLog {
+    type Output;
+    fn instrument_stream_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output;
+}
+
+/// Trait for instrumenting a channel whose forwarders run on the current `LocalSet`.
+///
+/// This trait is not intended for direct use. Use the `instrument_local!` macro instead.
+#[doc(hidden)]
+pub trait InstrumentLocal {
+    type Output;
+    fn instrument_local(self, source: &'static str, label: Option<String>) -> Self::Output;
+}
+
+/// Trait for instrumenting a channel whose forwarders run on the current `LocalSet`, with
+/// message logging.
+///
+/// This trait is not intended for direct use. Use the `instrument_local!` macro with
+/// `log = true` instead.
+#[doc(hidden)]
+pub trait InstrumentLocalLog {
+    type Output;
+    fn instrument_local_log(self, source: &'static str, label: Option<String>) -> Self::Output;
+}
+
+/// Trait for instrumenting a channel in place, without proxy channels. Without `enabled`, the
+/// channel is wrapped all the same but never counted or reported.
+///
+/// This trait is not intended for direct use. Use the `instrument!` macro with `mode = tap` instead.
+#[doc(hidden)]
+pub trait InstrumentTap {
+    type Output;
+    fn instrument_tap(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output;
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(any(feature = "tokio", feature = "futures"))] {
+        pub static RT: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_time()
+                .build()
+                .unwrap()
+        });
+
+        /// Spawns one of a channel's forwarders on `RT`. With the `tokio-console` feature and
+        /// `--cfg tokio_unstable` the task is named after its channel, e.g.
+        /// `channels-console #3 orders (send)`, so it can be told apart in tokio-console.
+        pub(crate) fn spawn_forwarder<F>(id: u64, name: &str, direction: &str, future: F)
+        where
+            F: std::future::Future<Output = ()> + Send + 'static,
+        {
+            cfg_if::cfg_if! {
+                if #[cfg(all(tokio_unstable, feature = "tokio-console"))] {
+                    tokio::task::Builder::new()
+                        .name(&format!("channels-console #{id} {name} ({direction})"))
+                        .spawn_on(future, RT.handle())
+                        .expect("Failed to spawn forwarder");
+                } else {
+                    let _ = (id, name, direction);
+                    RT.spawn(future);
+                }
+            }
+        }
+
+        /// Spawns one of a channel's forwarders on the current `LocalSet`, named like
+        /// `spawn_forwarder`. Used by `instrument_local!` for messages that aren't `Send`.
+        #[cfg(feature = "tokio")]
+        pub(crate) fn spawn_local_forwarder<F>(id: u64, name: &str, direction: &str, future: F)
+        where
+            F: std::future::Future<Output = ()> + 'static,
+        {
+            cfg_if::cfg_if! {
+                if #[cfg(all(tokio_unstable, feature = "tokio-console"))] {
+                    tokio::task::Builder::new()
+                        .name(&format!("channels-console #{id} {name} ({direction})"))
+                        .spawn_local(future)
+                        .expect("Failed to spawn forwarder");
+                } else {
+                    let _ = (id, name, direction);
+                    tokio::task::spawn_local(future);
+                }
+            }
+        }
+    }
+}
+
+/// Instrument a channel creation to wrap it with debugging proxies.
+/// Currently only supports bounded, unbounded and oneshot channels.
+///
+/// # Examples
+///
+/// ```
+/// use tokio::sync::mpsc;
+/// use channels_console::instrument;
+///
+/// #[tokio::main]
+/// async fn main() {
+///
+///    // Create channels normally
+///    let (tx, rx) = mpsc::channel::<String>(100);
+///
+///    // Instrument them only when the feature is enabled
+///    #[cfg(feature = "channels-console")]
+///    let (tx, rx) = channels_console::instrument!((tx, rx));
+///
+///    // The channel works exactly the same way
+///    tx.send("Hello".to_string()).await.unwrap();
+/// }
+/// ```
+///
+/// By default, channels are labeled with their file location and line number (e.g., `src/worker.rs:25`). You can provide custom labels for easier identification:
+///
+/// ```rust,no_run
+/// use tokio::sync::mpsc;
+/// use channels_console::instrument;
+/// let (tx, rx) = mpsc::channel::<String>(10);
+/// #[cfg(feature = "channels-console")]
+/// let (tx, rx) = channels_console::instrument!((tx, rx), label = "task-queue");
+/// ```
+///
+/// ## Capacity Parameter
+///
+/// **For `std::sync::mpsc` and `futures::channel::mpsc` bounded channels**, you **must** specify the `capacity` parameter
+/// because their APIs don't expose the capacity after creation. Without it, a warning is printed
+/// once and the channel is shown as `bounded[?]`, without usage or a `full` state:
+///
+/// ```rust,no_run
+/// use std::sync::mpsc;
+/// use channels_console::instrument;
+///
+/// // std::sync::mpsc::sync_channel - MUST specify capacity
+/// let (tx, rx) = mpsc::sync_channel::<String>(10);
+/// let (tx, rx) = instrument!((tx, rx), capacity = 10);
+///
+/// // With label
+/// let (tx, rx) = mpsc::sync_channel::<String>(10);
+/// let (tx, rx) = instrument!((tx, rx), label = "my-channel", capacity = 10);
+/// ```
+///
+/// Tokio channels don't require this because their capacity is accessible from the channel handles.
+///
+/// ## std Channels
+///
+/// The first 32 open std channels each get a forwarder thread, which blocks while the channel is
+/// empty, so idle channels cost no wakeups and add no latency. Forwarders of any further channels
+/// share a pool of two threads, which poll them since std receivers can't be waited on together:
+/// each pool thread wakes up about every millisecond, and a message sent after a quiet period can
+/// wait up to 1ms. Unbounded channels that sit idle most of the time can use `mode = tap`, which
+/// counts inline without forwarders.
+///
+/// ## Message Logging
+///
+/// By default, instrumentation only tracks message timestamps. To capture the actual content of messages for debugging,
+/// enable logging with the `log = true` parameter (the message type must implement `std::fmt::Debug`):
+///
+/// ```rust,no_run
+/// use tokio::sync::mpsc;
+/// use channels_console::instrument;
+///
+/// // Enable message logging (requires Debug trait on the message type)
+/// let (tx, rx) = mpsc::channel::<String>(10);
+/// #[cfg(feature = "channels-console")]
+/// let (tx, rx) = channels_console::instrument!((tx, rx), log = true);
+/// ```
+///
+/// For busy channels, `sample = N` formats and logs only every Nth message:
+///
+/// ```rust,no_run
+/// use tokio::sync::mpsc;
+///
+/// let (tx, rx) = mpsc::channel::<String>(10);
+/// #[cfg(feature = "channels-console")]
+/// let (tx, rx) = channels_console::instrument!((tx, rx), log = true, sample = 100);
+/// ```
+///
+/// When `Debug` output of a message type is hard to read, `log = json` logs messages serialized
+/// with `serde::Serialize` instead, which the TUI pretty-prints when inspecting them. `Debug`
+/// isn't required then, and `log = json` must come before the other parameters:
+///
+/// ```rust,no_run
+/// use serde::Serialize;
+/// use tokio::sync::mpsc;
+///
+/// #[derive(Serialize)]
+/// struct Order {
+///     id: u64,
+///     items: Vec<String>,
+/// }
+///
+/// let (tx, rx) = mpsc::channel::<Order>(10);
+/// #[cfg(feature = "channels-console")]
+/// let (tx, rx) = channels_console::instrument!((tx, rx), log = json, label = "orders");
+/// ```
+///
+/// ## Explicit Ids
+///
+/// Channels are identified by the source location of the macro call, so all channels created by
+/// the same line, e.g. in a loop or a factory function, share it and are told apart by a counter.
+/// The `id` parameter replaces the location with a string of your own, used as the `source` and,
+/// unless a `label` is given, as the label. A string literal is formatted, capturing variables
+/// like `format!`. It must come first:
+///
+/// ```rust,no_run
+/// use tokio::sync::mpsc;
+///
+/// for i in 0..4 {
+///     let (tx, rx) = mpsc::channel::<String>(10);
+///     #[cfg(feature = "channels-console")]
+///     let (tx, rx) = channels_console::instrument!((tx, rx), id = "ingest.worker.{i}");
+/// #   drop((tx, rx));
+/// }
+/// ```
+///
+/// ## Grouping
+///
+/// Channels belonging to the same subsystem can be grouped with the `group` parameter, which must
+/// come first. Grouped channels are listed together, under a header in the TUI:
+///
+/// ```rust,no_run
+/// use tokio::sync::mpsc;
+/// use channels_console::instrument;
+///
+/// let (tx, rx) = mpsc::channel::<String>(10);
+/// #[cfg(feature = "channels-console")]
+/// let (tx, rx) = channels_console::instrument!((tx, rx), group = "ingest", label = "raw-events");
+/// ```
+///
+/// ## Metadata
+///
+/// Arbitrary key/value tags can be attached with the `meta` parameter, which must come before the
+/// other parameters like `group`. They're reported in the `meta` field of `/metrics`, which can be
+/// filtered by them, e.g. `/metrics?meta.team=payments`, and the TUI groups channels by one with
+/// `--group-by team`:
+///
+/// ```rust,no_run
+/// use tokio::sync::mpsc;
+///
+/// let (tx, rx) = mpsc::channel::<String>(10);
+/// #[cfg(feature = "channels-console")]
+/// let (tx, rx) = channels_console::instrument!(
+///     (tx, rx),
+///     meta = [("team", "payments"), ("criticality", "high")],
+///     label = "charges"
+/// );
+/// ```
+///
+/// ## Message Size
+///
+/// Memory usage is estimated with `size_of` the message type, which is 0 for zero-sized signal
+/// types, the largest variant for enums, and doesn't include heap data owned by e.g. `String` or
+/// `Box`. The `size_hint` parameter overrides the size reported per message in bytes. Like
+/// `group`, it must come before the other parameters:
+///
+/// ```rust,no_run
+/// use tokio::sync::mpsc;
+/// use channels_console::instrument;
+///
+/// let (tx, rx) = mpsc::channel::<String>(10);
+/// #[cfg(feature = "channels-console")]
+/// let (tx, rx) = channels_console::instrument!((tx, rx), size_hint = 256, label = "payloads");
+/// ```
+///
+/// With `size = dynamic`, each message is measured as it's sent instead, adding the heap bytes
+/// reported by [`ChannelPayloadSize`] to its `size_of`:
+///
+/// ```rust,no_run
+/// use tokio::sync::mpsc;
+///
+/// let (tx, rx) = mpsc::channel::<Vec<u8>>(10);
+/// #[cfg(feature = "channels-console")]
+/// let (tx, rx) = channels_console::instrument!((tx, rx), size = dynamic, label = "buffers");
+/// ```
+///
+/// ## Creation Backtraces
+///
+/// `capture_backtrace = true` records where the channel was instrumented, served at
+/// `/channels/<id>/backtrace` and shown by pressing `b` in the TUI. Set
+/// `CHANNELS_CONSOLE_CAPTURE_BACKTRACE=1` to capture one for every channel.
 ///
+/// ## Selective Instrumentation
+///
+/// Setting `CHANNELS_CONSOLE_INSTRUMENT_PATHS` while compiling limits instrumentation to channels
+/// created in files under one of its comma-separated path prefixes, as reported by `file!()`,
+/// e.g. `CHANNELS_CONSOLE_INSTRUMENT_PATHS=src/net,src/db cargo run`. Elsewhere `instrument!`
+/// and `instrument_sender!` return the channel unchanged, decided at compile time. With
+/// `mode = tap` and `instrument_stream!` they return the usual wrappers, which then count and
+/// report nothing, so the channel's types don't depend on the paths.
+///
+/// ## Tap Mode
+///
+/// Instrumented channels normally forward messages through proxy channels, which changes task
+/// scheduling slightly. When that hides a timing-sensitive bug, `mode = tap` counts messages inline
+/// instead, so wakeups and backpressure are exactly those of the original channel. It returns
+/// wrappers like [`TappedSender`] that deref to the original handles. Message logging isn't
+/// available in this mode. Currently supports Tokio bounded, unbounded and oneshot channels, std
+/// unbounded channels, whose [`TappedStdReceiver`] also counts `recv_timeout` calls that time
+/// out, and sync and async kanal channels:
+///
+/// ```
+/// use tokio::sync::mpsc;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = mpsc::channel::<String>(10);
+///     #[cfg(feature = "channels-console")]
+///     let (tx, mut rx) = channels_console::instrument!((tx, rx), mode = tap, label = "exact");
+///
+///     tx.send("Hello".to_string()).await.unwrap();
+///     assert_eq!(rx.recv().await.unwrap(), "Hello");
+/// }
+/// ```
+///
+/// Tokio oneshots are tapped even without `mode = tap`, returning a [`TappedOneshotSender`] and
+/// [`TappedOneshotReceiver`], so per-request oneshots don't spawn forwarder tasks. They support
+/// message logging, and count the value as received once it's awaited. Unlike other channels,
+/// they don't keep their original types, so where the Tokio halves are needed, e.g. in a struct
+/// field, `into_inner()` returns them and stops counting. With the `disabled` feature the macro
+/// returns the original halves.
+#[cfg(not(feature = "disabled"))]
 #[macro_export]
 macro_rules! instrument {
+    ($expr:expr, id = $id:literal $(, $($rest:tt)+)?) => {{
+        let _id_scope = $crate::InstrumentScope::source_id(format!($id));
+        $crate::instrument!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, id = $id:expr $(, $($rest:tt)+)?) => {{
+        let _id_scope = $crate::InstrumentScope::source_id($id.to_string());
+        $crate::instrument!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, group = $group:expr $(, $($rest:tt)+)?) => {{
+        let _group_scope = $crate::InstrumentScope::group($group.to_string());
+        $crate::instrument!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, meta = $meta:expr $(, $($rest:tt)+)?) => {{
+        let _meta_scope = $crate::InstrumentScope::meta($meta);
+        $crate::instrument!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, size_hint = $size_hint:expr $(, $($rest:tt)+)?) => {{
+        let _size_hint_scope = $crate::InstrumentScope::size_hint($size_hint);
+        $crate::instrument!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, capture_backtrace = true $(, $($rest:tt)+)?) => {{
+        let _backtrace_scope = $crate::InstrumentScope::capture_backtrace();
+        $crate::instrument!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, sample = $sample:expr $(, $($rest:tt)+)?) => {{
+        let _sample_scope = $crate::InstrumentScope::log_sample($sample);
+        $crate::instrument!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, log = true, sample = $sample:expr $(, $($rest:tt)+)?) => {
+        $crate::instrument!($expr, sample = $sample, log = true $(, $($rest)+)?)
+    };
+
+    ($expr:expr, size = dynamic $(, $($rest:tt)+)?) => {{
+        let channel = $expr;
+        let _size_scope = $crate::InstrumentScope::dynamic_size(&channel);
+        $crate::instrument!(channel $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, log = json $(, $($rest:tt)+)?) => {{
+        let channel = $expr;
+        let _log_scope = $crate::InstrumentScope::json_log(&channel);
+        $crate::instrument!(channel $(, $($rest)+)?)
+    }};
+
+    ($expr:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::Instrument::instrument(channel, CHANNEL_ID, None, None)
+        })
+    }};
+
+    ($expr:expr, mode = tap) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentTap::instrument_tap($expr, CHANNEL_ID, None, $crate::__source_enabled!())
+    }};
+
+    ($expr:expr, mode = tap, label = $label:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentTap::instrument_tap(
+            $expr,
+            CHANNEL_ID,
+            Some($label.to_string()),
+            $crate::__source_enabled!(),
+        )
+    }};
+
+    ($expr:expr, label = $label:expr, mode = tap) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::InstrumentTap::instrument_tap(
+            $expr,
+            CHANNEL_ID,
+            Some($label.to_string()),
+            $crate::__source_enabled!(),
+        )
+    }};
+
+    ($expr:expr, label = $label:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::Instrument::instrument(channel, CHANNEL_ID, Some($label.to_string()), None)
+        })
+    }};
+
+    ($expr:expr, capacity = $capacity:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::Instrument::instrument(channel, CHANNEL_ID, None, Some($capacity))
+        })
+    }};
+
+    ($expr:expr, label = $label:expr, capacity = $capacity:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::Instrument::instrument(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                Some($capacity),
+            )
+        })
+    }};
+
+    ($expr:expr, capacity = $capacity:expr, label = $label:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::Instrument::instrument(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                Some($capacity),
+            )
+        })
+    }};
+
+    // Variants with log = true
+    ($expr:expr, log = true) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(channel, CHANNEL_ID, None, None)
+        })
+    }};
+
+    ($expr:expr, label = $label:expr, log = true) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                None,
+            )
+        })
+    }};
+
+    ($expr:expr, log = true, label = $label:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                None,
+            )
+        })
+    }};
+
+    ($expr:expr, capacity = $capacity:expr, log = true) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(channel, CHANNEL_ID, None, Some($capacity))
+        })
+    }};
+
+    ($expr:expr, log = true, capacity = $capacity:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(channel, CHANNEL_ID, None, Some($capacity))
+        })
+    }};
+
+    ($expr:expr, label = $label:expr, capacity = $capacity:expr, log = true) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                Some($capacity),
+            )
+        })
+    }};
+
+    ($expr:expr, label = $label:expr, log = true, capacity = $capacity:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                Some($capacity),
+            )
+        })
+    }};
+
+    ($expr:expr, capacity = $capacity:expr, label = $label:expr, log = true) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                Some($capacity),
+            )
+        })
+    }};
+
+    ($expr:expr, capacity = $capacity:expr, log = true, label = $label:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                Some($capacity),
+            )
+        })
+    }};
+
+    ($expr:expr, log = true, label = $label:expr, capacity = $capacity:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                Some($capacity),
+            )
+        })
+    }};
+
+    ($expr:expr, log = true, capacity = $capacity:expr, label = $label:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        const _: usize = $capacity;
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLog::instrument_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+                Some($capacity),
+            )
+        })
+    }};
+}
+
+/// Instrument an existing sender, e.g. a clone handed out before the channel could be wrapped
+/// with `instrument!`. Currently supports Tokio bounded and unbounded senders.
+///
+/// Each instrumented sender is reported as a separate, send-only channel: messages sent through it
+/// are counted and logged, but since the matching receiver is not instrumented, received counts
+/// and queue size are not tracked. Senders cloned from the returned sender share its statistics.
+///
+/// # Examples
+///
+/// ```
+/// use tokio::sync::mpsc;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = mpsc::channel::<String>(10);
+///     let late_tx = tx.clone();
+///
+///     #[cfg(feature = "channels-console")]
+///     let late_tx = channels_console::instrument_sender!(late_tx, label = "late-producer");
+///
+///     late_tx.send("Hello".to_string()).await.unwrap();
+///     assert_eq!(rx.recv().await.unwrap(), "Hello");
+/// }
+/// ```
+///
+/// Message logging, `group`, `meta` and `size_hint` work the same as for `instrument!`.
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! instrument_sender {
+    ($expr:expr, id = $id:literal $(, $($rest:tt)+)?) => {{
+        let _id_scope = $crate::InstrumentScope::source_id(format!($id));
+        $crate::instrument_sender!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, id = $id:expr $(, $($rest:tt)+)?) => {{
+        let _id_scope = $crate::InstrumentScope::source_id($id.to_string());
+        $crate::instrument_sender!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, group = $group:expr $(, $($rest:tt)+)?) => {{
+        let _group_scope = $crate::InstrumentScope::group($group.to_string());
+        $crate::instrument_sender!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, meta = $meta:expr $(, $($rest:tt)+)?) => {{
+        let _meta_scope = $crate::InstrumentScope::meta($meta);
+        $crate::instrument_sender!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, size_hint = $size_hint:expr $(, $($rest:tt)+)?) => {{
+        let _size_hint_scope = $crate::InstrumentScope::size_hint($size_hint);
+        $crate::instrument_sender!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, capture_backtrace = true $(, $($rest:tt)+)?) => {{
+        let _backtrace_scope = $crate::InstrumentScope::capture_backtrace();
+        $crate::instrument_sender!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, sample = $sample:expr $(, $($rest:tt)+)?) => {{
+        let _sample_scope = $crate::InstrumentScope::log_sample($sample);
+        $crate::instrument_sender!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, log = true, sample = $sample:expr $(, $($rest:tt)+)?) => {
+        $crate::instrument_sender!($expr, sample = $sample, log = true $(, $($rest)+)?)
+    };
+
     ($expr:expr) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::Instrument::instrument($expr, CHANNEL_ID, None, None)
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentSender::instrument_sender(channel, CHANNEL_ID, None)
+        })
+    }};
+
+    ($expr:expr, label = $label:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentSender::instrument_sender(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+            )
+        })
+    }};
+
+    ($expr:expr, log = true) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentSenderLog::instrument_sender_log(channel, CHANNEL_ID, None)
+        })
+    }};
+
+    ($expr:expr, label = $label:expr, log = true) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentSenderLog::instrument_sender_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+            )
+        })
+    }};
+
+    ($expr:expr, log = true, label = $label:expr) => {{
+        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentSenderLog::instrument_sender_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+            )
+        })
+    }};
+}
+
+/// Instrument a Tokio `mpsc` channel whose messages aren't `Send`, e.g. because they hold an
+/// `Rc`, on a single-threaded runtime. Supports bounded and unbounded channels.
+///
+/// `instrument!` forwards messages on a shared multi-threaded runtime, which requires them to
+/// be `Send`. This macro spawns the forwarders with `tokio::task::spawn_local` instead, so it
+/// must be called from within a `LocalSet`, and the channel only moves messages while the
+/// `LocalSet` is being driven. Counts, queue sizes and logs work the same as for `instrument!`.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use tokio::sync::mpsc;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let local = tokio::task::LocalSet::new();
+///     local
+///         .run_until(async {
+///             let (tx, mut rx) = mpsc::channel::<Rc<String>>(10);
+///             #[cfg(feature = "channels-console")]
+///             let (tx, mut rx) = channels_console::instrument_local!((tx, rx), label = "local");
+///
+///             tx.send(Rc::new("Hello".to_string())).await.unwrap();
+///             assert_eq!(*rx.recv().await.unwrap(), "Hello");
+///         })
+///         .await;
+/// }
+/// ```
+///
+/// Message logging, `group`, `meta` and `size_hint` work the same as for `instrument!`.
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! instrument_local {
+    ($expr:expr, id = $id:literal $(, $($rest:tt)+)?) => {{
+        let _id_scope = $crate::InstrumentScope::source_id(format!($id));
+        $crate::instrument_local!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, id = $id:expr $(, $($rest:tt)+)?) => {{
+        let _id_scope = $crate::InstrumentScope::source_id($id.to_string());
+        $crate::instrument_local!($expr $(, $($rest)+)?)
     }};
 
-    ($expr:expr, label = $label:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::Instrument::instrument($expr, CHANNEL_ID, Some($label.to_string()), None)
+    ($expr:expr, group = $group:expr $(, $($rest:tt)+)?) => {{
+        let _group_scope = $crate::InstrumentScope::group($group.to_string());
+        $crate::instrument_local!($expr $(, $($rest)+)?)
     }};
 
-    ($expr:expr, capacity = $capacity:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::Instrument::instrument($expr, CHANNEL_ID, None, Some($capacity))
+    ($expr:expr, meta = $meta:expr $(, $($rest:tt)+)?) => {{
+        let _meta_scope = $crate::InstrumentScope::meta($meta);
+        $crate::instrument_local!($expr $(, $($rest)+)?)
     }};
 
-    ($expr:expr, label = $label:expr, capacity = $capacity:expr) => {{
+    ($expr:expr, size_hint = $size_hint:expr $(, $($rest:tt)+)?) => {{
+        let _size_hint_scope = $crate::InstrumentScope::size_hint($size_hint);
+        $crate::instrument_local!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, capture_backtrace = true $(, $($rest:tt)+)?) => {{
+        let _backtrace_scope = $crate::InstrumentScope::capture_backtrace();
+        $crate::instrument_local!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, sample = $sample:expr $(, $($rest:tt)+)?) => {{
+        let _sample_scope = $crate::InstrumentScope::log_sample($sample);
+        $crate::instrument_local!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, log = true, sample = $sample:expr $(, $($rest:tt)+)?) => {
+        $crate::instrument_local!($expr, sample = $sample, log = true $(, $($rest)+)?)
+    };
+
+    ($expr:expr) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::Instrument::instrument($expr, CHANNEL_ID, Some($label.to_string()), Some($capacity))
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLocal::instrument_local(channel, CHANNEL_ID, None)
+        })
     }};
 
-    ($expr:expr, capacity = $capacity:expr, label = $label:expr) => {{
+    ($expr:expr, label = $label:expr) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::Instrument::instrument($expr, CHANNEL_ID, Some($label.to_string()), Some($capacity))
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLocal::instrument_local(channel, CHANNEL_ID, Some($label.to_string()))
+        })
     }};
 
-    // Variants with log = true
     ($expr:expr, log = true) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, None, None)
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLocalLog::instrument_local_log(channel, CHANNEL_ID, None)
+        })
     }};
 
     ($expr:expr, label = $label:expr, log = true) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, Some($label.to_string()), None)
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLocalLog::instrument_local_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+            )
+        })
     }};
 
     ($expr:expr, log = true, label = $label:expr) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, Some($label.to_string()), None)
+        $crate::instrument_if($crate::__source_enabled!(), $expr, |channel| {
+            $crate::InstrumentLocalLog::instrument_local_log(
+                channel,
+                CHANNEL_ID,
+                Some($label.to_string()),
+            )
+        })
     }};
+}
 
-    ($expr:expr, capacity = $capacity:expr, log = true) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, None, Some($capacity))
+/// Instrument a generic `Sink` and `Stream` pair, e.g. adapters exposing `impl Sink<T>` and
+/// `impl Stream<Item = T>` rather than concrete channel types.
+///
+/// The pair is wrapped with counting adapters: a message is counted as sent once the inner sink
+/// accepts it, and as received when the inner stream yields it. The capacity of a generic sink is
+/// unknown, so the pair is reported as unbounded and never as full. It's reported as closed once
+/// the stream ends or both halves are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use futures_util::{SinkExt, StreamExt};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, rx) = futures_channel::mpsc::unbounded::<u32>();
+///     let sink = tx.with(|n: u32| async move { Ok::<_, futures_channel::mpsc::SendError>(n * 2) });
+///     let stream = rx.map(|n| n + 1);
+///
+///     #[cfg(feature = "channels-console")]
+///     let (sink, stream) = channels_console::instrument_stream!((sink, stream), label = "doubler");
+///
+///     let mut sink = Box::pin(sink);
+///     let mut stream = Box::pin(stream);
+///     sink.send(1).await.unwrap();
+///     assert_eq!(stream.next().await, Some(3));
+/// }
+/// ```
+///
+/// Message logging, `group`, `meta` and `size_hint` work the same as for `instrument!`.
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! instrument_stream {
+    ($expr:expr, id = $id:literal $(, $($rest:tt)+)?) => {{
+        let _id_scope = $crate::InstrumentScope::source_id(format!($id));
+        $crate::instrument_stream!($expr $(, $($rest)+)?)
     }};
 
-    ($expr:expr, log = true, capacity = $capacity:expr) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log($expr, CHANNEL_ID, None, Some($capacity))
+    ($expr:expr, id = $id:expr $(, $($rest:tt)+)?) => {{
+        let _id_scope = $crate::InstrumentScope::source_id($id.to_string());
+        $crate::instrument_stream!($expr $(, $($rest)+)?)
     }};
 
-    ($expr:expr, label = $label:expr, capacity = $capacity:expr, log = true) => {{
-        const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
-            $expr,
-            CHANNEL_ID,
-            Some($label.to_string()),
-            Some($capacity),
-        )
+    ($expr:expr, group = $group:expr $(, $($rest:tt)+)?) => {{
+        let _group_scope = $crate::InstrumentScope::group($group.to_string());
+        $crate::instrument_stream!($expr $(, $($rest)+)?)
     }};
 
-    ($expr:expr, label = $label:expr, log = true, capacity = $capacity:expr) => {{
+    ($expr:expr, meta = $meta:expr $(, $($rest:tt)+)?) => {{
+        let _meta_scope = $crate::InstrumentScope::meta($meta);
+        $crate::instrument_stream!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, size_hint = $size_hint:expr $(, $($rest:tt)+)?) => {{
+        let _size_hint_scope = $crate::InstrumentScope::size_hint($size_hint);
+        $crate::instrument_stream!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, capture_backtrace = true $(, $($rest:tt)+)?) => {{
+        let _backtrace_scope = $crate::InstrumentScope::capture_backtrace();
+        $crate::instrument_stream!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, sample = $sample:expr $(, $($rest:tt)+)?) => {{
+        let _sample_scope = $crate::InstrumentScope::log_sample($sample);
+        $crate::instrument_stream!($expr $(, $($rest)+)?)
+    }};
+
+    ($expr:expr, log = true, sample = $sample:expr $(, $($rest:tt)+)?) => {
+        $crate::instrument_stream!($expr, sample = $sample, log = true $(, $($rest)+)?)
+    };
+
+    ($expr:expr) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
+        $crate::InstrumentStream::instrument_stream(
             $expr,
             CHANNEL_ID,
-            Some($label.to_string()),
-            Some($capacity),
+            None,
+            $crate::__source_enabled!(),
         )
     }};
 
-    ($expr:expr, capacity = $capacity:expr, label = $label:expr, log = true) => {{
+    ($expr:expr, label = $label:expr) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
+        $crate::InstrumentStream::instrument_stream(
             $expr,
             CHANNEL_ID,
             Some($label.to_string()),
-            Some($capacity),
+            $crate::__source_enabled!(),
         )
     }};
 
-    ($expr:expr, capacity = $capacity:expr, log = true, label = $label:expr) => {{
+    ($expr:expr, log = true) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
+        $crate::InstrumentStreamLog::instrument_stream_log(
             $expr,
             CHANNEL_ID,
-            Some($label.to_string()),
-            Some($capacity),
+            None,
+            $crate::__source_enabled!(),
         )
     }};
 
-    ($expr:expr, log = true, label = $label:expr, capacity = $capacity:expr) => {{
+    ($expr:expr, label = $label:expr, log = true) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
+        $crate::InstrumentStreamLog::instrument_stream_log(
             $expr,
             CHANNEL_ID,
             Some($label.to_string()),
-            Some($capacity),
+            $crate::__source_enabled!(),
         )
     }};
 
-    ($expr:expr, log = true, capacity = $capacity:expr, label = $label:expr) => {{
+    ($expr:expr, log = true, label = $label:expr) => {{
         const CHANNEL_ID: &'static str = concat!(file!(), ":", line!());
-        const _: usize = $capacity;
-        $crate::InstrumentLog::instrument_log(
+        $crate::InstrumentStreamLog::instrument_stream_log(
             $expr,
             CHANNEL_ID,
             Some($label.to_string()),
-            Some($capacity),
+            $crate::__source_enabled!(),
         )
     }};
 }
 
+/// No-op `instrument!` of the `disabled` feature, returns the channel unchanged.
+///
+/// Arguments like `label` are type checked but never evaluated.
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! instrument {
+    ($expr:expr $(, $($args:tt)*)?) => {{
+        $crate::__disabled_args!($($($args)*)?);
+        $expr
+    }};
+}
+
+/// No-op `instrument_sender!` of the `disabled` feature, returns the sender unchanged.
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! instrument_sender {
+    ($expr:expr $(, $($args:tt)*)?) => {{
+        $crate::__disabled_args!($($($args)*)?);
+        $expr
+    }};
+}
+
+/// No-op `instrument_local!` of the `disabled` feature, returns the channel unchanged.
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! instrument_local {
+    ($expr:expr $(, $($args:tt)*)?) => {{
+        $crate::__disabled_args!($($($args)*)?);
+        $expr
+    }};
+}
+
+/// No-op `instrument_stream!` of the `disabled` feature, returns the pair unchanged.
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! instrument_stream {
+    ($expr:expr $(, $($args:tt)*)?) => {{
+        $crate::__disabled_args!($($($args)*)?);
+        $expr
+    }};
+}
+
+/// References the arguments of the no-op macros in a closure that's never called,
+/// so variables only used for labels don't trigger unused warnings.
+#[cfg(feature = "disabled")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __disabled_args {
+    () => {};
+
+    (mode = $mode:ident $(, $($rest:tt)*)?) => {
+        $crate::__disabled_args!($($($rest)*)?)
+    };
+
+    (size = $size:ident $(, $($rest:tt)*)?) => {
+        $crate::__disabled_args!($($($rest)*)?)
+    };
+
+    (log = json $(, $($rest:tt)*)?) => {
+        $crate::__disabled_args!($($($rest)*)?)
+    };
+
+    (id = $id:literal $(, $($rest:tt)*)?) => {
+        let _ = || {
+            let _ = format!($id);
+        };
+        $crate::__disabled_args!($($($rest)*)?)
+    };
+
+    ($key:ident = $value:expr $(, $($rest:tt)*)?) => {
+        let _ = || {
+            let _ = &$value;
+        };
+        $crate::__disabled_args!($($($rest)*)?)
+    };
+}
+
 fn get_channel_stats() -> HashMap<u64, ChannelStats> {
     if let Some((_, stats_map)) = STATS_STATE.get() {
-        stats_map.read().unwrap().clone()
+        stats_map
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
     } else {
         HashMap::new()
     }
 }
 
 /// Compare two ChannelStats for sorting.
-/// Custom labels come first (sorted alphabetically), then auto-generated labels (sorted by source and iter).
-fn compare_channel_stats(a: &ChannelStats, b: &ChannelStats) -> std::cmp::Ordering {
+/// Grouped channels come first (sorted by group), then ungrouped ones. Within a group, custom labels
+/// come first (sorted alphabetically), then auto-generated labels (sorted by source and iter).
+pub(crate) fn compare_channel_stats(a: &ChannelStats, b: &ChannelStats) -> std::cmp::Ordering {
+    compare_groups(a.group.as_deref(), b.group.as_deref()).then_with(|| compare_labels(a, b))
+}
+
+fn compare_groups(a: Option<&str>, b: Option<&str>) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    }
+}
+
+fn compare_labels(a: &ChannelStats, b: &ChannelStats) -> std::cmp::Ordering {
     match (a.label.is_some(), b.label.is_some()) {
         (true, false) => std::cmp::Ordering::Less,
         (false, true) => std::cmp::Ordering::Greater,
@@ -731,22 +3514,269 @@ fn compare_channel_stats(a: &ChannelStats, b: &ChannelStats) -> std::cmp::Orderi
             .unwrap()
             .cmp(b.label.as_ref().unwrap())
             .then_with(|| a.iter.cmp(&b.iter)),
-        (false, false) => a.source.cmp(b.source).then_with(|| a.iter.cmp(&b.iter)),
+        (false, false) => a.source.cmp(&b.source).then_with(|| a.iter.cmp(&b.iter)),
+    }
+}
+
+/// Waits until the collector has applied every event emitted so far.
+///
+/// Statistics are collected on a background thread, so a message that was just sent may not be
+/// counted yet. After `flush` returns, it is, as far as the event has been emitted: tapped channels
+/// emit events inline, while proxied channels emit them once their forwarders have moved the
+/// message, which may still be pending. Returns immediately if no channel was instrumented yet.
+///
+/// # Examples
+///
+/// ```
+/// use tokio::sync::mpsc;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, rx) = mpsc::channel::<u32>(10);
+///     #[cfg(feature = "channels-console")]
+///     let (tx, rx) = channels_console::instrument!((tx, rx), mode = tap, label = "jobs");
+///
+///     tx.send(1).await.unwrap();
+///
+///     // With the `disabled` feature, `instrument!` leaves the channel as is
+///     #[cfg(all(feature = "channels-console", not(feature = "disabled")))]
+///     {
+///         // `snapshot` flushes before reading
+///         let stats = channels_console::snapshot();
+///         assert_eq!(stats[0].sent_count, 1);
+///     }
+/// #   drop(rx);
+/// }
+/// ```
+pub fn flush() {
+    let Some((stats_tx, _)) = STATS_STATE.get() else {
+        return;
+    };
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    if stats_tx.send(StatsEvent::Flush { done: done_tx }).is_ok() {
+        // Errors once the collector has stopped, dropping the event
+        let _ = done_rx.recv();
+    }
+}
+
+/// How long counts must stay unchanged for `flush_until_idle` to consider forwarders done.
+const IDLE_PERIOD: Duration = Duration::from_millis(20);
+
+/// Flushes until the counts stop changing, so forwarders of proxied channels get to report the
+/// messages they're still moving. Gives up once `timeout` has passed.
+pub(crate) fn flush_until_idle(timeout: Duration) {
+    let Some((stats_tx, _)) = STATS_STATE.get() else {
+        return;
+    };
+    let deadline = Instant::now() + timeout;
+    let counts = || -> Vec<(u64, u64, u64, u64, ChannelState)> {
+        get_serializable_stats()
+            .iter()
+            .map(|s| {
+                (
+                    s.id,
+                    s.sent_count,
+                    s.received_count,
+                    s.dropped_count,
+                    s.state,
+                )
+            })
+            .collect()
+    };
+
+    let mut previous = None;
+    loop {
+        let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+        if stats_tx.send(StatsEvent::Flush { done: done_tx }).is_err()
+            || done_rx.recv_deadline(deadline).is_err()
+        {
+            return;
+        }
+
+        let current = counts();
+        if previous.as_ref() == Some(&current) {
+            return;
+        }
+        previous = Some(current);
+
+        let now = Instant::now();
+        if now >= deadline {
+            return;
+        }
+        std::thread::sleep(IDLE_PERIOD.min(deadline - now));
+    }
+}
+
+/// Statistics of all instrumented channels, after a `flush`, in the order the TUI shows them.
+pub fn snapshot() -> Vec<SerializableChannelStats> {
+    flush();
+    get_serializable_stats()
+}
+
+/// Checks every instrumented channel against `healthy`, after a `flush`, returning those that
+/// fail it, e.g. to fail a test or CI run when a channel filled up or lost messages.
+///
+/// # Examples
+///
+/// ```
+/// use channels_console::ChannelState;
+///
+/// let result = channels_console::check(|s| s.state != ChannelState::Full && s.dropped_count == 0);
+/// if let Err(unhealthy) = result {
+///     for s in &unhealthy {
+///         eprintln!("unhealthy channel {}: {:?}", s.label, s.state);
+///     }
+/// }
+/// ```
+pub fn check<F>(healthy: F) -> Result<(), Vec<SerializableChannelStats>>
+where
+    F: Fn(&SerializableChannelStats) -> bool,
+{
+    let unhealthy: Vec<SerializableChannelStats> = snapshot()
+        .into_iter()
+        .filter(|stats| !healthy(stats))
+        .collect();
+    if unhealthy.is_empty() {
+        Ok(())
+    } else {
+        Err(unhealthy)
     }
 }
 
+/// Current state of the channel with `id`, `None` if no such channel was instrumented (yet).
+///
+/// Unlike `snapshot`, this reads the collected stats without waiting for the collector, so it's
+/// cheap enough to check on every send, e.g. to shed load while a channel is `Full`. The state may
+/// lag behind the last few events.
+///
+/// # Examples
+///
+/// ```
+/// use channels_console::ChannelState;
+///
+/// # let id = 0;
+/// if channels_console::channel_state(id) == Some(ChannelState::Full) {
+///     // Drop low priority work instead of waiting for capacity
+/// }
+/// ```
+pub fn channel_state(id: u64) -> Option<ChannelState> {
+    let (_, stats_map) = STATS_STATE.get()?;
+    let stats = stats_map.read().unwrap_or_else(PoisonError::into_inner);
+    stats.get(&id).map(|channel_stats| channel_stats.state)
+}
+
+/// Id, label and current state of every instrumented channel, in the order the TUI shows them.
+///
+/// Like `channel_state`, this doesn't wait for the collector, so states may lag behind the last
+/// few events. Call `flush` first when that matters.
+///
+/// # Examples
+///
+/// ```
+/// use channels_console::ChannelState;
+///
+/// for (id, label, state) in channels_console::list_channels() {
+///     if state == ChannelState::Full {
+///         eprintln!("channel {label} (#{id}) is full");
+///     }
+/// }
+/// ```
+pub fn list_channels() -> Vec<(u64, String, ChannelState)> {
+    let Some((_, stats_map)) = STATS_STATE.get() else {
+        return Vec::new();
+    };
+    let stats = stats_map.read().unwrap_or_else(PoisonError::into_inner);
+    let mut channels: Vec<&ChannelStats> = stats.values().collect();
+    channels.sort_by(|a, b| compare_channel_stats(a, b));
+    channels
+        .into_iter()
+        .map(|channel_stats| {
+            (
+                channel_stats.id,
+                channel_stats.resolved_label(),
+                channel_stats.state,
+            )
+        })
+        .collect()
+}
+
 pub(crate) fn get_sorted_channel_stats() -> Vec<ChannelStats> {
     let mut stats: Vec<ChannelStats> = get_channel_stats().into_values().collect();
     stats.sort_by(compare_channel_stats);
     stats
 }
 
-fn get_metrics_json() -> MetricsJson {
-    let stats = get_sorted_channel_stats()
+/// Creation backtrace of a channel, `None` if the channel doesn't exist.
+pub(crate) fn get_channel_backtrace(id: u64) -> Option<Option<Arc<str>>> {
+    let (_, stats_map) = STATS_STATE.get()?;
+    let stats = stats_map.read().unwrap_or_else(PoisonError::into_inner);
+    stats
+        .get(&id)
+        .map(|channel_stats| channel_stats.backtrace.clone())
+}
+
+pub(crate) fn get_server_info() -> ServerInfo {
+    let start_wall_clock = START_WALL_CLOCK
+        .get()
+        .expect("START_WALL_CLOCK must be initialized")
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+
+    ServerInfo { start_wall_clock }
+}
+
+pub(crate) fn get_diagnostics() -> Diagnostics {
+    let event_queue_len = STATS_STATE
+        .get()
+        .map_or(0, |(stats_tx, _)| stats_tx.len() as u64);
+
+    Diagnostics {
+        event_queue_len,
+        peak_event_queue_len: PEAK_EVENT_QUEUE_LEN.load(Ordering::Relaxed),
+    }
+}
+
+pub(crate) fn get_channel_index() -> Vec<ChannelIndexEntry> {
+    get_sorted_channel_stats()
+        .iter()
+        .map(|channel_stats| ChannelIndexEntry {
+            id: channel_stats.id,
+            label: channel_stats.resolved_label(),
+        })
+        .collect()
+}
+
+pub(crate) fn get_serializable_stats() -> Vec<SerializableChannelStats> {
+    get_sorted_channel_stats()
         .iter()
         .map(SerializableChannelStats::from)
-        .collect();
+        .collect()
+}
+
+fn get_metrics_json() -> MetricsJson {
+    metrics_json(get_serializable_stats())
+}
+
+/// The unversioned `/metrics` payload, served on `/metrics/v0` for consumers written against it.
+#[derive(Serialize)]
+pub(crate) struct LegacyMetricsJson {
+    current_elapsed_ns: u64,
+    stats: Vec<SerializableChannelStats>,
+    summary: StatsSummary,
+}
+
+impl From<MetricsJson> for LegacyMetricsJson {
+    fn from(metrics: MetricsJson) -> Self {
+        Self {
+            current_elapsed_ns: metrics.current_elapsed_ns,
+            stats: metrics.stats,
+            summary: metrics.summary,
+        }
+    }
+}
 
+pub(crate) fn metrics_json(stats: Vec<SerializableChannelStats>) -> MetricsJson {
     let current_elapsed_ns = START_TIME
         .get()
         .expect("START_TIME must be initialized")
@@ -754,20 +3784,144 @@ fn get_metrics_json() -> MetricsJson {
         .as_nanos() as u64;
 
     MetricsJson {
+        version: METRICS_SCHEMA_VERSION,
         current_elapsed_ns,
+        summary: StatsSummary::from_stats(&stats),
         stats,
     }
 }
 
+/// Channels changed since `cursor`, or all of them if it's `None` or no longer retained.
+///
+/// Cursors are change versions, so clients polling at the same time share them. The counts at
+/// the last `MAX_DIFF_CURSORS` handed out cursors are kept to compute deltas from.
+pub(crate) fn get_metrics_diff(cursor: Option<u64>) -> MetricsDiffJson {
+    let (_, stats_map) = init_stats_state();
+    let stats = stats_map.read().unwrap_or_else(PoisonError::into_inner);
+    // Read under the lock, so it matches the counts below
+    let version = CHANGE_VERSION.load(Ordering::Relaxed);
+    let mut cursors = DIFF_CURSORS.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let baseline = cursor.and_then(|cursor| {
+        cursors
+            .iter()
+            .find(|diff_cursor| diff_cursor.version == cursor)
+    });
+
+    let mut changed: Vec<&ChannelStats> = stats
+        .values()
+        .filter(|channel_stats| {
+            baseline.is_none_or(|baseline| channel_stats.changed_version > baseline.version)
+        })
+        .collect();
+    changed.sort_by(|a, b| compare_channel_stats(a, b));
+
+    let channels = changed
+        .into_iter()
+        .map(|channel_stats| {
+            let (sent_before, received_before) = baseline
+                .and_then(|baseline| baseline.counts.get(&channel_stats.id))
+                .copied()
+                .unwrap_or_default();
+            let delta = |now: u64, before: u64| now.checked_sub(before).unwrap_or(now);
+            ChannelDelta {
+                id: channel_stats.id,
+                label: channel_stats.resolved_label(),
+                state: channel_stats.state,
+                sent_count: channel_stats.sent_count,
+                received_count: channel_stats.received_count,
+                sent_delta: delta(channel_stats.sent_count, sent_before),
+                received_delta: delta(channel_stats.received_count, received_before),
+                queued: channel_stats.queued(),
+            }
+        })
+        .collect();
+
+    let mut removed: Vec<u64> = baseline
+        .map(|baseline| {
+            baseline
+                .counts
+                .keys()
+                .filter(|id| !stats.contains_key(id))
+                .copied()
+                .collect()
+        })
+        .unwrap_or_default();
+    removed.sort_unstable();
+
+    let full = baseline.is_none();
+    if !cursors
+        .iter()
+        .any(|diff_cursor| diff_cursor.version == version)
+    {
+        if cursors.len() >= MAX_DIFF_CURSORS {
+            cursors.pop_front();
+        }
+        cursors.push_back(DiffCursor {
+            version,
+            counts: stats
+                .values()
+                .map(|cs| (cs.id, (cs.sent_count, cs.received_count)))
+                .collect(),
+        });
+    }
+
+    let current_elapsed_ns = START_TIME
+        .get()
+        .expect("START_TIME must be initialized")
+        .elapsed()
+        .as_nanos() as u64;
+
+    MetricsDiffJson {
+        version: METRICS_SCHEMA_VERSION,
+        current_elapsed_ns,
+        cursor: version,
+        full,
+        channels,
+        removed,
+    }
+}
+
+pub(crate) fn reset_stats() {
+    let (tx, _) = init_stats_state();
+    let _ = tx.send(StatsEvent::Reset);
+}
+
+/// Starts or stops logging messages of a channel. Returns `false` if the channel doesn't exist.
+/// The switch is flipped before returning, so messages sent afterwards are affected.
+pub(crate) fn set_channel_logging(id: u64, enabled: bool) -> bool {
+    let (_, stats_map) = init_stats_state();
+    let stats = stats_map.read().unwrap_or_else(PoisonError::into_inner);
+    match stats.get(&id) {
+        Some(channel_stats) => {
+            channel_stats
+                .logging
+                .enabled
+                .store(enabled, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+fn get_prometheus_metrics() -> String {
+    prometheus::render(&get_sorted_channel_stats())
+}
+
 /// Serializable log response containing sent and received logs.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelLogs {
     pub id: String,
     pub sent_logs: Vec<LogEntry>,
     pub received_logs: Vec<LogEntry>,
+    /// Index of the last received message. Sent entries up to it were received, even when
+    /// their received entry was evicted or not logged. Servers predating it report 0.
+    #[serde(default)]
+    pub last_received_index: u64,
 }
 
-pub(crate) fn get_channel_logs(channel_id: &str) -> Option<ChannelLogs> {
+/// Logs of a channel, newest first unless `oldest_first` is set.
+pub(crate) fn get_channel_logs(channel_id: &str, oldest_first: bool) -> Option<ChannelLogs> {
     let id = channel_id.parse::<u64>().ok()?;
     let stats = get_channel_stats();
     stats.get(&id).map(|channel_stats| {
@@ -776,14 +3930,96 @@ pub(crate) fn get_channel_logs(channel_id: &str) -> Option<ChannelLogs> {
         let mut received_logs: Vec<LogEntry> =
             channel_stats.received_logs.iter().cloned().collect();
 
-        // Sort by index descending (most recent first)
-        sent_logs.sort_by(|a, b| b.index.cmp(&a.index));
-        received_logs.sort_by(|a, b| b.index.cmp(&a.index));
+        // Sort by index, most recent first unless asked otherwise
+        sent_logs.sort_by_key(|entry| entry.index);
+        received_logs.sort_by_key(|entry| entry.index);
+        if !oldest_first {
+            sent_logs.reverse();
+            received_logs.reverse();
+        }
 
         ChannelLogs {
             id: channel_id.to_string(),
             sent_logs,
             received_logs,
+            last_received_index: channel_stats.received_count,
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn created(id: u64, channel_type: ChannelType) -> StatsEvent {
+        StatsEvent::Created {
+            id,
+            source: Arc::from("src/lib.rs:1"),
+            display_label: None,
+            group: None,
+            meta: BTreeMap::new(),
+            channel_type,
+            type_name: "u32",
+            type_size: 4,
+            iter: 0,
+            sender_only: false,
+            proxied: false,
+            created_at: SystemTime::now(),
+            backtrace: None,
+        }
+    }
+
+    fn send(stats: &mut HashMap<u64, ChannelStats>, id: u64, count: usize) {
+        for _ in 0..count {
+            apply_event(
+                stats,
+                StatsEvent::MessageSent {
+                    id,
+                    log: None,
+                    size: None,
+                    timestamp: Instant::now(),
+                },
+            );
+        }
+    }
+
+    fn resize(stats: &mut HashMap<u64, ChannelStats>, id: u64, capacity: usize) {
+        apply_event(stats, StatsEvent::CapacityChanged { id, capacity });
+    }
+
+    #[test]
+    fn capacity_changed_resizes_bounded_channels() {
+        let mut stats = HashMap::new();
+        apply_event(&mut stats, created(1, ChannelType::Bounded(10)));
+        apply_event(&mut stats, StatsEvent::QueueCapped { id: 1 });
+        send(&mut stats, 1, 6);
+        assert_eq!(stats[&1].state, ChannelState::Active);
+        assert_eq!(stats[&1].queued(), 6);
+
+        resize(&mut stats, 1, 4);
+        assert_eq!(stats[&1].channel_type, ChannelType::Bounded(4));
+        assert_eq!(stats[&1].queued(), 4);
+        assert_eq!(stats[&1].state, ChannelState::Full);
+
+        resize(&mut stats, 1, 20);
+        assert_eq!(stats[&1].channel_type, ChannelType::Bounded(20));
+        assert_eq!(stats[&1].queued(), 6);
+        assert_eq!(stats[&1].state, ChannelState::Active);
+    }
+
+    #[test]
+    fn capacity_changed_ignores_unbounded_and_oneshot_channels() {
+        let mut stats = HashMap::new();
+        apply_event(&mut stats, created(1, ChannelType::Unbounded));
+        apply_event(&mut stats, created(2, ChannelType::Oneshot));
+        send(&mut stats, 1, 3);
+
+        resize(&mut stats, 1, 1);
+        resize(&mut stats, 2, 1);
+        assert_eq!(stats[&1].channel_type, ChannelType::Unbounded);
+        assert_eq!(stats[&1].queued(), 3);
+        assert_eq!(stats[&1].state, ChannelState::Active);
+        assert_eq!(stats[&2].channel_type, ChannelType::Oneshot);
+        assert_eq!(stats[&2].state, ChannelState::Active);
+    }
+}
@@ -0,0 +1,35 @@
+use metrics::{counter, gauge};
+
+use crate::ChannelStats;
+
+/// Counts sent messages, as `channel_sent_total`, and updates the queue gauge.
+pub(crate) fn record_sent(stats: &ChannelStats, count: u64) {
+    counter!("channel_sent_total", &labels(stats)).increment(count);
+    record_queued(stats);
+}
+
+/// Counts received messages, as `channel_received_total`, and updates the queue gauge.
+pub(crate) fn record_received(stats: &ChannelStats, count: u64) {
+    counter!("channel_received_total", &labels(stats)).increment(count);
+    record_queued(stats);
+}
+
+/// Counts a message lost because forwarding it failed, as `channel_dropped_total`.
+pub(crate) fn record_dropped(stats: &ChannelStats) {
+    counter!("channel_dropped_total", &labels(stats)).increment(1);
+}
+
+/// Sets `channel_queued`, unless receives of the channel aren't observed.
+fn record_queued(stats: &ChannelStats) {
+    if stats.sender_only {
+        return;
+    }
+    gauge!("channel_queued", &labels(stats)).set(stats.queued() as f64);
+}
+
+fn labels(stats: &ChannelStats) -> [(&'static str, String); 2] {
+    [
+        ("label", stats.resolved_label()),
+        ("channel_type", stats.channel_type.to_string()),
+    ]
+}
@@ -0,0 +1,76 @@
+use opentelemetry::metrics::{AsyncInstrument, Meter};
+use opentelemetry::KeyValue;
+
+use crate::{get_serializable_stats, SerializableChannelStats};
+
+/// Register channel metrics with an OpenTelemetry `Meter`.
+///
+/// Registers observable instruments that read the current channel statistics whenever the
+/// meter provider collects metrics, e.g. on every interval of a periodic OTLP exporter:
+///
+/// - `channel.sent`, `channel.received` and `channel.dropped` counters
+/// - `channel.queued` gauge
+///
+/// Each measurement carries the `id`, `label` and `channel_type` attributes. Received counts and
+/// queue sizes are not reported for send-only channels.
+///
+/// # Examples
+///
+/// ```no_run
+/// use opentelemetry::global;
+///
+/// // After configuring the global meter provider with an OTLP exporter
+/// let meter = global::meter("my-app");
+/// channels_console::register_otel_metrics(&meter);
+/// ```
+pub fn register_otel_metrics(meter: &Meter) {
+    meter
+        .u64_observable_counter("channel.sent")
+        .with_description("Messages sent.")
+        .with_callback(|observer| {
+            observe(observer, |_| true, |s| s.sent_count);
+        })
+        .build();
+
+    meter
+        .u64_observable_counter("channel.received")
+        .with_description("Messages received.")
+        .with_callback(|observer| {
+            observe(observer, |s| !s.sender_only, |s| s.received_count);
+        })
+        .build();
+
+    meter
+        .u64_observable_counter("channel.dropped")
+        .with_description("Messages lost because forwarding them failed.")
+        .with_callback(|observer| {
+            observe(observer, |_| true, |s| s.dropped_count);
+        })
+        .build();
+
+    meter
+        .u64_observable_gauge("channel.queued")
+        .with_description("Messages currently queued.")
+        .with_callback(|observer| {
+            observe(observer, |s| !s.sender_only, |s| s.queued);
+        })
+        .build();
+}
+
+fn observe(
+    observer: &dyn AsyncInstrument<u64>,
+    filter: impl Fn(&SerializableChannelStats) -> bool,
+    value: impl Fn(&SerializableChannelStats) -> u64,
+) {
+    for stats in get_serializable_stats().iter().filter(|s| filter(s)) {
+        observer.observe(value(stats), &attributes(stats));
+    }
+}
+
+fn attributes(stats: &SerializableChannelStats) -> [KeyValue; 3] {
+    [
+        KeyValue::new("id", stats.id as i64),
+        KeyValue::new("label", stats.label.clone()),
+        KeyValue::new("channel_type", stats.channel_type.to_string()),
+    ]
+}
@@ -0,0 +1,130 @@
+use crate::{ChannelStats, UTILIZATION_BUCKETS};
+use std::fmt::Write;
+
+const PREFIX: &str = "channels_console";
+
+/// Render channel statistics in the Prometheus text exposition format.
+pub(crate) fn render(stats: &[ChannelStats]) -> String {
+    let mut out = String::new();
+
+    write_header(&mut out, "sent_total", "counter", "Messages sent.");
+    for s in stats {
+        let _ = writeln!(out, "{PREFIX}_sent_total{{{}}} {}", labels(s), s.sent_count);
+    }
+
+    write_header(&mut out, "received_total", "counter", "Messages received.");
+    for s in stats.iter().filter(|s| !s.sender_only) {
+        let _ = writeln!(
+            out,
+            "{PREFIX}_received_total{{{}}} {}",
+            labels(s),
+            s.received_count
+        );
+    }
+
+    write_header(
+        &mut out,
+        "dropped_total",
+        "counter",
+        "Messages lost because forwarding them failed.",
+    );
+    for s in stats {
+        let _ = writeln!(
+            out,
+            "{PREFIX}_dropped_total{{{}}} {}",
+            labels(s),
+            s.dropped_count
+        );
+    }
+
+    write_header(
+        &mut out,
+        "try_send_full_total",
+        "counter",
+        "try_send calls rejected because the channel was full, tap mode only.",
+    );
+    for s in stats.iter().filter(|s| !s.proxied && !s.sender_only) {
+        let _ = writeln!(
+            out,
+            "{PREFIX}_try_send_full_total{{{}}} {}",
+            labels(s),
+            s.try_send_full_count
+        );
+    }
+
+    write_header(
+        &mut out,
+        "recv_timeout_total",
+        "counter",
+        "recv_timeout calls that timed out, tap mode only.",
+    );
+    for s in stats.iter().filter(|s| !s.proxied && !s.sender_only) {
+        let _ = writeln!(
+            out,
+            "{PREFIX}_recv_timeout_total{{{}}} {}",
+            labels(s),
+            s.timeout_count
+        );
+    }
+
+    write_header(&mut out, "queued", "gauge", "Messages currently queued.");
+    for s in stats.iter().filter(|s| !s.sender_only) {
+        let _ = writeln!(out, "{PREFIX}_queued{{{}}} {}", labels(s), s.queued());
+    }
+
+    write_header(
+        &mut out,
+        "queue_utilization",
+        "histogram",
+        "Queued messages as a fraction of capacity, sampled on every send and receive.",
+    );
+    for s in stats.iter().filter(|s| s.utilization.count > 0) {
+        let labels = labels(s);
+        let mut cumulative = 0;
+        for (le, count) in UTILIZATION_BUCKETS.iter().zip(s.utilization.buckets) {
+            cumulative += count;
+            let _ = writeln!(
+                out,
+                "{PREFIX}_queue_utilization_bucket{{{labels},le=\"{le}\"}} {cumulative}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{PREFIX}_queue_utilization_bucket{{{labels},le=\"+Inf\"}} {}",
+            s.utilization.count
+        );
+        let _ = writeln!(
+            out,
+            "{PREFIX}_queue_utilization_sum{{{labels}}} {}",
+            s.utilization.sum
+        );
+        let _ = writeln!(
+            out,
+            "{PREFIX}_queue_utilization_count{{{labels}}} {}",
+            s.utilization.count
+        );
+    }
+
+    out
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {PREFIX}_{name} {help}");
+    let _ = writeln!(out, "# TYPE {PREFIX}_{name} {kind}");
+}
+
+fn labels(stats: &ChannelStats) -> String {
+    format!(
+        "id=\"{}\",label=\"{}\",channel_type=\"{}\"",
+        stats.id,
+        escape(&stats.resolved_label()),
+        stats.channel_type
+    )
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,411 @@
+use crate::{
+    apply_event, compare_channel_stats, ChannelStats, ChannelType, CloseReason, MetricsJson,
+    SerializableChannelStats, StatsEvent, StatsSummary, METRICS_SCHEMA_VERSION, START_TIME,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// Session file events are appended to, see `record_to`.
+static RECORDER: Mutex<Option<BufWriter<File>>> = Mutex::new(None);
+
+/// A collector event written by `record_to`, one JSON object per line of the session file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Time since the process started instrumenting channels, in nanoseconds
+    pub elapsed_ns: u64,
+    #[serde(flatten)]
+    pub event: SessionEvent,
+}
+
+/// What happened to a channel, as recorded in a session file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEvent {
+    Created {
+        id: u64,
+        source: String,
+        label: Option<String>,
+        group: Option<String>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        meta: BTreeMap<String, String>,
+        channel_type: ChannelType,
+        type_name: String,
+        type_size: usize,
+        iter: u32,
+        sender_only: bool,
+        proxied: bool,
+        /// Wall-clock creation time, in milliseconds since the Unix epoch
+        created_at_ms: u64,
+    },
+    Sent {
+        id: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        log: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        size: Option<u64>,
+        /// Messages sent at once, more than one for the batched counts of tapped channels
+        #[serde(default = "one", skip_serializing_if = "is_one")]
+        count: u64,
+    },
+    Received {
+        id: u64,
+        count: u64,
+    },
+    Dropped {
+        id: u64,
+    },
+    SendRejected {
+        id: u64,
+    },
+    RecvTimeout {
+        id: u64,
+    },
+    Closed {
+        id: u64,
+        reason: CloseReason,
+    },
+    Notified {
+        id: u64,
+    },
+    Cancelled {
+        id: u64,
+    },
+    CapacityUnknown {
+        id: u64,
+    },
+    QueueCapped {
+        id: u64,
+    },
+    CapacityChanged {
+        id: u64,
+        capacity: usize,
+    },
+    Reset,
+}
+
+fn one() -> u64 {
+    1
+}
+
+fn is_one(count: &u64) -> bool {
+    *count == 1
+}
+
+/// Appends every event of the collector to a new JSON Lines file at `path`, so a whole session
+/// can be replayed later with `channels-console --replay <path>`, e.g. one attached to a bug
+/// report about an intermittent issue.
+///
+/// Only events collected after the call are recorded, and replays skip channels whose creation
+/// is missing, so call it before instrumenting any channel. Calling it again switches to a new
+/// file. The file is flushed whenever the collector catches up with the events.
+///
+/// # Examples
+///
+/// ```no_run
+/// channels_console::record_to("session.jsonl").expect("Failed to create session file");
+/// ```
+pub fn record_to(path: impl AsRef<Path>) -> std::io::Result<()> {
+    let file = File::create(path)?;
+    let mut recorder = RECORDER.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(previous) = recorder.as_mut() {
+        let _ = previous.flush();
+    }
+    *recorder = Some(BufWriter::new(file));
+    Ok(())
+}
+
+/// Appends an event to the session file, if recording. Called by the collector before applying
+/// the event, with `caught_up` set once no more events are queued.
+pub(crate) fn record(event: &StatsEvent, caught_up: bool) {
+    let mut recorder = RECORDER.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(writer) = recorder.as_mut() else {
+        return;
+    };
+    let written = match RecordedEvent::from_stats_event(event) {
+        Some(recorded) => serde_json::to_writer(&mut *writer, &recorded)
+            .map_err(std::io::Error::from)
+            .and_then(|()| writer.write_all(b"\n")),
+        None => Ok(()),
+    };
+    let written = match written {
+        Ok(()) if caught_up => writer.flush(),
+        written => written,
+    };
+    if let Err(e) = written {
+        eprintln!("channels-console: stopped recording the session: {}", e);
+        *recorder = None;
+    }
+}
+
+/// Nanoseconds between the start of instrumentation and `timestamp`.
+fn elapsed_ns(timestamp: Instant) -> u64 {
+    let start = START_TIME.get().copied().unwrap_or(timestamp);
+    timestamp.saturating_duration_since(start).as_nanos() as u64
+}
+
+impl RecordedEvent {
+    /// The recorded form of an event, `None` for events that don't change any channel.
+    fn from_stats_event(event: &StatsEvent) -> Option<Self> {
+        let (timestamp, event) = match event {
+            StatsEvent::Created {
+                id,
+                source,
+                display_label,
+                group,
+                meta,
+                channel_type,
+                type_name,
+                type_size,
+                iter,
+                sender_only,
+                proxied,
+                created_at,
+                backtrace: _,
+            } => (
+                // Created events are queued before the first send, but only timed in wall-clock
+                Instant::now()
+                    .checked_sub(created_at.elapsed().unwrap_or_default())
+                    .unwrap_or_else(Instant::now),
+                SessionEvent::Created {
+                    id: *id,
+                    source: source.to_string(),
+                    label: display_label.clone(),
+                    group: group.clone(),
+                    meta: meta.clone(),
+                    channel_type: *channel_type,
+                    type_name: type_name.to_string(),
+                    type_size: *type_size,
+                    iter: *iter,
+                    sender_only: *sender_only,
+                    proxied: *proxied,
+                    created_at_ms: created_at
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_millis() as u64,
+                },
+            ),
+            StatsEvent::MessageSent {
+                id,
+                log,
+                size,
+                timestamp,
+            } => (
+                *timestamp,
+                SessionEvent::Sent {
+                    id: *id,
+                    log: log.clone(),
+                    size: *size,
+                    count: 1,
+                },
+            ),
+            StatsEvent::MessageSentBatch {
+                id,
+                count,
+                timestamp,
+                peak_queued: _,
+            } => (
+                *timestamp,
+                SessionEvent::Sent {
+                    id: *id,
+                    log: None,
+                    size: None,
+                    count: *count,
+                },
+            ),
+            StatsEvent::MessageReceived { id, timestamp } => {
+                (*timestamp, SessionEvent::Received { id: *id, count: 1 })
+            }
+            StatsEvent::MessageReceivedBatch {
+                id,
+                count,
+                timestamp,
+            } => (
+                *timestamp,
+                SessionEvent::Received {
+                    id: *id,
+                    count: *count,
+                },
+            ),
+            StatsEvent::MessageDropped { id } => {
+                (Instant::now(), SessionEvent::Dropped { id: *id })
+            }
+            StatsEvent::SendRejected { id } => {
+                (Instant::now(), SessionEvent::SendRejected { id: *id })
+            }
+            StatsEvent::RecvTimeout { id } => {
+                (Instant::now(), SessionEvent::RecvTimeout { id: *id })
+            }
+            StatsEvent::Closed { id, reason } => (
+                Instant::now(),
+                SessionEvent::Closed {
+                    id: *id,
+                    reason: *reason,
+                },
+            ),
+            StatsEvent::Notified { id } => (Instant::now(), SessionEvent::Notified { id: *id }),
+            StatsEvent::Cancelled { id } => (Instant::now(), SessionEvent::Cancelled { id: *id }),
+            StatsEvent::CapacityUnknown { id } => {
+                (Instant::now(), SessionEvent::CapacityUnknown { id: *id })
+            }
+            StatsEvent::QueueCapped { id } => {
+                (Instant::now(), SessionEvent::QueueCapped { id: *id })
+            }
+            StatsEvent::CapacityChanged { id, capacity } => (
+                Instant::now(),
+                SessionEvent::CapacityChanged {
+                    id: *id,
+                    capacity: *capacity,
+                },
+            ),
+            StatsEvent::Reset => (Instant::now(), SessionEvent::Reset),
+            StatsEvent::BatchFilled { .. } | StatsEvent::Flush { .. } => return None,
+        };
+        Some(Self {
+            elapsed_ns: elapsed_ns(timestamp),
+            event,
+        })
+    }
+
+    /// The event to apply when replaying, timed relative to `start`.
+    fn into_stats_event(self, start: Instant) -> StatsEvent {
+        let timestamp = start + Duration::from_nanos(self.elapsed_ns);
+        match self.event {
+            SessionEvent::Created {
+                id,
+                source,
+                label,
+                group,
+                meta,
+                channel_type,
+                type_name,
+                type_size,
+                iter,
+                sender_only,
+                proxied,
+                created_at_ms,
+            } => StatsEvent::Created {
+                id,
+                source: Arc::from(source),
+                display_label: label,
+                group,
+                meta,
+                channel_type,
+                // Leaked once per replayed channel, type names of live ones are static too
+                type_name: Box::leak(type_name.into_boxed_str()),
+                type_size,
+                iter,
+                sender_only,
+                proxied,
+                created_at: UNIX_EPOCH + Duration::from_millis(created_at_ms),
+                backtrace: None,
+            },
+            SessionEvent::Sent {
+                id,
+                log,
+                size,
+                count: 1,
+            } => StatsEvent::MessageSent {
+                id,
+                log,
+                size,
+                timestamp,
+            },
+            SessionEvent::Sent { id, count, .. } => StatsEvent::MessageSentBatch {
+                id,
+                count,
+                timestamp,
+                peak_queued: None,
+            },
+            SessionEvent::Received { id, count } => StatsEvent::MessageReceivedBatch {
+                id,
+                count,
+                timestamp,
+            },
+            SessionEvent::Dropped { id } => StatsEvent::MessageDropped { id },
+            SessionEvent::SendRejected { id } => StatsEvent::SendRejected { id },
+            SessionEvent::RecvTimeout { id } => StatsEvent::RecvTimeout { id },
+            SessionEvent::Closed { id, reason } => StatsEvent::Closed { id, reason },
+            SessionEvent::Notified { id } => StatsEvent::Notified { id },
+            SessionEvent::Cancelled { id } => StatsEvent::Cancelled { id },
+            SessionEvent::CapacityUnknown { id } => StatsEvent::CapacityUnknown { id },
+            SessionEvent::QueueCapped { id } => StatsEvent::QueueCapped { id },
+            SessionEvent::CapacityChanged { id, capacity } => {
+                StatsEvent::CapacityChanged { id, capacity }
+            }
+            SessionEvent::Reset => StatsEvent::Reset,
+        }
+    }
+}
+
+/// Rebuilds the channel statistics of a recorded session, see `record_to`.
+///
+/// Returns a snapshot after each `interval` of recorded time that saw any event, taken once
+/// the last of those events was applied, so stepping through them shows how the channels
+/// evolved. Events of channels whose creation wasn't recorded are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use channels_console::RecordedEvent;
+/// use std::time::Duration;
+///
+/// let content = std::fs::read_to_string("session.jsonl").unwrap();
+/// let events = content
+///     .lines()
+///     .map(|line| serde_json::from_str::<RecordedEvent>(line).unwrap());
+/// for snapshot in channels_console::replay_session(events, Duration::from_millis(100)) {
+///     println!("{}ns: {} sent", snapshot.current_elapsed_ns, snapshot.summary.sent_count);
+/// }
+/// ```
+pub fn replay_session(
+    events: impl IntoIterator<Item = RecordedEvent>,
+    interval: Duration,
+) -> Vec<MetricsJson> {
+    let start = *START_TIME.get_or_init(Instant::now);
+    let interval_ns = interval.as_nanos().max(1) as u64;
+
+    let mut stats: HashMap<u64, ChannelStats> = HashMap::new();
+    let mut snapshots = Vec::new();
+    // End of the interval whose events aren't in a snapshot yet, and the last of them
+    let mut pending: Option<(u64, u64)> = None;
+
+    for recorded in events {
+        if let Some((until, last)) = pending {
+            if recorded.elapsed_ns >= until {
+                snapshots.push(session_snapshot(&stats, last));
+                pending = None;
+            }
+        }
+        // Events carrying their own timestamp can be recorded slightly out of order
+        let elapsed_ns = recorded.elapsed_ns;
+        pending = Some(match pending {
+            Some((until, last)) => (until, last.max(elapsed_ns)),
+            None => (elapsed_ns.saturating_add(interval_ns), elapsed_ns),
+        });
+        apply_event(&mut stats, recorded.into_stats_event(start));
+    }
+    if let Some((_, last)) = pending {
+        snapshots.push(session_snapshot(&stats, last));
+    }
+    snapshots
+}
+
+fn session_snapshot(stats: &HashMap<u64, ChannelStats>, elapsed_ns: u64) -> MetricsJson {
+    let mut channels: Vec<&ChannelStats> = stats.values().collect();
+    channels.sort_by(|a, b| compare_channel_stats(a, b));
+    let stats: Vec<SerializableChannelStats> = channels
+        .into_iter()
+        .map(SerializableChannelStats::from)
+        .collect();
+    MetricsJson {
+        version: METRICS_SCHEMA_VERSION,
+        current_elapsed_ns: elapsed_ns,
+        summary: StatsSummary::from_stats(&stats),
+        stats,
+    }
+}
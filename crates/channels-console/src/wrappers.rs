@@ -1,7 +1,18 @@
+#[cfg(feature = "async-channel")]
+pub(crate) mod async_channel;
 #[cfg(feature = "crossbeam")]
 pub(crate) mod crossbeam;
+#[cfg(any(feature = "async-channel", feature = "postage"))]
+pub(crate) mod executor;
 #[cfg(feature = "futures")]
 pub(crate) mod futures;
+#[cfg(feature = "kanal")]
+pub(crate) mod kanal;
+pub(crate) mod pool;
+#[cfg(feature = "postage")]
+pub(crate) mod postage;
 pub(crate) mod std;
+pub(crate) mod stream;
+pub(crate) mod tap;
 #[cfg(feature = "tokio")]
 pub(crate) mod tokio;
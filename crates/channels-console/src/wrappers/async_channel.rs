@@ -0,0 +1,221 @@
+use async_channel::{Receiver, Sender};
+use futures_lite::future;
+use std::sync::atomic::Ordering;
+
+use super::executor::EXECUTOR;
+use crate::{
+    channel_source, creation_backtrace, current_group, current_meta, init_stats_state,
+    log_formatter, log_gate, message_size, next_iter, payload_sizer, proxy_capacity,
+    report_stranded, ChannelType, CloseReason, StatsEvent, CHANNEL_ID_COUNTER,
+};
+
+/// Internal implementation for wrapping async-channel channels with optional logging.
+/// `capacity` is `None` for unbounded channels.
+fn wrap_channel_impl<T, F>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    capacity: Option<usize>,
+    mut log_on_send: F,
+) -> (Sender<T>, Receiver<T>)
+where
+    T: Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let (inner_tx, inner_rx) = inner;
+    let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
+
+    let (outer_tx, to_inner_rx) = match capacity {
+        // One message in flight through the inner channel and one in the inner proxy make up
+        // the rest of the original capacity
+        Some(capacity) => async_channel::bounded::<T>(proxy_capacity(capacity, 2)),
+        None => async_channel::unbounded::<T>(),
+    };
+    // async-channel doesn't support zero capacity, so hand messages over one at a time
+    let (from_inner_tx, outer_rx) = match capacity {
+        Some(_) => async_channel::bounded::<T>(1),
+        None => async_channel::unbounded::<T>(),
+    };
+
+    // Allow a single message in flight through a bounded inner channel, so the outer
+    // proxy's capacity drives backpressure instead of adding to it
+    let (in_flight_tx, in_flight_rx) = match capacity {
+        Some(_) => {
+            let (tx, rx) = async_channel::bounded::<()>(1);
+            (Some(tx), Some(rx))
+        }
+        None => (None, None),
+    };
+
+    let (stats_tx, _) = init_stats_state();
+
+    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
+
+    let source = channel_source(source);
+    let _ = stats_tx.send(StatsEvent::Created {
+        id,
+        source: source.clone(),
+        display_label: label,
+        group: current_group(),
+        meta: current_meta(),
+        channel_type: capacity.map_or(ChannelType::Unbounded, ChannelType::Bounded),
+        type_name,
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
+    });
+
+    let stats_tx_send = stats_tx.clone();
+    let stats_tx_recv = stats_tx.clone();
+
+    // Lets the recv forwarder reject further sends once the outer receiver is dropped
+    let to_inner_closer = to_inner_rx.clone();
+
+    // Forward outer -> inner (proxy the send path)
+    EXECUTOR
+        .spawn(async move {
+            let reason = loop {
+                // Wait until the previous message left the inner channel
+                if let Some(in_flight_tx) = &in_flight_tx {
+                    if in_flight_tx.send(()).await.is_err() {
+                        // Recv forwarder ended
+                        break CloseReason::ReceiverDropped;
+                    }
+                }
+
+                match to_inner_rx.recv().await {
+                    Ok(msg) => {
+                        let log = if logging.should_log() {
+                            log_on_send(&msg)
+                        } else {
+                            None
+                        };
+                        let size = payload_size.map(|size| size(&msg));
+                        if inner_tx.send(msg).await.is_err() {
+                            let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
+                            // Inner receiver dropped
+                            break CloseReason::ForwardFailed;
+                        }
+                        let _ = stats_tx_send.send(StatsEvent::MessageSent {
+                            id,
+                            log,
+                            size,
+                            timestamp: std::time::Instant::now(),
+                        });
+                    }
+                    Err(_) => break CloseReason::SenderDropped, // Outer senders dropped or channel closed
+                }
+            };
+            to_inner_rx.close();
+            // Messages left in the proxy will never be forwarded
+            report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
+            // Channel is closed
+            let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
+        })
+        .detach();
+
+    // Forward inner -> outer (proxy the recv path)
+    EXECUTOR
+        .spawn(async move {
+            let reason = loop {
+                let msg = future::or(async { inner_rx.recv().await.ok() }, async {
+                    // Outer receiver was dropped
+                    from_inner_tx.closed().await;
+                    None
+                })
+                .await;
+
+                let Some(msg) = msg else {
+                    break if from_inner_tx.is_closed() {
+                        CloseReason::ReceiverDropped
+                    } else {
+                        CloseReason::SenderDropped
+                    };
+                };
+
+                if from_inner_tx.send(msg).await.is_err() {
+                    let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
+                    // Outer receiver was closed
+                    break CloseReason::ReceiverDropped;
+                }
+                if let Some(in_flight_rx) = &in_flight_rx {
+                    let _ = in_flight_rx.recv().await;
+                }
+                let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
+                    id,
+                    timestamp: std::time::Instant::now(),
+                });
+            };
+            to_inner_closer.close();
+            // Messages left in the inner channel will never be received
+            inner_rx.close();
+            report_stranded(&stats_tx_recv, id, || inner_rx.try_recv().is_ok());
+            // Channel is closed (either inner sender dropped or outer receiver closed)
+            let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
+        })
+        .detach();
+
+    (outer_tx, outer_rx)
+}
+
+/// Wrap an async-channel channel with proxy ends. Returns (outer_tx, outer_rx).
+/// All messages pass through the two forwarders running on a background executor. For bounded
+/// channels only one message at a time is forwarded through the inner channel, and the outer
+/// proxy is sized so `try_send` reports the channel full at its original capacity, or at 3
+/// messages for smaller channels, as the proxy keeps a slot.
+pub(crate) fn wrap_channel<T: Send + 'static>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (Sender<T>, Receiver<T>) {
+    let capacity = inner.0.capacity();
+    wrap_channel_impl(inner, source, label, capacity, log_formatter::<T>())
+}
+
+/// Wrap an async-channel channel with logging enabled. Returns (outer_tx, outer_rx).
+pub(crate) fn wrap_channel_log<T: Send + std::fmt::Debug + 'static>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (Sender<T>, Receiver<T>) {
+    let capacity = inner.0.capacity();
+    wrap_channel_impl(inner, source, label, capacity, |msg| {
+        Some(format!("{:?}", msg))
+    })
+}
+
+use crate::Instrument;
+
+impl<T: Send + 'static> Instrument for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    type Payload = T;
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        // Bounded and unbounded channels share the same types, the capacity tells them apart
+        wrap_channel(self, source, label)
+    }
+}
+
+use crate::InstrumentLog;
+
+impl<T: Send + std::fmt::Debug + 'static> InstrumentLog for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        // Bounded and unbounded channels share the same types, the capacity tells them apart
+        wrap_channel_log(self, source, label)
+    }
+}
@@ -1,8 +1,11 @@
 use crossbeam_channel::{self, Receiver, Sender};
-use std::mem;
 use std::sync::atomic::Ordering;
 
-use crate::{init_stats_state, ChannelType, StatsEvent, CHANNEL_ID_COUNTER};
+use crate::{
+    channel_source, creation_backtrace, current_group, current_meta, init_stats_state,
+    log_formatter, log_gate, message_size, next_iter, payload_sizer, proxy_capacity,
+    report_stranded, ChannelType, CloseReason, StatsEvent, CHANNEL_ID_COUNTER,
+};
 
 /// Internal implementation for wrapping bounded crossbeam channels with optional logging.
 fn wrap_bounded_impl<T, F>(
@@ -18,21 +21,37 @@ where
 {
     let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
 
-    let (outer_tx, to_inner_rx) = crossbeam_channel::bounded::<T>(capacity);
-    let (from_inner_tx, outer_rx) = crossbeam_channel::bounded::<T>(capacity);
+    // One message in flight through the inner channel and one waiting for the outer receiver
+    // make up the rest of the original capacity
+    let (outer_tx, to_inner_rx) = crossbeam_channel::bounded::<T>(proxy_capacity(capacity, 2));
+    let (from_inner_tx, outer_rx) = crossbeam_channel::bounded::<T>(0);
+
+    // Allow a single message in flight through the inner channel, so the outer proxy's
+    // capacity drives backpressure instead of adding to it
+    let (in_flight_tx, in_flight_rx) = crossbeam_channel::bounded::<()>(1);
 
     let (stats_tx, _) = init_stats_state();
 
     let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
 
+    let source = channel_source(source);
     let _ = stats_tx.send(StatsEvent::Created {
         id,
-        source,
+        source: source.clone(),
         display_label: label,
+        group: current_group(),
+        meta: current_meta(),
         channel_type: ChannelType::Bounded(capacity),
         type_name,
-        type_size: mem::size_of::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -43,33 +62,51 @@ where
 
     // Forward outer -> inner (proxy the send path)
     std::thread::spawn(move || {
-        loop {
+        let mut has_in_flight_slot = false;
+        let reason = loop {
             // Check for close signal (non-blocking)
             match close_signal_rx.try_recv() {
                 Ok(_) => {
                     // Outer receiver was closed/dropped
-                    break;
+                    break CloseReason::ReceiverDropped;
                 }
                 Err(crossbeam_channel::TryRecvError::Disconnected) => {
                     // Close signal sender dropped, which means recv forwarder ended
-                    break;
+                    break CloseReason::ReceiverDropped;
                 }
                 Err(crossbeam_channel::TryRecvError::Empty) => {
                     // No close signal, continue
                 }
             }
 
+            // Wait until the previous message left the inner channel
+            if !has_in_flight_slot {
+                if in_flight_tx.send(()).is_err() {
+                    // Recv forwarder ended
+                    break CloseReason::ReceiverDropped;
+                }
+                has_in_flight_slot = true;
+            }
+
             // Try to receive with timeout to periodically check close signal
             match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
                 Ok(msg) => {
-                    let log = log_on_send(&msg);
+                    has_in_flight_slot = false;
+                    let log = if logging.should_log() {
+                        log_on_send(&msg)
+                    } else {
+                        None
+                    };
+                    let size = payload_size.map(|size| size(&msg));
                     if inner_tx.send(msg).is_err() {
+                        let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
                         // Inner receiver dropped
-                        break;
+                        break CloseReason::ForwardFailed;
                     }
                     let _ = stats_tx_send.send(StatsEvent::MessageSent {
                         id,
                         log,
+                        size,
                         timestamp: std::time::Instant::now(),
                     });
                 }
@@ -79,20 +116,26 @@ where
                 }
                 Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
                     // Outer sender dropped
-                    break;
+                    break CloseReason::SenderDropped;
                 }
             }
-        }
+        };
+        // Messages left in the proxy will never be forwarded
+        report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
         // Channel is closed
-        let _ = stats_tx_send.send(StatsEvent::Closed { id });
+        let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
     });
 
     // Forward inner -> outer (proxy the recv path)
     std::thread::spawn(move || {
+        let mut reason = CloseReason::SenderDropped;
         while let Ok(msg) = inner_rx.recv() {
+            let _ = in_flight_rx.recv();
             if from_inner_tx.send(msg).is_err() {
+                let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
                 // Outer receiver was closed
                 let _ = close_signal_tx.send(());
+                reason = CloseReason::ReceiverDropped;
                 break;
             }
             let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
@@ -100,22 +143,27 @@ where
                 timestamp: std::time::Instant::now(),
             });
         }
+        // Messages left in the inner channel will never be received
+        report_stranded(&stats_tx_recv, id, || inner_rx.try_recv().is_ok());
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(StatsEvent::Closed { id });
+        let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
     });
 
     (outer_tx, outer_rx)
 }
 
 /// Wrap a bounded crossbeam channel with proxy ends. Returns (outer_tx, outer_rx).
-/// All messages pass through the two forwarders running in separate threads.
+/// All messages pass through the two forwarders running in separate threads. Only one message
+/// at a time is forwarded through the inner channel, and the outer proxy is sized so `try_send`
+/// reports the channel full at its original capacity, or at 3 messages for smaller channels,
+/// as the proxy keeps a slot.
 pub(crate) fn wrap_bounded<T: Send + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
     label: Option<String>,
     capacity: usize,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_bounded_impl(inner, source, label, capacity, |_| None)
+    wrap_bounded_impl(inner, source, label, capacity, log_formatter::<T>())
 }
 
 /// Wrap a bounded crossbeam channel with logging enabled. Returns (outer_tx, outer_rx).
@@ -143,6 +191,7 @@ where
 {
     let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
 
     let (outer_tx, to_inner_rx) = crossbeam_channel::unbounded::<T>();
     let (from_inner_tx, outer_rx) = crossbeam_channel::unbounded::<T>();
@@ -150,14 +199,23 @@ where
     let (stats_tx, _) = init_stats_state();
 
     let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
 
+    let source = channel_source(source);
     let _ = stats_tx.send(StatsEvent::Created {
         id,
-        source,
+        source: source.clone(),
         display_label: label,
+        group: current_group(),
+        meta: current_meta(),
         channel_type: ChannelType::Unbounded,
         type_name,
-        type_size: mem::size_of::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -168,16 +226,16 @@ where
 
     // Forward outer -> inner (proxy the send path)
     std::thread::spawn(move || {
-        loop {
+        let reason = loop {
             // Check for close signal (non-blocking)
             match close_signal_rx.try_recv() {
                 Ok(_) => {
                     // Outer receiver was closed/dropped
-                    break;
+                    break CloseReason::ReceiverDropped;
                 }
                 Err(crossbeam_channel::TryRecvError::Disconnected) => {
                     // Close signal sender dropped, which means recv forwarder ended
-                    break;
+                    break CloseReason::ReceiverDropped;
                 }
                 Err(crossbeam_channel::TryRecvError::Empty) => {
                     // No close signal, continue
@@ -187,14 +245,21 @@ where
             // Try to receive with timeout to periodically check close signal
             match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
                 Ok(msg) => {
-                    let log = log_on_send(&msg);
+                    let log = if logging.should_log() {
+                        log_on_send(&msg)
+                    } else {
+                        None
+                    };
+                    let size = payload_size.map(|size| size(&msg));
                     if inner_tx.send(msg).is_err() {
+                        let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
                         // Inner receiver dropped
-                        break;
+                        break CloseReason::ForwardFailed;
                     }
                     let _ = stats_tx_send.send(StatsEvent::MessageSent {
                         id,
                         log,
+                        size,
                         timestamp: std::time::Instant::now(),
                     });
                 }
@@ -204,20 +269,25 @@ where
                 }
                 Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
                     // Outer sender dropped
-                    break;
+                    break CloseReason::SenderDropped;
                 }
             }
-        }
+        };
+        // Messages left in the proxy will never be forwarded
+        report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
         // Channel is closed
-        let _ = stats_tx_send.send(StatsEvent::Closed { id });
+        let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
     });
 
     // Forward inner -> outer (proxy the recv path)
     std::thread::spawn(move || {
+        let mut reason = CloseReason::SenderDropped;
         while let Ok(msg) = inner_rx.recv() {
             if from_inner_tx.send(msg).is_err() {
+                let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
                 // Outer receiver was closed
                 let _ = close_signal_tx.send(());
+                reason = CloseReason::ReceiverDropped;
                 break;
             }
             let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
@@ -225,8 +295,10 @@ where
                 timestamp: std::time::Instant::now(),
             });
         }
+        // Messages left in the inner channel will never be received
+        report_stranded(&stats_tx_recv, id, || inner_rx.try_recv().is_ok());
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(StatsEvent::Closed { id });
+        let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
     });
 
     (outer_tx, outer_rx)
@@ -238,7 +310,7 @@ pub(crate) fn wrap_unbounded<T: Send + 'static>(
     source: &'static str,
     label: Option<String>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |_| None)
+    wrap_unbounded_impl(inner, source, label, log_formatter::<T>())
 }
 
 /// Wrap an unbounded crossbeam channel with logging enabled. Returns (outer_tx, outer_rx).
@@ -256,6 +328,7 @@ impl<T: Send + 'static> Instrument
     for (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>)
 {
     type Output = (crossbeam_channel::Sender<T>, crossbeam_channel::Receiver<T>);
+    type Payload = T;
     fn instrument(
         self,
         source: &'static str,
@@ -0,0 +1,13 @@
+use async_executor::Executor;
+use futures_lite::future;
+use std::sync::LazyLock;
+
+/// Executor driving the forwarders on a single background thread, so users of runtime-agnostic
+/// channels (e.g. smol or async-std applications) don't need a tokio runtime.
+pub(crate) static EXECUTOR: LazyLock<Executor<'static>> = LazyLock::new(|| {
+    std::thread::Builder::new()
+        .name("channels-console-executor".into())
+        .spawn(|| future::block_on(EXECUTOR.run(future::pending::<()>())))
+        .expect("Failed to spawn channels-console-executor thread");
+    Executor::new()
+});
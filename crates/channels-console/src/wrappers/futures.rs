@@ -1,19 +1,25 @@
 use futures_channel::mpsc;
 use futures_channel::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
 use futures_channel::oneshot;
+use futures_util::future::poll_fn;
 use futures_util::sink::SinkExt;
-use std::mem;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
-use crate::RT;
-use crate::{init_stats_state, ChannelType, StatsEvent, CHANNEL_ID_COUNTER};
+use crate::spawn_forwarder;
+use crate::{
+    channel_source, creation_backtrace, current_group, current_meta, init_stats_state,
+    log_formatter, log_gate, message_size, next_iter, payload_sizer, report_stranded,
+    report_unknown_capacity, ChannelType, CloseReason, StatsEvent, CHANNEL_ID_COUNTER,
+};
 
 /// Internal implementation for wrapping bounded futures channels with optional logging.
 fn wrap_channel_impl<T, F>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
     label: Option<String>,
-    capacity: usize,
+    capacity: Option<usize>,
     mut get_msg_log: F,
 ) -> (Sender<T>, Receiver<T>)
 where
@@ -22,23 +28,50 @@ where
 {
     let (mut inner_tx, mut inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
 
-    let (outer_tx, mut to_inner_rx) = mpsc::channel::<T>(capacity);
-    let (mut from_inner_tx, outer_rx) = mpsc::channel::<T>(capacity);
+    // Without a known capacity the outer proxy only hands messages over, and the inner
+    // channel's own capacity drives backpressure. Otherwise one message in flight through the
+    // inner channel and one in the inner proxy make up the rest. The proxy keeps a slot per
+    // sender, like the original, so it can go down to zero.
+    let proxy_size = capacity.map_or(0, |capacity| capacity.saturating_sub(2));
+    let (outer_tx, mut to_inner_rx) = mpsc::channel::<T>(proxy_size);
+    let (mut from_inner_tx, outer_rx) = mpsc::channel::<T>(0);
+
+    // Allow a single message in flight through the inner channel, so the outer proxy's
+    // capacity drives backpressure instead of adding to it
+    let in_flight = capacity.map(|_| Arc::new(Semaphore::new(1)));
+    let in_flight_recv = in_flight.clone();
 
     let (stats_tx, _) = init_stats_state();
 
     // Generate unique ID for this channel
     let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
 
+    let source = channel_source(source);
+    let task_name = label.as_deref().unwrap_or(&source).to_string();
     let _ = stats_tx.send(StatsEvent::Created {
         id,
-        source,
+        source: source.clone(),
         display_label: label,
-        channel_type: ChannelType::Bounded(capacity),
+        group: current_group(),
+        meta: current_meta(),
+        channel_type: ChannelType::Bounded(capacity.unwrap_or(0)),
         type_name,
-        type_size: mem::size_of::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
     });
+    match capacity {
+        Some(_) => {
+            let _ = stats_tx.send(StatsEvent::QueueCapped { id });
+        }
+        None => report_unknown_capacity(stats_tx, id, &source),
+    }
 
     let stats_tx_send = stats_tx.clone();
     let stats_tx_recv = stats_tx.clone();
@@ -47,69 +80,115 @@ where
     let (close_signal_tx, mut close_signal_rx) = tokio::sync::oneshot::channel::<()>();
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
+    spawn_forwarder(id, &task_name, "send", async move {
         use futures_util::stream::StreamExt;
-        loop {
+        let reason = loop {
+            if let Some(in_flight) = &in_flight {
+                tokio::select! {
+                    permit = in_flight.acquire() => {
+                        // The semaphore is never closed
+                        permit.expect("in-flight semaphore closed").forget();
+                    }
+                    _ = &mut close_signal_rx => {
+                        // Outer receiver was closed/dropped, close our receiver to reject further sends
+                        to_inner_rx.close();
+                        break CloseReason::ReceiverDropped;
+                    }
+                }
+            }
+
             tokio::select! {
                 msg = to_inner_rx.next() => {
                     match msg {
                         Some(msg) => {
-                            let log = get_msg_log(&msg);
+                            let log = if logging.should_log() {
+                                get_msg_log(&msg)
+                            } else {
+                                None
+                            };
+                            let size = payload_size.map(|size| size(&msg));
                             if inner_tx.send(msg).await.is_err() {
+                                let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
                                 to_inner_rx.close();
-                                break;
+                                break CloseReason::ForwardFailed;
                             }
                             let _ = stats_tx_send.send(StatsEvent::MessageSent {
                                 id,
                                 log,
+                                size,
                                 timestamp: std::time::Instant::now(),
                             });
                         }
-                        None => break, // Outer sender dropped
+                        None => break CloseReason::SenderDropped, // Outer sender dropped
                     }
                 }
                 _ = &mut close_signal_rx => {
                     // Outer receiver was closed/dropped, close our receiver to reject further sends
                     to_inner_rx.close();
-                    break;
+                    break CloseReason::ReceiverDropped;
                 }
             }
-        }
+        };
+        // Messages left in the proxy will never be forwarded
+        report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
         // Channel is closed
-        let _ = stats_tx_send.send(StatsEvent::Closed { id });
+        let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
     });
 
     // Forward inner -> outer (proxy the recv path)
-    RT.spawn(async move {
+    spawn_forwarder(id, &task_name, "recv", async move {
         use futures_util::stream::StreamExt;
-        while let Some(msg) = inner_rx.next().await {
-            if from_inner_tx.send(msg).await.is_ok() {
-                let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
-                    id,
-                    timestamp: std::time::Instant::now(),
-                });
-            } else {
+        let reason = loop {
+            // Wait for room in the outer receiver before pulling from the inner channel,
+            // so messages don't pile up in the forwarder while the consumer is busy
+            if poll_fn(|cx| from_inner_tx.poll_ready(cx)).await.is_err() {
                 // Outer receiver was closed
                 let _ = close_signal_tx.send(());
-                break;
+                break CloseReason::ReceiverDropped;
             }
-        }
+
+            match inner_rx.next().await {
+                Some(msg) => {
+                    if let Some(in_flight) = &in_flight_recv {
+                        in_flight.add_permits(1);
+                    }
+                    if from_inner_tx.start_send(msg).is_ok() {
+                        let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
+                            id,
+                            timestamp: std::time::Instant::now(),
+                        });
+                    } else {
+                        let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
+                        // Outer receiver was closed
+                        let _ = close_signal_tx.send(());
+                        break CloseReason::ReceiverDropped;
+                    }
+                }
+                None => break CloseReason::SenderDropped, // Inner sender dropped
+            }
+        };
+        // Messages left in the inner channel will never be received
+        inner_rx.close();
+        report_stranded(&stats_tx_recv, id, || inner_rx.try_recv().is_ok());
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(StatsEvent::Closed { id });
+        let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
     });
 
     (outer_tx, outer_rx)
 }
 
 /// Wrap the inner futures channel with proxy ends. Returns (outer_tx, outer_rx).
-/// All messages pass through the two forwarders.
+/// All messages pass through the two forwarders. Only one message at a time is forwarded
+/// through the inner channel, and the outer proxy is sized so `try_send` reports the channel
+/// full at the same count as the original.
+/// Without a capacity, messages are forwarded as long as the inner channel has room.
 pub(crate) fn wrap_channel<T: Send + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
     label: Option<String>,
-    capacity: usize,
+    capacity: Option<usize>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, capacity, |_| None)
+    wrap_channel_impl(inner, source, label, capacity, log_formatter::<T>())
 }
 
 /// Wrap a bounded futures channel with logging enabled. Returns (outer_tx, outer_rx).
@@ -117,7 +196,7 @@ pub(crate) fn wrap_channel_log<T: Send + std::fmt::Debug + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
     label: Option<String>,
-    capacity: usize,
+    capacity: Option<usize>,
 ) -> (Sender<T>, Receiver<T>) {
     wrap_channel_impl(inner, source, label, capacity, |msg| {
         Some(format!("{:?}", msg))
@@ -137,6 +216,7 @@ where
 {
     let (inner_tx, mut inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
 
     let (outer_tx, mut to_inner_rx) = mpsc::unbounded::<T>();
     let (from_inner_tx, outer_rx) = mpsc::unbounded::<T>();
@@ -145,14 +225,24 @@ where
 
     // Generate unique ID for this channel
     let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
 
+    let source = channel_source(source);
+    let task_name = label.as_deref().unwrap_or(&source).to_string();
     let _ = stats_tx.send(StatsEvent::Created {
         id,
-        source,
+        source: source.clone(),
         display_label: label,
+        group: current_group(),
+        meta: current_meta(),
         channel_type: ChannelType::Unbounded,
         type_name,
-        type_size: mem::size_of::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -162,41 +252,51 @@ where
     let (close_signal_tx, mut close_signal_rx) = tokio::sync::oneshot::channel::<()>();
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
+    spawn_forwarder(id, &task_name, "send", async move {
         use futures_util::stream::StreamExt;
-        loop {
+        let reason = loop {
             tokio::select! {
                 msg = to_inner_rx.next() => {
                     match msg {
                         Some(msg) => {
-                            let log = get_msg_log(&msg);
+                            let log = if logging.should_log() {
+                                get_msg_log(&msg)
+                            } else {
+                                None
+                            };
+                            let size = payload_size.map(|size| size(&msg));
                             if inner_tx.unbounded_send(msg).is_err() {
+                                let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
                                 to_inner_rx.close();
-                                break;
+                                break CloseReason::ForwardFailed;
                             }
                             let _ = stats_tx_send.send(StatsEvent::MessageSent {
                                 id,
                                 log,
+                                size,
                                 timestamp: std::time::Instant::now(),
                             });
                         }
-                        None => break, // Outer sender dropped
+                        None => break CloseReason::SenderDropped, // Outer sender dropped
                     }
                 }
                 _ = &mut close_signal_rx => {
                     // Outer receiver was closed/dropped, close our receiver to reject further sends
                     to_inner_rx.close();
-                    break;
+                    break CloseReason::ReceiverDropped;
                 }
             }
-        }
+        };
+        // Messages left in the proxy will never be forwarded
+        report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
         // Channel is closed
-        let _ = stats_tx_send.send(StatsEvent::Closed { id });
+        let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
     });
 
     // Forward inner -> outer (proxy the recv path)
-    RT.spawn(async move {
+    spawn_forwarder(id, &task_name, "recv", async move {
         use futures_util::stream::StreamExt;
+        let mut reason = CloseReason::SenderDropped;
         while let Some(msg) = inner_rx.next().await {
             if from_inner_tx.unbounded_send(msg).is_ok() {
                 let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
@@ -204,13 +304,18 @@ where
                     timestamp: std::time::Instant::now(),
                 });
             } else {
+                let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
                 // Outer receiver was closed
                 let _ = close_signal_tx.send(());
+                reason = CloseReason::ReceiverDropped;
                 break;
             }
         }
+        // Messages left in the inner channel will never be received
+        inner_rx.close();
+        report_stranded(&stats_tx_recv, id, || inner_rx.try_recv().is_ok());
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(StatsEvent::Closed { id });
+        let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
     });
 
     (outer_tx, outer_rx)
@@ -222,7 +327,7 @@ pub(crate) fn wrap_unbounded<T: Send + 'static>(
     source: &'static str,
     label: Option<String>,
 ) -> (UnboundedSender<T>, UnboundedReceiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |_| None)
+    wrap_unbounded_impl(inner, source, label, log_formatter::<T>())
 }
 
 /// Wrap an unbounded futures channel with logging enabled. Returns (outer_tx, outer_rx).
@@ -247,6 +352,7 @@ where
 {
     let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
 
     let (outer_tx, outer_rx_proxy) = oneshot::channel::<T>();
     let (inner_tx_proxy, outer_rx) = oneshot::channel::<T>();
@@ -255,14 +361,24 @@ where
 
     // Generate unique ID for this channel
     let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
 
+    let source = channel_source(source);
+    let task_name = label.as_deref().unwrap_or(&source).to_string();
     let _ = stats_tx.send(StatsEvent::Created {
         id,
-        source,
+        source: source.clone(),
         display_label: label,
+        group: current_group(),
+        meta: current_meta(),
         channel_type: ChannelType::Oneshot,
         type_name,
-        type_size: mem::size_of::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -272,9 +388,10 @@ where
     let (close_signal_tx, mut close_signal_rx) = tokio::sync::oneshot::channel::<()>();
 
     // Monitor outer receiver and drop inner receiver when outer is dropped
-    RT.spawn(async move {
+    spawn_forwarder(id, &task_name, "recv", async move {
         let mut inner_rx = Some(inner_rx);
         let mut message_received = false;
+        let mut reason = CloseReason::SenderDropped;
         tokio::select! {
             msg = async { inner_rx.take().unwrap().await }, if inner_rx.is_some() => {
                 // Message received from inner
@@ -286,6 +403,9 @@ where
                                 timestamp: std::time::Instant::now(),
                             });
                             message_received = true;
+                        } else {
+                            let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
+                            reason = CloseReason::ReceiverDropped;
                         }
                     }
                     Err(_) => {
@@ -305,44 +425,57 @@ where
                 // Outer receiver was dropped - drop inner_rx to make sends fail
                 drop(inner_rx);
                 let _ = close_signal_tx.send(());
+                reason = CloseReason::ReceiverDropped;
             }
         }
         // Only send Closed if message was not successfully received
         if !message_received {
-            let _ = stats_tx_recv.send(StatsEvent::Closed { id });
+            let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
         }
     });
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
+    spawn_forwarder(id, &task_name, "send", async move {
         let mut message_sent = false;
+        let mut reason = CloseReason::SenderDropped;
         tokio::select! {
             msg = outer_rx_proxy => {
                 match msg {
                     Ok(msg) => {
-                        let log = get_msg_log(&msg);
+                        let log = if logging.should_log() {
+                            get_msg_log(&msg)
+                        } else {
+                            None
+                        };
+                        let size = payload_size.map(|size| size(&msg));
                         if inner_tx.send(msg).is_ok() {
                             let _ = stats_tx_send.send(StatsEvent::MessageSent {
                                 id,
                                 log,
+                                size,
                                 timestamp: std::time::Instant::now(),
                             });
                             let _ = stats_tx_send.send(StatsEvent::Notified { id });
                             message_sent = true;
+                        } else {
+                            let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
+                            reason = CloseReason::ForwardFailed;
                         }
                     }
                     Err(_) => {
                         // Outer sender was dropped without sending
+                        let _ = stats_tx_send.send(StatsEvent::Cancelled { id });
                     }
                 }
             }
             _ = &mut close_signal_rx => {
                 // Outer receiver was closed/dropped before send
+                reason = CloseReason::ReceiverDropped;
             }
         }
         // Only send Closed if message was not successfully sent
         if !message_sent {
-            let _ = stats_tx_send.send(StatsEvent::Closed { id });
+            let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
         }
     });
 
@@ -355,7 +488,7 @@ pub(crate) fn wrap_oneshot<T: Send + 'static>(
     source: &'static str,
     label: Option<String>,
 ) -> (oneshot::Sender<T>, oneshot::Receiver<T>) {
-    wrap_oneshot_impl(inner, source, label, |_| None)
+    wrap_oneshot_impl(inner, source, label, log_formatter::<T>())
 }
 
 /// Wrap a oneshot futures channel with logging enabled. Returns (outer_tx, outer_rx).
@@ -369,6 +502,10 @@ pub(crate) fn wrap_oneshot_log<T: Send + std::fmt::Debug + 'static>(
 
 use crate::Instrument;
 
+/// `futures_channel::mpsc::channel(buffer)` holds up to `buffer` messages plus one per
+/// `Sender`, so senders can get further ahead than the `capacity` passed to `instrument!`.
+/// The sender clones can't be counted, so the queue is capped at `capacity` instead,
+/// and a channel with more messages in flight is shown as full.
 impl<T: Send + 'static> Instrument
     for (
         futures_channel::mpsc::Sender<T>,
@@ -379,16 +516,14 @@ impl<T: Send + 'static> Instrument
         futures_channel::mpsc::Sender<T>,
         futures_channel::mpsc::Receiver<T>,
     );
+    type Payload = T;
     fn instrument(
         self,
         source: &'static str,
         label: Option<String>,
         capacity: Option<usize>,
     ) -> Self::Output {
-        if capacity.is_none() {
-            panic!("Capacity is required for bounded futures channels, because they don't expose their capacity in a public API");
-        }
-        wrap_channel(self, source, label, capacity.unwrap())
+        wrap_channel(self, source, label, capacity)
     }
 }
 
@@ -402,6 +537,7 @@ impl<T: Send + 'static> Instrument
         futures_channel::mpsc::UnboundedSender<T>,
         futures_channel::mpsc::UnboundedReceiver<T>,
     );
+    type Payload = T;
     fn instrument(
         self,
         source: &'static str,
@@ -422,6 +558,7 @@ impl<T: Send + 'static> Instrument
         futures_channel::oneshot::Sender<T>,
         futures_channel::oneshot::Receiver<T>,
     );
+    type Payload = T;
     fn instrument(
         self,
         source: &'static str,
@@ -450,10 +587,7 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
         label: Option<String>,
         capacity: Option<usize>,
     ) -> Self::Output {
-        if capacity.is_none() {
-            panic!("Capacity is required for bounded futures channels, because they don't expose their capacity in a public API");
-        }
-        wrap_channel_log(self, source, label, capacity.unwrap())
+        wrap_channel_log(self, source, label, capacity)
     }
 }
 
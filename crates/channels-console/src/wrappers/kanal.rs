@@ -0,0 +1,270 @@
+use kanal::{AsyncReceiver, AsyncSender, ReceiveErrorTimeout, Receiver, Sender};
+use std::sync::atomic::Ordering;
+
+use crate::{
+    channel_source, creation_backtrace, current_group, current_meta, init_stats_state,
+    log_formatter, log_gate, message_size, next_iter, payload_sizer, proxy_capacity,
+    report_stranded, ChannelType, CloseReason, StatsEvent, CHANNEL_ID_COUNTER,
+};
+
+/// Capacity of a kanal channel, `None` for unbounded ones, which report `usize::MAX`
+fn capacity<T>(tx: &Sender<T>) -> Option<usize> {
+    Some(tx.capacity()).filter(|&capacity| capacity != usize::MAX)
+}
+
+/// Internal implementation for wrapping kanal channels with optional logging.
+fn wrap_channel_impl<T, F>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    mut log_on_send: F,
+) -> (Sender<T>, Receiver<T>)
+where
+    T: Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let (inner_tx, inner_rx) = inner;
+    let capacity = capacity(&inner_tx);
+    let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
+
+    let (outer_tx, to_inner_rx) = match capacity {
+        // One message in flight through the inner channel and one waiting for the outer
+        // receiver make up the rest of the original capacity
+        Some(capacity) => kanal::bounded::<T>(proxy_capacity(capacity, 2)),
+        None => kanal::unbounded::<T>(),
+    };
+    let (from_inner_tx, outer_rx) = match capacity {
+        Some(_) => kanal::bounded::<T>(0),
+        None => kanal::unbounded::<T>(),
+    };
+
+    // Allow a single message in flight through a bounded inner channel, so the outer
+    // proxy's capacity drives backpressure instead of adding to it
+    let limit_in_flight = capacity.is_some();
+    let (in_flight_tx, in_flight_rx) = kanal::bounded::<()>(1);
+
+    let (stats_tx, _) = init_stats_state();
+
+    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
+
+    let source = channel_source(source);
+    let _ = stats_tx.send(StatsEvent::Created {
+        id,
+        source: source.clone(),
+        display_label: label,
+        group: current_group(),
+        meta: current_meta(),
+        channel_type: capacity.map_or(ChannelType::Unbounded, ChannelType::Bounded),
+        type_name,
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
+    });
+
+    let stats_tx_send = stats_tx.clone();
+    let stats_tx_recv = stats_tx.clone();
+
+    // Create a signal channel to notify send-forwarder when outer_rx is closed. Closing the
+    // proxy instead would clear the messages still queued in it.
+    let (close_signal_tx, close_signal_rx) = kanal::bounded::<()>(1);
+
+    // Forward outer -> inner (proxy the send path)
+    std::thread::spawn(move || {
+        let mut has_in_flight_slot = !limit_in_flight;
+        let reason = loop {
+            // Check for close signal (non-blocking)
+            match close_signal_rx.try_recv() {
+                Ok(Some(())) => {
+                    // Outer receiver was closed/dropped
+                    break CloseReason::ReceiverDropped;
+                }
+                Err(_) => {
+                    // Close signal sender dropped, which means recv forwarder ended
+                    break CloseReason::ReceiverDropped;
+                }
+                Ok(None) => {
+                    // No close signal, continue
+                }
+            }
+
+            // Wait until the previous message left the inner channel
+            if !has_in_flight_slot {
+                if in_flight_tx.send(()).is_err() {
+                    // Recv forwarder ended
+                    break CloseReason::ReceiverDropped;
+                }
+                has_in_flight_slot = true;
+            }
+
+            // Try to receive with timeout to periodically check close signal
+            match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
+                Ok(msg) => {
+                    has_in_flight_slot = !limit_in_flight;
+                    let log = if logging.should_log() {
+                        log_on_send(&msg)
+                    } else {
+                        None
+                    };
+                    let size = payload_size.map(|size| size(&msg));
+                    if inner_tx.send(msg).is_err() {
+                        let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
+                        // Inner receiver dropped
+                        break CloseReason::ForwardFailed;
+                    }
+                    let _ = stats_tx_send.send(StatsEvent::MessageSent {
+                        id,
+                        log,
+                        size,
+                        timestamp: std::time::Instant::now(),
+                    });
+                }
+                Err(ReceiveErrorTimeout::Timeout) => {
+                    // No message, loop again to check close signal
+                    continue;
+                }
+                Err(_) => {
+                    // Outer senders dropped
+                    break CloseReason::SenderDropped;
+                }
+            }
+        };
+        // Messages left in the proxy will never be forwarded
+        report_stranded(&stats_tx_send, id, || {
+            matches!(to_inner_rx.try_recv(), Ok(Some(_)))
+        });
+        // Channel is closed
+        let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
+    });
+
+    // Forward inner -> outer (proxy the recv path)
+    std::thread::spawn(move || {
+        let mut reason = CloseReason::SenderDropped;
+        while let Ok(msg) = inner_rx.recv() {
+            if limit_in_flight {
+                let _ = in_flight_rx.recv();
+            }
+            if from_inner_tx.send(msg).is_err() {
+                let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
+                // Outer receiver was closed
+                let _ = close_signal_tx.send(());
+                reason = CloseReason::ReceiverDropped;
+                break;
+            }
+            let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
+                id,
+                timestamp: std::time::Instant::now(),
+            });
+        }
+        // Messages left in the inner channel will never be received
+        report_stranded(&stats_tx_recv, id, || {
+            matches!(inner_rx.try_recv(), Ok(Some(_)))
+        });
+        // Channel is closed (either inner sender dropped or outer receiver closed)
+        let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
+    });
+
+    (outer_tx, outer_rx)
+}
+
+/// Wrap a kanal channel with proxy ends. Returns (outer_tx, outer_rx).
+/// All messages pass through the two forwarders running in separate threads. For bounded
+/// channels only one message at a time is forwarded through the inner channel, and the outer
+/// proxy is sized so `try_send` reports the channel full at its original capacity, or at 3
+/// messages for smaller channels, as the proxy keeps a slot.
+pub(crate) fn wrap_channel<T: Send + 'static>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (Sender<T>, Receiver<T>) {
+    wrap_channel_impl(inner, source, label, log_formatter::<T>())
+}
+
+/// Wrap a kanal channel with logging enabled. Returns (outer_tx, outer_rx).
+pub(crate) fn wrap_channel_log<T: Send + std::fmt::Debug + 'static>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (Sender<T>, Receiver<T>) {
+    wrap_channel_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+}
+
+/// Wrap an async kanal channel with proxy ends. kanal channels can be used from sync and async
+/// code alike, so the forwarders are the threads of the sync wrapper and no runtime is needed.
+pub(crate) fn wrap_async_channel<T: Send + 'static>(
+    inner: (AsyncSender<T>, AsyncReceiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (AsyncSender<T>, AsyncReceiver<T>) {
+    let (tx, rx) = wrap_channel((inner.0.to_sync(), inner.1.to_sync()), source, label);
+    (tx.to_async(), rx.to_async())
+}
+
+/// Wrap an async kanal channel with logging enabled. Returns (outer_tx, outer_rx).
+pub(crate) fn wrap_async_channel_log<T: Send + std::fmt::Debug + 'static>(
+    inner: (AsyncSender<T>, AsyncReceiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (AsyncSender<T>, AsyncReceiver<T>) {
+    let (tx, rx) = wrap_channel_log((inner.0.to_sync(), inner.1.to_sync()), source, label);
+    (tx.to_async(), rx.to_async())
+}
+
+use crate::Instrument;
+
+impl<T: Send + 'static> Instrument for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    type Payload = T;
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        // Bounded and unbounded channels share the same types, the capacity tells them apart
+        wrap_channel(self, source, label)
+    }
+}
+
+impl<T: Send + 'static> Instrument for (AsyncSender<T>, AsyncReceiver<T>) {
+    type Output = (AsyncSender<T>, AsyncReceiver<T>);
+    type Payload = T;
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_async_channel(self, source, label)
+    }
+}
+
+use crate::InstrumentLog;
+
+impl<T: Send + std::fmt::Debug + 'static> InstrumentLog for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_channel_log(self, source, label)
+    }
+}
+
+impl<T: Send + std::fmt::Debug + 'static> InstrumentLog for (AsyncSender<T>, AsyncReceiver<T>) {
+    type Output = (AsyncSender<T>, AsyncReceiver<T>);
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_async_channel_log(self, source, label)
+    }
+}
@@ -0,0 +1,164 @@
+use crossbeam_channel::{unbounded, RecvTimeoutError, Sender};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Channels whose forwarder gets a thread of its own, which blocks while the channel is empty.
+/// Forwarders of any further channels share the pool threads.
+const DEDICATED_THREADS: usize = 32;
+
+/// Threads shared by the forwarders of std channels past `DEDICATED_THREADS`, however many are
+/// instrumented.
+const POOL_THREADS: usize = 2;
+
+/// Idle passes over all forwarders before a pool thread starts sleeping between passes.
+const SPIN_PASSES: u32 = 16;
+
+/// Longest sleep between passes, which bounds the latency a message picks up on an idle pool.
+const MAX_IDLE_SLEEP: Duration = Duration::from_millis(1);
+
+/// Polls a forwarder gets in each pass to drain a burst, before it's the next one's turn.
+const MAX_POLLS_PER_PASS: usize = 64;
+
+/// Outcome of polling a forwarder once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Poll {
+    /// Moved a message, so polling again right away may move another one.
+    Progress,
+    /// Nothing to move until one of the channel's ends acts.
+    Idle,
+    /// The channel closed and the forwarder is done.
+    Done,
+}
+
+/// Forwards messages of one channel. Polling never blocks, so a pool thread can drive many.
+pub(crate) trait Forward: Send {
+    fn poll(&mut self) -> Poll;
+
+    /// Blocks until a message arrives while the channel holds none, as nothing else can move it
+    /// along then. Returns `false` right away when messages are waiting on one of its ends.
+    fn wait(&mut self) -> bool;
+}
+
+/// Forwarders currently running on a thread of their own.
+static DEDICATED: AtomicUsize = AtomicUsize::new(0);
+
+struct Pool {
+    threads: Vec<Sender<Box<dyn Forward>>>,
+    next: AtomicUsize,
+}
+
+/// Forwarder threads of std channels past `DEDICATED_THREADS`. std receivers can't be waited on
+/// together, so each thread polls its forwarders in turn, and sleeps for up to `MAX_IDLE_SLEEP`
+/// once all of them are idle. Nothing wakes a thread when a message arrives, so it never blocks
+/// while it has forwarders.
+static POOL: LazyLock<Pool> = LazyLock::new(|| Pool {
+    threads: (0..POOL_THREADS).map(spawn_pool_thread).collect(),
+    next: AtomicUsize::new(0),
+});
+
+fn spawn_pool_thread(index: usize) -> Sender<Box<dyn Forward>> {
+    let (tx, rx) = unbounded::<Box<dyn Forward>>();
+    std::thread::Builder::new()
+        .name(format!("channels-console-forwarder-{}", index))
+        .spawn(move || {
+            let mut forwarders: Vec<Box<dyn Forward>> = Vec::new();
+            let mut idle_passes = 0;
+            loop {
+                // Nothing to poll, wait for the next channel
+                if forwarders.is_empty() {
+                    match rx.recv() {
+                        Ok(forwarder) => forwarders.push(forwarder),
+                        Err(_) => return,
+                    }
+                }
+                forwarders.extend(rx.try_iter());
+
+                let mut progress = false;
+                forwarders.retain_mut(|forwarder| {
+                    for _ in 0..MAX_POLLS_PER_PASS {
+                        match forwarder.poll() {
+                            Poll::Progress => progress = true,
+                            Poll::Idle => return true,
+                            Poll::Done => return false,
+                        }
+                    }
+                    true
+                });
+
+                if progress {
+                    idle_passes = 0;
+                    continue;
+                }
+                idle_passes += 1;
+                let Some(sleep) = idle_pause(idle_passes) else {
+                    std::thread::yield_now();
+                    continue;
+                };
+                // Back off, waking up early for a new channel
+                match rx.recv_timeout(sleep) {
+                    Ok(forwarder) => forwarders.push(forwarder),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        })
+        .expect("Failed to spawn channels-console-forwarder thread");
+    tx
+}
+
+/// Sleep before the next pass once `idle_passes` passes in a row moved nothing, `None` while
+/// only yielding.
+fn idle_pause(idle_passes: u32) -> Option<Duration> {
+    (idle_passes > SPIN_PASSES).then(|| {
+        Duration::from_micros(10 << (idle_passes - SPIN_PASSES).min(7)).min(MAX_IDLE_SLEEP)
+    })
+}
+
+/// Runs a single forwarder, blocking while its channel is empty so an idle channel costs no
+/// wakeups and its next message no latency. Messages waiting on the consumer are polled for as
+/// in the pool.
+fn spawn_dedicated_thread(mut forwarder: Box<dyn Forward>) {
+    std::thread::Builder::new()
+        .name("channels-console-forwarder".into())
+        .spawn(move || {
+            let mut idle_passes = 0;
+            loop {
+                match forwarder.poll() {
+                    Poll::Progress => {
+                        idle_passes = 0;
+                        continue;
+                    }
+                    Poll::Idle => {}
+                    Poll::Done => break,
+                }
+                if forwarder.wait() {
+                    idle_passes = 0;
+                    continue;
+                }
+                idle_passes += 1;
+                match idle_pause(idle_passes) {
+                    Some(sleep) => std::thread::sleep(sleep),
+                    None => std::thread::yield_now(),
+                }
+            }
+            DEDICATED.fetch_sub(1, Ordering::Relaxed);
+        })
+        .expect("Failed to spawn channels-console-forwarder thread");
+}
+
+/// Starts a thread for the forwarder while fewer than `DEDICATED_THREADS` channels have one,
+/// and hands it to the pool otherwise, spreading channels over the pool threads in turn.
+pub(crate) fn spawn_forwarder(forwarder: impl Forward + 'static) {
+    let dedicated = DEDICATED
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |running| {
+            (running < DEDICATED_THREADS).then_some(running + 1)
+        })
+        .is_ok();
+    if dedicated {
+        spawn_dedicated_thread(Box::new(forwarder));
+        return;
+    }
+    let next = POOL.next.fetch_add(1, Ordering::Relaxed) % POOL.threads.len();
+    let _ = POOL.threads[next].send(Box::new(forwarder));
+}
@@ -0,0 +1,339 @@
+use postage::mpsc::{self, Receiver, Sender};
+use postage::oneshot;
+use postage::prelude::{Sink, Stream};
+use std::sync::atomic::Ordering;
+
+use super::executor::EXECUTOR;
+use crate::{
+    channel_source, creation_backtrace, current_group, current_meta, init_stats_state,
+    log_formatter, log_gate, message_size, next_iter, payload_sizer, proxy_capacity,
+    report_stranded, ChannelType, CloseReason, StatsEvent, CHANNEL_ID_COUNTER,
+};
+
+/// Internal implementation for wrapping postage mpsc channels with optional logging.
+fn wrap_channel_impl<T, F>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    capacity: usize,
+    mut log_on_send: F,
+) -> (Sender<T>, Receiver<T>)
+where
+    T: Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let (mut inner_tx, mut inner_rx) = inner;
+    let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
+
+    // One message in flight through the inner channel and one in the inner proxy make up the
+    // rest of the original capacity
+    let (outer_tx, mut to_inner_rx) = mpsc::channel::<T>(proxy_capacity(capacity, 2));
+    // postage doesn't support zero capacity, so hand messages over one at a time
+    let (mut from_inner_tx, outer_rx) = mpsc::channel::<T>(1);
+
+    // Allow a single message in flight through the inner channel, so the outer proxy's
+    // capacity drives backpressure instead of adding to it
+    let (mut in_flight_tx, mut in_flight_rx) = mpsc::channel::<()>(1);
+
+    let (stats_tx, _) = init_stats_state();
+
+    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
+
+    let source = channel_source(source);
+    let _ = stats_tx.send(StatsEvent::Created {
+        id,
+        source: source.clone(),
+        display_label: label,
+        group: current_group(),
+        meta: current_meta(),
+        channel_type: ChannelType::Bounded(capacity),
+        type_name,
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
+    });
+
+    let stats_tx_send = stats_tx.clone();
+    let stats_tx_recv = stats_tx.clone();
+
+    // Forward outer -> inner (proxy the send path)
+    EXECUTOR
+        .spawn(async move {
+            let reason = loop {
+                // Wait until the previous message left the inner channel
+                if in_flight_tx.send(()).await.is_err() {
+                    // Recv forwarder ended
+                    break CloseReason::ReceiverDropped;
+                }
+
+                match to_inner_rx.recv().await {
+                    Some(msg) => {
+                        let log = if logging.should_log() {
+                            log_on_send(&msg)
+                        } else {
+                            None
+                        };
+                        let size = payload_size.map(|size| size(&msg));
+                        if inner_tx.send(msg).await.is_err() {
+                            let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
+                            // Inner receiver dropped
+                            break CloseReason::ForwardFailed;
+                        }
+                        let _ = stats_tx_send.send(StatsEvent::MessageSent {
+                            id,
+                            log,
+                            size,
+                            timestamp: std::time::Instant::now(),
+                        });
+                    }
+                    None => break CloseReason::SenderDropped, // Outer senders dropped
+                }
+            };
+            // Messages left in the proxy will never be forwarded
+            report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
+            // Channel is closed
+            let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
+        })
+        .detach();
+
+    // Forward inner -> outer (proxy the recv path). postage senders can't wait for their
+    // receiver to be dropped, so that's only noticed when forwarding the next message.
+    EXECUTOR
+        .spawn(async move {
+            let mut reason = CloseReason::SenderDropped;
+            while let Some(msg) = inner_rx.recv().await {
+                if from_inner_tx.send(msg).await.is_err() {
+                    let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
+                    // Outer receiver was dropped
+                    reason = CloseReason::ReceiverDropped;
+                    break;
+                }
+                let _ = in_flight_rx.recv().await;
+                let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
+                    id,
+                    timestamp: std::time::Instant::now(),
+                });
+            }
+            // Messages left in the inner channel will never be received
+            report_stranded(&stats_tx_recv, id, || inner_rx.try_recv().is_ok());
+            // Channel is closed (either inner sender dropped or outer receiver dropped)
+            let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
+        })
+        .detach();
+
+    (outer_tx, outer_rx)
+}
+
+/// Wrap a postage mpsc channel with proxy ends. Returns (outer_tx, outer_rx).
+/// All messages pass through the two forwarders running on a background executor. Only one
+/// message at a time is forwarded through the inner channel, and the outer proxy is sized so
+/// `try_send` reports the channel full at its original capacity, or at 3 messages for smaller
+/// channels, as the proxy keeps a slot.
+pub(crate) fn wrap_channel<T: Send + 'static>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    capacity: usize,
+) -> (Sender<T>, Receiver<T>) {
+    wrap_channel_impl(inner, source, label, capacity, log_formatter::<T>())
+}
+
+/// Wrap a postage mpsc channel with logging enabled. Returns (outer_tx, outer_rx).
+pub(crate) fn wrap_channel_log<T: Send + std::fmt::Debug + 'static>(
+    inner: (Sender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    capacity: usize,
+) -> (Sender<T>, Receiver<T>) {
+    wrap_channel_impl(inner, source, label, capacity, |msg| {
+        Some(format!("{:?}", msg))
+    })
+}
+
+/// Internal implementation for wrapping postage oneshot channels with optional logging.
+fn wrap_oneshot_impl<T, F>(
+    inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    mut log_on_send: F,
+) -> (oneshot::Sender<T>, oneshot::Receiver<T>)
+where
+    T: Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let (mut inner_tx, mut inner_rx) = inner;
+    let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
+
+    let (outer_tx, mut outer_rx_proxy) = oneshot::channel::<T>();
+    let (mut inner_tx_proxy, outer_rx) = oneshot::channel::<T>();
+
+    let (stats_tx, _) = init_stats_state();
+
+    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
+
+    let source = channel_source(source);
+    let _ = stats_tx.send(StatsEvent::Created {
+        id,
+        source: source.clone(),
+        display_label: label,
+        group: current_group(),
+        meta: current_meta(),
+        channel_type: ChannelType::Oneshot,
+        type_name,
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
+    });
+
+    let stats_tx_send = stats_tx.clone();
+    let stats_tx_recv = stats_tx;
+
+    // Forward inner -> outer (proxy the recv path)
+    EXECUTOR
+        .spawn(async move {
+            let reason = match inner_rx.recv().await {
+                Some(msg) => {
+                    if inner_tx_proxy.send(msg).await.is_ok() {
+                        let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
+                            id,
+                            timestamp: std::time::Instant::now(),
+                        });
+                        // Delivered, the channel stays notified
+                        return;
+                    }
+                    let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
+                    CloseReason::ReceiverDropped
+                }
+                // Inner sender was dropped without sending
+                None => CloseReason::SenderDropped,
+            };
+            let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
+        })
+        .detach();
+
+    // Forward outer -> inner (proxy the send path)
+    EXECUTOR
+        .spawn(async move {
+            let reason = match outer_rx_proxy.recv().await {
+                Some(msg) => {
+                    let log = if logging.should_log() {
+                        log_on_send(&msg)
+                    } else {
+                        None
+                    };
+                    let size = payload_size.map(|size| size(&msg));
+                    if inner_tx.send(msg).await.is_ok() {
+                        let _ = stats_tx_send.send(StatsEvent::MessageSent {
+                            id,
+                            log,
+                            size,
+                            timestamp: std::time::Instant::now(),
+                        });
+                        let _ = stats_tx_send.send(StatsEvent::Notified { id });
+                        return;
+                    }
+                    let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
+                    CloseReason::ForwardFailed
+                }
+                None => {
+                    // Outer sender was dropped without sending
+                    let _ = stats_tx_send.send(StatsEvent::Cancelled { id });
+                    CloseReason::SenderDropped
+                }
+            };
+            let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
+        })
+        .detach();
+
+    (outer_tx, outer_rx)
+}
+
+/// Wrap a postage oneshot channel with proxy ends. Returns (outer_tx, outer_rx).
+pub(crate) fn wrap_oneshot<T: Send + 'static>(
+    inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (oneshot::Sender<T>, oneshot::Receiver<T>) {
+    wrap_oneshot_impl(inner, source, label, log_formatter::<T>())
+}
+
+/// Wrap a postage oneshot channel with logging enabled. Returns (outer_tx, outer_rx).
+pub(crate) fn wrap_oneshot_log<T: Send + std::fmt::Debug + 'static>(
+    inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+) -> (oneshot::Sender<T>, oneshot::Receiver<T>) {
+    wrap_oneshot_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+}
+
+use crate::Instrument;
+
+impl<T: Send + 'static> Instrument for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    type Payload = T;
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output {
+        if capacity.is_none() {
+            panic!("Capacity is required for postage mpsc channels, because they don't expose their capacity in a public API");
+        }
+        wrap_channel(self, source, label, capacity.unwrap())
+    }
+}
+
+impl<T: Send + 'static> Instrument for (oneshot::Sender<T>, oneshot::Receiver<T>) {
+    type Output = (oneshot::Sender<T>, oneshot::Receiver<T>);
+    type Payload = T;
+    fn instrument(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_oneshot(self, source, label)
+    }
+}
+
+use crate::InstrumentLog;
+
+impl<T: Send + std::fmt::Debug + 'static> InstrumentLog for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        capacity: Option<usize>,
+    ) -> Self::Output {
+        if capacity.is_none() {
+            panic!("Capacity is required for postage mpsc channels, because they don't expose their capacity in a public API");
+        }
+        wrap_channel_log(self, source, label, capacity.unwrap())
+    }
+}
+
+impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
+    for (oneshot::Sender<T>, oneshot::Receiver<T>)
+{
+    type Output = (oneshot::Sender<T>, oneshot::Receiver<T>);
+    fn instrument_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        _capacity: Option<usize>,
+    ) -> Self::Output {
+        wrap_oneshot_log(self, source, label)
+    }
+}
@@ -1,122 +1,319 @@
-use std::mem;
+use crossbeam_channel::Sender as CbSender;
 use std::sync::atomic::Ordering;
-use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryRecvError, TrySendError};
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::pool::{spawn_forwarder, Forward, Poll};
+use crate::{
+    channel_source, creation_backtrace, current_group, current_meta, init_stats_state,
+    log_formatter, log_gate, message_size, next_iter, payload_sizer, proxy_capacity,
+    report_stranded, report_unknown_capacity, ChannelType, CloseReason, LogGate, StatsEvent,
+    CHANNEL_ID_COUNTER,
+};
+
+/// Non-blocking send of both std sender flavors.
+trait TrySend<T>: Send {
+    fn try_send(&self, msg: T) -> Result<(), TrySendError<T>>;
+}
 
-use crate::{init_stats_state, ChannelType, StatsEvent, CHANNEL_ID_COUNTER};
+impl<T: Send> TrySend<T> for Sender<T> {
+    fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
+        self.send(msg)
+            .map_err(|mpsc::SendError(msg)| TrySendError::Disconnected(msg))
+    }
+}
 
-/// Internal implementation for wrapping bounded std channels with optional logging.
-fn wrap_sync_channel_impl<T, F>(
-    inner: (SyncSender<T>, Receiver<T>),
+impl<T: Send> TrySend<T> for SyncSender<T> {
+    fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
+        SyncSender::try_send(self, msg)
+    }
+}
+
+/// A message taken from the outer proxy, with its log entry and size.
+struct Taken<T> {
+    msg: T,
+    log: Option<String>,
+    size: Option<u64>,
+}
+
+/// Moves messages of one channel from the outer proxy through the inner channel to the outer
+/// receiver, on a thread of its own or of the shared forwarder pool.
+struct StdForwarder<T, S, F> {
+    id: u64,
+    stats_tx: CbSender<StatsEvent>,
+    proxy_rx: Receiver<T>,
+    /// `None` once the inner channel turned out to be a rendezvous one, which never accepts a
+    /// message without a blocked receiver, so messages skip it instead.
+    inner_tx: Option<S>,
+    inner_rx: Receiver<T>,
+    outer_tx: S,
+    /// Allow a single message in flight through the inner channel, so the outer proxy's
+    /// capacity drives backpressure instead of adding to it
+    limit_in_flight: bool,
+    in_inner: usize,
+    /// Taken from the proxy, waiting for room in the inner channel
+    to_inner: Option<Taken<T>>,
+    /// Taken from the inner channel, waiting for room in the outer one
+    to_outer: Option<T>,
+    senders_dropped: bool,
+    logging: Arc<LogGate>,
+    log_on_send: F,
+    payload_size: Option<fn(&T) -> u64>,
+}
+
+impl<T, S, F> StdForwarder<T, S, F>
+where
+    T: Send,
+    S: TrySend<T>,
+    F: FnMut(&T) -> Option<String> + Send,
+{
+    fn report_sent(&self, log: Option<String>, size: Option<u64>) {
+        let _ = self.stats_tx.send(StatsEvent::MessageSent {
+            id: self.id,
+            log,
+            size,
+            timestamp: Instant::now(),
+        });
+    }
+
+    fn take(&mut self, msg: T) -> Taken<T> {
+        let log = if self.logging.should_log() {
+            (self.log_on_send)(&msg)
+        } else {
+            None
+        };
+        let size = self.payload_size.map(|size| size(&msg));
+        Taken { msg, log, size }
+    }
+
+    fn close(&mut self, reason: CloseReason) -> Poll {
+        if reason == CloseReason::ReceiverDropped {
+            // Messages left anywhere in the proxies will never be received
+            if self.to_inner.take().is_some() {
+                let _ = self
+                    .stats_tx
+                    .send(StatsEvent::MessageDropped { id: self.id });
+            }
+            report_stranded(&self.stats_tx, self.id, || self.proxy_rx.try_recv().is_ok());
+            report_stranded(&self.stats_tx, self.id, || self.inner_rx.try_recv().is_ok());
+        }
+        let _ = self.stats_tx.send(StatsEvent::Closed {
+            id: self.id,
+            reason,
+        });
+        Poll::Done
+    }
+}
+
+impl<T, S, F> Forward for StdForwarder<T, S, F>
+where
+    T: Send,
+    S: TrySend<T>,
+    F: FnMut(&T) -> Option<String> + Send,
+{
+    fn poll(&mut self) -> Poll {
+        let mut progress = Poll::Idle;
+
+        // Forward inner -> outer (proxy the recv path)
+        if self.to_outer.is_none() {
+            if let Ok(msg) = self.inner_rx.try_recv() {
+                self.in_inner -= 1;
+                self.to_outer = Some(msg);
+            }
+        }
+        if let Some(msg) = self.to_outer.take() {
+            match self.outer_tx.try_send(msg) {
+                Ok(()) => {
+                    let _ = self.stats_tx.send(StatsEvent::MessageReceived {
+                        id: self.id,
+                        timestamp: Instant::now(),
+                    });
+                    progress = Poll::Progress;
+                }
+                Err(TrySendError::Full(msg)) => self.to_outer = Some(msg),
+                Err(TrySendError::Disconnected(_)) => {
+                    let _ = self
+                        .stats_tx
+                        .send(StatsEvent::MessageDropped { id: self.id });
+                    // Outer receiver was closed
+                    return self.close(CloseReason::ReceiverDropped);
+                }
+            }
+        }
+
+        // Forward outer -> inner (proxy the send path), once the previous message left the
+        // inner channel
+        let has_in_flight_slot = !self.limit_in_flight || self.in_inner == 0;
+        if self.to_inner.is_none() && !self.senders_dropped && has_in_flight_slot {
+            match self.proxy_rx.try_recv() {
+                Ok(msg) => self.to_inner = Some(self.take(msg)),
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.senders_dropped = true,
+            }
+        }
+        if let Some(Taken { msg, log, size }) = self.to_inner.take() {
+            let msg = match &self.inner_tx {
+                Some(inner_tx) => match inner_tx.try_send(msg) {
+                    Ok(()) => {
+                        self.in_inner += 1;
+                        self.report_sent(log, size);
+                        return Poll::Progress;
+                    }
+                    // An empty channel without room has no capacity at all
+                    Err(TrySendError::Full(msg)) if self.in_inner == 0 => {
+                        self.inner_tx = None;
+                        msg
+                    }
+                    Err(TrySendError::Full(msg)) => {
+                        self.to_inner = Some(Taken { msg, log, size });
+                        return progress;
+                    }
+                    // The inner receiver is held right here
+                    Err(TrySendError::Disconnected(_)) => unreachable!(),
+                },
+                None => msg,
+            };
+            if self.to_outer.is_none() {
+                self.to_outer = Some(msg);
+                self.report_sent(log, size);
+                return Poll::Progress;
+            }
+            self.to_inner = Some(Taken { msg, log, size });
+            return progress;
+        }
+
+        if self.senders_dropped && self.in_inner == 0 && self.to_outer.is_none() {
+            // Outer senders dropped and every message was delivered
+            return self.close(CloseReason::SenderDropped);
+        }
+        progress
+    }
+
+    fn wait(&mut self) -> bool {
+        let empty = self.to_inner.is_none() && self.to_outer.is_none() && self.in_inner == 0;
+        if !empty || self.senders_dropped {
+            return false;
+        }
+        match self.proxy_rx.recv() {
+            Ok(msg) => self.to_inner = Some(self.take(msg)),
+            Err(_) => self.senders_dropped = true,
+        }
+        true
+    }
+}
+
+/// Registers a std channel and starts its forwarder. Returns the channel id.
+#[allow(clippy::too_many_arguments)]
+fn spawn_std_forwarder<T, S, F>(
+    proxy_rx: Receiver<T>,
+    inner: (S, Receiver<T>),
+    outer_tx: S,
     source: &'static str,
     label: Option<String>,
-    capacity: usize,
-    mut log_on_send: F,
-) -> (SyncSender<T>, Receiver<T>)
+    channel_type: ChannelType,
+    limit_in_flight: bool,
+    log_on_send: F,
+) -> u64
 where
     T: Send + 'static,
+    S: TrySend<T> + 'static,
     F: FnMut(&T) -> Option<String> + Send + 'static,
 {
     let (inner_tx, inner_rx) = inner;
-    let type_name = std::any::type_name::<T>();
-
-    let (outer_tx, to_inner_rx) = mpsc::sync_channel::<T>(capacity);
-    let (from_inner_tx, outer_rx) = mpsc::sync_channel::<T>(capacity);
-
     let (stats_tx, _) = init_stats_state();
 
     // Generate unique ID for this channel
     let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
 
+    let source = channel_source(source);
     let _ = stats_tx.send(StatsEvent::Created {
         id,
-        source,
+        source: source.clone(),
         display_label: label,
-        channel_type: ChannelType::Bounded(capacity),
-        type_name,
-        type_size: mem::size_of::<T>(),
+        group: current_group(),
+        meta: current_meta(),
+        channel_type,
+        type_name: std::any::type_name::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
     });
 
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
-
-    // Create a signal channel to notify send-forwarder when outer_rx is closed
-    let (close_signal_tx, close_signal_rx) = mpsc::channel::<()>();
-
-    // Forward outer -> inner (proxy the send path)
-    std::thread::spawn(move || {
-        loop {
-            // Check for close signal (non-blocking)
-            match close_signal_rx.try_recv() {
-                Ok(_) => {
-                    // Outer receiver was closed/dropped
-                    break;
-                }
-                Err(mpsc::TryRecvError::Disconnected) => {
-                    // Close signal sender dropped, which means recv forwarder ended
-                    break;
-                }
-                Err(mpsc::TryRecvError::Empty) => {
-                    // No close signal, continue
-                }
-            }
-
-            // Try to receive with timeout to periodically check close signal
-            match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
-                Ok(msg) => {
-                    let log = log_on_send(&msg);
-                    if inner_tx.send(msg).is_err() {
-                        // Inner receiver dropped
-                        break;
-                    }
-                    let _ = stats_tx_send.send(StatsEvent::MessageSent {
-                        id,
-                        log,
-                        timestamp: std::time::Instant::now(),
-                    });
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // No message, loop again to check close signal
-                    continue;
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    // Outer sender dropped
-                    break;
-                }
-            }
-        }
-        // Channel is closed
-        let _ = stats_tx_send.send(StatsEvent::Closed { id });
+    spawn_forwarder(StdForwarder {
+        id,
+        stats_tx: stats_tx.clone(),
+        proxy_rx,
+        inner_tx: Some(inner_tx),
+        inner_rx,
+        outer_tx,
+        limit_in_flight,
+        in_inner: 0,
+        to_inner: None,
+        to_outer: None,
+        senders_dropped: false,
+        logging: log_gate(id),
+        log_on_send,
+        payload_size: payload_sizer::<T>(),
     });
+    id
+}
 
-    // Forward inner -> outer (proxy the recv path)
-    std::thread::spawn(move || {
-        while let Ok(msg) = inner_rx.recv() {
-            if from_inner_tx.send(msg).is_err() {
-                // Outer receiver was closed
-                let _ = close_signal_tx.send(());
-                break;
-            }
-            let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
-                id,
-                timestamp: std::time::Instant::now(),
-            });
-        }
-        // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(StatsEvent::Closed { id });
-    });
+/// Internal implementation for wrapping bounded std channels with optional logging.
+fn wrap_sync_channel_impl<T, F>(
+    inner: (SyncSender<T>, Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    capacity: Option<usize>,
+    log_on_send: F,
+) -> (SyncSender<T>, Receiver<T>)
+where
+    T: Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    // Without a known capacity the outer proxy only hands messages over, and the inner
+    // channel's own capacity drives backpressure. Otherwise one message in flight through the
+    // inner channel, one waiting for the outer receiver and one in it make up the rest.
+    let proxy_size = capacity.map_or(0, |capacity| proxy_capacity(capacity, 3));
+    let (outer_tx, proxy_rx) = mpsc::sync_channel::<T>(proxy_size);
+    // A rendezvous outer receiver would never meet the forwarder, which doesn't block,
+    // when it's only polled with `try_recv`
+    let (from_inner_tx, outer_rx) = mpsc::sync_channel::<T>(1);
+
+    let id = spawn_std_forwarder(
+        proxy_rx,
+        inner,
+        from_inner_tx,
+        source,
+        label,
+        ChannelType::Bounded(capacity.unwrap_or(0)),
+        capacity.is_some(),
+        log_on_send,
+    );
+    if capacity.is_none() {
+        let (stats_tx, _) = init_stats_state();
+        report_unknown_capacity(stats_tx, id, &channel_source(source));
+    }
 
     (outer_tx, outer_rx)
 }
 
 /// Wrap a bounded std channel with proxy ends. Returns (outer_tx, outer_rx).
-/// All messages pass through the two forwarders running in separate threads.
+/// All messages pass through a forwarder on a background thread. Only one message at a time
+/// is forwarded through the inner channel, and the outer proxy is sized so `try_send` reports
+/// the channel full at `capacity`. Channels smaller than 4 accept 4 messages, as the outer proxy
+/// keeps a slot on top of the three held by the forwarder. Without a capacity, messages are
+/// forwarded as long as the inner channel has room.
 pub(crate) fn wrap_sync_channel<T: Send + 'static>(
     inner: (SyncSender<T>, Receiver<T>),
     source: &'static str,
     label: Option<String>,
-    capacity: usize,
+    capacity: Option<usize>,
 ) -> (SyncSender<T>, Receiver<T>) {
-    wrap_sync_channel_impl(inner, source, label, capacity, |_| None)
+    wrap_sync_channel_impl(inner, source, label, capacity, log_formatter::<T>())
 }
 
 /// Wrap a bounded std channel with logging enabled. Returns (outer_tx, outer_rx).
@@ -124,7 +321,7 @@ pub(crate) fn wrap_sync_channel_log<T: Send + std::fmt::Debug + 'static>(
     inner: (SyncSender<T>, Receiver<T>),
     source: &'static str,
     label: Option<String>,
-    capacity: usize,
+    capacity: Option<usize>,
 ) -> (SyncSender<T>, Receiver<T>) {
     wrap_sync_channel_impl(inner, source, label, capacity, |msg| {
         Some(format!("{:?}", msg))
@@ -136,100 +333,25 @@ fn wrap_channel_impl<T, F>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
     label: Option<String>,
-    mut log_on_send: F,
+    log_on_send: F,
 ) -> (Sender<T>, Receiver<T>)
 where
     T: Send + 'static,
     F: FnMut(&T) -> Option<String> + Send + 'static,
 {
-    let (inner_tx, inner_rx) = inner;
-    let type_name = std::any::type_name::<T>();
-
-    let (outer_tx, to_inner_rx) = mpsc::channel::<T>();
+    let (outer_tx, proxy_rx) = mpsc::channel::<T>();
     let (from_inner_tx, outer_rx) = mpsc::channel::<T>();
 
-    let (stats_tx, _) = init_stats_state();
-
-    // Generate unique ID for this channel
-    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
-
-    let _ = stats_tx.send(StatsEvent::Created {
-        id,
+    spawn_std_forwarder(
+        proxy_rx,
+        inner,
+        from_inner_tx,
         source,
-        display_label: label,
-        channel_type: ChannelType::Unbounded,
-        type_name,
-        type_size: mem::size_of::<T>(),
-    });
-
-    let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx.clone();
-
-    // Create a signal channel to notify send-forwarder when outer_rx is closed
-    let (close_signal_tx, close_signal_rx) = mpsc::channel::<()>();
-
-    // Forward outer -> inner (proxy the send path)
-    std::thread::spawn(move || {
-        loop {
-            // Check for close signal (non-blocking)
-            match close_signal_rx.try_recv() {
-                Ok(_) => {
-                    // Outer receiver was closed/dropped
-                    break;
-                }
-                Err(mpsc::TryRecvError::Disconnected) => {
-                    // Close signal sender dropped, which means recv forwarder ended
-                    break;
-                }
-                Err(mpsc::TryRecvError::Empty) => {
-                    // No close signal, continue
-                }
-            }
-
-            // Try to receive with timeout to periodically check close signal
-            match to_inner_rx.recv_timeout(std::time::Duration::from_millis(10)) {
-                Ok(msg) => {
-                    let log = log_on_send(&msg);
-                    if inner_tx.send(msg).is_err() {
-                        // Inner receiver dropped
-                        break;
-                    }
-                    let _ = stats_tx_send.send(StatsEvent::MessageSent {
-                        id,
-                        log,
-                        timestamp: std::time::Instant::now(),
-                    });
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // No message, loop again to check close signal
-                    continue;
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    // Outer sender dropped
-                    break;
-                }
-            }
-        }
-        // Channel is closed
-        let _ = stats_tx_send.send(StatsEvent::Closed { id });
-    });
-
-    // Forward inner -> outer (proxy the recv path)
-    std::thread::spawn(move || {
-        while let Ok(msg) = inner_rx.recv() {
-            if from_inner_tx.send(msg).is_err() {
-                // Outer receiver was closed
-                let _ = close_signal_tx.send(());
-                break;
-            }
-            let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
-                id,
-                timestamp: std::time::Instant::now(),
-            });
-        }
-        // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(StatsEvent::Closed { id });
-    });
+        label,
+        ChannelType::Unbounded,
+        false,
+        log_on_send,
+    );
 
     (outer_tx, outer_rx)
 }
@@ -240,7 +362,7 @@ pub(crate) fn wrap_channel<T: Send + 'static>(
     source: &'static str,
     label: Option<String>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, |_| None)
+    wrap_channel_impl(inner, source, label, log_formatter::<T>())
 }
 
 /// Wrap an unbounded std channel with logging enabled. Returns (outer_tx, outer_rx).
@@ -256,6 +378,7 @@ use crate::Instrument;
 
 impl<T: Send + 'static> Instrument for (std::sync::mpsc::Sender<T>, std::sync::mpsc::Receiver<T>) {
     type Output = (std::sync::mpsc::Sender<T>, std::sync::mpsc::Receiver<T>);
+    type Payload = T;
     fn instrument(
         self,
         source: &'static str,
@@ -270,16 +393,14 @@ impl<T: Send + 'static> Instrument
     for (std::sync::mpsc::SyncSender<T>, std::sync::mpsc::Receiver<T>)
 {
     type Output = (std::sync::mpsc::SyncSender<T>, std::sync::mpsc::Receiver<T>);
+    type Payload = T;
     fn instrument(
         self,
         source: &'static str,
         label: Option<String>,
         capacity: Option<usize>,
     ) -> Self::Output {
-        if capacity.is_none() {
-            panic!("Capacity is required for bounded std channels, because they don't expose their capacity in a public API");
-        }
-        wrap_sync_channel(self, source, label, capacity.unwrap())
+        wrap_sync_channel(self, source, label, capacity)
     }
 }
 
@@ -309,9 +430,6 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
         label: Option<String>,
         capacity: Option<usize>,
     ) -> Self::Output {
-        if capacity.is_none() {
-            panic!("Capacity is required for bounded std channels, because they don't expose their capacity in a public API");
-        }
-        wrap_sync_channel_log(self, source, label, capacity.unwrap())
+        wrap_sync_channel_log(self, source, label, capacity)
     }
 }
@@ -0,0 +1,231 @@
+use futures_util::{Sink, Stream};
+use pin_project_lite::pin_project;
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::{
+    channel_source, creation_backtrace, current_group, current_meta, init_stats_state, log_gate,
+    message_size, next_iter, ChannelType, CloseReason, LogGate, StatsEvent, CHANNEL_ID_COUNTER,
+};
+
+/// Stats reporting state shared by both halves of an instrumented pair.
+/// The pair is reported as closed once the stream ends or both halves are dropped.
+struct PairHandle {
+    id: u64,
+    /// `None` for pairs outside `CHANNELS_CONSOLE_INSTRUMENT_PATHS`, which report nothing.
+    logging: Option<Arc<LogGate>>,
+}
+
+impl PairHandle {
+    fn send(&self, event: StatsEvent) {
+        if self.logging.is_none() {
+            return;
+        }
+        let (stats_tx, _) = init_stats_state();
+        let _ = stats_tx.send(event);
+    }
+}
+
+impl Drop for PairHandle {
+    fn drop(&mut self) {
+        // A stream that already ended was reported as closed by its sender
+        self.send(StatsEvent::Closed {
+            id: self.id,
+            reason: CloseReason::ReceiverDropped,
+        });
+    }
+}
+
+pin_project! {
+    /// Sink half returned by `instrument_stream!`. Counts every message accepted by the inner sink.
+    pub struct InstrumentedSink<Si, T> {
+        #[pin]
+        inner: Si,
+        handle: Arc<PairHandle>,
+        get_msg_log: fn(&T) -> Option<String>,
+    }
+}
+
+pin_project! {
+    /// Stream half returned by `instrument_stream!`. Counts every message yielded by the inner stream.
+    pub struct InstrumentedStream<St> {
+        #[pin]
+        inner: St,
+        handle: Arc<PairHandle>,
+    }
+}
+
+impl<Si, T> Sink<T> for InstrumentedSink<Si, T>
+where
+    Si: Sink<T>,
+{
+    type Error = Si::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        let this = self.project();
+        let log = if this
+            .handle
+            .logging
+            .as_ref()
+            .is_some_and(|gate| gate.should_log())
+        {
+            (this.get_msg_log)(&item)
+        } else {
+            None
+        };
+        if let Err(err) = this.inner.start_send(item) {
+            this.handle
+                .send(StatsEvent::MessageDropped { id: this.handle.id });
+            return Err(err);
+        }
+        this.handle.send(StatsEvent::MessageSent {
+            id: this.handle.id,
+            log,
+            size: None,
+            timestamp: std::time::Instant::now(),
+        });
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+impl<St: Stream> Stream for InstrumentedStream<St> {
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let item = std::task::ready!(this.inner.poll_next(cx));
+        match &item {
+            Some(_) => this.handle.send(StatsEvent::MessageReceived {
+                id: this.handle.id,
+                timestamp: std::time::Instant::now(),
+            }),
+            None => this.handle.send(StatsEvent::Closed {
+                id: this.handle.id,
+                reason: CloseReason::SenderDropped,
+            }),
+        }
+        Poll::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Reports a new pair to the collector.
+fn register_pair<T>(source: &'static str, label: Option<String>) -> PairHandle {
+    let (stats_tx, _) = init_stats_state();
+
+    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let source = channel_source(source);
+    let _ = stats_tx.send(StatsEvent::Created {
+        id,
+        source: source.clone(),
+        display_label: label,
+        group: current_group(),
+        meta: current_meta(),
+        // A generic sink doesn't expose its capacity
+        channel_type: ChannelType::Unbounded,
+        type_name: std::any::type_name::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: false,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
+    });
+
+    PairHandle {
+        id,
+        logging: Some(log_gate(id)),
+    }
+}
+
+/// Internal implementation for wrapping a sink and stream pair with optional logging.
+fn wrap_stream_impl<T, Si, St>(
+    inner: (Si, St),
+    source: &'static str,
+    label: Option<String>,
+    enabled: bool,
+    get_msg_log: fn(&T) -> Option<String>,
+) -> (InstrumentedSink<Si, T>, InstrumentedStream<St>)
+where
+    Si: Sink<T>,
+    St: Stream<Item = T>,
+{
+    let (sink, stream) = inner;
+    let handle = Arc::new(if enabled {
+        register_pair::<T>(source, label)
+    } else {
+        PairHandle {
+            id: 0,
+            logging: None,
+        }
+    });
+
+    (
+        InstrumentedSink {
+            inner: sink,
+            handle: Arc::clone(&handle),
+            get_msg_log,
+        },
+        InstrumentedStream {
+            inner: stream,
+            handle,
+        },
+    )
+}
+
+fn debug_log<T: std::fmt::Debug>(msg: &T) -> Option<String> {
+    Some(format!("{:?}", msg))
+}
+
+use crate::{InstrumentStream, InstrumentStreamLog};
+
+impl<T, Si, St> InstrumentStream for (Si, St)
+where
+    Si: Sink<T>,
+    St: Stream<Item = T>,
+{
+    type Output = (InstrumentedSink<Si, T>, InstrumentedStream<St>);
+    fn instrument_stream(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output {
+        wrap_stream_impl(self, source, label, enabled, |_| None)
+    }
+}
+
+impl<T, Si, St> InstrumentStreamLog for (Si, St)
+where
+    T: std::fmt::Debug,
+    Si: Sink<T>,
+    St: Stream<Item = T>,
+{
+    type Output = (InstrumentedSink<Si, T>, InstrumentedStream<St>);
+    fn instrument_stream_log(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output {
+        wrap_stream_impl(self, source, label, enabled, debug_log::<T>)
+    }
+}
@@ -0,0 +1,285 @@
+use kanal::{
+    AsyncReceiver, AsyncSender, ReceiveError, ReceiveErrorTimeout, Receiver, SendError,
+    SendErrorTimeout, Sender,
+};
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{register, report_recv, report_sent, PendingCounts};
+use crate::{ChannelType, CloseReason, StatsEvent};
+
+/// Sender half returned by `instrument!((tx, rx), mode = tap)` for kanal channels.
+///
+/// Counts messages inline and derefs to the wrapped [`Sender`] for everything else.
+/// `try_send` calls rejected because the channel is full are counted separately.
+#[derive(Debug)]
+pub struct TappedKanalSender<T> {
+    inner: Sender<T>,
+    tap: Arc<PendingCounts>,
+}
+
+/// Receiver half returned by `instrument!((tx, rx), mode = tap)` for kanal channels.
+///
+/// Counts messages inline and derefs to the wrapped [`Receiver`] for everything else.
+/// Messages received through methods reached via `Deref`, e.g. `drain_into`, aren't counted.
+/// The channel is reported as closed once `recv` fails or the last receiver is dropped.
+#[derive(Debug)]
+pub struct TappedKanalReceiver<T> {
+    inner: Receiver<T>,
+    tap: Arc<PendingCounts>,
+}
+
+/// Sender half returned by `instrument!((tx, rx), mode = tap)` for async kanal channels.
+#[derive(Debug)]
+pub struct TappedKanalAsyncSender<T> {
+    inner: AsyncSender<T>,
+    tap: Arc<PendingCounts>,
+}
+
+/// Receiver half returned by `instrument!((tx, rx), mode = tap)` for async kanal channels.
+///
+/// Messages received through methods reached via `Deref`, e.g. `stream`, aren't counted.
+#[derive(Debug)]
+pub struct TappedKanalAsyncReceiver<T> {
+    inner: AsyncReceiver<T>,
+    tap: Arc<PendingCounts>,
+}
+
+/// Reports the outcome of `try_send`, which returns `Ok(false)` if the channel is full
+fn report_try_send(tap: &PendingCounts, sent: &Result<bool, SendError>) {
+    match sent {
+        Ok(true) => report_sent(tap),
+        Ok(false) => tap.emit(StatsEvent::SendRejected { id: tap.id }),
+        Err(_) => {}
+    }
+}
+
+/// Reports the outcome of `try_recv`, which returns `Ok(None)` if the channel is empty
+fn report_try_recv<T>(tap: &PendingCounts, msg: &Result<Option<T>, ReceiveError>) {
+    match msg {
+        Ok(Some(_)) => report_recv(tap, true),
+        Ok(None) => {}
+        Err(_) => report_recv(tap, false),
+    }
+}
+
+impl<T> TappedKanalSender<T> {
+    pub fn send(&self, value: T) -> Result<(), SendError> {
+        self.inner.send(value)?;
+        report_sent(&self.tap);
+        Ok(())
+    }
+
+    pub fn try_send(&self, value: T) -> Result<bool, SendError> {
+        let sent = self.inner.try_send(value);
+        report_try_send(&self.tap, &sent);
+        sent
+    }
+
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), SendErrorTimeout> {
+        self.inner.send_timeout(value, timeout)?;
+        report_sent(&self.tap);
+        Ok(())
+    }
+}
+
+impl<T> TappedKanalReceiver<T> {
+    pub fn recv(&self) -> Result<T, ReceiveError> {
+        let msg = self.inner.recv();
+        report_recv(&self.tap, msg.is_ok());
+        msg
+    }
+
+    pub fn try_recv(&self) -> Result<Option<T>, ReceiveError> {
+        let msg = self.inner.try_recv();
+        report_try_recv(&self.tap, &msg);
+        msg
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, ReceiveErrorTimeout> {
+        let msg = self.inner.recv_timeout(timeout);
+        match &msg {
+            Ok(_) => report_recv(&self.tap, true),
+            Err(ReceiveErrorTimeout::Timeout) => {
+                self.tap.emit(StatsEvent::RecvTimeout { id: self.tap.id })
+            }
+            Err(_) => report_recv(&self.tap, false),
+        }
+        msg
+    }
+}
+
+impl<T> TappedKanalAsyncSender<T> {
+    pub async fn send(&self, value: T) -> Result<(), SendError> {
+        self.inner.send(value).await?;
+        report_sent(&self.tap);
+        Ok(())
+    }
+
+    pub fn try_send(&self, value: T) -> Result<bool, SendError> {
+        let sent = self.inner.try_send(value);
+        report_try_send(&self.tap, &sent);
+        sent
+    }
+}
+
+impl<T> TappedKanalAsyncReceiver<T> {
+    pub async fn recv(&self) -> Result<T, ReceiveError> {
+        let msg = self.inner.recv().await;
+        report_recv(&self.tap, msg.is_ok());
+        msg
+    }
+
+    pub fn try_recv(&self) -> Result<Option<T>, ReceiveError> {
+        let msg = self.inner.try_recv();
+        report_try_recv(&self.tap, &msg);
+        msg
+    }
+}
+
+impl<T> Iterator for TappedKanalReceiver<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.recv().ok()
+    }
+}
+
+impl<T> Clone for TappedKanalSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            tap: Arc::clone(&self.tap),
+        }
+    }
+}
+
+impl<T> Clone for TappedKanalReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            tap: Arc::clone(&self.tap),
+        }
+    }
+}
+
+impl<T> Clone for TappedKanalAsyncSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            tap: Arc::clone(&self.tap),
+        }
+    }
+}
+
+impl<T> Clone for TappedKanalAsyncReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            tap: Arc::clone(&self.tap),
+        }
+    }
+}
+
+// kanal receivers can be cloned, the channel only closes once the last one is dropped
+
+impl<T> Drop for TappedKanalReceiver<T> {
+    fn drop(&mut self) {
+        if self.inner.receiver_count() == 1 {
+            self.tap.emit(StatsEvent::Closed {
+                id: self.tap.id,
+                reason: CloseReason::ReceiverDropped,
+            });
+        }
+    }
+}
+
+impl<T> Drop for TappedKanalAsyncReceiver<T> {
+    fn drop(&mut self) {
+        if self.inner.receiver_count() == 1 {
+            self.tap.emit(StatsEvent::Closed {
+                id: self.tap.id,
+                reason: CloseReason::ReceiverDropped,
+            });
+        }
+    }
+}
+
+impl<T> Deref for TappedKanalSender<T> {
+    type Target = Sender<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> Deref for TappedKanalReceiver<T> {
+    type Target = Receiver<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> Deref for TappedKanalAsyncSender<T> {
+    type Target = AsyncSender<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> Deref for TappedKanalAsyncReceiver<T> {
+    type Target = AsyncReceiver<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+/// Channel type of a kanal channel, whose unbounded channels report a capacity of `usize::MAX`
+fn channel_type(capacity: usize) -> ChannelType {
+    if capacity == usize::MAX {
+        ChannelType::Unbounded
+    } else {
+        ChannelType::Bounded(capacity)
+    }
+}
+
+use crate::InstrumentTap;
+
+impl<T> InstrumentTap for (Sender<T>, Receiver<T>) {
+    type Output = (TappedKanalSender<T>, TappedKanalReceiver<T>);
+    fn instrument_tap(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output {
+        let (tx, rx) = self;
+        let tap = register::<T>(source, label, channel_type(tx.capacity()), enabled);
+        (
+            TappedKanalSender {
+                inner: tx,
+                tap: Arc::clone(&tap),
+            },
+            TappedKanalReceiver { inner: rx, tap },
+        )
+    }
+}
+
+impl<T> InstrumentTap for (AsyncSender<T>, AsyncReceiver<T>) {
+    type Output = (TappedKanalAsyncSender<T>, TappedKanalAsyncReceiver<T>);
+    fn instrument_tap(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output {
+        let (tx, rx) = self;
+        let tap = register::<T>(source, label, channel_type(tx.capacity()), enabled);
+        (
+            TappedKanalAsyncSender {
+                inner: tx,
+                tap: Arc::clone(&tap),
+            },
+            TappedKanalAsyncReceiver { inner: rx, tap },
+        )
+    }
+}
@@ -0,0 +1,287 @@
+// The submodules shadow the `std` crate within this module
+use ::std::any::type_name;
+use ::std::collections::HashMap;
+use ::std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use ::std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use ::std::time::{Duration, Instant, SystemTime};
+
+use crate::{
+    channel_source, creation_backtrace, current_group, current_meta, init_stats_state,
+    message_size, next_iter, ChannelStats, ChannelType, CloseReason, StatsEvent,
+    CHANNEL_ID_COUNTER, START_TIME,
+};
+
+#[cfg(feature = "kanal")]
+mod kanal;
+mod std;
+#[cfg(feature = "tokio")]
+mod tokio;
+
+#[cfg(feature = "kanal")]
+pub use self::kanal::{
+    TappedKanalAsyncReceiver, TappedKanalAsyncSender, TappedKanalReceiver, TappedKanalSender,
+};
+pub use self::std::{TappedStdReceiver, TappedStdSender};
+#[cfg(feature = "tokio")]
+pub(crate) use self::tokio::tap_oneshot;
+#[cfg(feature = "tokio")]
+pub use self::tokio::{
+    TappedOneshotReceiver, TappedOneshotSender, TappedReceiver, TappedSender,
+    TappedUnboundedReceiver, TappedUnboundedSender,
+};
+
+/// Messages counted by a tapped channel before it reports them without waiting for the collector.
+const BATCH_SIZE: u64 = 64;
+
+/// How often the collector reports the counts of tapped channels that haven't filled a batch.
+pub(crate) const PENDING_FLUSH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Counts of every tapped channel still held by one of its halves, drained by the collector.
+static PENDING: LazyLock<Mutex<HashMap<u64, Arc<PendingCounts>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Set while `PENDING` isn't empty, so the collector drains it periodically.
+static HAS_TAPPED_CHANNELS: AtomicBool = AtomicBool::new(false);
+
+/// Messages of a tapped channel that weren't reported to the collector yet, shared by its halves.
+///
+/// Sending an event per message makes the collector take its lock once per message, which adds
+/// up on busy channels. Tapped channels count messages here instead and have the collector drain
+/// them once `BATCH_SIZE` of either pile up, while it drains the rest every
+/// `PENDING_FLUSH_INTERVAL`, on `flush` and before any other event of the channel, so counts stay
+/// exact. Sends and receives are always drained together, so messages already received never
+/// count as queued.
+#[derive(Debug)]
+pub(crate) struct PendingCounts {
+    id: u64,
+    /// Unset for channels excluded by `CHANNELS_CONSOLE_INSTRUMENT_PATHS`, which keep their
+    /// wrapper types but are never registered, counted or reported
+    enabled: bool,
+    sent: AtomicU64,
+    received: AtomicU64,
+    /// Nanoseconds since `START_TIME` of the first unreported send, the timestamp of its batch
+    first_sent_ns: AtomicU64,
+    /// Nanoseconds since `START_TIME` of the first unreported receive
+    first_received_ns: AtomicU64,
+    /// Whether the collector was asked to drain a full batch it hasn't drained yet
+    batch_filled: AtomicBool,
+    /// Messages sent and not yet received, negative while a receive is counted before its send
+    queued: AtomicI64,
+    /// Most messages queued at once since the counts were last taken
+    peak_queued: AtomicI64,
+}
+
+impl PendingCounts {
+    fn new(id: u64, enabled: bool) -> Self {
+        Self {
+            id,
+            enabled,
+            sent: AtomicU64::new(0),
+            received: AtomicU64::new(0),
+            first_sent_ns: AtomicU64::new(0),
+            first_received_ns: AtomicU64::new(0),
+            batch_filled: AtomicBool::new(false),
+            queued: AtomicI64::new(0),
+            peak_queued: AtomicI64::new(0),
+        }
+    }
+
+    /// Sends an event of the channel to the collector, if the channel is instrumented
+    fn emit(&self, event: StatsEvent) {
+        if self.enabled {
+            emit(event);
+        }
+    }
+
+    fn add_sent(&self, count: u64) {
+        if !self.enabled {
+            return;
+        }
+        let queued = self.queued.fetch_add(count as i64, Ordering::Relaxed) + count as i64;
+        self.peak_queued.fetch_max(queued, Ordering::Relaxed);
+        if add_pending(&self.sent, &self.first_sent_ns, count) {
+            self.report_batch();
+        }
+    }
+
+    fn add_received(&self, count: u64) {
+        if !self.enabled {
+            return;
+        }
+        self.queued.fetch_sub(count as i64, Ordering::Relaxed);
+        if add_pending(&self.received, &self.first_received_ns, count) {
+            self.report_batch();
+        }
+    }
+
+    /// Asks the collector to drain the counts, once until it did
+    fn report_batch(&self) {
+        if !self.batch_filled.swap(true, Ordering::Relaxed) {
+            self.emit(StatsEvent::BatchFilled { id: self.id });
+        }
+    }
+
+    fn has_pending(&self) -> bool {
+        self.sent.load(Ordering::Relaxed) > 0 || self.received.load(Ordering::Relaxed) > 0
+    }
+
+    fn take_sent(&self) -> Option<StatsEvent> {
+        let count = self.sent.swap(0, Ordering::Relaxed);
+        let queued = self.queued.load(Ordering::Relaxed);
+        let peak = self.peak_queued.swap(queued, Ordering::Relaxed);
+        (count > 0).then(|| StatsEvent::MessageSentBatch {
+            id: self.id,
+            count,
+            timestamp: pending_timestamp(&self.first_sent_ns),
+            peak_queued: Some(peak.max(0) as u64),
+        })
+    }
+
+    fn take_received(&self) -> Option<StatsEvent> {
+        let count = self.received.swap(0, Ordering::Relaxed);
+        (count > 0).then(|| StatsEvent::MessageReceivedBatch {
+            id: self.id,
+            count,
+            timestamp: pending_timestamp(&self.first_received_ns),
+        })
+    }
+}
+
+/// Adds `count` messages to `pending`, returning whether they fill a batch.
+fn add_pending(pending: &AtomicU64, first_ns: &AtomicU64, count: u64) -> bool {
+    let previous = pending.fetch_add(count, Ordering::Relaxed);
+    if previous == 0 {
+        // Racing a drain may time the batch by an older first message, which only skews latency
+        let start = START_TIME.get_or_init(Instant::now);
+        first_ns.store(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    }
+    previous + count >= BATCH_SIZE
+}
+
+fn pending_timestamp(first_ns: &AtomicU64) -> Instant {
+    let start = *START_TIME.get_or_init(Instant::now);
+    start + Duration::from_nanos(first_ns.load(Ordering::Relaxed))
+}
+
+/// Whether tapped channels are still held or have counts left, so the collector has counts to
+/// drain periodically.
+pub(crate) fn has_tapped_channels() -> bool {
+    HAS_TAPPED_CHANNELS.load(Ordering::Relaxed)
+}
+
+/// Takes the unreported counts of the tapped channel `id`, or of every tapped channel if `None`,
+/// as events to apply, sends before receives. Channels missing from `stats` keep theirs until
+/// the collector applied their creation. Also forgets channels whose halves were all dropped once
+/// their counts are taken.
+pub(crate) fn drain_pending(
+    id: Option<u64>,
+    stats: &HashMap<u64, ChannelStats>,
+) -> Vec<StatsEvent> {
+    let mut pending = PENDING.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut events = Vec::new();
+    let mut drain = |counts: &PendingCounts| {
+        if !stats.contains_key(&counts.id) {
+            return;
+        }
+        counts.batch_filled.store(false, Ordering::Relaxed);
+        events.extend(counts.take_sent());
+        events.extend(counts.take_received());
+    };
+    match id {
+        Some(id) => {
+            if let Some(counts) = pending.get(&id) {
+                drain(counts);
+            }
+        }
+        None => {
+            pending.values().for_each(|counts| drain(counts));
+            // Once only the map holds them, nothing can add to their counts anymore
+            pending.retain(|_, counts| Arc::strong_count(counts) > 1 || counts.has_pending());
+            // Under the lock, so a channel tapped meanwhile sets it again after its insert
+            HAS_TAPPED_CHANNELS.store(!pending.is_empty(), Ordering::Relaxed);
+        }
+    }
+    events
+}
+
+fn emit(event: StatsEvent) {
+    let (stats_tx, _) = init_stats_state();
+    let _ = stats_tx.send(event);
+}
+
+fn report_sent(tap: &PendingCounts) {
+    tap.add_sent(1);
+}
+
+/// Reports a received message, or the channel closing once `recv` yields `None`
+fn report_recv(tap: &PendingCounts, has_msg: bool) {
+    if has_msg {
+        tap.add_received(1);
+    } else {
+        tap.emit(StatsEvent::Closed {
+            id: tap.id,
+            reason: CloseReason::SenderDropped,
+        });
+    }
+}
+
+/// Registers a tapped channel, or only returns counts that are never reported if not `enabled`.
+fn register<T>(
+    source: &'static str,
+    label: Option<String>,
+    channel_type: ChannelType,
+    enabled: bool,
+) -> Arc<PendingCounts> {
+    if !enabled {
+        return Arc::new(PendingCounts::new(0, false));
+    }
+    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let source = channel_source(source);
+    let tap = Arc::new(PendingCounts::new(id, true));
+    // Oneshots report their single message as it happens, leaving nothing to drain
+    if channel_type != ChannelType::Oneshot {
+        PENDING
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id, Arc::clone(&tap));
+        // Before the event wakes up the collector, so it starts draining periodically
+        HAS_TAPPED_CHANNELS.store(true, Ordering::Relaxed);
+    }
+
+    emit(StatsEvent::Created {
+        id,
+        source: source.clone(),
+        display_label: label,
+        group: current_group(),
+        meta: current_meta(),
+        channel_type,
+        type_name: type_name::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: false,
+        created_at: SystemTime::now(),
+        backtrace: creation_backtrace(),
+    });
+    tap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn periodic_drain_stops_once_tapped_channels_are_gone() {
+        let oneshot = register::<u32>("oneshot", None, ChannelType::Oneshot, true);
+        assert!(!has_tapped_channels());
+
+        let tap = register::<u32>("tap", None, ChannelType::Unbounded, true);
+        assert!(has_tapped_channels());
+        drain_pending(None, &HashMap::new());
+        assert!(has_tapped_channels());
+
+        drop((tap, oneshot));
+        drain_pending(None, &HashMap::new());
+        assert!(!has_tapped_channels());
+    }
+}
@@ -0,0 +1,119 @@
+use std::ops::Deref;
+use std::sync::mpsc::{Receiver, RecvError, RecvTimeoutError, SendError, Sender, TryRecvError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{register, report_recv, report_sent, PendingCounts};
+use crate::{ChannelType, CloseReason, StatsEvent};
+
+/// Sender half returned by `instrument!((tx, rx), mode = tap)` for std unbounded channels.
+///
+/// Counts messages inline and derefs to the wrapped [`Sender`].
+#[derive(Debug)]
+pub struct TappedStdSender<T> {
+    inner: Sender<T>,
+    tap: Arc<PendingCounts>,
+}
+
+/// Receiver half returned by `instrument!((tx, rx), mode = tap)` for std unbounded channels.
+///
+/// Counts messages inline and derefs to the wrapped [`Receiver`] for everything else.
+/// Messages received through methods reached via `Deref`, e.g. `iter`, aren't counted.
+/// `recv_timeout` calls that time out are counted separately.
+#[derive(Debug)]
+pub struct TappedStdReceiver<T> {
+    inner: Receiver<T>,
+    tap: Arc<PendingCounts>,
+}
+
+impl<T> TappedStdSender<T> {
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.inner.send(value)?;
+        report_sent(&self.tap);
+        Ok(())
+    }
+}
+
+impl<T> TappedStdReceiver<T> {
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let msg = self.inner.recv();
+        report_recv(&self.tap, msg.is_ok());
+        msg
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let msg = self.inner.try_recv();
+        match &msg {
+            Ok(_) => report_recv(&self.tap, true),
+            Err(TryRecvError::Disconnected) => report_recv(&self.tap, false),
+            Err(TryRecvError::Empty) => {}
+        }
+        msg
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let msg = self.inner.recv_timeout(timeout);
+        match &msg {
+            Ok(_) => report_recv(&self.tap, true),
+            Err(RecvTimeoutError::Disconnected) => report_recv(&self.tap, false),
+            Err(RecvTimeoutError::Timeout) => {
+                self.tap.emit(StatsEvent::RecvTimeout { id: self.tap.id })
+            }
+        }
+        msg
+    }
+}
+
+impl<T> Clone for TappedStdSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            tap: Arc::clone(&self.tap),
+        }
+    }
+}
+
+impl<T> Drop for TappedStdReceiver<T> {
+    fn drop(&mut self) {
+        self.tap.emit(StatsEvent::Closed {
+            id: self.tap.id,
+            reason: CloseReason::ReceiverDropped,
+        });
+    }
+}
+
+impl<T> Deref for TappedStdSender<T> {
+    type Target = Sender<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> Deref for TappedStdReceiver<T> {
+    type Target = Receiver<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+use crate::InstrumentTap;
+
+impl<T> InstrumentTap for (Sender<T>, Receiver<T>) {
+    type Output = (TappedStdSender<T>, TappedStdReceiver<T>);
+    fn instrument_tap(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output {
+        let (tx, rx) = self;
+        let tap = register::<T>(source, label, ChannelType::Unbounded, enabled);
+        (
+            TappedStdSender {
+                inner: tx,
+                tap: Arc::clone(&tap),
+            },
+            TappedStdReceiver { inner: rx, tap },
+        )
+    }
+}
@@ -0,0 +1,516 @@
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc::error::{SendError, TryRecvError, TrySendError};
+use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+
+use super::{register, report_recv, report_sent, PendingCounts};
+use crate::{
+    log_formatter, log_gate, payload_sizer, ChannelType, CloseReason, LogGate, StatsEvent,
+};
+
+/// Reports a batch received by `recv_many`, which only returns 0 for a non-zero `limit` once the
+/// channel is closed
+fn report_recv_many(tap: &PendingCounts, count: usize, limit: usize) {
+    if count > 0 {
+        tap.add_received(count as u64);
+    } else if limit > 0 {
+        tap.emit(StatsEvent::Closed {
+            id: tap.id,
+            reason: CloseReason::SenderDropped,
+        });
+    }
+}
+
+/// Sender half returned by `instrument!((tx, rx), mode = tap)` for Tokio bounded channels.
+///
+/// Counts messages inline and derefs to the wrapped [`Sender`] for everything else.
+/// Messages sent through methods reached via `Deref`, e.g. `reserve`, aren't counted.
+/// `try_send` calls rejected because the channel is full are counted separately.
+#[derive(Debug)]
+pub struct TappedSender<T> {
+    inner: Sender<T>,
+    tap: Arc<PendingCounts>,
+}
+
+/// Receiver half returned by `instrument!((tx, rx), mode = tap)` for Tokio bounded channels.
+///
+/// Counts messages inline and derefs to the wrapped [`Receiver`] for everything else.
+/// The channel is reported as closed once `recv` returns `None` or the receiver is dropped.
+#[derive(Debug)]
+pub struct TappedReceiver<T> {
+    inner: Receiver<T>,
+    tap: Arc<PendingCounts>,
+}
+
+/// Sender half returned by `instrument!((tx, rx), mode = tap)` for Tokio unbounded channels.
+#[derive(Debug)]
+pub struct TappedUnboundedSender<T> {
+    inner: UnboundedSender<T>,
+    tap: Arc<PendingCounts>,
+}
+
+/// Receiver half returned by `instrument!((tx, rx), mode = tap)` for Tokio unbounded channels.
+#[derive(Debug)]
+pub struct TappedUnboundedReceiver<T> {
+    inner: UnboundedReceiver<T>,
+    tap: Arc<PendingCounts>,
+}
+
+/// Sender half returned by `instrument!((tx, rx))` for Tokio oneshot channels.
+///
+/// Dropping it without sending reports the channel as cancelled.
+#[derive(Debug)]
+pub struct TappedOneshotSender<T> {
+    /// Taken by `send`, which consumes the sender
+    inner: Option<oneshot::Sender<T>>,
+    tap: Arc<PendingCounts>,
+    /// `None` for channels that aren't instrumented
+    logging: Option<Arc<LogGate>>,
+    log_on_send: fn(&T) -> Option<String>,
+    payload_size: Option<fn(&T) -> u64>,
+}
+
+/// Receiver half returned by `instrument!((tx, rx))` for Tokio oneshot channels.
+///
+/// Await it like the wrapped [`oneshot::Receiver`]. The value counts as received once it's
+/// awaited, and a value that was sent but never received is reported as closed once the
+/// receiver is dropped.
+#[derive(Debug)]
+pub struct TappedOneshotReceiver<T> {
+    /// Taken by `blocking_recv`, which consumes the receiver
+    inner: Option<oneshot::Receiver<T>>,
+    tap: Arc<PendingCounts>,
+    done: bool,
+}
+
+impl<T> TappedSender<T> {
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.inner.send(value).await?;
+        report_sent(&self.tap);
+        Ok(())
+    }
+
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if let Err(err) = self.inner.try_send(value) {
+            if matches!(err, TrySendError::Full(_)) {
+                self.tap.emit(StatsEvent::SendRejected { id: self.tap.id });
+            }
+            return Err(err);
+        }
+        report_sent(&self.tap);
+        Ok(())
+    }
+
+    pub fn blocking_send(&self, value: T) -> Result<(), SendError<T>> {
+        self.inner.blocking_send(value)?;
+        report_sent(&self.tap);
+        Ok(())
+    }
+}
+
+impl<T> TappedReceiver<T> {
+    pub async fn recv(&mut self) -> Option<T> {
+        let msg = self.inner.recv().await;
+        report_recv(&self.tap, msg.is_some());
+        msg
+    }
+
+    pub async fn recv_many(&mut self, buffer: &mut Vec<T>, limit: usize) -> usize {
+        let count = self.inner.recv_many(buffer, limit).await;
+        report_recv_many(&self.tap, count, limit);
+        count
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let msg = self.inner.try_recv();
+        match &msg {
+            Ok(_) => report_recv(&self.tap, true),
+            Err(TryRecvError::Disconnected) => report_recv(&self.tap, false),
+            Err(TryRecvError::Empty) => {}
+        }
+        msg
+    }
+
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        let msg = self.inner.blocking_recv();
+        report_recv(&self.tap, msg.is_some());
+        msg
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let msg = std::task::ready!(self.inner.poll_recv(cx));
+        report_recv(&self.tap, msg.is_some());
+        Poll::Ready(msg)
+    }
+}
+
+impl<T> TappedUnboundedSender<T> {
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.inner.send(value)?;
+        report_sent(&self.tap);
+        Ok(())
+    }
+}
+
+impl<T> TappedUnboundedReceiver<T> {
+    pub async fn recv(&mut self) -> Option<T> {
+        let msg = self.inner.recv().await;
+        report_recv(&self.tap, msg.is_some());
+        msg
+    }
+
+    pub async fn recv_many(&mut self, buffer: &mut Vec<T>, limit: usize) -> usize {
+        let count = self.inner.recv_many(buffer, limit).await;
+        report_recv_many(&self.tap, count, limit);
+        count
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let msg = self.inner.try_recv();
+        match &msg {
+            Ok(_) => report_recv(&self.tap, true),
+            Err(TryRecvError::Disconnected) => report_recv(&self.tap, false),
+            Err(TryRecvError::Empty) => {}
+        }
+        msg
+    }
+
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        let msg = self.inner.blocking_recv();
+        report_recv(&self.tap, msg.is_some());
+        msg
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let msg = std::task::ready!(self.inner.poll_recv(cx));
+        report_recv(&self.tap, msg.is_some());
+        Poll::Ready(msg)
+    }
+}
+
+impl<T> TappedOneshotSender<T> {
+    /// Sends the value, reported as a single event since a oneshot has nothing to batch
+    pub fn send(mut self, value: T) -> Result<(), T> {
+        let inner = self.inner.take().expect("Sender used after send");
+        let log = if self.logging.as_ref().is_some_and(|gate| gate.should_log()) {
+            (self.log_on_send)(&value)
+        } else {
+            None
+        };
+        let size = self.payload_size.map(|size| size(&value));
+        inner.send(value)?;
+        self.tap.emit(StatsEvent::MessageSent {
+            id: self.tap.id,
+            log,
+            size,
+            timestamp: std::time::Instant::now(),
+        });
+        self.tap.emit(StatsEvent::Notified { id: self.tap.id });
+        Ok(())
+    }
+
+    /// Returns the wrapped [`oneshot::Sender`], e.g. for an API that takes the Tokio type.
+    /// A value sent through it isn't counted, and dropping it isn't reported as cancelled.
+    pub fn into_inner(mut self) -> oneshot::Sender<T> {
+        self.inner.take().expect("Sender used after send")
+    }
+}
+
+impl<T> TappedOneshotReceiver<T> {
+    /// Reports the outcome of the first receive, the oneshot yields a value or error only once
+    fn report<E>(&mut self, msg: &Result<T, E>) {
+        if self.done {
+            return;
+        }
+        self.done = true;
+        if msg.is_ok() {
+            self.tap.emit(StatsEvent::MessageReceived {
+                id: self.tap.id,
+                timestamp: std::time::Instant::now(),
+            });
+        } else {
+            report_recv(&self.tap, false);
+        }
+    }
+
+    /// Returns the wrapped [`oneshot::Receiver`], e.g. for an API that takes the Tokio type.
+    /// Receiving from it isn't counted, and dropping it isn't reported as closed.
+    pub fn into_inner(mut self) -> oneshot::Receiver<T> {
+        self.done = true;
+        self.inner
+            .take()
+            .expect("Receiver used after blocking_recv")
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, oneshot::error::TryRecvError> {
+        let msg = self
+            .inner
+            .as_mut()
+            .expect("Receiver used after blocking_recv")
+            .try_recv();
+        if !matches!(msg, Err(oneshot::error::TryRecvError::Empty)) {
+            self.report(&msg);
+        }
+        msg
+    }
+
+    pub fn blocking_recv(mut self) -> Result<T, oneshot::error::RecvError> {
+        let inner = self
+            .inner
+            .take()
+            .expect("Receiver used after blocking_recv");
+        let msg = inner.blocking_recv();
+        self.report(&msg);
+        msg
+    }
+}
+
+impl<T> Future for TappedOneshotReceiver<T> {
+    type Output = Result<T, oneshot::error::RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self
+            .inner
+            .as_mut()
+            .expect("Receiver used after blocking_recv");
+        let msg = std::task::ready!(Pin::new(inner).poll(cx));
+        self.report(&msg);
+        Poll::Ready(msg)
+    }
+}
+
+impl<T> Clone for TappedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            tap: Arc::clone(&self.tap),
+        }
+    }
+}
+
+impl<T> Clone for TappedUnboundedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            tap: Arc::clone(&self.tap),
+        }
+    }
+}
+
+impl<T> Drop for TappedReceiver<T> {
+    fn drop(&mut self) {
+        self.tap.emit(StatsEvent::Closed {
+            id: self.tap.id,
+            reason: CloseReason::ReceiverDropped,
+        });
+    }
+}
+
+impl<T> Drop for TappedUnboundedReceiver<T> {
+    fn drop(&mut self) {
+        self.tap.emit(StatsEvent::Closed {
+            id: self.tap.id,
+            reason: CloseReason::ReceiverDropped,
+        });
+    }
+}
+
+impl<T> Drop for TappedOneshotSender<T> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            self.tap.emit(StatsEvent::Cancelled { id: self.tap.id });
+        }
+    }
+}
+
+impl<T> Drop for TappedOneshotReceiver<T> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.tap.emit(StatsEvent::Closed {
+                id: self.tap.id,
+                reason: CloseReason::ReceiverDropped,
+            });
+        }
+    }
+}
+
+impl<T> Deref for TappedSender<T> {
+    type Target = Sender<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> Deref for TappedReceiver<T> {
+    type Target = Receiver<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for TappedReceiver<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> Deref for TappedUnboundedSender<T> {
+    type Target = UnboundedSender<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> Deref for TappedUnboundedReceiver<T> {
+    type Target = UnboundedReceiver<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for TappedUnboundedReceiver<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> Deref for TappedOneshotSender<T> {
+    type Target = oneshot::Sender<T>;
+    fn deref(&self) -> &Self::Target {
+        self.inner.as_ref().expect("Sender used after send")
+    }
+}
+
+impl<T> DerefMut for TappedOneshotSender<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner.as_mut().expect("Sender used after send")
+    }
+}
+
+impl<T> Deref for TappedOneshotReceiver<T> {
+    type Target = oneshot::Receiver<T>;
+    fn deref(&self) -> &Self::Target {
+        self.inner
+            .as_ref()
+            .expect("Receiver used after blocking_recv")
+    }
+}
+
+impl<T> DerefMut for TappedOneshotReceiver<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner
+            .as_mut()
+            .expect("Receiver used after blocking_recv")
+    }
+}
+
+use crate::{FromUninstrumented, InstrumentTap};
+
+impl<T> InstrumentTap for (Sender<T>, Receiver<T>) {
+    type Output = (TappedSender<T>, TappedReceiver<T>);
+    fn instrument_tap(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output {
+        let (tx, rx) = self;
+        let tap = register::<T>(
+            source,
+            label,
+            ChannelType::Bounded(tx.max_capacity()),
+            enabled,
+        );
+        (
+            TappedSender {
+                inner: tx,
+                tap: Arc::clone(&tap),
+            },
+            TappedReceiver { inner: rx, tap },
+        )
+    }
+}
+
+impl<T> InstrumentTap for (UnboundedSender<T>, UnboundedReceiver<T>) {
+    type Output = (TappedUnboundedSender<T>, TappedUnboundedReceiver<T>);
+    fn instrument_tap(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output {
+        let (tx, rx) = self;
+        let tap = register::<T>(source, label, ChannelType::Unbounded, enabled);
+        (
+            TappedUnboundedSender {
+                inner: tx,
+                tap: Arc::clone(&tap),
+            },
+            TappedUnboundedReceiver { inner: rx, tap },
+        )
+    }
+}
+
+impl<T: 'static> InstrumentTap for (oneshot::Sender<T>, oneshot::Receiver<T>) {
+    type Output = (TappedOneshotSender<T>, TappedOneshotReceiver<T>);
+    fn instrument_tap(
+        self,
+        source: &'static str,
+        label: Option<String>,
+        enabled: bool,
+    ) -> Self::Output {
+        tap_oneshot(self, source, label, enabled, log_formatter::<T>())
+    }
+}
+
+impl<T: 'static> FromUninstrumented<(oneshot::Sender<T>, oneshot::Receiver<T>)>
+    for (TappedOneshotSender<T>, TappedOneshotReceiver<T>)
+{
+    fn from_uninstrumented(channel: (oneshot::Sender<T>, oneshot::Receiver<T>)) -> Self {
+        tap_oneshot(channel, "", None, false, |_| None)
+    }
+}
+
+/// Wraps a oneshot channel in counting adapters, which unlike proxies spawn no forwarder tasks.
+/// Used by `instrument!` with and without `mode = tap`.
+pub(crate) fn tap_oneshot<T: 'static>(
+    inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
+    source: &'static str,
+    label: Option<String>,
+    enabled: bool,
+    log_on_send: fn(&T) -> Option<String>,
+) -> (TappedOneshotSender<T>, TappedOneshotReceiver<T>) {
+    let (tx, rx) = inner;
+    let tap = register::<T>(source, label, ChannelType::Oneshot, enabled);
+    (
+        TappedOneshotSender {
+            inner: Some(tx),
+            logging: enabled.then(|| log_gate(tap.id)),
+            log_on_send,
+            payload_size: payload_sizer::<T>(),
+            tap: Arc::clone(&tap),
+        },
+        TappedOneshotReceiver {
+            inner: Some(rx),
+            tap,
+            done: false,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oneshot_into_inner_returns_working_halves() {
+        let (tx, rx) = tap_oneshot(oneshot::channel::<u32>(), "", None, false, |_| None);
+        let (tx, mut rx) = (tx.into_inner(), rx.into_inner());
+        tx.send(7).expect("Failed to send oneshot");
+        assert_eq!(rx.try_recv(), Ok(7));
+    }
+}
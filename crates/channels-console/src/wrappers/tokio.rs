@@ -1,11 +1,161 @@
-use std::mem;
-use std::sync::atomic::Ordering;
+use crossbeam_channel::Sender as CbSender;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
 use tokio::sync::mpsc;
-use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
+use tokio::sync::mpsc::{Permit, Receiver, Sender, UnboundedReceiver, UnboundedSender};
 use tokio::sync::oneshot;
+use tokio::sync::Semaphore;
+
+use super::tap::{tap_oneshot, TappedOneshotReceiver, TappedOneshotSender};
+use crate::{
+    channel_source, creation_backtrace, current_group, current_meta, init_stats_state,
+    log_formatter, log_gate, message_size, next_iter, payload_sizer, proxy_capacity,
+    report_stranded, ChannelType, CloseReason, StatsEvent, CHANNEL_ID_COUNTER,
+};
+use crate::{spawn_forwarder, spawn_local_forwarder};
+
+/// The two forwarders of a proxied channel, not running until spawned.
+struct Forwarders<S, R> {
+    id: u64,
+    task_name: String,
+    send: S,
+    recv: R,
+}
+
+impl<S, R> Forwarders<S, R>
+where
+    S: Future<Output = ()> + Send + 'static,
+    R: Future<Output = ()> + Send + 'static,
+{
+    /// Spawns the forwarders on the shared runtime.
+    fn spawn(self) {
+        spawn_forwarder(self.id, &self.task_name, "send", self.send);
+        spawn_forwarder(self.id, &self.task_name, "recv", self.recv);
+    }
+}
+
+impl<S, R> Forwarders<S, R>
+where
+    S: Future<Output = ()> + 'static,
+    R: Future<Output = ()> + 'static,
+{
+    /// Spawns the forwarders on the current `LocalSet`, for messages that aren't `Send`.
+    fn spawn_local(self) {
+        spawn_local_forwarder(self.id, &self.task_name, "send", self.send);
+        spawn_local_forwarder(self.id, &self.task_name, "recv", self.recv);
+    }
+}
+
+/// Tells whether the outer receiver of a bounded proxy is closed. `None` for messages that aren't
+/// `Send`, as the sender it checks can't be shared with the consumer's thread.
+type ClosedProbe = Option<Arc<dyn Fn() -> bool + Send + Sync>>;
+
+fn closed_probe<T: Send + 'static>(tx: &Sender<T>) -> ClosedProbe {
+    let tx = tx.clone();
+    Some(Arc::new(move || tx.is_closed()))
+}
+
+/// A message handed to the outer receiver of a bounded proxy, reported once as either received
+/// or dropped.
+struct Handover {
+    stats_tx: CbSender<StatsEvent>,
+    id: u64,
+    reported: AtomicBool,
+}
+
+impl Handover {
+    fn report(&self, received: bool) {
+        if self.reported.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let _ = self.stats_tx.send(if received {
+            StatsEvent::MessageReceived {
+                id: self.id,
+                timestamp: std::time::Instant::now(),
+            }
+        } else {
+            StatsEvent::MessageDropped { id: self.id }
+        });
+    }
+}
+
+/// Wakes the recv forwarder. Taking the message wakes it while the outer receiver is still open,
+/// dropping the receiver only once closed, so a wake-up on an open channel reports it received.
+struct TakenWaker {
+    waker: Waker,
+    is_closed: Arc<dyn Fn() -> bool + Send + Sync>,
+    handover: Arc<Handover>,
+}
+
+impl Wake for TakenWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if !(self.is_closed)() {
+            self.handover.report(true);
+        }
+        self.waker.wake_by_ref();
+    }
+}
 
-use crate::RT;
-use crate::{init_stats_state, ChannelType, StatsEvent, CHANNEL_ID_COUNTER};
+/// Hands `msg` to the outer receiver, which holds a single message, and waits for room for the
+/// next one. `None` once the receiver is dropped, reporting `msg` as dropped unless it was taken
+/// first. Both leave the same closed channel, only the wake-up tells them apart. Without a probe,
+/// a message handed over counts as received either way.
+async fn hand_over<'a, T>(
+    permit: Permit<'a, T>,
+    msg: T,
+    tx: &'a Sender<T>,
+    is_closed: &ClosedProbe,
+    stats_tx: &CbSender<StatsEvent>,
+    id: u64,
+) -> Option<Permit<'a, T>> {
+    let handover = Arc::new(Handover {
+        stats_tx: stats_tx.clone(),
+        id,
+        reported: AtomicBool::new(false),
+    });
+    let mut pending = Some((permit, msg));
+    let mut handed_over = false;
+    let mut reserve = std::pin::pin!(tx.reserve());
+    let room = std::future::poll_fn(|cx| {
+        let poll = match is_closed {
+            Some(is_closed) => {
+                let waker = Waker::from(Arc::new(TakenWaker {
+                    waker: cx.waker().clone(),
+                    is_closed: Arc::clone(is_closed),
+                    handover: Arc::clone(&handover),
+                }));
+                reserve.as_mut().poll(&mut Context::from_waker(&waker))
+            }
+            None => reserve.as_mut().poll(cx),
+        };
+        // Wait for room before handing the message over, so taking it wakes the forwarder
+        if let Some((permit, msg)) = pending.take() {
+            if poll.is_pending() {
+                permit.send(msg);
+                handed_over = true;
+            }
+        }
+        poll
+    })
+    .await;
+
+    match room {
+        Ok(permit) => {
+            handover.report(true);
+            Some(permit)
+        }
+        Err(_) => {
+            handover.report(handed_over && is_closed.is_none());
+            None
+        }
+    }
+}
 
 /// Internal implementation for wrapping bounded Tokio channels with optional logging.
 fn wrap_channel_impl<T, F>(
@@ -13,29 +163,51 @@ fn wrap_channel_impl<T, F>(
     source: &'static str,
     label: Option<String>,
     mut log_on_send: F,
-) -> (Sender<T>, Receiver<T>)
+    closed_probe: fn(&Sender<T>) -> ClosedProbe,
+) -> (
+    (Sender<T>, Receiver<T>),
+    Forwarders<impl Future<Output = ()>, impl Future<Output = ()>>,
+)
 where
-    T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static,
+    T: 'static,
+    F: FnMut(&T) -> Option<String> + 'static,
 {
     let (inner_tx, mut inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
+
+    let capacity = inner_tx.max_capacity();
+    // One message in flight through the inner channel and one in the inner proxy make up
+    // the rest of the original capacity
+    let (outer_tx, mut to_inner_rx) = mpsc::channel::<T>(proxy_capacity(capacity, 2));
+    let (from_inner_tx, outer_rx) = mpsc::channel::<T>(1);
 
-    let capacity = inner_tx.capacity();
-    let (outer_tx, mut to_inner_rx) = mpsc::channel::<T>(capacity);
-    let (from_inner_tx, outer_rx) = mpsc::channel::<T>(capacity);
+    // Allow a single message in flight through the inner channel, so the outer proxy's
+    // capacity drives backpressure instead of adding to it
+    let in_flight = Arc::new(Semaphore::new(1));
+    let in_flight_recv = Arc::clone(&in_flight);
 
     let (stats_tx, _) = init_stats_state();
 
     let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
 
+    let source = channel_source(source);
+    let task_name = label.as_deref().unwrap_or(&source).to_string();
     let _ = stats_tx.send(StatsEvent::Created {
         id,
-        source,
+        source: source.clone(),
         display_label: label,
+        group: current_group(),
+        meta: current_meta(),
         channel_type: ChannelType::Bounded(capacity),
         type_name,
-        type_size: mem::size_of::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -45,79 +217,139 @@ where
     let (close_signal_tx, mut close_signal_rx) = oneshot::channel::<()>();
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
-        loop {
+    let send = async move {
+        let reason = loop {
+            tokio::select! {
+                permit = in_flight.acquire() => {
+                    // The semaphore is never closed
+                    permit.expect("in-flight semaphore closed").forget();
+                }
+                _ = &mut close_signal_rx => {
+                    // Outer receiver was closed/dropped, close our receiver to reject further sends
+                    to_inner_rx.close();
+                    break CloseReason::ReceiverDropped;
+                }
+            }
+
             tokio::select! {
                 msg = to_inner_rx.recv() => {
                     match msg {
                         Some(msg) => {
-                            let log = log_on_send(&msg);
+                            let log = if logging.should_log() {
+                                log_on_send(&msg)
+                            } else {
+                                None
+                            };
+                            let size = payload_size.map(|size| size(&msg));
                             if inner_tx.send(msg).await.is_err() {
+                                let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
                                 to_inner_rx.close();
-                                break;
+                                break CloseReason::ForwardFailed;
                             }
                             let _ = stats_tx_send.send(StatsEvent::MessageSent {
                                 id,
                                 log,
+                                size,
                                 timestamp: std::time::Instant::now(),
                             });
                         }
-                        None => break, // Outer sender dropped
+                        None => break CloseReason::SenderDropped, // Outer sender dropped
                     }
                 }
                 _ = &mut close_signal_rx => {
                     // Outer receiver was closed/dropped, close our receiver to reject further sends
                     to_inner_rx.close();
-                    break;
+                    break CloseReason::ReceiverDropped;
                 }
             }
-        }
+        };
+        // Messages left in the proxy will never be forwarded
+        report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
         // Channel is closed
-        let _ = stats_tx_send.send(StatsEvent::Closed { id });
-    });
+        let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
+    };
 
     // Forward inner -> outer (proxy the recv path)
-    RT.spawn(async move {
-        loop {
-            tokio::select! {
-                msg = inner_rx.recv() => {
-                    match msg {
-                        Some(msg) => {
-                            if from_inner_tx.send(msg).await.is_ok() {
-                                let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
+    let recv = async move {
+        let is_closed = closed_probe(&from_inner_tx);
+        let reason = 'forward: {
+            // Wait for room in the outer receiver before pulling from the inner channel,
+            // so messages don't pile up in the forwarder while the consumer is busy
+            let Ok(mut permit) = from_inner_tx.reserve().await else {
+                // Outer receiver was closed/dropped
+                let _ = close_signal_tx.send(());
+                break 'forward CloseReason::ReceiverDropped;
+            };
+
+            loop {
+                tokio::select! {
+                    msg = inner_rx.recv() => {
+                        match msg {
+                            Some(msg) => {
+                                in_flight_recv.add_permits(1);
+                                let room = hand_over(
+                                    permit,
+                                    msg,
+                                    &from_inner_tx,
+                                    &is_closed,
+                                    &stats_tx_recv,
                                     id,
-                                    timestamp: std::time::Instant::now(),
-                                });
-                            } else {
-                                let _ = close_signal_tx.send(());
-                                break;
+                                );
+                                match room.await {
+                                    Some(next) => permit = next,
+                                    None => {
+                                        // Outer receiver was closed/dropped
+                                        let _ = close_signal_tx.send(());
+                                        break CloseReason::ReceiverDropped;
+                                    }
+                                }
                             }
+                            None => break CloseReason::SenderDropped, // Inner sender dropped
                         }
-                        None => break, // Inner sender dropped
                     }
-                }
-                _ = from_inner_tx.closed() => {
-                    // Outer receiver was closed/dropped
-                    let _ = close_signal_tx.send(());
-                    break;
+                    _ = from_inner_tx.closed() => {
+                        // Outer receiver was closed/dropped
+                        let _ = close_signal_tx.send(());
+                        break CloseReason::ReceiverDropped;
+                    }
                 }
             }
-        }
+        };
+        // Messages left in the inner channel will never be received
+        inner_rx.close();
+        report_stranded(&stats_tx_recv, id, || inner_rx.try_recv().is_ok());
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(StatsEvent::Closed { id });
-    });
+        let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
+    };
 
-    (outer_tx, outer_rx)
+    let forwarders = Forwarders {
+        id,
+        task_name,
+        send,
+        recv,
+    };
+    ((outer_tx, outer_rx), forwarders)
 }
 
 /// Wrap the inner channel with proxy ends. Returns (outer_tx, outer_rx).
-/// All messages pass through the two forwarders.
+/// All messages pass through the two forwarders. Only one message at a time is forwarded
+/// through the inner channel, and the outer proxy is sized so `try_send` reports the channel
+/// full at its original capacity. Channels smaller than 3 accept 3 messages, as the outer proxy
+/// keeps a slot on top of the two held by the forwarders.
 pub(crate) fn wrap_channel<T: Send + 'static>(
     inner: (Sender<T>, Receiver<T>),
     source: &'static str,
     label: Option<String>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, |_| None)
+    let (channel, forwarders) = wrap_channel_impl(
+        inner,
+        source,
+        label,
+        log_formatter::<T>(),
+        closed_probe::<T>,
+    );
+    forwarders.spawn();
+    channel
 }
 
 /// Wrap a bounded Tokio channel with logging enabled. Returns (outer_tx, outer_rx).
@@ -126,7 +358,15 @@ pub(crate) fn wrap_channel_log<T: Send + std::fmt::Debug + 'static>(
     source: &'static str,
     label: Option<String>,
 ) -> (Sender<T>, Receiver<T>) {
-    wrap_channel_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    let (channel, forwarders) = wrap_channel_impl(
+        inner,
+        source,
+        label,
+        |msg| Some(format!("{:?}", msg)),
+        closed_probe::<T>,
+    );
+    forwarders.spawn();
+    channel
 }
 
 /// Internal implementation for wrapping unbounded Tokio channels with optional logging.
@@ -135,13 +375,17 @@ fn wrap_unbounded_impl<T, F>(
     source: &'static str,
     label: Option<String>,
     mut log_on_send: F,
-) -> (UnboundedSender<T>, UnboundedReceiver<T>)
+) -> (
+    (UnboundedSender<T>, UnboundedReceiver<T>),
+    Forwarders<impl Future<Output = ()>, impl Future<Output = ()>>,
+)
 where
-    T: Send + 'static,
-    F: FnMut(&T) -> Option<String> + Send + 'static,
+    T: 'static,
+    F: FnMut(&T) -> Option<String> + 'static,
 {
     let (inner_tx, mut inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
 
     let (outer_tx, mut to_inner_rx) = mpsc::unbounded_channel::<T>();
     let (from_inner_tx, outer_rx) = mpsc::unbounded_channel::<T>();
@@ -149,14 +393,24 @@ where
     let (stats_tx, _) = init_stats_state();
 
     let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
 
+    let source = channel_source(source);
+    let task_name = label.as_deref().unwrap_or(&source).to_string();
     let _ = stats_tx.send(StatsEvent::Created {
         id,
-        source,
+        source: source.clone(),
         display_label: label,
+        group: current_group(),
+        meta: current_meta(),
         channel_type: ChannelType::Unbounded,
         type_name,
-        type_size: mem::size_of::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: false,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
     });
 
     let stats_tx_send = stats_tx.clone();
@@ -166,40 +420,49 @@ where
     let (close_signal_tx, mut close_signal_rx) = oneshot::channel::<()>();
 
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
-        loop {
+    let send = async move {
+        let reason = loop {
             tokio::select! {
                 msg = to_inner_rx.recv() => {
                     match msg {
                         Some(msg) => {
-                            let log = log_on_send(&msg);
+                            let log = if logging.should_log() {
+                                log_on_send(&msg)
+                            } else {
+                                None
+                            };
+                            let size = payload_size.map(|size| size(&msg));
                             if inner_tx.send(msg).is_err() {
+                                let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
                                 to_inner_rx.close();
-                                break;
+                                break CloseReason::ForwardFailed;
                             }
                             let _ = stats_tx_send.send(StatsEvent::MessageSent {
                                 id,
                                 log,
+                                size,
                                 timestamp: std::time::Instant::now(),
                             });
                         }
-                        None => break, // Outer sender dropped
+                        None => break CloseReason::SenderDropped, // Outer sender dropped
                     }
                 }
                 _ = &mut close_signal_rx => {
                     // Outer receiver was closed/dropped, close our receiver to reject further sends
                     to_inner_rx.close();
-                    break;
+                    break CloseReason::ReceiverDropped;
                 }
             }
-        }
+        };
+        // Messages left in the proxy will never be forwarded
+        report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
         // Channel is closed
-        let _ = stats_tx_send.send(StatsEvent::Closed { id });
-    });
+        let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
+    };
 
     // Forward inner -> outer (proxy the recv path)
-    RT.spawn(async move {
-        loop {
+    let recv = async move {
+        let reason = loop {
             tokio::select! {
                 msg = inner_rx.recv() => {
                     match msg {
@@ -210,26 +473,36 @@ where
                                     timestamp: std::time::Instant::now(),
                                 });
                             } else {
+                                let _ = stats_tx_recv.send(StatsEvent::MessageDropped { id });
                                 // Outer receiver was closed
                                 let _ = close_signal_tx.send(());
-                                break;
+                                break CloseReason::ReceiverDropped;
                             }
                         }
-                        None => break, // Inner sender dropped
+                        None => break CloseReason::SenderDropped, // Inner sender dropped
                     }
                 }
                 _ = from_inner_tx.closed() => {
                     // Outer receiver was closed/dropped
                     let _ = close_signal_tx.send(());
-                    break;
+                    break CloseReason::ReceiverDropped;
                 }
             }
-        }
+        };
+        // Messages left in the inner channel will never be received
+        inner_rx.close();
+        report_stranded(&stats_tx_recv, id, || inner_rx.try_recv().is_ok());
         // Channel is closed (either inner sender dropped or outer receiver closed)
-        let _ = stats_tx_recv.send(StatsEvent::Closed { id });
-    });
+        let _ = stats_tx_recv.send(StatsEvent::Closed { id, reason });
+    };
 
-    (outer_tx, outer_rx)
+    let forwarders = Forwarders {
+        id,
+        task_name,
+        send,
+        recv,
+    };
+    ((outer_tx, outer_rx), forwarders)
 }
 
 /// Wrap an unbounded channel with proxy ends. Returns (outer_tx, outer_rx).
@@ -238,7 +511,9 @@ pub(crate) fn wrap_unbounded<T: Send + 'static>(
     source: &'static str,
     label: Option<String>,
 ) -> (UnboundedSender<T>, UnboundedReceiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |_| None)
+    let (channel, forwarders) = wrap_unbounded_impl(inner, source, label, log_formatter::<T>());
+    forwarders.spawn();
+    channel
 }
 
 /// Wrap an unbounded Tokio channel with logging enabled. Returns (outer_tx, outer_rx).
@@ -247,137 +522,191 @@ pub(crate) fn wrap_unbounded_log<T: Send + std::fmt::Debug + 'static>(
     source: &'static str,
     label: Option<String>,
 ) -> (UnboundedSender<T>, UnboundedReceiver<T>) {
-    wrap_unbounded_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    let (channel, forwarders) =
+        wrap_unbounded_impl(inner, source, label, |msg| Some(format!("{:?}", msg)));
+    forwarders.spawn();
+    channel
 }
 
-/// Internal implementation for wrapping oneshot Tokio channels with optional logging.
-fn wrap_oneshot_impl<T, F>(
-    inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
+/// Internal implementation for wrapping standalone bounded Tokio senders with optional logging.
+fn wrap_sender_impl<T, F>(
+    inner_tx: Sender<T>,
     source: &'static str,
     label: Option<String>,
     mut log_on_send: F,
-) -> (oneshot::Sender<T>, oneshot::Receiver<T>)
+) -> Sender<T>
 where
     T: Send + 'static,
     F: FnMut(&T) -> Option<String> + Send + 'static,
 {
-    let (inner_tx, inner_rx) = inner;
     let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
 
-    let (outer_tx, outer_rx_proxy) = oneshot::channel::<T>();
-    let (mut inner_tx_proxy, outer_rx) = oneshot::channel::<T>();
+    let capacity = inner_tx.max_capacity();
+    let (outer_tx, mut to_inner_rx) = mpsc::channel::<T>(1);
 
     let (stats_tx, _) = init_stats_state();
 
     let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
 
+    let source = channel_source(source);
+    let task_name = label.as_deref().unwrap_or(&source).to_string();
     let _ = stats_tx.send(StatsEvent::Created {
         id,
-        source,
+        source: source.clone(),
         display_label: label,
-        channel_type: ChannelType::Oneshot,
+        group: current_group(),
+        meta: current_meta(),
+        channel_type: ChannelType::Bounded(capacity),
         type_name,
-        type_size: mem::size_of::<T>(),
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: true,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
     });
 
     let stats_tx_send = stats_tx.clone();
-    let stats_tx_recv = stats_tx;
 
-    // Create a signal channel to notify send-forwarder when outer_rx is closed
-    let (close_signal_tx, mut close_signal_rx) = oneshot::channel::<()>();
-
-    // Monitor outer receiver and drop inner receiver when outer is dropped
-    RT.spawn(async move {
-        let mut inner_rx = Some(inner_rx);
-        let mut message_received = false;
-        tokio::select! {
-            msg = async { inner_rx.take().unwrap().await }, if inner_rx.is_some() => {
-                // Message received from inner
-                match msg {
-                    Ok(msg) => {
-                        if inner_tx_proxy.send(msg).is_ok() {
-                            let _ = stats_tx_recv.send(StatsEvent::MessageReceived {
+    // Forward outer -> inner (proxy the send path)
+    spawn_forwarder(id, &task_name, "send", async move {
+        let reason = loop {
+            // Only take a slot in the inner channel once there's a message for it, so the
+            // idle proxy doesn't hold one back from other clones of the sender
+            tokio::select! {
+                msg = to_inner_rx.recv() => {
+                    match msg {
+                        Some(msg) => {
+                            let log = if logging.should_log() {
+                                log_on_send(&msg)
+                            } else {
+                                None
+                            };
+                            let size = payload_size.map(|size| size(&msg));
+                            if inner_tx.send(msg).await.is_err() {
+                                // Receiver was closed/dropped while the message waited for room
+                                let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
+                                to_inner_rx.close();
+                                break CloseReason::ReceiverDropped;
+                            }
+                            let _ = stats_tx_send.send(StatsEvent::MessageSent {
                                 id,
+                                log,
+                                size,
                                 timestamp: std::time::Instant::now(),
                             });
-                            message_received = true;
                         }
-                    }
-                    Err(_) => {
-                        // Inner sender was dropped without sending
+                        None => break CloseReason::SenderDropped, // Outer sender dropped
                     }
                 }
+                _ = inner_tx.closed() => {
+                    // Receiver was closed/dropped, close our receiver to reject further sends
+                    to_inner_rx.close();
+                    break CloseReason::ReceiverDropped;
+                }
             }
-            _ = inner_tx_proxy.closed() => {
-                // Outer receiver was dropped - drop inner_rx to make sends fail
-                drop(inner_rx);
-                let _ = close_signal_tx.send(());
-            }
-        }
-        // Only send Closed if message was not successfully received
-        if !message_received {
-            let _ = stats_tx_recv.send(StatsEvent::Closed { id });
-        }
+        };
+        // Messages left in the proxy will never be forwarded
+        report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
+        // Channel is closed
+        let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
     });
 
+    outer_tx
+}
+
+/// Internal implementation for wrapping standalone unbounded Tokio senders with optional logging.
+fn wrap_unbounded_sender_impl<T, F>(
+    inner_tx: UnboundedSender<T>,
+    source: &'static str,
+    label: Option<String>,
+    mut log_on_send: F,
+) -> UnboundedSender<T>
+where
+    T: Send + 'static,
+    F: FnMut(&T) -> Option<String> + Send + 'static,
+{
+    let type_name = std::any::type_name::<T>();
+    let payload_size = payload_sizer::<T>();
+
+    let (outer_tx, mut to_inner_rx) = mpsc::unbounded_channel::<T>();
+
+    let (stats_tx, _) = init_stats_state();
+
+    let id = CHANNEL_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let logging = log_gate(id);
+
+    let source = channel_source(source);
+    let task_name = label.as_deref().unwrap_or(&source).to_string();
+    let _ = stats_tx.send(StatsEvent::Created {
+        id,
+        source: source.clone(),
+        display_label: label,
+        group: current_group(),
+        meta: current_meta(),
+        channel_type: ChannelType::Unbounded,
+        type_name,
+        type_size: message_size::<T>(),
+        iter: next_iter(&source),
+        sender_only: true,
+        proxied: true,
+        created_at: std::time::SystemTime::now(),
+        backtrace: creation_backtrace(),
+    });
+
+    let stats_tx_send = stats_tx.clone();
+
     // Forward outer -> inner (proxy the send path)
-    RT.spawn(async move {
-        let mut message_sent = false;
-        tokio::select! {
-            msg = outer_rx_proxy => {
-                match msg {
-                    Ok(msg) => {
-                        let log = log_on_send(&msg);
-                        if inner_tx.send(msg).is_ok() {
+    spawn_forwarder(id, &task_name, "send", async move {
+        let reason = loop {
+            tokio::select! {
+                msg = to_inner_rx.recv() => {
+                    match msg {
+                        Some(msg) => {
+                            let log = if logging.should_log() {
+                                log_on_send(&msg)
+                            } else {
+                                None
+                            };
+                            let size = payload_size.map(|size| size(&msg));
+                            if inner_tx.send(msg).is_err() {
+                                let _ = stats_tx_send.send(StatsEvent::MessageDropped { id });
+                                to_inner_rx.close();
+                                break CloseReason::ReceiverDropped;
+                            }
                             let _ = stats_tx_send.send(StatsEvent::MessageSent {
                                 id,
                                 log,
+                                size,
                                 timestamp: std::time::Instant::now(),
                             });
-                            let _ = stats_tx_send.send(StatsEvent::Notified { id });
-                            message_sent = true;
                         }
-                    }
-                    Err(_) => {
-                        // Outer sender was dropped without sending
+                        None => break CloseReason::SenderDropped, // Outer sender dropped
                     }
                 }
+                _ = inner_tx.closed() => {
+                    // Receiver was closed/dropped, close our receiver to reject further sends
+                    to_inner_rx.close();
+                    break CloseReason::ReceiverDropped;
+                }
             }
-            _ = &mut close_signal_rx => {
-                // Outer receiver was closed/dropped before send
-            }
-        }
-        // Only send Closed if message was not successfully sent
-        if !message_sent {
-            let _ = stats_tx_send.send(StatsEvent::Closed { id });
-        }
+        };
+        // Messages left in the proxy will never be forwarded
+        report_stranded(&stats_tx_send, id, || to_inner_rx.try_recv().is_ok());
+        // Channel is closed
+        let _ = stats_tx_send.send(StatsEvent::Closed { id, reason });
     });
 
-    (outer_tx, outer_rx)
-}
-
-/// Wrap a oneshot channel with proxy ends. Returns (outer_tx, outer_rx).
-pub(crate) fn wrap_oneshot<T: Send + 'static>(
-    inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
-    source: &'static str,
-    label: Option<String>,
-) -> (oneshot::Sender<T>, oneshot::Receiver<T>) {
-    wrap_oneshot_impl(inner, source, label, |_| None)
-}
-
-/// Wrap a oneshot Tokio channel with logging enabled. Returns (outer_tx, outer_rx).
-pub(crate) fn wrap_oneshot_log<T: Send + std::fmt::Debug + 'static>(
-    inner: (oneshot::Sender<T>, oneshot::Receiver<T>),
-    source: &'static str,
-    label: Option<String>,
-) -> (oneshot::Sender<T>, oneshot::Receiver<T>) {
-    wrap_oneshot_impl(inner, source, label, |msg| Some(format!("{:?}", msg)))
+    outer_tx
 }
 
 use crate::Instrument;
 
 impl<T: Send + 'static> Instrument for (Sender<T>, Receiver<T>) {
     type Output = (Sender<T>, Receiver<T>);
+    type Payload = T;
     fn instrument(
         self,
         source: &'static str,
@@ -390,6 +719,7 @@ impl<T: Send + 'static> Instrument for (Sender<T>, Receiver<T>) {
 
 impl<T: Send + 'static> Instrument for (UnboundedSender<T>, UnboundedReceiver<T>) {
     type Output = (UnboundedSender<T>, UnboundedReceiver<T>);
+    type Payload = T;
     fn instrument(
         self,
         source: &'static str,
@@ -401,14 +731,15 @@ impl<T: Send + 'static> Instrument for (UnboundedSender<T>, UnboundedReceiver<T>
 }
 
 impl<T: Send + 'static> Instrument for (oneshot::Sender<T>, oneshot::Receiver<T>) {
-    type Output = (oneshot::Sender<T>, oneshot::Receiver<T>);
+    type Output = (TappedOneshotSender<T>, TappedOneshotReceiver<T>);
+    type Payload = T;
     fn instrument(
         self,
         source: &'static str,
         label: Option<String>,
         _capacity: Option<usize>,
     ) -> Self::Output {
-        wrap_oneshot(self, source, label)
+        tap_oneshot(self, source, label, true, log_formatter::<T>())
     }
 }
 
@@ -443,13 +774,95 @@ impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
 impl<T: Send + std::fmt::Debug + 'static> InstrumentLog
     for (oneshot::Sender<T>, oneshot::Receiver<T>)
 {
-    type Output = (oneshot::Sender<T>, oneshot::Receiver<T>);
+    type Output = (TappedOneshotSender<T>, TappedOneshotReceiver<T>);
     fn instrument_log(
         self,
         source: &'static str,
         label: Option<String>,
         _capacity: Option<usize>,
     ) -> Self::Output {
-        wrap_oneshot_log(self, source, label)
+        tap_oneshot(self, source, label, true, |msg| Some(format!("{:?}", msg)))
+    }
+}
+
+use crate::InstrumentSender;
+
+impl<T: Send + 'static> InstrumentSender for Sender<T> {
+    type Output = Sender<T>;
+    fn instrument_sender(self, source: &'static str, label: Option<String>) -> Self::Output {
+        wrap_sender_impl(self, source, label, |_| None)
+    }
+}
+
+impl<T: Send + 'static> InstrumentSender for UnboundedSender<T> {
+    type Output = UnboundedSender<T>;
+    fn instrument_sender(self, source: &'static str, label: Option<String>) -> Self::Output {
+        wrap_unbounded_sender_impl(self, source, label, |_| None)
+    }
+}
+
+use crate::InstrumentSenderLog;
+
+impl<T: Send + std::fmt::Debug + 'static> InstrumentSenderLog for Sender<T> {
+    type Output = Sender<T>;
+    fn instrument_sender_log(self, source: &'static str, label: Option<String>) -> Self::Output {
+        wrap_sender_impl(self, source, label, |msg| Some(format!("{:?}", msg)))
+    }
+}
+
+impl<T: Send + std::fmt::Debug + 'static> InstrumentSenderLog for UnboundedSender<T> {
+    type Output = UnboundedSender<T>;
+    fn instrument_sender_log(self, source: &'static str, label: Option<String>) -> Self::Output {
+        wrap_unbounded_sender_impl(self, source, label, |msg| Some(format!("{:?}", msg)))
+    }
+}
+
+use crate::InstrumentLocal;
+
+impl<T: 'static> InstrumentLocal for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_local(self, source: &'static str, label: Option<String>) -> Self::Output {
+        let (channel, forwarders) =
+            wrap_channel_impl(self, source, label, log_formatter::<T>(), |_| None);
+        forwarders.spawn_local();
+        channel
+    }
+}
+
+impl<T: 'static> InstrumentLocal for (UnboundedSender<T>, UnboundedReceiver<T>) {
+    type Output = (UnboundedSender<T>, UnboundedReceiver<T>);
+    fn instrument_local(self, source: &'static str, label: Option<String>) -> Self::Output {
+        let (channel, forwarders) = wrap_unbounded_impl(self, source, label, log_formatter::<T>());
+        forwarders.spawn_local();
+        channel
+    }
+}
+
+use crate::InstrumentLocalLog;
+
+impl<T: std::fmt::Debug + 'static> InstrumentLocalLog for (Sender<T>, Receiver<T>) {
+    type Output = (Sender<T>, Receiver<T>);
+    fn instrument_local_log(self, source: &'static str, label: Option<String>) -> Self::Output {
+        let (channel, forwarders) = wrap_channel_impl(
+            self,
+            source,
+            label,
+            |msg| Some(format!("{:?}", msg)),
+            |_| None,
+        );
+        forwarders.spawn_local();
+        channel
+    }
+}
+
+impl<T: std::fmt::Debug + 'static> InstrumentLocalLog
+    for (UnboundedSender<T>, UnboundedReceiver<T>)
+{
+    type Output = (UnboundedSender<T>, UnboundedReceiver<T>);
+    fn instrument_local_log(self, source: &'static str, label: Option<String>) -> Self::Output {
+        let (channel, forwarders) =
+            wrap_unbounded_impl(self, source, label, |msg| Some(format!("{:?}", msg)));
+        forwarders.spawn_local();
+        channel
     }
 }
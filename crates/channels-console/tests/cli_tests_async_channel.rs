@@ -0,0 +1,75 @@
+#[cfg(test)]
+pub mod tests {
+    use std::process::Command;
+
+    #[test]
+    fn test_basic_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-async-channel-test",
+                "--example",
+                "basic_async_channel",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        assert!(!output.stderr.is_empty(), "Stderr is empty");
+        let all_expected = [
+            "examples/basic_async_channel.rs",
+            "hello-there",
+            "unbounded",
+            "bounded[10]",
+            "bounded[1]",
+        ];
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_closed_channels_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-async-channel-test",
+                "--example",
+                "closed_async_channel",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // Match "closed" with flexible spacing (table cells are padded)
+        let closed_count = stdout.matches("| closed").count();
+        assert_eq!(
+            closed_count, 2,
+            "Expected 'closed' state to appear 2 times in table (bounded and unbounded), found {}.\nOutput:\n{}",
+            closed_count, stdout
+        );
+    }
+}
@@ -263,9 +263,9 @@ pub mod tests {
             "bounded",
             "bounded-2",
             "bounded-3",
-            "examples/iter_futures.rs:58",
-            "examples/iter_futures.rs:58-2",
-            "examples/iter_futures.rs:58-3",
+            "examples/iter_futures.rs:60",
+            "examples/iter_futures.rs:60-2",
+            "examples/iter_futures.rs:60-3",
         ];
 
         for expected in all_expected {
@@ -308,4 +308,131 @@ pub mod tests {
             stdout
         );
     }
+
+    #[test]
+    fn test_stream_sink_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-futures-test",
+                "--example",
+                "stream_sink_futures",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let all_expected = [
+            "\"label\": \"stream-pair\"",
+            "\"channel_type\": \"unbounded\"",
+            "\"sent_count\": 5",
+            "\"received_count\": 3",
+            "\"queued\": 2",
+            "\"logging\": true",
+        ];
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_capacity_warning() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-futures-test",
+                "--example",
+                "unknown_capacity_futures",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}\n{}",
+            output.status,
+            stdout,
+            stderr
+        );
+        assert!(
+            stderr.contains("capacity of the bounded channel"),
+            "Expected a warning in:\n{stderr}"
+        );
+
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+        let channel = stats
+            .iter()
+            .find(|s| s.label == "no-capacity")
+            .unwrap_or_else(|| panic!("No no-capacity channel in output:\n{}", stdout));
+        assert!(channel.unknown_capacity, "Got: {:?}", channel);
+        assert_eq!((channel.sent_count, channel.received_count), (4, 4));
+    }
+
+    #[test]
+    fn test_queue_capped_at_capacity() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-futures-test",
+                "--example",
+                "senders_futures",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6802")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let sent: u64 = stdout
+            .lines()
+            .find_map(|line| line.strip_prefix("[Shared] Sent: "))
+            .and_then(|sent| sent.parse().ok())
+            .unwrap_or_else(|| panic!("No sent count in output:\n{}", stdout));
+        assert!(
+            sent > 2,
+            "Senders should get ahead of the capacity:\n{}",
+            stdout
+        );
+
+        let queued: u64 = stdout
+            .lines()
+            .find_map(|line| line.strip_prefix("shared: queued "))
+            .and_then(|line| line.strip_suffix(" of bounded[2]"))
+            .and_then(|queued| queued.parse().ok())
+            .unwrap_or_else(|| panic!("No queue in output:\n{}", stdout));
+        assert!(queued <= 2, "Queue above capacity:\n{}", stdout);
+        assert!(stdout.contains(&format!("[Shared] Received: {}", sent)));
+    }
 }
@@ -0,0 +1,43 @@
+#[cfg(test)]
+pub mod tests {
+    use std::process::Command;
+
+    #[test]
+    fn test_basic_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-kanal-test",
+                "--example",
+                "basic_kanal",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // Counted without sleeping, the guard waits for the forwarders
+        let all_expected = [
+            "| async-jobs         | bounded[5]  | closed | 2    |",
+            "| events             | unbounded   | closed | 1    |",
+            "| jobs               | bounded[10] | closed | 3    |",
+            "| tapped             | bounded[2]  | closed | 2    |",
+        ];
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+pub mod tests {
+    use std::process::Command;
+
+    #[test]
+    fn test_basic_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-postage-test",
+                "--example",
+                "basic_postage",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // Counted without sleeping, the guard waits for the forwarders
+        let all_expected = [
+            "| jobs               | bounded[10] | closed    | 3    |",
+            "| reply              | oneshot     | notified  | 1    |",
+            "| abandoned          | oneshot     | cancelled | 0    |",
+        ];
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+}
@@ -245,4 +245,562 @@ pub mod tests {
             stdout
         );
     }
+
+    #[test]
+    fn test_rendezvous_channel_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "rendezvous_std",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let row = stdout
+            .lines()
+            .find(|line| line.contains("| rendezvous "))
+            .unwrap_or_else(|| panic!("Missing rendezvous row.\nOutput:\n{stdout}"));
+
+        for expected in ["bounded[0]", "| closed", "| 3 "] {
+            assert!(
+                row.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{row}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_recv_timeout_count() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "recv_timeout_std",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+
+        let jobs = stats
+            .iter()
+            .find(|s| s.label == "jobs")
+            .unwrap_or_else(|| panic!("No jobs channel in output:\n{}", stdout));
+        assert_eq!(
+            (jobs.sent_count, jobs.received_count, jobs.timeout_count),
+            (3, 3, 2),
+            "Output:\n{}",
+            stdout
+        );
+        assert_eq!(jobs.error_count(), 2, "Output:\n{}", stdout);
+        assert_eq!(jobs.state, channels_console::ChannelState::Closed);
+    }
+
+    #[test]
+    fn test_unknown_capacity_warning() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "unknown_capacity_std",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}\n{}",
+            output.status,
+            stdout,
+            stderr
+        );
+        assert_eq!(
+            stderr.matches("capacity of the bounded channel").count(),
+            1,
+            "Expected a single warning in:\n{stderr}"
+        );
+
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+        let channel = stats
+            .iter()
+            .find(|s| s.label == "no-capacity")
+            .unwrap_or_else(|| panic!("No no-capacity channel in output:\n{}", stdout));
+        assert!(channel.unknown_capacity, "Got: {:?}", channel);
+        assert_eq!(channel.channel_type_label(), "bounded[?]");
+        assert_eq!((channel.sent_count, channel.received_count), (4, 4));
+        assert_ne!(channel.state, channels_console::ChannelState::Full);
+    }
+
+    #[test]
+    fn test_many_channels_share_forwarder_threads() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "many_channels_std",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        // 500 more channels start 31 more forwarder threads, up to 32 in total, and the two
+        // pool threads for the rest
+        if cfg!(target_os = "linux") {
+            assert!(
+                stdout.contains("extra threads: 33"),
+                "Expected 33 extra threads in:\n{stdout}"
+            );
+        }
+
+        let delivered = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| {
+                serde_json::from_str::<channels_console::SerializableChannelStats>(line)
+                    .expect("Each line should be a JSON object")
+            })
+            .filter(|s| s.sent_count == 1 && s.received_count == 1)
+            .count();
+        assert_eq!(delivered, 501, "Output:\n{stdout}");
+    }
+
+    #[test]
+    fn test_backpressure_matches_capacity() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "backpressure_std",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        // (capacity, uninstrumented, instrumented) accepted before try_send reports full. The
+        // outer proxy keeps a slot on top of the three messages held by the forwarder, so
+        // channels smaller than 4 accept 4.
+        let accepted: Vec<(usize, usize, usize)> = stdout
+            .lines()
+            .filter_map(|line| {
+                let (capacity, counts) = line.strip_prefix("Capacity ")?.split_once(':')?;
+                let (plain, instrumented) = counts
+                    .trim()
+                    .strip_prefix("uninstrumented accepted ")?
+                    .split_once(", instrumented accepted ")?;
+                Some((
+                    capacity.parse().ok()?,
+                    plain.parse().ok()?,
+                    instrumented.parse().ok()?,
+                ))
+            })
+            .collect();
+
+        assert_eq!(
+            accepted,
+            [(1, 1, 4), (5, 5, 5)],
+            "Unexpected buffering\nOutput:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_idle_channel_latency() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "idle_latency_std",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let latency = |prefix: &str| -> u128 {
+            stdout
+                .lines()
+                .find_map(|line| line.strip_prefix(prefix))
+                .and_then(|latency| latency.trim().trim_end_matches("us").parse().ok())
+                .unwrap_or_else(|| panic!("Missing '{prefix}' line.\nOutput:\n{stdout}"))
+        };
+
+        let plain = latency("Uninstrumented latency:");
+        let instrumented = latency("Instrumented latency:");
+
+        // The forwarder blocks on the empty channel instead of polling for it
+        assert!(
+            instrumented <= plain + 200,
+            "Idle channel picked up latency\nOutput:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_scoped_guard() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "scoped_guard_std",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let report = |lines: &str| -> Vec<(String, u64)> {
+            lines
+                .lines()
+                .filter(|line| line.starts_with('{'))
+                .map(|line| {
+                    let stats: channels_console::SerializableChannelStats =
+                        serde_json::from_str(line).expect("Each line should be a JSON object");
+                    (stats.label, stats.sent_count)
+                })
+                .collect()
+        };
+        let (phase, main) = stdout
+            .split_once("phase done")
+            .expect("Expected the phase to end");
+
+        // The scoped guard only reports the channel instrumented during its lifetime
+        assert_eq!(
+            report(phase),
+            [("phase".to_string(), 3)],
+            "Output:\n{stdout}"
+        );
+        let mut main = report(main);
+        main.sort();
+        assert_eq!(
+            main,
+            [("phase".to_string(), 3), ("setup".to_string(), 2)],
+            "Output:\n{stdout}"
+        );
+    }
+
+    #[test]
+    fn test_health_check() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "health_check_std",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        // The channel that lost messages fails the guard's check after the report
+        assert_eq!(output.status.code(), Some(1), "Stderr:\n{stderr}");
+        assert!(stdout.contains("\"label\":\"lossy\""), "Output:\n{stdout}");
+        assert!(
+            stderr.contains("1 channel(s) failed the health checks"),
+            "Stderr:\n{stderr}"
+        );
+        assert!(stderr.contains("lossy ("), "Stderr:\n{stderr}");
+
+        // `check` returns the unhealthy channels to the caller
+        let unhealthy: Vec<&str> = stdout
+            .lines()
+            .filter_map(|line| line.strip_prefix("unhealthy: "))
+            .collect();
+        assert_eq!(unhealthy, ["lossy"], "Output:\n{stdout}");
+    }
+
+    #[test]
+    fn test_instrument_paths() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "selective_std",
+                "--features",
+                "channels-console",
+            ])
+            .env(
+                "CHANNELS_CONSOLE_INSTRUMENT_PATHS",
+                "src/unrelated, crates/channels-console-std-test/examples/selective_std/net",
+            )
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let reported: Vec<(String, u64)> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| {
+                let stats: channels_console::SerializableChannelStats =
+                    serde_json::from_str(line).expect("Each line should be a JSON object");
+                (stats.label, stats.sent_count)
+            })
+            .collect();
+
+        // Only the channels created under the configured path prefix are instrumented, the
+        // tapped channel in main.rs is wrapped but reports nothing
+        assert_eq!(reported, [("net".to_string(), 2)], "Output:\n{stdout}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_report_on_sigint() {
+        use std::io::{BufRead, BufReader};
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::Stdio;
+
+        // Sends SIGINT to the example once it's ready, returning its output and exit status
+        let interrupt = |example: &str| {
+            let mut child = Command::new("cargo")
+                .args([
+                    "run",
+                    "-p",
+                    "channels-console-std-test",
+                    "--example",
+                    example,
+                    "--features",
+                    "sigint",
+                ])
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Failed to spawn command");
+
+            let mut stdout = BufReader::new(child.stdout.take().expect("Failed to open stdout"));
+            let mut line = String::new();
+            while line.trim() != "ready" {
+                line.clear();
+                let read = stdout.read_line(&mut line).expect("Failed to read stdout");
+                assert!(read > 0, "Expected the example to get ready");
+            }
+
+            // `cargo run` replaces itself with the example on Unix
+            let killed = Command::new("kill")
+                .args(["-INT", &child.id().to_string()])
+                .status()
+                .expect("Failed to run kill");
+            assert!(killed.success());
+
+            let mut report = String::new();
+            for line in stdout.lines() {
+                report.push_str(&line.expect("Failed to read stdout"));
+                report.push('\n');
+            }
+            let status = child.wait().expect("Failed to wait for child");
+            let stats: Vec<channels_console::SerializableChannelStats> = report
+                .lines()
+                .filter(|line| line.starts_with('{'))
+                .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+                .collect();
+            (report, stats, status)
+        };
+
+        let (report, stats, status) = interrupt("sigint_std");
+        assert_eq!(
+            status.signal(),
+            Some(2),
+            "Expected SIGINT to end the process"
+        );
+        assert_eq!(stats.len(), 1, "Report:\n{report}");
+        assert_eq!(stats[0].label, "interrupted");
+        assert_eq!(stats[0].sent_count, 3, "Report:\n{report}");
+
+        // With its own handler, the program drops the guard after the report, which still
+        // fails it on the unhealthy channel without printing the report again
+        let (report, stats, status) = interrupt("sigint_checks_std");
+        assert_eq!(status.code(), Some(1), "Report:\n{report}");
+        assert_eq!(stats.len(), 1, "Report:\n{report}");
+        assert_eq!(stats[0].label, "interrupted");
+    }
+
+    #[test]
+    fn test_tap_batched_counts() {
+        use channels_console::MetricsJson;
+        use std::io::{BufRead, BufReader};
+        use std::process::Stdio;
+        use std::time::{Duration, Instant};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-std-test",
+                "--example",
+                "tap_batching_std",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6792")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let stdout = BufReader::new(child.stdout.take().expect("Failed to open stdout"));
+        let mut output = String::new();
+        for line in stdout.lines() {
+            let line = line.expect("Failed to read stdout");
+            output.push_str(&line);
+            output.push('\n');
+            if line == "ready" {
+                break;
+            }
+        }
+        assert!(
+            output.contains("received 100000"),
+            "Expected every message to be received, got:\n{output}"
+        );
+        assert!(
+            output.contains("busy sent 100000 received 100000"),
+            "Expected exact counts despite batching, got:\n{output}"
+        );
+
+        // Without a flush, the collector reports counts that never filled a batch by itself
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let quiet = loop {
+            let metrics: MetricsJson = ureq::get("http://127.0.0.1:6792/metrics")
+                .call()
+                .expect("Failed to fetch metrics")
+                .body_mut()
+                .read_json()
+                .expect("Failed to parse metrics");
+            let quiet = metrics.stats.into_iter().find(|s| s.label == "quiet");
+            let reported = quiet
+                .as_ref()
+                .is_some_and(|s| (s.sent_count, s.received_count) == (3, 1));
+            if reported || Instant::now() >= deadline {
+                break quiet.expect("Expected the quiet channel");
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+
+        drop(child.stdin.take());
+        let _ = child.wait();
+
+        assert_eq!((quiet.sent_count, quiet.received_count), (3, 1));
+        assert_eq!(quiet.queued, 2);
+    }
+
+    #[test]
+    fn test_concurrent_start() {
+        // The collector starts once per process, so race it a few times
+        for port in ["6793", "6794", "6795"] {
+            let output = Command::new("cargo")
+                .args([
+                    "run",
+                    "-p",
+                    "channels-console-std-test",
+                    "--example",
+                    "concurrent_start_std",
+                    "--features",
+                    "channels-console",
+                ])
+                .env("CHANNELS_CONSOLE_METRICS_PORT", port)
+                .output()
+                .expect("Failed to execute command");
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(
+                output.status.success(),
+                "Command failed with status: {}\n{stderr}",
+                output.status
+            );
+            assert!(stdout.contains("channels 8"), "Got:\n{stdout}");
+        }
+    }
 }
@@ -80,17 +80,18 @@ pub mod tests {
     }
 
     #[test]
-    fn test_closed_channels_output() {
+    fn test_summary_totals() {
         let output = Command::new("cargo")
             .args([
                 "run",
                 "-p",
                 "channels-console-tokio-test",
                 "--example",
-                "closed_tokio",
+                "basic_json_tokio",
                 "--features",
                 "channels-console",
             ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6803")
             .output()
             .expect("Failed to execute command");
 
@@ -101,50 +102,114 @@ pub mod tests {
         );
 
         let stdout = String::from_utf8_lossy(&output.stdout);
+        let json_start = stdout.find("\n{").expect("No JSON in output") + 1;
+        let metrics: channels_console::MetricsJson =
+            serde_json::from_str(&stdout[json_start..]).expect("Output should be JSON");
 
-        // Match "closed" with flexible spacing (table cells are padded)
-        let closed_count = stdout.matches("| closed").count();
+        let summary = &metrics.summary;
+        let stats = &metrics.stats;
+        assert_eq!(summary.channels, 3, "Output:\n{stdout}");
+        assert_eq!(summary.channels, stats.len());
         assert_eq!(
-            closed_count, 2,
-            "Expected 'closed' state to appear 2 times in table (bounded and unbounded), found {}.\nOutput:\n{}",
-            closed_count, stdout
+            summary.sent_count,
+            stats.iter().map(|s| s.sent_count).sum::<u64>()
         );
-
-        let notified_count = stdout.matches("| notified").count();
         assert_eq!(
-            notified_count, 1,
-            "Expected 'notified' state to appear 1 time in table (oneshot), found {}.\nOutput:\n{}",
-            notified_count, stdout
+            summary.received_count,
+            stats.iter().map(|s| s.received_count).sum::<u64>()
+        );
+        assert_eq!(summary.queued, stats.iter().map(|s| s.queued).sum::<u64>());
+        assert_eq!(
+            summary.total_bytes_sent,
+            stats.iter().map(|s| s.total_bytes_sent).sum::<u64>()
         );
     }
 
     #[test]
-    fn test_oneshot_closed_output() {
+    fn test_json_lines_output() {
         let output = Command::new("cargo")
             .args([
                 "run",
                 "-p",
                 "channels-console-tokio-test",
                 "--example",
-                "oneshot_closed_tokio",
+                "json_lines_tokio",
                 "--features",
                 "channels-console",
             ])
             .output()
             .expect("Failed to execute command");
 
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+
+        let mut labels: Vec<&str> = stats.iter().map(|s| s.label.as_str()).collect();
+        labels.sort();
+        assert_eq!(
+            labels,
+            ["lines-bounded", "lines-unbounded"],
+            "Output:\n{}",
+            stdout
+        );
+        // Counted without sleeping before the guard drops
+        assert!(stats
+            .iter()
+            .all(|s| s.sent_count == 3 && s.received_count == 3));
+
+        // RFC 3339 in UTC, e.g. 2025-10-15T12:03:21.532Z
+        for stat in &stats {
+            let created_at = stat.created_at.as_bytes();
+            assert_eq!(created_at.len(), 24, "Got: {}", stat.created_at);
+            assert_eq!(
+                (created_at[4], created_at[10], created_at[23]),
+                (b'-', b'T', b'Z'),
+                "Got: {}",
+                stat.created_at
+            );
+        }
+    }
+
+    #[test]
+    fn test_markdown_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "markdown_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
 
         assert!(
             output.status.success(),
-            "Command failed with status: {}\nStdout:\n{}\nStderr:\n{}",
-            output.status,
-            stdout,
-            stderr
+            "Command failed with status: {}",
+            output.status
         );
 
-        let all_expected = ["| closed |", "oneshot_closed_tokio.rs:"];
+        let all_expected = [
+            "### Channel Statistics (runtime: ",
+            "| Channel | Type | State | Sent | Sent B | Received | Dropped | Queued | Mem |",
+            "| --- | --- | --- | --- | --- | --- | --- | --- | --- |",
+            "| md\\|bounded | bounded[10] | active | 3 | 12 B |",
+            "| Total (1 channels) |  |  | 3 | 12 B |",
+        ];
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
 
         for expected in all_expected {
             assert!(
@@ -155,91 +220,150 @@ pub mod tests {
     }
 
     #[test]
-    fn test_data_endpoints() {
-        use std::{process::Command, thread::sleep, time::Duration};
-
-        // Spawn example process
-        let mut child = Command::new("cargo")
+    fn test_plain_table_output() {
+        let output = Command::new("cargo")
             .args([
                 "run",
                 "-p",
                 "channels-console-tokio-test",
                 "--example",
-                "basic_tokio",
+                "plain_table_tokio",
                 "--features",
                 "channels-console",
             ])
-            .spawn()
-            .expect("Failed to spawn command");
+            .output()
+            .expect("Failed to execute command");
 
-        let mut json_text = String::new();
-        let mut last_error = None;
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
 
-        // Test /metrics endpoint
-        for _attempt in 0..4 {
-            sleep(Duration::from_millis(500));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout
+            .lines()
+            .skip_while(|line| !line.starts_with("=== Channel Statistics (runtime: "))
+            .skip(1)
+            .collect();
 
-            match ureq::get("http://127.0.0.1:6770/metrics").call() {
-                Ok(mut response) => {
-                    json_text = response
-                        .body_mut()
-                        .read_to_string()
-                        .expect("Failed to read response body");
-                    last_error = None;
-                    break;
-                }
-                Err(e) => {
-                    last_error = Some(format!("Request error: {}", e));
-                }
-            }
-        }
+        assert_eq!(
+            lines,
+            [
+                "| Channel | Type | State | Sent | Sent B | Received | Dropped | Queued | Mem |",
+                "| plain-bounded | bounded[10] | closed | 3 | 12 B | 3 | 0 | 0 | 0 B |",
+                "| Total (1 channels) |  |  | 3 | 12 B | 3 | 0 | 0 | 0 B |",
+            ],
+            "Output:\n{stdout}",
+        );
+    }
 
-        if let Some(error) = last_error {
-            let _ = child.kill();
-            panic!("Failed after 4 retries: {}", error);
-        }
+    #[test]
+    fn test_total_bytes_sent() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "bytes_sent_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
 
-        let all_expected = ["Actor 1", "bounded-channel", "hello-there"];
-        for expected in all_expected {
-            assert!(
-                json_text.contains(expected),
-                "Expected:\n{expected}\n\nGot:\n{json_text}",
-            );
-        }
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
 
-        // Test /logs/:id endpoint
-        let metrics: channels_console::MetricsJson =
-            serde_json::from_str(&json_text).expect("Failed to parse metrics JSON");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let cells = |prefix: &str| -> Vec<String> {
+            stdout
+                .lines()
+                .find(|line| line.starts_with(prefix))
+                .unwrap_or_else(|| panic!("Missing '{prefix}' row:\n{stdout}"))
+                .split('|')
+                .map(|cell| cell.trim().to_string())
+                .collect()
+        };
+        let header = cells("| Channel ");
+        let row = cells("| drained ");
+        let column = |name: &str| {
+            let index = header
+                .iter()
+                .position(|cell| cell == name)
+                .unwrap_or_else(|| panic!("Missing '{name}' column:\n{stdout}"));
+            row[index].as_str()
+        };
 
-        if let Some(first_channel) = metrics.stats.first() {
-            let logs_url = format!("http://127.0.0.1:6770/logs/{}", first_channel.id);
-            let response = ureq::get(&logs_url)
-                .call()
-                .expect("Failed to call /logs/:id endpoint");
+        // Sent B keeps counting every message, while Mem only holds the queued ones
+        assert_eq!(column("Sent"), "5", "Output:\n{stdout}");
+        assert_eq!(column("Sent B"), "40 B", "Output:\n{stdout}");
+        assert_eq!(column("Mem"), "0 B", "Output:\n{stdout}");
 
-            assert_eq!(
-                response.status(),
-                200,
-                "Expected status 200 for /logs/:id endpoint"
-            );
-        }
+        // Servers built before the cumulative count was added don't report it
+        let stats: channels_console::SerializableChannelStats = serde_json::from_str(
+            r#"{"id":1,"source":"src/main.rs:1","label":"jobs","has_custom_label":true,"group":null,"channel_type":"bounded[10]","state":"active","sent_count":3,"received_count":3,"dropped_count":0,"queued":0,"type_name":"u64","type_size":8,"queued_bytes":0,"iter":0,"sender_only":false,"logging":false}"#,
+        )
+        .expect("Failed to parse stats without total_bytes_sent");
+        assert_eq!(stats.total_bytes_sent, 0);
+    }
 
-        let _ = child.kill();
-        let _ = child.wait();
+    #[test]
+    fn test_state_change_callback() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "state_change_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6788")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let transitions: Vec<&str> = stdout
+            .lines()
+            .filter(|line| line.starts_with("jobs: "))
+            .collect();
+
+        assert_eq!(
+            transitions,
+            [
+                "jobs: active -> full",
+                "jobs: full -> active",
+                "jobs: active -> closed"
+            ],
+            "Output:\n{stdout}",
+        );
     }
 
     #[test]
-    fn test_iter_output() {
+    fn test_tap_lockstep_never_full() {
         let output = Command::new("cargo")
             .args([
                 "run",
                 "-p",
                 "channels-console-tokio-test",
                 "--example",
-                "iter_tokio",
+                "lockstep_tokio",
                 "--features",
                 "channels-console",
             ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6801")
             .output()
             .expect("Failed to execute command");
 
@@ -250,19 +374,53 @@ pub mod tests {
         );
 
         let stdout = String::from_utf8_lossy(&output.stdout);
+        let transitions: Vec<&str> = stdout
+            .lines()
+            .filter(|line| line.starts_with("lockstep: "))
+            .collect();
+        assert_eq!(
+            transitions,
+            ["lockstep: active -> closed"],
+            "Output:\n{stdout}",
+        );
+
+        let stats: channels_console::SerializableChannelStats = stdout
+            .lines()
+            .find(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Line should be a JSON object"))
+            .expect("Missing channel stats");
+        assert_eq!(stats.sent_count, 1000, "Output:\n{stdout}");
+        assert_eq!(stats.received_count, 1000, "Output:\n{stdout}");
+    }
+
+    #[test]
+    fn test_si_byte_units() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "units_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
 
         let all_expected = [
-            "Actor 1",
-            "Actor 1-2",
-            "Actor 1-3",
-            "examples/iter_tokio.rs:37",
-            "examples/iter_tokio.rs:37-2",
-            "examples/iter_tokio.rs:37-3",
-            "examples/iter_tokio.rs:52",
-            "examples/iter_tokio.rs:52-2",
-            "examples/iter_tokio.rs:52-3",
+            "| si-bounded | bounded[10] | active | 3 | 3.0 kB |",
+            "| Total (1 channels) |  |  | 3 | 3.0 kB |",
         ];
 
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
         for expected in all_expected {
             assert!(
                 stdout.contains(expected),
@@ -272,35 +430,2821 @@ pub mod tests {
     }
 
     #[test]
-    fn test_slow_consumer_no_panic() {
+    fn test_metrics_port_fallback() {
         let output = Command::new("cargo")
             .args([
                 "run",
                 "-p",
                 "channels-console-tokio-test",
                 "--example",
-                "slow_consumer_tokio",
+                "port_in_use_tokio",
                 "--features",
                 "channels-console",
             ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6780")
             .output()
             .expect("Failed to execute command");
 
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
 
         assert!(
-            output.status.success(),
-            "Command failed with status: {}\nStdout:\n{}\nStderr:\n{}",
-            output.status,
-            stdout,
-            stderr
+            stderr.contains("127.0.0.1:6780 is already in use, trying port 6781"),
+            "Got:\n{stderr}",
         );
+        for expected in [
+            "Channel metrics server listening on http://127.0.0.1:6781",
+            "Some(Listening(\"127.0.0.1:6781\"))",
+        ] {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_flush_snapshot() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "flush_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
 
         assert!(
-            stdout.contains("Slow consumer example completed!"),
-            "Expected completion message not found.\nOutput:\n{}",
-            stdout
+            output.status.success(),
+            "Command failed with status: {}\n{stderr}",
+            output.status
         );
+        assert!(stdout.contains("sent 100 received 100"), "Got:\n{stdout}");
+    }
+
+    #[test]
+    fn test_channel_state_api() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "channel_state_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6791")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\n{stderr}",
+            output.status
+        );
+
+        let expected = [
+            "channel #1 events active",
+            "channel #0 jobs active",
+            "jobs is full, shedding load",
+            "jobs is Some(Active)",
+            "unknown is None",
+        ];
+        let lines: Vec<&str> = stdout
+            .lines()
+            .filter(|line| !line.starts_with("Channel metrics server"))
+            .collect();
+        assert_eq!(lines, expected, "Got:\n{stdout}");
+    }
+
+    #[test]
+    fn test_recv_many_batches() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "recv_many_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\n{stderr}",
+            output.status
+        );
+        assert!(stdout.contains("batches [4, 4, 2]"), "Got:\n{stdout}");
+        assert!(
+            stdout.contains("batched received 10 state closed"),
+            "Got:\n{stdout}"
+        );
+        assert!(
+            stdout.contains("batched-unbounded received 10 state closed"),
+            "Got:\n{stdout}"
+        );
+    }
+
+    #[test]
+    fn test_channel_groups() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "groups_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+
+        let rows: Vec<(Option<&str>, &str)> = stats
+            .iter()
+            .map(|s| (s.group.as_deref(), s.label.as_str()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (Some("ingest"), "raw-events"),
+                (Some("ingest"), "examples/groups_tokio.rs:25"),
+                (Some("processing"), "transform"),
+                (None, "metrics"),
+            ],
+            "Output:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_dropped_messages() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "dropped_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+
+        let drained = stats.iter().find(|s| s.label == "drained").unwrap();
+        assert_eq!(drained.received_count, 5, "Output:\n{}", stdout);
+        assert_eq!(drained.dropped_count, 0, "Output:\n{}", stdout);
+
+        // Every message sent by the user is either received or dropped
+        let stranded = stats.iter().find(|s| s.label == "stranded").unwrap();
+        assert_eq!(stranded.received_count, 1, "Output:\n{}", stdout);
+        assert_eq!(stranded.dropped_count, 4, "Output:\n{}", stdout);
+    }
+
+    #[test]
+    fn test_tap_mode() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "tap_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("[Tapped] Full: 3"), "Output:\n{}", stdout);
+
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+
+        let tapped = stats.iter().find(|s| s.label == "tapped").unwrap();
+        assert_eq!(tapped.sent_count, 2, "Output:\n{}", stdout);
+        assert_eq!(tapped.received_count, 1, "Output:\n{}", stdout);
+        assert_eq!(tapped.queued, 1, "Output:\n{}", stdout);
+        assert_eq!(tapped.try_send_full_count, 1, "Output:\n{}", stdout);
+
+        let unbounded = stats
+            .iter()
+            .find(|s| s.label == "tapped-unbounded")
+            .unwrap();
+        assert_eq!(unbounded.sent_count, 3, "Output:\n{}", stdout);
+        assert_eq!(unbounded.received_count, 3, "Output:\n{}", stdout);
+        assert_eq!(
+            unbounded.state,
+            channels_console::ChannelState::Closed,
+            "Output:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "latency_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+
+        let backlog = stats.iter().find(|s| s.label == "backlog").unwrap();
+        let latency = backlog.latency.expect("Latency should be reported");
+
+        // The burst waits between 50ms and 50ms + 19 * 10ms to be received
+        assert!(latency.p50_ns >= 50_000_000, "Latency: {:?}", latency);
+        assert!(latency.p50_ns < latency.p99_ns, "Latency: {:?}", latency);
+        assert!(latency.p95_ns <= latency.p99_ns, "Latency: {:?}", latency);
+        assert!(latency.p99_ns <= latency.max_ns, "Latency: {:?}", latency);
+        assert!(latency.max_ns >= 200_000_000, "Latency: {:?}", latency);
+    }
+
+    #[test]
+    fn test_guard_filters() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "guard_filter_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let labels: Vec<String> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| {
+                serde_json::from_str::<channels_console::SerializableChannelStats>(line)
+                    .expect("Each line should be a JSON object")
+                    .label
+            })
+            .collect();
+        assert_eq!(labels, ["worker-1"], "Output:\n{}", stdout);
+    }
+
+    #[test]
+    fn test_guard_interval() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "guard_interval_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let sent: Vec<u64> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| {
+                let metrics: channels_console::MetricsJson =
+                    serde_json::from_str(line).expect("Each report should be a JSON object");
+                metrics
+                    .stats
+                    .iter()
+                    .find(|s| s.label == "ticks")
+                    .map_or(0, |s| s.sent_count)
+            })
+            .collect();
+
+        assert!(
+            sent.len() >= 3,
+            "Expected periodic reports before the final one, got {} reports:\n{}",
+            sent.len(),
+            stdout
+        );
+        assert!(
+            sent.windows(2).all(|pair| pair[0] <= pair[1]),
+            "Sent counts should only grow between reports: {:?}",
+            sent
+        );
+        assert_eq!(sent.last(), Some(&10), "Output:\n{}", stdout);
+    }
+
+    #[test]
+    fn test_message_size_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "message_size_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let row = |label: &str| {
+            stdout
+                .lines()
+                .find(|line| line.contains(label))
+                .unwrap_or_else(|| panic!("Missing {label} row:\n{stdout}"))
+                .to_string()
+        };
+
+        let signals = row("signals");
+        assert!(
+            signals.contains("—") && !signals.contains("0 B"),
+            "Zero-sized messages should not report bytes:\n{signals}",
+        );
+
+        let payloads = row("payloads");
+        assert!(
+            payloads.contains("384 B"),
+            "Expected 3 messages of 128 bytes:\n{payloads}",
+        );
+
+        let buffers = row("buffers");
+        assert!(
+            buffers.contains("5.9 KB"),
+            "Expected 6000 heap bytes plus 3 Vec handles:\n{buffers}",
+        );
+    }
+
+    #[test]
+    fn test_closed_channels_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "closed_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // Match "closed" with flexible spacing (table cells are padded)
+        let closed_count = stdout.matches("| closed").count();
+        assert_eq!(
+            closed_count, 2,
+            "Expected 'closed' state to appear 2 times in table (bounded and unbounded), found {}.\nOutput:\n{}",
+            closed_count, stdout
+        );
+
+        let notified_count = stdout.matches("| notified").count();
+        assert_eq!(
+            notified_count, 1,
+            "Expected 'notified' state to appear 1 time in table (oneshot), found {}.\nOutput:\n{}",
+            notified_count, stdout
+        );
+    }
+
+    #[test]
+    fn test_closed_channels_purged_after_ttl() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "closed_ttl_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_CLOSED_TTL_MS", "200")
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6773")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            stdout.contains("long-lived"),
+            "Expected live channel to be kept.\nOutput:\n{}",
+            stdout
+        );
+        assert!(
+            !stdout.contains("short-lived"),
+            "Expected closed channel to be purged.\nOutput:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_growing_unbounded_channel() {
+        use channels_console::ChannelState;
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "growing_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_GROWING_THRESHOLD", "10")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut states: Vec<(String, u64, ChannelState)> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| {
+                let stat: channels_console::SerializableChannelStats =
+                    serde_json::from_str(line).expect("Each line should be a JSON object");
+                (stat.label, stat.queued, stat.state)
+            })
+            .collect();
+        states.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            states,
+            [
+                ("backlog".to_string(), 20, ChannelState::Growing),
+                ("drained".to_string(), 0, ChannelState::Active),
+            ],
+            "Output:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_oneshot_closed_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "oneshot_closed_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nStdout:\n{}\nStderr:\n{}",
+            output.status,
+            stdout,
+            stderr
+        );
+
+        let all_expected = ["| closed |", "oneshot_closed_tokio.rs:"];
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_oneshot_state_transitions() {
+        use channels_console::{ChannelState, MetricsJson};
+        use std::io::{BufRead, BufReader, Write};
+        use std::process::Stdio;
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "oneshot_states_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6774")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut stdin = child.stdin.take().expect("Failed to open stdin");
+        let stdout = BufReader::new(child.stdout.take().expect("Failed to open stdout"));
+
+        // (step, [(label, expected state, sent, received)]). Oneshots are tapped with or
+        // without `mode = tap`, so the receive counts once the value is awaited.
+        let expected = [
+            (
+                "created",
+                [
+                    ("oneshot-delivered", ChannelState::Active, 0, 0),
+                    ("tapped-delivered", ChannelState::Active, 0, 0),
+                ],
+            ),
+            (
+                "sent",
+                [
+                    ("oneshot-delivered", ChannelState::Notified, 1, 0),
+                    ("tapped-delivered", ChannelState::Notified, 1, 0),
+                ],
+            ),
+            (
+                "received",
+                [
+                    ("oneshot-delivered", ChannelState::Notified, 1, 1),
+                    ("tapped-delivered", ChannelState::Notified, 1, 1),
+                ],
+            ),
+            (
+                "dropped",
+                [
+                    ("oneshot-dropped", ChannelState::Cancelled, 0, 0),
+                    ("tapped-dropped", ChannelState::Cancelled, 0, 0),
+                ],
+            ),
+            (
+                "abandoned",
+                [
+                    ("oneshot-abandoned", ChannelState::Closed, 0, 0),
+                    ("tapped-abandoned", ChannelState::Closed, 0, 0),
+                ],
+            ),
+        ];
+        let mut expected = expected.iter();
+        let mut failure = None;
+
+        'steps: for line in stdout.lines() {
+            let line = line.expect("Failed to read stdout");
+            let Some(step) = line.strip_prefix("step: ") else {
+                continue;
+            };
+            let Some((name, channels)) = expected.next() else {
+                failure = Some(format!("Unexpected step: {}", step));
+                break;
+            };
+            assert_eq!(step, *name, "Steps out of order");
+
+            let metrics: MetricsJson = ureq::get("http://127.0.0.1:6774/metrics")
+                .call()
+                .expect("Failed to call /metrics endpoint")
+                .body_mut()
+                .read_json()
+                .expect("Failed to parse metrics JSON");
+
+            for (label, state, sent, received) in channels.iter() {
+                match metrics.stats.iter().find(|s| s.label == *label) {
+                    Some(stat)
+                        if stat.state == *state
+                            && stat.sent_count == *sent
+                            && stat.received_count == *received => {}
+                    other => {
+                        failure = Some(format!(
+                            "Step {}: expected {} to be {} with sent={} received={}, got {:?}",
+                            name, label, state, sent, received, other
+                        ));
+                        break 'steps;
+                    }
+                }
+            }
+
+            writeln!(stdin, "next").expect("Failed to write stdin");
+        }
+
+        let _ = child.kill();
+        let status = child.wait().expect("Failed to wait for child");
+
+        if let Some(failure) = failure {
+            panic!("{}", failure);
+        }
+        assert!(
+            expected.next().is_none(),
+            "Example exited before all steps were checked"
+        );
+        assert!(status.success(), "Command failed with status: {}", status);
+    }
+
+    #[test]
+    fn test_runtime_log_toggle() {
+        use channels_console::{ChannelLogs, MetricsJson};
+        use std::io::{BufRead, BufReader, Write};
+        use std::process::Stdio;
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "log_toggle_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6778")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut stdin = child.stdin.take().expect("Failed to open stdin");
+        let stdout = BufReader::new(child.stdout.take().expect("Failed to open stdout"));
+
+        let mut logged = Vec::new();
+
+        for line in stdout.lines() {
+            let line = line.expect("Failed to read stdout");
+            let Some(step) = line.strip_prefix("step: ") else {
+                continue;
+            };
+
+            let metrics: MetricsJson = ureq::get("http://127.0.0.1:6778/metrics")
+                .call()
+                .expect("Failed to call /metrics endpoint")
+                .body_mut()
+                .read_json()
+                .expect("Failed to parse metrics JSON");
+            let stat = metrics
+                .stats
+                .iter()
+                .find(|s| s.label == "toggled")
+                .expect("Channel should be instrumented");
+
+            // Mute logging for the second burst only
+            let enabled = match step {
+                "first" => Some(false),
+                "muted" => Some(true),
+                _ => None,
+            };
+            if let Some(enabled) = enabled {
+                assert_eq!(stat.logging, !enabled, "Step {}", step);
+                let url = format!(
+                    "http://127.0.0.1:6778/channels/{}/log?enabled={}",
+                    stat.id, enabled
+                );
+                let response = ureq::post(&url)
+                    .send_empty()
+                    .expect("Failed to toggle logging");
+                assert_eq!(response.status().as_u16(), 204);
+            } else {
+                let logs: ChannelLogs =
+                    ureq::get(&format!("http://127.0.0.1:6778/logs/{}", stat.id))
+                        .call()
+                        .expect("Failed to call /logs endpoint")
+                        .body_mut()
+                        .read_json()
+                        .expect("Failed to parse logs JSON");
+                // Messages sent while muted were received too
+                assert_eq!(logs.last_received_index, 6);
+                logged = logs
+                    .sent_logs
+                    .iter()
+                    .map(|entry| (entry.index, entry.message.clone().unwrap_or_default()))
+                    .collect();
+
+                let oldest_first: ChannelLogs =
+                    ureq::get(&format!("http://127.0.0.1:6778/logs/{}?order=asc", stat.id))
+                        .call()
+                        .expect("Failed to call /logs endpoint")
+                        .body_mut()
+                        .read_json()
+                        .expect("Failed to parse logs JSON");
+                let indices: Vec<u64> = oldest_first
+                    .sent_logs
+                    .iter()
+                    .map(|entry| entry.index)
+                    .collect();
+                assert_eq!(indices, [1, 2, 5, 6]);
+
+                let invalid = ureq::get(&format!(
+                    "http://127.0.0.1:6778/logs/{}?order=newest",
+                    stat.id
+                ))
+                .call();
+                assert!(
+                    matches!(invalid, Err(ureq::Error::StatusCode(400))),
+                    "Expected 400 for an invalid order, got: {:?}",
+                    invalid
+                );
+            }
+
+            writeln!(stdin, "next").expect("Failed to write stdin");
+        }
+
+        let status = child.wait().expect("Failed to wait for child");
+        assert!(status.success(), "Command failed with status: {}", status);
+
+        let expected = [
+            (6, "\"last-2\"".to_string()),
+            (5, "\"last-1\"".to_string()),
+            (2, "\"first-2\"".to_string()),
+            (1, "\"first-1\"".to_string()),
+        ];
+        assert_eq!(logged, expected);
+    }
+
+    #[test]
+    fn test_metrics_diff() {
+        use channels_console::MetricsDiffJson;
+        use std::io::{BufRead, BufReader, Write};
+        use std::process::Stdio;
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "metrics_diff_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6789")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut stdin = child.stdin.take().expect("Failed to open stdin");
+        let stdout = BufReader::new(child.stdout.take().expect("Failed to open stdout"));
+
+        // (step, [(label, sent_delta, received_delta)]) of the channels listed in each diff
+        let expected = [
+            ("sent 3", vec![("busy", 3, 3), ("idle", 1, 0)]),
+            ("sent 2", vec![("busy", 2, 2)]),
+            ("sent 0", vec![]),
+        ];
+        let mut expected = expected.iter();
+        let mut cursor = None;
+        let mut failure = None;
+
+        for line in stdout.lines() {
+            let line = line.expect("Failed to read stdout");
+            let Some(step) = line.strip_prefix("step: ") else {
+                continue;
+            };
+            let Some((name, channels)) = expected.next() else {
+                failure = Some(format!("Unexpected step: {}", step));
+                break;
+            };
+            assert_eq!(step, *name, "Steps out of order");
+
+            let url = match cursor {
+                Some(cursor) => format!("http://127.0.0.1:6789/metrics/diff?cursor={}", cursor),
+                None => "http://127.0.0.1:6789/metrics/diff".to_string(),
+            };
+            let diff: MetricsDiffJson = ureq::get(&url)
+                .call()
+                .expect("Failed to call /metrics/diff endpoint")
+                .body_mut()
+                .read_json()
+                .expect("Failed to parse diff JSON");
+
+            let got: Vec<(&str, u64, u64)> = diff
+                .channels
+                .iter()
+                .map(|c| (c.label.as_str(), c.sent_delta, c.received_delta))
+                .collect();
+            if got != *channels || diff.full != cursor.is_none() {
+                failure = Some(format!(
+                    "Step {}: expected {:?}, got {:?}",
+                    name, channels, diff
+                ));
+                break;
+            }
+            cursor = Some(diff.cursor);
+
+            let invalid = ureq::get("http://127.0.0.1:6789/metrics/diff?cursor=abc").call();
+            assert!(
+                matches!(invalid, Err(ureq::Error::StatusCode(400))),
+                "Expected 400 for an invalid cursor, got: {:?}",
+                invalid
+            );
+
+            writeln!(stdin, "next").expect("Failed to write stdin");
+        }
+
+        let _ = child.kill();
+        let status = child.wait().expect("Failed to wait for child");
+
+        if let Some(failure) = failure {
+            panic!("{}", failure);
+        }
+        assert!(
+            expected.next().is_none(),
+            "Example exited before all steps were checked"
+        );
+        assert!(status.success(), "Command failed with status: {}", status);
+    }
+
+    #[test]
+    fn test_data_endpoints() {
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        // Spawn example process
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "basic_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut json_text = String::new();
+        let mut last_error = None;
+
+        // Test /metrics endpoint
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6770/metrics").call() {
+                Ok(mut response) => {
+                    json_text = response
+                        .body_mut()
+                        .read_to_string()
+                        .expect("Failed to read response body");
+                    last_error = None;
+                    break;
+                }
+                Err(e) => {
+                    last_error = Some(format!("Request error: {}", e));
+                }
+            }
+        }
+
+        if let Some(error) = last_error {
+            let _ = child.kill();
+            panic!("Failed after 4 retries: {}", error);
+        }
+
+        let all_expected = ["Actor 1", "bounded-channel", "hello-there"];
+        for expected in all_expected {
+            assert!(
+                json_text.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{json_text}",
+            );
+        }
+
+        let envelope: serde_json::Value =
+            serde_json::from_str(&json_text).expect("Failed to parse metrics JSON");
+        assert_eq!(
+            envelope["version"],
+            channels_console::METRICS_SCHEMA_VERSION
+        );
+        assert!(envelope["channels"].is_array(), "Got:\n{json_text}");
+
+        // Test the unversioned compatibility route
+        let legacy: serde_json::Value = ureq::get("http://127.0.0.1:6770/metrics/v0")
+            .call()
+            .expect("Failed to call /metrics/v0 endpoint")
+            .body_mut()
+            .read_json()
+            .expect("Failed to parse legacy metrics JSON");
+        assert!(legacy.get("version").is_none(), "Got:\n{legacy}");
+        assert!(legacy["stats"].is_array(), "Got:\n{legacy}");
+
+        // Test /diagnostics endpoint
+        let diagnostics: channels_console::Diagnostics =
+            ureq::get("http://127.0.0.1:6770/diagnostics")
+                .call()
+                .expect("Failed to call /diagnostics endpoint")
+                .body_mut()
+                .read_json()
+                .expect("Failed to parse diagnostics JSON");
+        assert!(
+            diagnostics.peak_event_queue_len >= 1,
+            "Got: {:?}",
+            diagnostics
+        );
+
+        // Test /logs/:id endpoint
+        let metrics: channels_console::MetricsJson =
+            serde_json::from_str(&json_text).expect("Failed to parse metrics JSON");
+
+        // Test /channels endpoint
+        let index: Vec<channels_console::ChannelIndexEntry> =
+            ureq::get("http://127.0.0.1:6770/channels")
+                .call()
+                .expect("Failed to call /channels endpoint")
+                .body_mut()
+                .read_json()
+                .expect("Failed to parse channel index JSON");
+        for stat in &metrics.stats {
+            assert!(
+                index
+                    .iter()
+                    .any(|entry| entry.id == stat.id && entry.label == stat.label),
+                "Expected channel {} ({}) in index: {:?}",
+                stat.id,
+                stat.label,
+                index
+            );
+        }
+
+        // Test /types endpoint
+        let types: Vec<channels_console::TypeStats> = ureq::get("http://127.0.0.1:6770/types")
+            .call()
+            .expect("Failed to call /types endpoint")
+            .body_mut()
+            .read_json()
+            .expect("Failed to parse types JSON");
+        assert!(!types.is_empty(), "Expected message types");
+        let channels: usize = types.iter().map(|row| row.channels).sum();
+        assert!(
+            channels >= metrics.stats.len(),
+            "Expected at least {} channels across types, got: {:?}",
+            metrics.stats.len(),
+            types
+        );
+
+        if let Some(first_channel) = metrics.stats.first() {
+            let logs_url = format!("http://127.0.0.1:6770/logs/{}", first_channel.id);
+            let response = ureq::get(&logs_url)
+                .call()
+                .expect("Failed to call /logs/:id endpoint");
+
+            assert_eq!(
+                response.status(),
+                200,
+                "Expected status 200 for /logs/:id endpoint"
+            );
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn test_prometheus_endpoint() {
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "basic_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6771")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut text = String::new();
+        let mut last_error = None;
+
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6771/metrics/prometheus").call() {
+                Ok(mut response) => {
+                    text = response
+                        .body_mut()
+                        .read_to_string()
+                        .expect("Failed to read response body");
+                    last_error = None;
+                    break;
+                }
+                Err(e) => {
+                    last_error = Some(format!("Request error: {}", e));
+                }
+            }
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+
+        if let Some(error) = last_error {
+            panic!("Failed after 4 retries: {}", error);
+        }
+
+        let all_expected = [
+            "# TYPE channels_console_sent_total counter",
+            "# TYPE channels_console_queue_utilization histogram",
+            "label=\"bounded-channel\"",
+            "channel_type=\"bounded[10]\",le=\"0.9\"}",
+            "channel_type=\"bounded[10]\",le=\"+Inf\"}",
+            "channels_console_queue_utilization_count{",
+        ];
+        for expected in all_expected {
+            assert!(
+                text.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{text}",
+            );
+        }
+
+        assert!(
+            !text.contains("channel_type=\"unbounded\",le="),
+            "Unbounded channels should not have a utilization histogram:\n{text}",
+        );
+    }
+
+    #[test]
+    fn test_console_once_json() {
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let build = Command::new("cargo")
+            .args(["build", "-p", "channels-console", "--features", "tui"])
+            .status()
+            .expect("Failed to build console");
+        assert!(build.success(), "Failed to build console");
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "basic_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6775")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut stdout = String::new();
+        let mut last_error = None;
+
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            let output = Command::new("cargo")
+                .args([
+                    "run",
+                    "-p",
+                    "channels-console",
+                    "--features",
+                    "tui",
+                    "--",
+                    "--metrics-port",
+                    "6775",
+                    "--once",
+                    "--format",
+                    "json",
+                ])
+                .output()
+                .expect("Failed to run console");
+
+            if output.status.success() {
+                stdout = String::from_utf8_lossy(&output.stdout).to_string();
+                last_error = None;
+                break;
+            }
+            last_error = Some(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+
+        if let Some(error) = last_error {
+            panic!("Failed after 4 retries: {}", error);
+        }
+
+        let metrics: channels_console::MetricsJson =
+            serde_json::from_str(stdout.trim()).expect("Failed to parse snapshot JSON");
+
+        let labels: Vec<&str> = metrics.stats.iter().map(|s| s.label.as_str()).collect();
+        assert!(
+            labels.contains(&"bounded-channel"),
+            "Expected bounded-channel in snapshot, got: {labels:?}",
+        );
+    }
+
+    #[test]
+    fn test_console_from_file() {
+        use std::process::Command;
+
+        let path = std::env::temp_dir().join(format!(
+            "channels-console-replay-{}.jsonl",
+            std::process::id()
+        ));
+        let snapshot = |sent: u64| {
+            format!(
+                r#"{{"version":1,"current_elapsed_ns":{sent}000,"channels":[{{"id":1,"source":"src/main.rs:1","label":"jobs","has_custom_label":true,"group":null,"channel_type":"bounded[10]","state":"active","sent_count":{sent},"received_count":0,"dropped_count":0,"queued":{sent},"type_name":"u32","type_size":4,"queued_bytes":0,"total_bytes_sent":0,"iter":0,"sender_only":false,"logging":false}}]}}"#
+            )
+        };
+        std::fs::write(&path, format!("{}\n\n{}\n", snapshot(3), snapshot(7)))
+            .expect("Failed to write snapshots");
+
+        let output = Command::new("cargo")
+            .args(["run", "-p", "channels-console", "--features", "tui", "--"])
+            .args(["--once", "--format", "json", "--from-file"])
+            .arg(&path)
+            .output()
+            .expect("Failed to run console");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(
+            output.status.success(),
+            "Console failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let metrics: channels_console::MetricsJson =
+            serde_json::from_str(stdout.trim()).expect("Failed to parse snapshot JSON");
+
+        assert_eq!(metrics.stats.len(), 1);
+        assert_eq!(metrics.stats[0].label, "jobs");
+        assert_eq!(
+            metrics.stats[0].sent_count, 7,
+            "Expected the last snapshot to be printed"
+        );
+    }
+
+    #[test]
+    fn test_record_session() {
+        use channels_console::{RecordedEvent, SessionEvent};
+        use std::time::Duration;
+
+        let path = std::env::temp_dir().join(format!(
+            "channels-console-session-{}.jsonl",
+            std::process::id()
+        ));
+
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "record_session_tokio",
+                "--features",
+                "channels-console",
+                "--",
+            ])
+            .arg(&path)
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6790")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let content = std::fs::read_to_string(&path).expect("Failed to read session file");
+        let events: Vec<RecordedEvent> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("Failed to parse recorded event"))
+            .collect();
+
+        assert!(
+            matches!(events[0].event, SessionEvent::Created { ref label, .. } if label.as_deref() == Some("recorded")),
+            "Expected the session to start with the channel's creation, got: {:?}",
+            events[0]
+        );
+        // Tapped channels report their sends in batches
+        let sent: u64 = events
+            .iter()
+            .map(|e| match e.event {
+                SessionEvent::Sent { count, .. } => count,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(sent, 5, "Expected 5 recorded sends");
+
+        let snapshots = channels_console::replay_session(events, Duration::from_millis(100));
+        let last = snapshots.last().expect("Expected a replayed snapshot");
+        assert_eq!(last.stats.len(), 1);
+        assert_eq!(last.stats[0].label, "recorded");
+        assert_eq!(last.stats[0].sent_count, 5);
+        assert_eq!(last.stats[0].received_count, 3);
+        assert_eq!(last.stats[0].queued, 2);
+
+        let output = Command::new("cargo")
+            .args(["run", "-p", "channels-console", "--features", "tui", "--"])
+            .args(["--once", "--format", "json", "--replay"])
+            .arg(&path)
+            .output()
+            .expect("Failed to run console");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(
+            output.status.success(),
+            "Console failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let metrics: channels_console::MetricsJson =
+            serde_json::from_str(stdout.trim()).expect("Failed to parse snapshot JSON");
+        assert_eq!(metrics.stats[0].sent_count, 5);
+        assert_eq!(metrics.stats[0].received_count, 3);
+    }
+
+    #[test]
+    fn test_info_endpoint() {
+        use std::{
+            process::Command,
+            thread::sleep,
+            time::{Duration, SystemTime, UNIX_EPOCH},
+        };
+
+        let before_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "basic_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6776")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut info = None;
+        let mut last_error = None;
+
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6776/info").call() {
+                Ok(mut response) => {
+                    info = Some(
+                        response
+                            .body_mut()
+                            .read_json::<channels_console::ServerInfo>()
+                            .expect("Failed to parse server info"),
+                    );
+                    last_error = None;
+                    break;
+                }
+                Err(e) => {
+                    last_error = Some(format!("Request error: {}", e));
+                }
+            }
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+
+        if let Some(error) = last_error {
+            panic!("Failed after 4 retries: {}", error);
+        }
+
+        let after_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        let start_wall_clock = info
+            .expect("Server info should be fetched")
+            .start_wall_clock;
+        assert!(
+            (before_ms..=after_ms).contains(&start_wall_clock),
+            "Expected start wall clock between {before_ms} and {after_ms}, got {start_wall_clock}",
+        );
+    }
+
+    #[test]
+    fn test_token_auth() {
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "basic_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6777")
+            .env("CHANNELS_CONSOLE_TOKEN", "secret-token")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut authorized = None;
+        let mut last_error = None;
+
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6777/metrics")
+                .header("Authorization", "Bearer secret-token")
+                .call()
+            {
+                Ok(response) => {
+                    authorized = Some(response.status().as_u16());
+                    last_error = None;
+                    break;
+                }
+                Err(e) => {
+                    last_error = Some(format!("Request error: {}", e));
+                }
+            }
+        }
+
+        let missing = ureq::get("http://127.0.0.1:6777/metrics").call();
+        let wrong = ureq::get("http://127.0.0.1:6777/logs/0")
+            .header("Authorization", "Bearer wrong-token")
+            .call();
+
+        let _ = child.kill();
+        let _ = child.wait();
+
+        if let Some(error) = last_error {
+            panic!("Failed after 4 retries: {}", error);
+        }
+
+        assert_eq!(authorized, Some(200));
+        assert!(
+            matches!(missing, Err(ureq::Error::StatusCode(401))),
+            "Expected 401 without a token, got: {missing:?}",
+        );
+        assert!(
+            matches!(wrong, Err(ureq::Error::StatusCode(401))),
+            "Expected 401 with a wrong token, got: {wrong:?}",
+        );
+    }
+
+    #[test]
+    fn test_reset_endpoint() {
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "reset_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6772")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let fetch_metrics = || -> Result<channels_console::MetricsJson, String> {
+            ureq::get("http://127.0.0.1:6772/metrics")
+                .call()
+                .map_err(|e| format!("Request error: {}", e))?
+                .body_mut()
+                .read_json()
+                .map_err(|e| format!("Invalid JSON: {}", e))
+        };
+
+        let mut before = None;
+        let mut last_error = None;
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            match fetch_metrics() {
+                Ok(metrics) if metrics.stats.len() == 2 => {
+                    before = Some(metrics);
+                    break;
+                }
+                Ok(metrics) => last_error = Some(format!("Unexpected stats: {:?}", metrics.stats)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let Some(before) = before else {
+            let _ = child.kill();
+            panic!("Failed after 4 retries: {:?}", last_error);
+        };
+        assert!(before.stats.iter().any(|s| s.label == "reset-oneshot"));
+
+        let response = ureq::post("http://127.0.0.1:6772/reset").send_empty();
+        sleep(Duration::from_millis(100));
+        let after = fetch_metrics();
+
+        let _ = child.kill();
+        let _ = child.wait();
+
+        assert_eq!(
+            response.expect("Failed to call /reset endpoint").status(),
+            204
+        );
+        let after = after.expect("Failed to fetch metrics after reset");
+
+        assert_eq!(after.stats.len(), 1, "Got: {:?}", after.stats);
+        let bounded = &after.stats[0];
+        assert_eq!(bounded.label, "reset-bounded");
+        assert_eq!(bounded.sent_count, 0);
+        assert_eq!(bounded.received_count, 0);
+    }
+
+    #[test]
+    fn test_log_sampling() {
+        use channels_console::{ChannelLogs, MetricsJson};
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "log_sample_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6783")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut metrics = None;
+        let mut last_error = None;
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6783/metrics")
+                .call()
+                .map_err(|e| format!("Request error: {}", e))
+                .and_then(|mut response| {
+                    response
+                        .body_mut()
+                        .read_json::<MetricsJson>()
+                        .map_err(|e| format!("Invalid JSON: {}", e))
+                }) {
+                Ok(m) if m.stats.first().is_some_and(|s| s.received_count == 50) => {
+                    metrics = Some(m);
+                    break;
+                }
+                Ok(m) => last_error = Some(format!("Unexpected stats: {:?}", m.stats)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let Some(metrics) = metrics else {
+            let _ = child.kill();
+            panic!("Failed after 4 retries: {:?}", last_error);
+        };
+        let stat = &metrics.stats[0];
+
+        let logs: ChannelLogs = ureq::get(format!("http://127.0.0.1:6783/logs/{}", stat.id))
+            .call()
+            .expect("Failed to call /logs/:id endpoint")
+            .body_mut()
+            .read_json()
+            .expect("Failed to parse logs JSON");
+
+        let status = child.wait().expect("Failed to wait for child");
+        assert!(status.success(), "Command failed with status: {}", status);
+
+        assert_eq!(stat.log_sample, Some(10));
+        let sent: Vec<(u64, Option<&str>)> = logs
+            .sent_logs
+            .iter()
+            .map(|entry| (entry.index, entry.message.as_deref()))
+            .collect();
+        assert_eq!(
+            sent,
+            [
+                (41, Some("Job(41)")),
+                (31, Some("Job(31)")),
+                (21, Some("Job(21)")),
+                (11, Some("Job(11)")),
+                (1, Some("Job(1)")),
+            ]
+        );
+        let received: Vec<u64> = logs.received_logs.iter().map(|entry| entry.index).collect();
+        assert_eq!(received, [41, 31, 21, 11, 1]);
+    }
+
+    #[test]
+    fn test_json_log() {
+        use channels_console::{ChannelLogs, MetricsJson};
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "json_log_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6785")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut metrics = None;
+        let mut last_error = None;
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6785/metrics")
+                .call()
+                .map_err(|e| format!("Request error: {}", e))
+                .and_then(|mut response| {
+                    response
+                        .body_mut()
+                        .read_json::<MetricsJson>()
+                        .map_err(|e| format!("Invalid JSON: {}", e))
+                }) {
+                Ok(m) if m.stats.first().is_some_and(|s| s.received_count == 2) => {
+                    metrics = Some(m);
+                    break;
+                }
+                Ok(m) => last_error = Some(format!("Unexpected stats: {:?}", m.stats)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let Some(metrics) = metrics else {
+            let _ = child.kill();
+            panic!("Failed after 4 retries: {:?}", last_error);
+        };
+
+        let logs: ChannelLogs = ureq::get(format!(
+            "http://127.0.0.1:6785/logs/{}",
+            metrics.stats[0].id
+        ))
+        .call()
+        .expect("Failed to call /logs/:id endpoint")
+        .body_mut()
+        .read_json()
+        .expect("Failed to parse logs JSON");
+
+        let status = child.wait().expect("Failed to wait for child");
+        assert!(status.success(), "Command failed with status: {}", status);
+
+        let messages: Vec<serde_json::Value> = logs
+            .sent_logs
+            .iter()
+            .map(|entry| {
+                let message = entry.message.as_deref().expect("Message should be logged");
+                serde_json::from_str(message).expect("Message should be JSON")
+            })
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                serde_json::json!({"id": 2, "customer": "customer-2", "items": ["apple", "pear"]}),
+                serde_json::json!({"id": 1, "customer": "customer-1", "items": ["apple", "pear"]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backtrace_endpoint() {
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "backtrace_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6782")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut metrics = None;
+        let mut last_error = None;
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6782/metrics")
+                .call()
+                .map_err(|e| format!("Request error: {}", e))
+                .and_then(|mut response| {
+                    response
+                        .body_mut()
+                        .read_json::<channels_console::MetricsJson>()
+                        .map_err(|e| format!("Invalid JSON: {}", e))
+                }) {
+                Ok(m) if m.stats.len() == 2 => {
+                    metrics = Some(m);
+                    break;
+                }
+                Ok(m) => last_error = Some(format!("Unexpected stats: {:?}", m.stats)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let Some(metrics) = metrics else {
+            let _ = child.kill();
+            panic!("Failed after 4 retries: {:?}", last_error);
+        };
+
+        let fetch_backtrace = |label: &str| {
+            let stat = metrics
+                .stats
+                .iter()
+                .find(|s| s.label == label)
+                .expect("Channel not found");
+            let result = ureq::get(format!(
+                "http://127.0.0.1:6782/channels/{}/backtrace",
+                stat.id
+            ))
+            .call()
+            .map(|mut response| response.body_mut().read_to_string());
+            (stat.has_backtrace, result)
+        };
+        let worker = fetch_backtrace("worker");
+        let plain = fetch_backtrace("plain");
+
+        let _ = child.kill();
+        let _ = child.wait();
+
+        let (has_backtrace, backtrace) = worker;
+        assert!(has_backtrace);
+        let backtrace = backtrace
+            .expect("Failed to call backtrace endpoint")
+            .expect("Failed to read backtrace");
+        assert!(
+            backtrace.contains("backtrace_tokio::spawn_worker"),
+            "Got:\n{backtrace}"
+        );
+
+        let (has_backtrace, backtrace) = plain;
+        assert!(!has_backtrace);
+        assert!(
+            matches!(backtrace, Err(ureq::Error::StatusCode(404))),
+            "Got: {:?}",
+            backtrace
+        );
+    }
+
+    #[test]
+    fn test_iter_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "iter_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let all_expected = [
+            "Actor 1",
+            "Actor 1-2",
+            "Actor 1-3",
+            "examples/iter_tokio.rs:39",
+            "examples/iter_tokio.rs:39-2",
+            "examples/iter_tokio.rs:39-3",
+            "examples/iter_tokio.rs:54",
+            "examples/iter_tokio.rs:54-2",
+            "examples/iter_tokio.rs:54-3",
+        ];
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_slow_consumer_no_panic() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "slow_consumer_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nStdout:\n{}\nStderr:\n{}",
+            output.status,
+            stdout,
+            stderr
+        );
+
+        assert!(
+            stdout.contains("Slow consumer example completed!"),
+            "Expected completion message not found.\nOutput:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_backpressure_matches_capacity() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "backpressure_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        // (capacity, uninstrumented, instrumented) accepted before try_send reports full. The
+        // outer proxy keeps a slot on top of the two messages held by the forwarders, so
+        // channels smaller than 3 accept 3.
+        let accepted: Vec<(usize, usize, usize)> = stdout
+            .lines()
+            .filter_map(|line| {
+                let (capacity, counts) = line.strip_prefix("Capacity ")?.split_once(':')?;
+                let (plain, instrumented) = counts
+                    .trim()
+                    .strip_prefix("uninstrumented accepted ")?
+                    .split_once(", instrumented accepted ")?;
+                Some((
+                    capacity.parse().ok()?,
+                    plain.parse().ok()?,
+                    instrumented.parse().ok()?,
+                ))
+            })
+            .collect();
+
+        assert_eq!(
+            accepted,
+            [(1, 1, 3), (2, 2, 3), (5, 5, 5)],
+            "Unexpected buffering\nOutput:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_message_order_preserved() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "ordering_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let all_expected = [
+            "bounded: received 10000 of 10000 in order",
+            "unbounded: received 10000 of 10000 in order",
+        ];
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_overhead_example() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "overhead_tokio",
+                "--features",
+                "channels-console",
+                "--",
+                "1000",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6787")
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        for mode in ["plain ", "proxied ", "tap "] {
+            assert!(
+                stdout
+                    .lines()
+                    .any(|line| line.starts_with(mode) && line.contains("msg/s")),
+                "Missing '{mode}' measurement.\nOutput:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_sender_only_output() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "sender_only_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let row = stdout
+            .lines()
+            .find(|line| line.contains("| late-producer "))
+            .unwrap_or_else(|| panic!("Missing late-producer row.\nOutput:\n{stdout}"));
+
+        for expected in ["bounded[10]", "| 3 ", "| N/A "] {
+            assert!(
+                row.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{row}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_idle_sender_keeps_capacity() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "sender_idle_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        // An idle instrumented sender must not hold the only slot of the channel
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}\n{}",
+            output.status,
+            stdout,
+            stderr
+        );
+        assert!(
+            stdout.contains("[Receiver] Received: 3"),
+            "Output:\n{stdout}"
+        );
+    }
+
+    #[test]
+    fn test_tracing_events() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "tracing_tokio",
+                "--features",
+                "tracing",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let sent_count = stdout
+            .lines()
+            .filter(|line| line.contains(" sent channel=traced-channel"))
+            .count();
+        assert_eq!(
+            sent_count, 3,
+            "Expected 3 'sent' events.\nOutput:\n{stdout}"
+        );
+
+        assert!(
+            stdout
+                .lines()
+                .any(|line| line.contains("label=traced-channel") && line.contains(" closed ")),
+            "Expected a 'closed' event within the channel span.\nOutput:\n{stdout}"
+        );
+    }
+
+    #[test]
+    fn test_disabled_feature() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "disabled_tokio",
+                "--features",
+                "disabled",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\n{stderr}",
+            output.status
+        );
+        assert!(
+            stdout.contains("instrumented channels: 0, metrics server: None"),
+            "Got:\n{stdout}"
+        );
+        assert!(
+            !stdout.contains("No instrumented channels found"),
+            "Got:\n{stdout}"
+        );
+    }
+
+    #[test]
+    fn test_otel_metrics() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "otel_tokio",
+                "--features",
+                "otel",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let all_expected = [
+            "channel.sent [5]",
+            "channel.received [5]",
+            "channel.dropped [0]",
+            "channel.queued [0]",
+        ];
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_metrics_facade() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "metrics_tokio",
+                "--features",
+                "metrics",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let labels = r#"{label="metrics-channel",channel_type="bounded[10]"}"#;
+        let all_expected = [
+            format!("channel_sent_total{labels} 5"),
+            format!("channel_received_total{labels} 5"),
+            format!("channel_queued{labels} 0"),
+        ];
+
+        for expected in all_expected {
+            assert!(
+                stdout.contains(&expected),
+                "Expected:\n{expected}\n\nGot:\n{stdout}",
+            );
+        }
+        assert!(!stdout.contains("channel_dropped_total"), "Got:\n{stdout}");
+    }
+
+    #[test]
+    fn test_explicit_ids() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "explicit_id_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}",
+            output.status
+        );
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+        stats.sort_by_key(|s| s.id);
+
+        let ids: Vec<(&str, &str, u64)> = stats
+            .iter()
+            .map(|s| (s.source.as_str(), s.label.as_str(), s.sent_count))
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                ("ingest.worker.0", "ingest.worker.0", 1),
+                ("ingest.worker.1", "ingest.worker.1", 2),
+                ("ingest.worker.2", "ingest.worker.2", 3),
+                ("pool.conn", "CONN", 1),
+            ],
+            "Output:\n{}",
+            stdout
+        );
+    }
+
+    #[test]
+    fn test_aggregate_by_source() {
+        use channels_console::MetricsJson;
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "aggregate_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6784")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut metrics = None;
+        let mut last_error = None;
+        for _attempt in 0..10 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6784/metrics?aggregate=source")
+                .call()
+                .map_err(|e| format!("Request error: {}", e))
+                .and_then(|mut response| {
+                    response
+                        .body_mut()
+                        .read_json::<MetricsJson>()
+                        .map_err(|e| format!("Invalid JSON: {}", e))
+                }) {
+                Ok(m)
+                    if m.stats
+                        .iter()
+                        .any(|s| s.label == "control" && s.received_count == 1) =>
+                {
+                    metrics = Some(m);
+                    break;
+                }
+                Ok(m) => last_error = Some(format!("Unexpected stats: {:?}", m.stats)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let invalid = ureq::get("http://127.0.0.1:6784/metrics?aggregate=label").call();
+
+        let status = child.wait().expect("Failed to wait for child");
+        assert!(status.success(), "Command failed with status: {}", status);
+
+        let Some(metrics) = metrics else {
+            panic!("Failed after 10 retries: {:?}", last_error);
+        };
+        let mut rows: Vec<(&str, Option<usize>, u64, u64)> = metrics
+            .stats
+            .iter()
+            .map(|s| (s.label.as_str(), s.count, s.sent_count, s.received_count))
+            .collect();
+        rows.sort();
+        assert_eq!(rows, [("control", None, 1, 1), ("worker", Some(3), 6, 6)]);
+        // The summary still covers every channel
+        assert_eq!(metrics.summary.channels, 4);
+
+        assert!(
+            matches!(invalid, Err(ureq::Error::StatusCode(400))),
+            "Unexpected response: {:?}",
+            invalid
+        );
+    }
+
+    #[test]
+    fn test_meta_filter() {
+        use channels_console::MetricsJson;
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let fetch = |query: &str| {
+            ureq::get(format!("http://127.0.0.1:6796/metrics{query}"))
+                .call()
+                .map_err(|e| format!("Request error: {}", e))
+                .and_then(|mut response| {
+                    response
+                        .body_mut()
+                        .read_json::<MetricsJson>()
+                        .map_err(|e| format!("Invalid JSON: {}", e))
+                })
+        };
+        let labels = |metrics: &MetricsJson| {
+            let mut labels: Vec<String> = metrics.stats.iter().map(|s| s.label.clone()).collect();
+            labels.sort();
+            labels
+        };
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "meta_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6796")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut metrics = None;
+        let mut last_error = None;
+        for _attempt in 0..10 {
+            sleep(Duration::from_millis(500));
+
+            match fetch("") {
+                Ok(m) if m.stats.iter().all(|s| s.received_count == 1) && m.stats.len() == 4 => {
+                    metrics = Some(m);
+                    break;
+                }
+                Ok(m) => last_error = Some(format!("Unexpected stats: {:?}", m.stats)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let payments = fetch("?meta.team=payments");
+        let critical = fetch("?meta.team=payments&meta.criticality=high&aggregate=source");
+        let unknown = fetch("?meta.owner=nobody");
+
+        let status = child.wait().expect("Failed to wait for child");
+        assert!(status.success(), "Command failed with status: {}", status);
+
+        let Some(metrics) = metrics else {
+            panic!("Failed after 10 retries: {:?}", last_error);
+        };
+        let refunds = metrics.stats.iter().find(|s| s.label == "refunds").unwrap();
+        assert_eq!(refunds.group.as_deref(), Some("billing"));
+        assert_eq!(
+            refunds.meta,
+            [("criticality", "low"), ("team", "payments")]
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .into()
+        );
+        let plain = metrics.stats.iter().find(|s| s.label == "plain").unwrap();
+        assert!(plain.meta.is_empty());
+
+        let payments = payments.expect("Failed to fetch payments channels");
+        assert_eq!(labels(&payments), ["charges", "refunds"]);
+        assert_eq!(payments.summary.channels, 2);
+        assert_eq!(labels(&critical.unwrap()), ["charges"]);
+        let unknown = unknown.unwrap();
+        assert!(unknown.stats.is_empty());
+        assert_eq!(unknown.summary.channels, 0);
+    }
+
+    #[test]
+    fn test_invalid_metrics_response() {
+        use std::io::{Read, Write};
+        use std::{net::TcpListener, process::Command, thread};
+
+        // Stands in for a proxy answering with an error page instead of the metrics
+        let listener = TcpListener::bind("127.0.0.1:6798").expect("Failed to bind");
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("Failed to accept");
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request);
+            let body = "<html><h1>502 Bad Gateway</h1></html>";
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        });
+
+        let output = Command::new("cargo")
+            .args(["run", "-p", "channels-console", "--features", "tui", "--"])
+            .args(["--metrics-port", "6798", "--once"])
+            .output()
+            .expect("Failed to execute command");
+        server.join().expect("Server thread panicked");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success(), "Expected failure:\n{stderr}");
+        assert!(
+            stderr.contains("Invalid JSON response")
+                && stderr.contains(r#""<html><h1>502 Bad Gateway</h1></html>""#),
+            "Expected the body in the error:\n{stderr}"
+        );
+    }
+
+    #[test]
+    fn test_binary_metrics() {
+        use channels_console::{MetricsJson, CBOR_CONTENT_TYPE, MSGPACK_CONTENT_TYPE};
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let fetch = |accept: &str| {
+            let mut response = ureq::get("http://127.0.0.1:6797/metrics")
+                .header("Accept", accept)
+                .call()
+                .map_err(|e| format!("Request error: {}", e))?;
+            let content_type = response
+                .headers()
+                .get("Content-Type")
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            let body = response
+                .body_mut()
+                .read_to_vec()
+                .map_err(|e| format!("Read error: {}", e))?;
+            Ok::<_, String>((content_type, body))
+        };
+        let rows = |metrics: &MetricsJson| {
+            let mut rows: Vec<(String, u64, u64)> = metrics
+                .stats
+                .iter()
+                .map(|s| (s.label.clone(), s.sent_count, s.received_count))
+                .collect();
+            rows.sort();
+            rows
+        };
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "aggregate_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6797")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut json = None;
+        let mut last_error = None;
+        for _attempt in 0..10 {
+            sleep(Duration::from_millis(500));
+
+            match fetch("application/json").and_then(|(_, body)| {
+                serde_json::from_slice::<MetricsJson>(&body).map_err(|e| e.to_string())
+            }) {
+                Ok(m)
+                    if m.stats
+                        .iter()
+                        .any(|s| s.label == "control" && s.received_count == 1) =>
+                {
+                    json = Some(m);
+                    break;
+                }
+                Ok(m) => last_error = Some(format!("Unexpected stats: {:?}", m.stats)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let msgpack = fetch(&format!("{}, application/json", MSGPACK_CONTENT_TYPE));
+        let cbor = fetch(&format!("{};q=0.9", CBOR_CONTENT_TYPE));
+        let unsupported = fetch("text/html, */*");
+
+        let status = child.wait().expect("Failed to wait for child");
+        assert!(status.success(), "Command failed with status: {}", status);
+
+        let Some(json) = json else {
+            panic!("Failed after 10 retries: {:?}", last_error);
+        };
+
+        let (content_type, body) = msgpack.expect("Failed to fetch MessagePack metrics");
+        assert_eq!(content_type, MSGPACK_CONTENT_TYPE);
+        let metrics: MetricsJson = rmp_serde::from_slice(&body).expect("Invalid MessagePack");
+        assert_eq!(rows(&metrics), rows(&json));
+        assert_eq!(metrics.summary, json.summary);
+
+        let (content_type, body) = cbor.expect("Failed to fetch CBOR metrics");
+        assert_eq!(content_type, CBOR_CONTENT_TYPE);
+        let metrics: MetricsJson = ciborium::from_reader(body.as_slice()).expect("Invalid CBOR");
+        assert_eq!(rows(&metrics), rows(&json));
+        assert_eq!(metrics.summary, json.summary);
+
+        let (content_type, _) = unsupported.expect("Failed to fetch metrics");
+        assert_eq!(content_type, "application/json");
+    }
+
+    #[test]
+    fn test_websocket_snapshots() {
+        use channels_console::MetricsJson;
+        use std::{net::TcpStream, process::Command, thread::sleep, time::Duration};
+        use tungstenite::Message;
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "websocket_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6779")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let mut socket = None;
+        let mut last_error = None;
+        for _attempt in 0..20 {
+            sleep(Duration::from_millis(500));
+
+            let connected = TcpStream::connect("127.0.0.1:6779")
+                .map_err(|e| e.to_string())
+                .and_then(|stream| {
+                    tungstenite::client("ws://127.0.0.1:6779/ws", stream).map_err(|e| e.to_string())
+                });
+            match connected {
+                Ok((ws, _)) => {
+                    socket = Some(ws);
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let Some(mut socket) = socket else {
+            let _ = child.kill();
+            panic!("Failed to connect after 20 retries: {:?}", last_error);
+        };
+        let set_timeout = |socket: &mut tungstenite::WebSocket<TcpStream>, ms| {
+            socket
+                .get_mut()
+                .set_read_timeout(Some(Duration::from_millis(ms)))
+                .unwrap();
+        };
+        // Returns the next snapshot, or None once the read times out
+        let read_snapshot = |socket: &mut tungstenite::WebSocket<TcpStream>| loop {
+            match socket.read() {
+                Ok(Message::Text(text)) => {
+                    break Some(
+                        serde_json::from_str::<MetricsJson>(&text)
+                            .expect("Failed to parse snapshot"),
+                    )
+                }
+                Ok(_) => continue,
+                Err(_) => break None,
+            }
+        };
+
+        // Let the example send some messages first
+        sleep(Duration::from_secs(1));
+        set_timeout(&mut socket, 300);
+        socket.send(Message::text("pause")).unwrap();
+        let _ = std::iter::from_fn(|| read_snapshot(&mut socket)).count();
+        let paused = std::iter::from_fn(|| read_snapshot(&mut socket)).count();
+
+        let before: MetricsJson = ureq::get("http://127.0.0.1:6779/metrics")
+            .call()
+            .expect("Failed to call /metrics endpoint")
+            .body_mut()
+            .read_json()
+            .expect("Failed to parse metrics JSON");
+
+        socket.send(Message::text("reset")).unwrap();
+        socket.send(Message::text("refresh 100")).unwrap();
+        socket.send(Message::text("resume")).unwrap();
+        set_timeout(&mut socket, 2000);
+        let after = read_snapshot(&mut socket);
+
+        let _ = socket.close(None);
+        let _ = child.kill();
+        let _ = child.wait();
+
+        let sent = |metrics: &MetricsJson| {
+            metrics
+                .stats
+                .iter()
+                .find(|s| s.label == "ws-ticks")
+                .map(|s| s.sent_count)
+                .expect("Channel should be instrumented")
+        };
+
+        assert_eq!(paused, 0, "Got snapshots while paused");
+        let after = after.expect("Expected a snapshot after resuming");
+        assert!(
+            sent(&after) < sent(&before),
+            "Reset should lower the sent count: {} -> {}",
+            sent(&before),
+            sent(&after)
+        );
+    }
+
+    #[test]
+    fn test_close_reason() {
+        use channels_console::{CloseReason, MetricsJson};
+        use std::{process::Command, thread::sleep, time::Duration};
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "close_reason_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .env("CHANNELS_CONSOLE_METRICS_PORT", "6786")
+            .spawn()
+            .expect("Failed to spawn command");
+
+        let reason_of = |metrics: &MetricsJson, label: &str| {
+            metrics
+                .stats
+                .iter()
+                .find(|stat| stat.label == label)
+                .and_then(|stat| stat.close_reason)
+        };
+
+        let mut metrics = None;
+        let mut last_error = None;
+        for _attempt in 0..4 {
+            sleep(Duration::from_millis(500));
+
+            match ureq::get("http://127.0.0.1:6786/metrics")
+                .call()
+                .map_err(|e| format!("Request error: {}", e))
+                .and_then(|mut response| {
+                    response
+                        .body_mut()
+                        .read_json::<MetricsJson>()
+                        .map_err(|e| format!("Invalid JSON: {}", e))
+                }) {
+                Ok(m) if reason_of(&m, "consumer-gone").is_some() => {
+                    metrics = Some(m);
+                    break;
+                }
+                Ok(m) => last_error = Some(format!("Unexpected stats: {:?}", m.stats)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let Some(metrics) = metrics else {
+            let _ = child.kill();
+            panic!("Failed after 4 retries: {:?}", last_error);
+        };
+
+        let status = child.wait().expect("Failed to wait for child");
+        assert!(status.success(), "Command failed with status: {}", status);
+
+        assert_eq!(
+            reason_of(&metrics, "producer-done"),
+            Some(CloseReason::SenderDropped)
+        );
+        assert_eq!(
+            reason_of(&metrics, "consumer-gone"),
+            Some(CloseReason::ReceiverDropped)
+        );
+    }
+
+    #[test]
+    fn test_instrument_local() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "local_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+
+        // Messages holding an `Rc` are forwarded on the LocalSet
+        let events = stats.iter().find(|s| s.label == "events").unwrap();
+        assert_eq!(events.type_name, "local_tokio::Event");
+        assert_eq!(
+            (events.sent_count, events.received_count),
+            (3, 3),
+            "Output:\n{}",
+            stdout
+        );
+        assert_eq!(events.channel_type.to_string(), "bounded[10]");
+
+        let names = stats.iter().find(|s| s.label == "names").unwrap();
+        assert_eq!(
+            (names.sent_count, names.received_count),
+            (3, 3),
+            "Output:\n{}",
+            stdout
+        );
+        assert_eq!(names.state, channels_console::ChannelState::Closed);
+    }
+
+    #[test]
+    fn test_recommended_capacity() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "channels-console-tokio-test",
+                "--example",
+                "capacity_tokio",
+                "--features",
+                "channels-console",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "Command failed with status: {}\nOutput:\n{}",
+            output.status,
+            stdout
+        );
+
+        let stats: Vec<channels_console::SerializableChannelStats> = stdout
+            .lines()
+            .filter(|line| line.starts_with('{'))
+            .map(|line| serde_json::from_str(line).expect("Each line should be a JSON object"))
+            .collect();
+        let channel = |label: &str| stats.iter().find(|s| s.label == label).unwrap();
+
+        // The full channel needs more room than it has
+        let busy = channel("busy");
+        let busy_p99 = busy.queued_p99.expect("Expected a queue depth percentile");
+        assert!(busy_p99 >= 4, "Output:\n{}", stdout);
+        assert!(
+            busy.recommended_capacity
+                .is_some_and(|capacity| capacity > 8),
+            "Output:\n{}",
+            stdout
+        );
+
+        // An empty queue gives nothing to size the capacity by
+        let idle = channel("idle");
+        assert_eq!(idle.queued_p99, Some(0), "Output:\n{}", stdout);
+        assert_eq!(idle.recommended_capacity, None, "Output:\n{}", stdout);
+
+        // The bursty one could do with far less
+        let bursty = channel("bursty");
+        assert!(
+            bursty
+                .recommended_capacity
+                .is_some_and(|capacity| capacity < 64),
+            "Output:\n{}",
+            stdout
+        );
+
+        let rare = channel("rare");
+        assert_eq!(rare.queued_p99, None);
+        assert_eq!(rare.recommended_capacity, None);
     }
 }